@@ -84,6 +84,25 @@ impl SymbolSet {
         serde_json::from_str(&content).map_err(LlmError::JsonError)
     }
 
+    /// Load SymbolSet from a plain-text file with one SymbolId per line.
+    ///
+    /// Blank lines and lines starting with `#` are skipped, so the output of
+    /// `--output entity-ids-only` can be fed back in directly.
+    ///
+    /// # Errors
+    ///
+    /// Returns `LlmError::IoError` if the file cannot be read.
+    pub fn from_id_lines_file(path: &Path) -> Result<Self, LlmError> {
+        let content = std::fs::read_to_string(path).map_err(LlmError::IoError)?;
+        let symbol_ids = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(str::to_string)
+            .collect();
+        Ok(Self { symbol_ids })
+    }
+
     /// Validate that all SymbolIds are in the correct format (32 hex characters).
     ///
     /// Magellan SymbolIds are 32-character BLAKE3 hashes represented as lowercase
@@ -147,6 +166,8 @@ impl SymbolSet {
 pub struct AlgorithmOptions<'a> {
     /// Load pre-computed SymbolSet from JSON file
     pub from_symbol_set: Option<&'a str>,
+    /// Load SymbolIds from a plain-text file, one per line
+    pub symbol_ids_file: Option<&'a str>,
     /// One-shot: reachable from symbol (shell-out to magellan reachable)
     pub reachable_from: Option<&'a str>,
     /// One-shot: dead code from entry point (shell-out to magellan dead-code)
@@ -169,6 +190,7 @@ impl<'a> AlgorithmOptions<'a> {
     /// Check if any algorithm filter is active
     pub fn is_active(&self) -> bool {
         self.from_symbol_set.is_some()
+            || self.symbol_ids_file.is_some()
             || self.reachable_from.is_some()
             || self.dead_code_in.is_some()
             || self.in_cycle.is_some()
@@ -195,7 +217,7 @@ pub type AlgorithmFilterResult = Result<(Vec<String>, HashMap<String, String>, b
 /// Apply algorithm filters and return SymbolSet for search filtering
 ///
 /// Handles:
-/// - Pre-computed SymbolSet from file (--from-symbol-set)
+/// - Pre-computed SymbolSet from file (--from-symbol-set, --symbol-ids-file)
 /// - One-shot algorithm execution (--reachable-from, --dead-code-in, etc.)
 /// - FQN resolution for simple names (resolves to SymbolId before shelling out)
 ///
@@ -211,6 +233,11 @@ pub fn apply_algorithm_filters(
         symbol_set.validate()?;
         return Ok((symbol_set.symbol_ids, HashMap::new(), false));
     }
+    if let Some(file_path) = options.symbol_ids_file {
+        let symbol_set = SymbolSet::from_id_lines_file(Path::new(file_path))?;
+        symbol_set.validate()?;
+        return Ok((symbol_set.symbol_ids, HashMap::new(), false));
+    }
 
     // Priority 2: One-shot algorithm execution (only one allowed)
     // Check for exactly one active one-shot filter
@@ -55,3 +55,22 @@ fn test_symbol_set_json_serialize() {
     assert!(json.contains("symbol_ids"));
     assert!(json.contains("abc123def456789012345678901234ab"));
 }
+
+#[test]
+fn test_symbol_set_from_id_lines_file() {
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    std::io::Write::write_all(
+        &mut file,
+        b"# from --output entity-ids-only\nabc123def456789012345678901234ab\n\n0123456789abcdef0123456789abcdef\n",
+    )
+    .unwrap();
+    let symbol_set = SymbolSet::from_id_lines_file(file.path()).unwrap();
+    assert_eq!(
+        symbol_set.symbol_ids,
+        vec![
+            "abc123def456789012345678901234ab",
+            "0123456789abcdef0123456789abcdef"
+        ]
+    );
+    assert!(symbol_set.validate().is_ok());
+}
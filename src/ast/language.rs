@@ -34,7 +34,7 @@ pub static AST_SHORTHANDS: &[(&str, &str)] = &[
 /// language-specific node kinds.
 #[derive(Debug, Clone)]
 pub struct LanguageNodeKinds {
-    /// Language identifier (rust, python, javascript, typescript)
+    /// Language identifier (python, javascript, go, ruby, ...)
     pub language: &'static str,
     /// Loop constructs
     pub loops: &'static [&'static str],
@@ -109,18 +109,128 @@ pub static TYPESCRIPT_NODE_KINDS: LanguageNodeKinds = LanguageNodeKinds {
     ],
 };
 
+/// Node kind mappings for Go (tree-sitter-go)
+pub static GO_NODE_KINDS: LanguageNodeKinds = LanguageNodeKinds {
+    language: "go",
+    loops: &["for_statement"],
+    conditionals: &[
+        "if_statement",
+        "expression_switch_statement",
+        "type_switch_statement",
+        "select_statement",
+    ],
+    functions: &["function_declaration", "method_declaration", "func_literal"],
+    declarations: &["type_declaration", "var_declaration", "const_declaration"],
+};
+
+/// Node kind mappings for Ruby (tree-sitter-ruby)
+///
+/// `def` has no node of its own: method definitions are `method` and
+/// `singleton_method` nodes.
+pub static RUBY_NODE_KINDS: LanguageNodeKinds = LanguageNodeKinds {
+    language: "ruby",
+    loops: &["for", "while", "until", "while_modifier", "until_modifier"],
+    conditionals: &[
+        "if",
+        "unless",
+        "case",
+        "if_modifier",
+        "unless_modifier",
+        "conditional",
+    ],
+    functions: &["method", "singleton_method", "lambda", "block", "do_block"],
+    declarations: &["class", "module", "singleton_class"],
+};
+
+/// Node kind mappings for PHP (tree-sitter-php)
+pub static PHP_NODE_KINDS: LanguageNodeKinds = LanguageNodeKinds {
+    language: "php",
+    loops: &[
+        "for_statement",
+        "foreach_statement",
+        "while_statement",
+        "do_statement",
+    ],
+    conditionals: &["if_statement", "switch_statement", "match_expression"],
+    functions: &[
+        "function_definition",
+        "method_declaration",
+        "anonymous_function",
+        "arrow_function",
+    ],
+    declarations: &[
+        "class_declaration",
+        "interface_declaration",
+        "trait_declaration",
+        "enum_declaration",
+    ],
+};
+
+/// Node kind mappings for Swift (tree-sitter-swift)
+pub static SWIFT_NODE_KINDS: LanguageNodeKinds = LanguageNodeKinds {
+    language: "swift",
+    loops: &["for_statement", "while_statement", "repeat_while_statement"],
+    conditionals: &["if_statement", "guard_statement", "switch_statement"],
+    functions: &[
+        "function_declaration",
+        "init_declaration",
+        "closure_expression",
+        "lambda_literal",
+    ],
+    declarations: &[
+        "class_declaration",
+        "protocol_declaration",
+        "typealias_declaration",
+    ],
+};
+
+/// Node kind mappings for Kotlin (tree-sitter-kotlin)
+pub static KOTLIN_NODE_KINDS: LanguageNodeKinds = LanguageNodeKinds {
+    language: "kotlin",
+    loops: &["for_statement", "while_statement", "do_while_statement"],
+    conditionals: &["if_expression", "when_expression"],
+    functions: &[
+        "function_declaration",
+        "anonymous_function",
+        "lambda_literal",
+    ],
+    declarations: &["class_declaration", "object_declaration", "type_alias"],
+};
+
+/// Every per-language mapping, looked up by `language`.
+static LANGUAGE_NODE_KINDS: &[&LanguageNodeKinds] = &[
+    &PYTHON_NODE_KINDS,
+    &JAVASCRIPT_NODE_KINDS,
+    &TYPESCRIPT_NODE_KINDS,
+    &GO_NODE_KINDS,
+    &RUBY_NODE_KINDS,
+    &PHP_NODE_KINDS,
+    &SWIFT_NODE_KINDS,
+    &KOTLIN_NODE_KINDS,
+];
+
 /// Get all supported languages for AST node kind expansion.
 ///
 /// Returns a slice of language identifiers that have specific node kind mappings.
 pub fn get_supported_languages() -> &'static [&'static str] {
-    &["rust", "python", "javascript", "typescript"]
+    &[
+        "rust",
+        "python",
+        "javascript",
+        "typescript",
+        "go",
+        "ruby",
+        "php",
+        "swift",
+        "kotlin",
+    ]
 }
 
 /// Get node kinds for a specific language and shorthand category.
 ///
 /// # Arguments
 ///
-/// * `language` - Language identifier (rust, python, javascript, typescript)
+/// * `language` - Language identifier (python, javascript, typescript, go, ruby, php, swift, kotlin)
 /// * `category` - Shorthand category (loops, conditionals, functions, declarations)
 ///
 /// # Returns
@@ -138,37 +248,15 @@ pub fn get_supported_languages() -> &'static [&'static str] {
 /// assert!(python_funcs.unwrap().iter().any(|s| s == "function_definition"));
 /// ```
 pub fn get_node_kinds_for_language(language: &str, category: &str) -> Option<Vec<String>> {
-    let kinds = match language.to_lowercase().as_str() {
-        "python" => {
-            let mapping = &PYTHON_NODE_KINDS;
-            match category.to_lowercase().as_str() {
-                "loops" => mapping.loops,
-                "conditionals" => mapping.conditionals,
-                "functions" => mapping.functions,
-                "declarations" => mapping.declarations,
-                _ => return None,
-            }
-        }
-        "javascript" => {
-            let mapping = &JAVASCRIPT_NODE_KINDS;
-            match category.to_lowercase().as_str() {
-                "loops" => mapping.loops,
-                "conditionals" => mapping.conditionals,
-                "functions" => mapping.functions,
-                "declarations" => mapping.declarations,
-                _ => return None,
-            }
-        }
-        "typescript" => {
-            let mapping = &TYPESCRIPT_NODE_KINDS;
-            match category.to_lowercase().as_str() {
-                "loops" => mapping.loops,
-                "conditionals" => mapping.conditionals,
-                "functions" => mapping.functions,
-                "declarations" => mapping.declarations,
-                _ => return None,
-            }
-        }
+    let language = language.to_lowercase();
+    let mapping = LANGUAGE_NODE_KINDS
+        .iter()
+        .find(|mapping| mapping.language == language)?;
+    let kinds = match category.to_lowercase().as_str() {
+        "loops" => mapping.loops,
+        "conditionals" => mapping.conditionals,
+        "functions" => mapping.functions,
+        "declarations" => mapping.declarations,
         _ => return None,
     };
     Some(kinds.iter().map(|s| s.to_string()).collect())
@@ -288,3 +376,53 @@ pub fn expand_shorthand_with_language(shorthand: &str, language: Option<&str>) -
     let expanded = expand_shorthand(&normalized);
     expanded.split(',').map(|s| s.trim().to_string()).collect()
 }
+
+/// Node-kind suffixes that denote structure across tree-sitter grammars.
+///
+/// Declarations, definitions, expressions, and statements are kept by
+/// `--semantic-only`; tokens, delimiters, and identifiers are not.
+const SEMANTIC_KIND_SUFFIXES: &[&str] = &[
+    "_item",
+    "_declaration",
+    "_definition",
+    "_expression",
+    "_statement",
+];
+
+/// Check whether an AST node kind is structurally interesting.
+///
+/// A kind is interesting if it appears in any shorthand expansion or
+/// per-language mapping, or ends with a declaration/expression/statement
+/// suffix. Punctuation and bare tokens are not.
+///
+/// # Example
+///
+/// ```
+/// use llmgrep::ast::is_semantic_kind;
+///
+/// assert!(is_semantic_kind("function_item"));
+/// assert!(is_semantic_kind("if_statement"));
+/// assert!(!is_semantic_kind("{"));
+/// assert!(!is_semantic_kind("identifier"));
+/// ```
+pub fn is_semantic_kind(kind: &str) -> bool {
+    if SEMANTIC_KIND_SUFFIXES.iter().any(|s| kind.ends_with(s)) {
+        return true;
+    }
+    if AST_SHORTHANDS
+        .iter()
+        .any(|(_, expansion)| expansion.split(',').any(|k| k == kind))
+    {
+        return true;
+    }
+    // Ruby is skipped: its bare kinds (`if`, `for`, `class`) are keyword
+    // tokens in the other grammars
+    LANGUAGE_NODE_KINDS
+        .iter()
+        .filter(|m| m.language != "ruby")
+        .any(|m| {
+            [m.loops, m.conditionals, m.functions, m.declarations]
+                .iter()
+                .any(|kinds| kinds.contains(&kind))
+        })
+}
@@ -65,7 +65,7 @@
 
 use anyhow::Result;
 use rusqlite::Connection;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 /// AST node context from Magellan's ast_nodes table.
@@ -76,7 +76,7 @@ use std::collections::HashMap;
 /// Basic fields are always populated when AST data is available.
 /// Enriched fields (depth, parent_kind, children_count_by_kind, decision_points)
 /// are only populated when `--with-ast-context` flag is used.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AstContext {
     /// AST node ID (matches symbol ID)
     pub ast_id: i64,
@@ -289,6 +289,44 @@ pub fn calculate_decision_depth(conn: &Connection, ast_id: i64) -> Result<Option
     }
 }
 
+/// Build the kind path of an AST node, from the file root down to the node.
+///
+/// Walks `parent_id` upward with the same ancestry CTE pattern as
+/// `calculate_decision_depth`, e.g. `["source_file", "function_item",
+/// "block", "if_expression"]` for an `if` nested in a function body.
+///
+/// # Arguments
+///
+/// * `conn` - SQLite connection
+/// * `ast_id` - AST node ID to build the path for
+///
+/// # Returns
+///
+/// * `Ok(kinds)` - Kinds ordered root first; empty if the node is not found
+/// * `Err(...)` - Database error
+pub fn ast_kind_path(conn: &Connection, ast_id: i64) -> Result<Vec<String>> {
+    let sql = r#"
+        WITH RECURSIVE kind_ancestry AS (
+            -- Base case: the node itself at distance 0
+            SELECT id, parent_id, kind, 0 as distance
+            FROM ast_nodes
+            WHERE id = ?
+            UNION ALL
+            -- Recursive case: step to the parent
+            SELECT a.id, a.parent_id, a.kind, ka.distance + 1
+            FROM ast_nodes a
+            JOIN kind_ancestry ka ON a.id = ka.parent_id
+        )
+        SELECT kind FROM kind_ancestry ORDER BY distance DESC
+    "#;
+
+    let mut stmt = conn.prepare(sql)?;
+    let kinds = stmt
+        .query_map([ast_id], |row| row.get::<_, String>(0))?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(kinds)
+}
+
 /// Get the kind of an AST node's parent.
 ///
 /// # Arguments
@@ -564,10 +602,199 @@ pub fn get_ast_context_for_symbol_with_preference(
     Ok(Some(ctx))
 }
 
+/// Translate AST filters into a tree-sitter query s-expression.
+///
+/// Produces a starting point for tree-sitter tooling that mirrors llmgrep's
+/// `--ast-kind`, `--inside`, and `--contains` filters. The matched node is
+/// captured as `@match`; multiple kinds become an alternation.
+///
+/// llmgrep's `--inside` matches any ancestor, while tree-sitter nesting only
+/// matches direct children, so a leading comment flags where intermediate
+/// nodes (e.g. `block`) may need to be added.
+///
+/// # Example
+///
+/// ```
+/// use llmgrep::ast::to_tree_sitter_query;
+///
+/// let query = to_tree_sitter_query(&["for_expression".to_string()], Some("function_item"), None);
+/// assert!(query.ends_with("(function_item\n  (for_expression) @match)"));
+/// ```
+pub fn to_tree_sitter_query(
+    ast_kinds: &[String],
+    inside: Option<&str>,
+    contains: Option<&str>,
+) -> String {
+    let node = |kind: &str| match contains {
+        Some(child) => format!("({} ({}))", kind, child),
+        None => format!("({})", kind),
+    };
+
+    let matched = match ast_kinds {
+        [] => format!("{} @match", node("_")),
+        [kind] => format!("{} @match", node(kind)),
+        kinds => format!(
+            "[{}] @match",
+            kinds.iter().map(|k| node(k)).collect::<Vec<_>>().join(" ")
+        ),
+    };
+
+    match inside {
+        Some(ancestor) => format!(
+            "; --inside matches any ancestor; tree-sitter nesting is direct-child only\n({}\n  {})",
+            ancestor, matched
+        ),
+        None => matched,
+    }
+}
+
+/// Drop non-semantic nodes from an `ast` command result in place.
+///
+/// Operates on the `{"file_path", "count", "nodes": [...]}` value returned by
+/// `BackendTrait::ast`. Kept nodes whose parent was dropped are re-parented to
+/// their nearest kept ancestor so the tree stays connected, and `count` is
+/// updated to the filtered length.
+pub fn retain_semantic_nodes(value: &mut serde_json::Value) {
+    let Some(nodes) = value.get_mut("nodes").and_then(|n| n.as_array_mut()) else {
+        return;
+    };
+
+    let parents: HashMap<i64, Option<i64>> = nodes
+        .iter()
+        .filter_map(|n| Some((n.get("id")?.as_i64()?, n.get("parent_id")?.as_i64())))
+        .collect();
+    let kept: std::collections::HashSet<i64> = nodes
+        .iter()
+        .filter(|n| {
+            n.get("kind")
+                .and_then(|k| k.as_str())
+                .is_some_and(is_semantic_kind)
+        })
+        .filter_map(|n| n.get("id")?.as_i64())
+        .collect();
+
+    nodes.retain(|n| {
+        n.get("id")
+            .and_then(|id| id.as_i64())
+            .is_some_and(|id| kept.contains(&id))
+    });
+    for node in nodes.iter_mut() {
+        let mut parent = node.get("parent_id").and_then(|p| p.as_i64());
+        while let Some(p) = parent {
+            if kept.contains(&p) {
+                break;
+            }
+            parent = parents.get(&p).copied().flatten();
+        }
+        node["parent_id"] = serde_json::json!(parent);
+    }
+
+    let count = nodes.len();
+    value["count"] = serde_json::json!(count);
+}
+
+/// Prune an `ast` command result to `max_depth` levels in place.
+///
+/// Operates on the same value as [`retain_semantic_nodes`]. Depth follows
+/// `parent_id`: nodes whose parent is not in the result are level 1. Deeper
+/// nodes are dropped, and every level-`max_depth` node that loses children is
+/// replaced by a `{"id", "parent_id", "kind", "truncated": true, "child_count"}`
+/// placeholder. `count` is updated to the pruned length.
+pub fn prune_to_depth(value: &mut serde_json::Value, max_depth: usize) {
+    let Some(nodes) = value.get_mut("nodes").and_then(|n| n.as_array_mut()) else {
+        return;
+    };
+
+    let parents: HashMap<i64, Option<i64>> = nodes
+        .iter()
+        .filter_map(|n| Some((n.get("id")?.as_i64()?, n.get("parent_id")?.as_i64())))
+        .collect();
+    let mut child_counts: HashMap<i64, usize> = HashMap::new();
+    for parent in parents.values().flatten() {
+        if parents.contains_key(parent) {
+            *child_counts.entry(*parent).or_default() += 1;
+        }
+    }
+    // Stops walking once past max_depth, which also bounds malformed cycles
+    let depth_of = |id: i64| {
+        let mut depth = 1;
+        let mut parent = parents.get(&id).copied().flatten();
+        while let Some(p) = parent.filter(|p| parents.contains_key(p)) {
+            depth += 1;
+            if depth > max_depth {
+                break;
+            }
+            parent = parents[&p];
+        }
+        depth
+    };
+
+    nodes.retain(|n| {
+        n.get("id")
+            .and_then(|id| id.as_i64())
+            .is_some_and(|id| depth_of(id) <= max_depth)
+    });
+    for node in nodes.iter_mut() {
+        let Some(id) = node.get("id").and_then(|id| id.as_i64()) else {
+            continue;
+        };
+        let child_count = child_counts.get(&id).copied().unwrap_or(0);
+        if child_count > 0 && depth_of(id) == max_depth {
+            *node = serde_json::json!({
+                "id": id,
+                "parent_id": node.get("parent_id").cloned().unwrap_or_default(),
+                "kind": node.get("kind").cloned().unwrap_or_default(),
+                "truncated": true,
+                "child_count": child_count,
+            });
+        }
+    }
+
+    let count = nodes.len();
+    value["count"] = serde_json::json!(count);
+}
+
+/// Add a `summary` object with `node_count` and `max_depth` to an `ast`
+/// command result.
+///
+/// Computed from the returned node set only, with the same levels as
+/// [`prune_to_depth`]: nodes whose parent is not in the result are level 1.
+/// Run after any filtering so the summary describes what is printed.
+pub fn summarize_ast(value: &mut serde_json::Value) {
+    let Some(nodes) = value.get("nodes").and_then(|n| n.as_array()) else {
+        return;
+    };
+
+    let parents: HashMap<i64, Option<i64>> = nodes
+        .iter()
+        .filter_map(|n| Some((n.get("id")?.as_i64()?, n.get("parent_id")?.as_i64())))
+        .collect();
+    // Walks are capped at the node count, which also bounds malformed cycles
+    let depth_of = |id: i64| {
+        let mut depth = 1;
+        let mut parent = parents.get(&id).copied().flatten();
+        while let Some(p) = parent.filter(|p| parents.contains_key(p)) {
+            depth += 1;
+            if depth > parents.len() {
+                break;
+            }
+            parent = parents[&p];
+        }
+        depth
+    };
+    let max_depth = parents.keys().map(|&id| depth_of(id)).max().unwrap_or(0);
+
+    value["summary"] = serde_json::json!({
+        "node_count": nodes.len(),
+        "max_depth": max_depth,
+    });
+}
+
 pub use language::{
     expand_shorthand, expand_shorthand_with_language, expand_shorthands,
-    get_node_kinds_for_language, get_supported_languages, LanguageNodeKinds, AST_SHORTHANDS,
-    JAVASCRIPT_NODE_KINDS, PYTHON_NODE_KINDS, TYPESCRIPT_NODE_KINDS,
+    get_node_kinds_for_language, get_supported_languages, is_semantic_kind, LanguageNodeKinds,
+    AST_SHORTHANDS, GO_NODE_KINDS, JAVASCRIPT_NODE_KINDS, KOTLIN_NODE_KINDS, PHP_NODE_KINDS,
+    PYTHON_NODE_KINDS, RUBY_NODE_KINDS, SWIFT_NODE_KINDS, TYPESCRIPT_NODE_KINDS,
 };
 
 mod language;
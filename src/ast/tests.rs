@@ -174,3 +174,121 @@ fn test_calculate_decision_depth() {
         "match_expression (child of let) should have decision depth 3"
     );
 }
+
+#[test]
+fn test_to_tree_sitter_query_inside_with_kind() {
+    let query = to_tree_sitter_query(&["for_expression".to_string()], Some("function_item"), None);
+    let lines: Vec<&str> = query.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(
+        lines[0].starts_with(';'),
+        "leading comment about nesting depth"
+    );
+    assert_eq!(lines[1], "(function_item");
+    assert_eq!(lines[2], "  (for_expression) @match)");
+}
+
+#[test]
+fn test_to_tree_sitter_query_alternation_and_contains() {
+    let kinds = vec!["for_expression".to_string(), "while_expression".to_string()];
+    let query = to_tree_sitter_query(&kinds, None, Some("block"));
+    assert_eq!(
+        query,
+        "[(for_expression (block)) (while_expression (block))] @match"
+    );
+    assert_eq!(to_tree_sitter_query(&[], None, None), "(_) @match");
+}
+
+#[test]
+fn test_retain_semantic_nodes_drops_punctuation() {
+    let mut value = serde_json::json!({
+        "file_path": "src/lib.rs",
+        "count": 4,
+        "nodes": [
+            {"id": 1, "parent_id": null, "kind": "function_item", "byte_start": 0, "byte_end": 20},
+            {"id": 2, "parent_id": 1, "kind": "(", "byte_start": 6, "byte_end": 7},
+            {"id": 3, "parent_id": 1, "kind": "block", "byte_start": 9, "byte_end": 20},
+            {"id": 4, "parent_id": 3, "kind": "call_expression", "byte_start": 10, "byte_end": 18},
+        ]
+    });
+
+    retain_semantic_nodes(&mut value);
+
+    let kinds: Vec<&str> = value["nodes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|n| n["kind"].as_str().unwrap())
+        .collect();
+    assert_eq!(kinds, vec!["function_item", "call_expression"]);
+    assert_eq!(value["count"], 2);
+    // call_expression re-parented past the dropped block
+    assert_eq!(value["nodes"][1]["parent_id"], 1);
+}
+
+#[test]
+fn test_prune_to_depth_replaces_deeper_subtrees_with_placeholders() {
+    let mut value = serde_json::json!({
+        "file_path": "src/lib.rs",
+        "count": 6,
+        "nodes": [
+            {"id": 1, "parent_id": null, "kind": "source_file", "byte_start": 0, "byte_end": 60},
+            {"id": 2, "parent_id": 1, "kind": "function_item", "byte_start": 0, "byte_end": 30},
+            {"id": 3, "parent_id": 2, "kind": "parameters", "byte_start": 6, "byte_end": 8},
+            {"id": 4, "parent_id": 2, "kind": "block", "byte_start": 9, "byte_end": 30},
+            {"id": 5, "parent_id": 4, "kind": "call_expression", "byte_start": 10, "byte_end": 28},
+            {"id": 6, "parent_id": 1, "kind": "use_declaration", "byte_start": 31, "byte_end": 60},
+        ]
+    });
+
+    prune_to_depth(&mut value, 2);
+
+    assert_eq!(value["count"], 3);
+    let nodes = value["nodes"].as_array().unwrap();
+    assert_eq!(nodes.len(), 3);
+    assert_eq!(nodes[0]["kind"], "source_file");
+    assert!(nodes[0].get("truncated").is_none());
+    assert_eq!(
+        nodes[1],
+        serde_json::json!({
+            "id": 2,
+            "parent_id": 1,
+            "kind": "function_item",
+            "truncated": true,
+            "child_count": 2,
+        })
+    );
+    // Leaf at the depth limit keeps its full node
+    assert_eq!(nodes[2]["kind"], "use_declaration");
+    assert_eq!(nodes[2]["byte_end"], 60);
+    assert!(nodes[2].get("truncated").is_none());
+}
+
+#[test]
+fn test_summarize_ast_counts_nodes_and_levels() {
+    let mut value = serde_json::json!({
+        "file_path": "src/lib.rs",
+        "count": 4,
+        "nodes": [
+            {"id": 2, "parent_id": 1, "kind": "function_item", "byte_start": 0, "byte_end": 30},
+            {"id": 4, "parent_id": 2, "kind": "block", "byte_start": 9, "byte_end": 30},
+            {"id": 5, "parent_id": 4, "kind": "call_expression", "byte_start": 10, "byte_end": 28},
+            {"id": 6, "parent_id": 1, "kind": "use_declaration", "byte_start": 31, "byte_end": 60},
+        ]
+    });
+
+    summarize_ast(&mut value);
+
+    // Parent 1 is not in the result, so nodes 2 and 6 are level 1
+    assert_eq!(
+        value["summary"],
+        serde_json::json!({"node_count": 4, "max_depth": 3})
+    );
+
+    let mut empty = serde_json::json!({"file_path": "src/lib.rs", "count": 0, "nodes": []});
+    summarize_ast(&mut empty);
+    assert_eq!(
+        empty["summary"],
+        serde_json::json!({"node_count": 0, "max_depth": 0})
+    );
+}
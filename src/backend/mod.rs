@@ -7,10 +7,11 @@
 
 use crate::error::LlmError;
 use crate::output::{
-    CallSearchResponse, DocsSearchResponse, FactsSearchResponse, ImplementsSearchResponse,
-    ReferenceSearchResponse, SearchResponse,
+    CallMatch, CallSearchResponse, DocsSearchResponse, FactsSearchResponse,
+    ImplementsSearchResponse, ReferenceMatch, ReferenceSearchResponse, SearchResponse,
+    SymbolMatch,
 };
-use crate::query::{DocsSearchOptions, FactsSearchOptions, SearchOptions};
+use crate::query::{DocsSearchOptions, FactsSearchOptions, RowSink, SearchOptions};
 use std::path::Path;
 
 // Backend implementation modules
@@ -18,7 +19,10 @@ pub mod schema_check;
 pub mod sqlite;
 pub mod vector;
 
-pub use sqlite::SqliteBackend;
+pub use sqlite::{
+    install_query_deadline, open_connection, open_connection_with_flags, restart_query_clock,
+    retry_busy, set_busy_retries, set_query_timeout, SqliteBackend, DEFAULT_BUSY_RETRIES,
+};
 
 /// Backend trait for abstracting over SQLite storage.
 ///
@@ -44,6 +48,11 @@ pub trait BackendTrait {
         options: SearchOptions,
     ) -> Result<(SearchResponse, bool, bool), LlmError>;
 
+    /// Count symbols matching the given options without building result rows.
+    ///
+    /// Returns a tuple of (count, partial_results_flag).
+    fn count_symbols(&self, options: SearchOptions) -> Result<(u64, bool), LlmError>;
+
     /// Search for references (incoming edges) to symbols.
     fn search_references(
         &self,
@@ -53,6 +62,31 @@ pub trait BackendTrait {
     /// Search for function calls (outgoing edges) from symbols.
     fn search_calls(&self, options: SearchOptions) -> Result<(CallSearchResponse, bool), LlmError>;
 
+    /// Search for symbols, handing each match to `sink` as its row is read.
+    ///
+    /// The returned response has no results but keeps `total_count` and the
+    /// flags. Fails with [`LlmError::InvalidQuery`] unless the options sort by
+    /// position.
+    fn stream_symbols(
+        &self,
+        options: SearchOptions,
+        sink: RowSink<'_, SymbolMatch>,
+    ) -> Result<(SearchResponse, bool, bool), LlmError>;
+
+    /// Streaming counterpart of [`BackendTrait::search_references`].
+    fn stream_references(
+        &self,
+        options: SearchOptions,
+        sink: RowSink<'_, ReferenceMatch>,
+    ) -> Result<(ReferenceSearchResponse, bool), LlmError>;
+
+    /// Streaming counterpart of [`BackendTrait::search_calls`].
+    fn stream_calls(
+        &self,
+        options: SearchOptions,
+        sink: RowSink<'_, CallMatch>,
+    ) -> Result<(CallSearchResponse, bool), LlmError>;
+
     /// Search for type-trait implementation relationships.
     fn search_implements(
         &self,
@@ -76,6 +110,21 @@ pub trait BackendTrait {
         limit: usize,
     ) -> Result<serde_json::Value, LlmError>;
 
+    /// Query the AST nodes of a file that lie within a byte range.
+    ///
+    /// # Arguments
+    /// * `file_path` - File path as stored in the database
+    /// * `byte_start` - Start of the range (inclusive)
+    /// * `byte_end` - End of the range (exclusive)
+    /// * `limit` - Maximum number of AST nodes to return
+    fn ast_span(
+        &self,
+        file_path: &str,
+        byte_start: u64,
+        byte_end: u64,
+        limit: usize,
+    ) -> Result<serde_json::Value, LlmError>;
+
     /// Find AST nodes by kind.
     ///
     /// # Arguments
@@ -189,6 +238,13 @@ impl Backend {
         }
     }
 
+    /// Delegate count_symbols to inner backend.
+    pub fn count_symbols(&self, options: SearchOptions) -> Result<(u64, bool), LlmError> {
+        match self {
+            Backend::Sqlite(b) => b.count_symbols(options),
+        }
+    }
+
     /// Delegate search_references to inner backend.
     pub fn search_references(
         &self,
@@ -209,6 +265,39 @@ impl Backend {
         }
     }
 
+    /// Delegate stream_symbols to inner backend.
+    pub fn stream_symbols(
+        &self,
+        options: SearchOptions,
+        sink: RowSink<'_, SymbolMatch>,
+    ) -> Result<(SearchResponse, bool, bool), LlmError> {
+        match self {
+            Backend::Sqlite(b) => b.stream_symbols(options, sink),
+        }
+    }
+
+    /// Delegate stream_references to inner backend.
+    pub fn stream_references(
+        &self,
+        options: SearchOptions,
+        sink: RowSink<'_, ReferenceMatch>,
+    ) -> Result<(ReferenceSearchResponse, bool), LlmError> {
+        match self {
+            Backend::Sqlite(b) => b.stream_references(options, sink),
+        }
+    }
+
+    /// Delegate stream_calls to inner backend.
+    pub fn stream_calls(
+        &self,
+        options: SearchOptions,
+        sink: RowSink<'_, CallMatch>,
+    ) -> Result<(CallSearchResponse, bool), LlmError> {
+        match self {
+            Backend::Sqlite(b) => b.stream_calls(options, sink),
+        }
+    }
+
     /// Delegate search_implements to inner backend.
     pub fn search_implements(
         &self,
@@ -246,6 +335,19 @@ impl Backend {
         }
     }
 
+    /// Delegate ast_span to inner backend.
+    pub fn ast_span(
+        &self,
+        file_path: &str,
+        byte_start: u64,
+        byte_end: u64,
+        limit: usize,
+    ) -> Result<serde_json::Value, LlmError> {
+        match self {
+            Backend::Sqlite(b) => b.ast_span(file_path, byte_start, byte_end, limit),
+        }
+    }
+
     /// Delegate find_ast to inner backend.
     pub fn find_ast(&self, kind: &str) -> Result<serde_json::Value, LlmError> {
         match self {
@@ -329,4 +431,104 @@ mod tests {
             _ => panic!("Layer 2: Expected DatabaseNotFound error"),
         }
     }
+
+    #[test]
+    fn test_query_deadline_interrupts_running_statement() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        let runaway = "WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n)
+                       SELECT count(*) FROM n";
+
+        install_query_deadline(&conn, std::time::Instant::now());
+        let err: LlmError = conn
+            .query_row(runaway, [], |row| row.get::<_, i64>(0))
+            .unwrap_err()
+            .into();
+        assert!(
+            err.is_interrupted(),
+            "expected SQLITE_INTERRUPT, got {err:?}"
+        );
+
+        // A deadline in the future leaves short statements alone
+        install_query_deadline(
+            &conn,
+            std::time::Instant::now() + std::time::Duration::from_secs(60),
+        );
+        let one: i64 = conn.query_row("SELECT 1", [], |row| row.get(0)).unwrap();
+        assert_eq!(one, 1);
+    }
+
+    #[test]
+    fn test_ast_span_returns_nodes_inside_range() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let conn = rusqlite::Connection::open(temp_file.path()).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE graph_entities (
+                 id INTEGER PRIMARY KEY, kind TEXT NOT NULL, name TEXT, data TEXT NOT NULL
+             );
+             CREATE TABLE ast_nodes (
+                 id INTEGER PRIMARY KEY, parent_id INTEGER, kind TEXT NOT NULL,
+                 byte_start INTEGER NOT NULL, byte_end INTEGER NOT NULL, file_id INTEGER
+             );
+             INSERT INTO graph_entities VALUES (1, 'File', 'src/lib.rs', '{}');
+             INSERT INTO ast_nodes VALUES
+                 (1, NULL, 'source_file', 0, 100, 1),
+                 (2, 1, 'function_item', 10, 50, 1),
+                 (3, 2, 'block', 20, 50, 1),
+                 (4, 1, 'function_item', 60, 90, 1);",
+        )
+        .unwrap();
+
+        let backend = Backend::detect_and_open(temp_file.path()).unwrap();
+        let json = backend.ast_span("src/lib.rs", 10, 50, 100).unwrap();
+        let ids: Vec<i64> = json["nodes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|node| node["id"].as_i64().unwrap())
+            .collect();
+        assert_eq!(ids, vec![2, 3]);
+        assert_eq!(json["count"], 2);
+    }
+
+    #[test]
+    fn test_retry_busy_maps_exhausted_retries_to_database_busy() {
+        let busy = || {
+            LlmError::from(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_BUSY),
+                None,
+            ))
+        };
+
+        let mut calls = 0;
+        let result: Result<(), LlmError> = retry_busy(|| {
+            calls += 1;
+            Err(busy())
+        });
+        assert_eq!(calls, DEFAULT_BUSY_RETRIES + 1);
+        assert!(matches!(
+            result,
+            Err(LlmError::DatabaseBusy { attempts }) if attempts == DEFAULT_BUSY_RETRIES + 1
+        ));
+
+        let mut calls = 0;
+        let result = retry_busy(|| {
+            calls += 1;
+            if calls == 1 {
+                Err(busy())
+            } else {
+                Ok(calls)
+            }
+        });
+        assert_eq!(result.unwrap(), 2);
+
+        let mut calls = 0;
+        let result: Result<(), LlmError> = retry_busy(|| {
+            calls += 1;
+            Err(LlmError::InvalidQuery {
+                query: "bad".to_string(),
+            })
+        });
+        assert_eq!(calls, 1);
+        assert!(matches!(result, Err(LlmError::InvalidQuery { .. })));
+    }
 }
@@ -6,16 +6,127 @@
 use crate::error::LlmError;
 use crate::infer_language;
 use crate::output::{
-    CallSearchResponse, DocsSearchResponse, FactsSearchResponse, ImplementsSearchResponse,
-    ReferenceSearchResponse, SearchResponse, Span, SymbolMatch,
+    CallMatch, CallSearchResponse, DocsSearchResponse, FactsSearchResponse,
+    ImplementsSearchResponse, ReferenceMatch, ReferenceSearchResponse, SearchResponse, Span,
+    SymbolMatch,
 };
 use crate::query::{
-    search_calls_impl, search_docs_impl, search_facts_impl, search_implements_impl,
-    search_references_impl, search_symbols_impl, DocsSearchOptions, FactsSearchOptions,
-    SearchOptions,
+    count_symbols_impl, search_calls_impl, search_docs_impl, search_facts_impl,
+    search_implements_impl, search_references_impl, search_symbols_impl, DocsSearchOptions,
+    FactsSearchOptions, RowSink, SearchOptions,
 };
-use rusqlite::{params, Connection};
+use rusqlite::{params, Connection, OpenFlags};
 use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock, PoisonError};
+use std::time::{Duration, Instant};
+
+/// SQLite virtual machine instructions between deadline checks
+const QUERY_DEADLINE_CHECK_OPS: i32 = 1000;
+
+/// Process-wide time budget per query, set once from `--timeout`
+static QUERY_TIMEOUT: OnceLock<Duration> = OnceLock::new();
+
+/// When the running query started, reset by [`restart_query_clock`]
+static QUERY_STARTED: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// Abort SQLite statements on connections opened from now on once the
+/// running query has taken longer than `timeout`.
+///
+/// Only the first call takes effect, and it starts the clock for the first
+/// query. Interrupted statements fail with `SQLITE_INTERRUPT`; see
+/// [`LlmError::is_interrupted`].
+pub fn set_query_timeout(timeout: Duration) {
+    if QUERY_TIMEOUT.set(timeout).is_ok() {
+        restart_query_clock();
+    }
+}
+
+/// Give the next query a fresh `--timeout` budget.
+///
+/// Called before each `--queries-file` line and each `watch` re-evaluation,
+/// which would otherwise share the budget of the first query.
+pub fn restart_query_clock() {
+    *QUERY_STARTED.lock().unwrap_or_else(PoisonError::into_inner) = Some(Instant::now());
+}
+
+/// Whether the running query has used up its [`set_query_timeout`] budget.
+fn query_clock_expired() -> bool {
+    let started = *QUERY_STARTED.lock().unwrap_or_else(PoisonError::into_inner);
+    match (QUERY_TIMEOUT.get(), started) {
+        (Some(&timeout), Some(started)) => started.elapsed() >= timeout,
+        _ => false,
+    }
+}
+
+/// Interrupt statements running on `conn` once `deadline` has passed.
+pub fn install_query_deadline(conn: &Connection, deadline: Instant) {
+    conn.progress_handler(
+        QUERY_DEADLINE_CHECK_OPS,
+        Some(move || Instant::now() >= deadline),
+    );
+}
+
+/// Retries after a busy first attempt, unless `--busy-retries` says otherwise
+pub const DEFAULT_BUSY_RETRIES: u32 = 3;
+
+/// Wait before the first busy retry; doubled before each later one
+const BUSY_BACKOFF: Duration = Duration::from_millis(50);
+
+/// Process-wide busy retry count, set once from `--busy-retries`
+static BUSY_RETRIES: OnceLock<u32> = OnceLock::new();
+
+/// Retry busy databases `retries` times instead of [`DEFAULT_BUSY_RETRIES`].
+///
+/// Only the first call takes effect.
+pub fn set_busy_retries(retries: u32) {
+    let _ = BUSY_RETRIES.set(retries);
+}
+
+/// Run `op`, retrying with exponential backoff (50ms, 100ms, 200ms, ...)
+/// while it fails with a busy or locked database.
+///
+/// Meant for opening and validating a connection while Magellan may be
+/// re-indexing. Once the retries run out the busy error becomes
+/// [`LlmError::DatabaseBusy`]; other errors are returned unchanged.
+pub fn retry_busy<T>(mut op: impl FnMut() -> Result<T, LlmError>) -> Result<T, LlmError> {
+    let retries = BUSY_RETRIES.get().copied().unwrap_or(DEFAULT_BUSY_RETRIES);
+    let mut backoff = BUSY_BACKOFF;
+    for _ in 0..retries {
+        match op() {
+            Err(err) if err.is_busy() => {
+                std::thread::sleep(backoff);
+                backoff *= 2;
+            }
+            result => return result,
+        }
+    }
+    op().map_err(|err| {
+        if err.is_busy() {
+            LlmError::DatabaseBusy {
+                attempts: retries + 1,
+            }
+        } else {
+            err
+        }
+    })
+}
+
+/// Open a database connection that honours the [`set_query_timeout`] budget.
+pub fn open_connection(db_path: &Path) -> Result<Connection, rusqlite::Error> {
+    open_connection_with_flags(db_path, OpenFlags::default())
+}
+
+/// [`open_connection`] with explicit open flags, e.g. read-only.
+pub fn open_connection_with_flags(
+    db_path: &Path,
+    flags: OpenFlags,
+) -> Result<Connection, rusqlite::Error> {
+    let conn = Connection::open_with_flags(db_path, flags)?;
+    if QUERY_TIMEOUT.get().is_some() {
+        conn.progress_handler(QUERY_DEADLINE_CHECK_OPS, Some(query_clock_expired));
+    }
+    Ok(conn)
+}
 
 /// SQLite backend implementation.
 ///
@@ -33,7 +144,19 @@ impl SqliteBackend {
     /// # Arguments
     /// * `db_path` - Path to the SQLite database file
     pub fn open(db_path: &Path) -> Result<Self, LlmError> {
-        let conn = Connection::open(db_path)?;
+        let conn = retry_busy(|| {
+            let conn = open_connection(db_path)?;
+            // Touch the schema first so a concurrent writer's lock surfaces
+            // here as a retryable error; anything else is left to the
+            // schema check below
+            if let Err(e) = conn.query_row("SELECT 1 FROM sqlite_master LIMIT 1", [], |_| Ok(())) {
+                let err = LlmError::from(e);
+                if err.is_busy() {
+                    return Err(err);
+                }
+            }
+            Ok(conn)
+        })?;
         crate::backend::schema_check::check_schema_version(&conn)
             .map_err(|e| LlmError::SchemaMismatch { reason: e })?;
         Ok(Self {
@@ -48,18 +171,46 @@ impl super::BackendTrait for SqliteBackend {
         &self,
         options: SearchOptions,
     ) -> Result<(SearchResponse, bool, bool), LlmError> {
-        search_symbols_impl(&self.conn, &self.db_path, &options)
+        search_symbols_impl(&self.conn, &self.db_path, &options, None)
+    }
+
+    fn count_symbols(&self, options: SearchOptions) -> Result<(u64, bool), LlmError> {
+        count_symbols_impl(&self.conn, &self.db_path, &options)
     }
 
     fn search_references(
         &self,
         options: SearchOptions,
     ) -> Result<(ReferenceSearchResponse, bool), LlmError> {
-        search_references_impl(&self.conn, &options)
+        search_references_impl(&self.conn, &options, None)
     }
 
     fn search_calls(&self, options: SearchOptions) -> Result<(CallSearchResponse, bool), LlmError> {
-        search_calls_impl(&self.conn, &options)
+        search_calls_impl(&self.conn, &options, None)
+    }
+
+    fn stream_symbols(
+        &self,
+        options: SearchOptions,
+        sink: RowSink<'_, SymbolMatch>,
+    ) -> Result<(SearchResponse, bool, bool), LlmError> {
+        search_symbols_impl(&self.conn, &self.db_path, &options, Some(sink))
+    }
+
+    fn stream_references(
+        &self,
+        options: SearchOptions,
+        sink: RowSink<'_, ReferenceMatch>,
+    ) -> Result<(ReferenceSearchResponse, bool), LlmError> {
+        search_references_impl(&self.conn, &options, Some(sink))
+    }
+
+    fn stream_calls(
+        &self,
+        options: SearchOptions,
+        sink: RowSink<'_, CallMatch>,
+    ) -> Result<(CallSearchResponse, bool), LlmError> {
+        search_calls_impl(&self.conn, &options, Some(sink))
     }
 
     fn search_implements(
@@ -163,6 +314,64 @@ impl super::BackendTrait for SqliteBackend {
         }))
     }
 
+    fn ast_span(
+        &self,
+        file_path: &str,
+        byte_start: u64,
+        byte_end: u64,
+        limit: usize,
+    ) -> Result<serde_json::Value, LlmError> {
+        let table_exists: bool = self
+            .conn
+            .query_row(
+                "SELECT 1 FROM sqlite_master WHERE type='table' AND name='ast_nodes'",
+                [],
+                |_| Ok(true),
+            )
+            .unwrap_or_else(|e| {
+                eprintln!("Warning: Failed to check ast_nodes table existence: {}", e);
+                false
+            });
+
+        if !table_exists {
+            return Ok(serde_json::json!({
+                "file_path": file_path,
+                "count": 0,
+                "nodes": [],
+            }));
+        }
+
+        let mut stmt = self.conn.prepare(
+            "SELECT an.id, an.parent_id, an.kind, an.byte_start, an.byte_end
+             FROM ast_nodes an
+             JOIN graph_entities f ON an.file_id = f.id AND f.kind = 'File'
+             WHERE f.name = ?1
+               AND an.byte_start >= ?2 AND an.byte_end <= ?3
+             ORDER BY an.byte_start
+             LIMIT ?4",
+        )?;
+
+        let rows = stmt.query_map(
+            params![file_path, byte_start as i64, byte_end as i64, limit as i64],
+            |row| {
+                Ok(serde_json::json!({
+                    "id": row.get::<_, i64>(0)?,
+                    "parent_id": row.get::<_, Option<i64>>(1)?,
+                    "kind": row.get::<_, String>(2)?,
+                    "byte_start": row.get::<_, i64>(3)?,
+                    "byte_end": row.get::<_, i64>(4)?,
+                }))
+            },
+        )?;
+        let nodes = rows.collect::<Result<Vec<_>, _>>()?;
+
+        Ok(serde_json::json!({
+            "file_path": file_path,
+            "count": nodes.len(),
+            "nodes": nodes,
+        }))
+    }
+
     fn find_ast(&self, kind: &str) -> Result<serde_json::Value, LlmError> {
         // Check if ast_nodes table exists
         let table_exists: bool = self
@@ -220,7 +429,7 @@ impl super::BackendTrait for SqliteBackend {
              FROM graph_entities
              WHERE kind = 'Symbol'
                AND (fqn LIKE ?1 ESCAPE '\\' OR json_extract(data, '$.fqn') LIKE ?1 ESCAPE '\\')
-             ORDER BY fqn
+             ORDER BY length(fqn), fqn
              LIMIT ?2",
         )?;
         let rows = stmt.query_map(params![like_prefix, limit as i64], |row| {
@@ -300,8 +509,17 @@ impl super::BackendTrait for SqliteBackend {
                     fan_out: None,
                     cyclomatic_complexity: None,
                     ast_context: None,
+                    ast_node_kind: None,
                     supernode_id: None,
                     coverage: None,
+                    line_text: None,
+                    visibility: None,
+                    complexity_percentile: None,
+                    call_count: None,
+                    source_db: None,
+                    captures: None,
+                    defined_in: None,
+                    callers: None,
                 })
             }
             Err(rusqlite::Error::QueryReturnedNoRows) => {
@@ -334,6 +552,7 @@ impl super::BackendTrait for SqliteBackend {
                 path_filter: None,
                 kind_filter: None,
                 notice: None,
+                available_fields: None,
             },
             false,
             false,
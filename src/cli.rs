@@ -1,46 +1,82 @@
 use clap::builder::{RangedI64ValueParser, TypedValueParser};
 use clap::{Parser, Subcommand, ValueEnum};
 use llmgrep::error::LlmError;
-use llmgrep::output::{json_response, ErrorResponse, OutputFormat};
+use llmgrep::output::{json_response, ErrorResponse, OutputFormat, Prefer};
 use llmgrep::SortMode;
 use std::path::{Path, PathBuf};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct SearchParams {
     pub query: String,
+    pub all: bool,
     pub mode: SearchMode,
     pub path: Option<PathBuf>,
+    pub exclude_paths: Vec<PathBuf>,
+    pub path_glob: bool,
+    pub file_set: Option<Vec<String>>,
     pub kind: Option<String>,
+    pub reference_kind: Option<String>,
     pub language: Option<String>,
     pub label: Option<String>,
     pub limit: usize,
+    pub max_per_file: Option<usize>,
+    pub dedupe: bool,
     pub regex: bool,
     pub candidates: usize,
     pub with_context: bool,
+    pub context_only: bool,
+    pub context_exclude_self: bool,
+    pub distinct_names: bool,
+    pub files_with_matches: bool,
+    pub count: bool,
+    pub flatten: bool,
+    pub score_histogram: bool,
+    pub partition_by: Option<PartitionBy>,
     pub context_lines: usize,
+    pub context_before: Option<usize>,
+    pub context_after: Option<usize>,
     pub max_context_lines: usize,
     pub with_snippet: bool,
     pub with_fqn: bool,
     pub max_snippet_bytes: usize,
+    pub snippet_lines: Option<usize>,
+    pub snippet_line_numbers: bool,
+    pub with_line_text: bool,
+    pub with_provenance: bool,
+    pub resolve_callees: bool,
+    pub resolve_defs: bool,
+    pub file_io_timeout: Option<u64>,
+    pub with_complexity_percentile: bool,
     pub fields: Option<String>,
     pub sort_by: SortMode,
     pub auto_limit: AutoLimitMode,
     pub min_complexity: Option<usize>,
     pub max_complexity: Option<usize>,
     pub min_fan_in: Option<usize>,
+    pub max_fan_in: Option<usize>,
     pub min_fan_out: Option<usize>,
+    pub max_fan_out: Option<usize>,
+    pub min_loc: Option<usize>,
+    pub max_loc: Option<usize>,
+    pub weight_by_calls: bool,
+    pub boost_fan_in: bool,
     pub symbol_id: Option<String>,
     pub fqn: Option<String>,
     pub exact_fqn: Option<String>,
+    pub normalize_fqn: bool,
     pub ast_kind: Option<String>,
     pub with_ast_context: bool,
+    pub with_ast_kind: bool,
+    pub ts_query: bool,
     pub min_depth: Option<usize>,
     pub max_depth: Option<usize>,
     pub inside: Option<String>,
     pub contains: Option<String>,
     pub from_symbol_set: Option<String>,
+    pub symbol_ids_file: Option<String>,
     pub reachable_from: Option<String>,
     pub dead_code_in: Option<String>,
+    pub with_callers: bool,
     pub in_cycle: Option<String>,
     pub slice_backward_from: Option<String>,
     pub slice_forward_from: Option<String>,
@@ -58,6 +94,10 @@ pub struct SearchParams {
     pub fact_status_filter: Option<String>,
     pub subject_type: Option<String>,
     pub tokens: Option<usize>,
+    pub skip_corrupt: bool,
+    pub strict_complete: bool,
+    pub visibility: Option<Visibility>,
+    pub prefer: Option<Prefer>,
 }
 
 fn ranged_usize(min: i64, max: i64) -> impl TypedValueParser<Value = usize> {
@@ -76,21 +116,147 @@ pub struct Cli {
     pub output: OutputFormat,
 
     #[arg(long, global = true)]
-    pub db: Option<PathBuf>,
+    pub db: Vec<PathBuf>,
 
     #[arg(long, global = true)]
     pub show_metrics: bool,
 
+    /// Match names case-insensitively (regex queries, name filters and scoring)
+    #[arg(short = 'i', long, global = true)]
+    pub ignore_case: bool,
+
     #[arg(long, global = true, default_value = "false")]
     pub detect_backend: bool,
 
     #[arg(long, global = true, default_value = "false")]
     pub record: bool,
 
+    /// Reuse symbol search responses cached on disk until the database changes
+    /// (also enabled by LLMGREP_CACHE=1; disabled when HOME is unset)
+    #[arg(long, global = true)]
+    pub cache: bool,
+
+    /// Interrupt a query's database work after this many seconds; a search
+    /// scan cut short returns the matches read so far as partial results.
+    /// Each --queries-file line and watch re-evaluation gets its own budget
+    #[arg(long, global = true, value_name = "SECONDS", value_parser = clap::value_parser!(u64).range(1..))]
+    pub timeout: Option<u64>,
+
+    /// Retry opening a database this many times while another process holds
+    /// its write lock, backing off 50ms, 100ms, 200ms, ...
+    #[arg(long, global = true, value_name = "N", default_value_t = llmgrep::backend::DEFAULT_BUSY_RETRIES)]
+    pub busy_retries: u32,
+
+    /// Shrink JSON results: each span becomes a `loc` string and span/match
+    /// IDs are dropped unless search `--fields` names them (not covered by
+    /// the `schema` command's JSON Schema)
+    #[arg(long, global = true, visible_alias = "json-compact-spans")]
+    pub compact: bool,
+
+    /// Emit errors as a JSON envelope on stdout even with a text --output
+    /// format (JSON formats always do)
+    #[arg(long, global = true, conflicts_with = "errors_minimal")]
+    pub errors_json: bool,
+
+    #[arg(long, global = true)]
+    pub errors_minimal: bool,
+
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        default_value = "auto",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "always"
+    )]
+    pub hyperlinks: HyperlinkMode,
+
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        default_value = "auto",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "always"
+    )]
+    pub sanitize: SanitizeMode,
+
+    /// Colorize human output: cyan paths, green line:col, bold names, dim
+    /// scores. `auto` colors only a TTY and honours `NO_COLOR`
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        default_value = "auto",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "always"
+    )]
+    pub color: ColorMode,
+
     #[command(subcommand)]
     pub command: Option<Command>,
 }
 
+impl Cli {
+    /// Whether `--compact` keeps `span_id` and `match_id`, because search
+    /// `--fields` names them.
+    pub fn compact_keeps_ids(&self) -> (bool, bool) {
+        match &self.command {
+            Some(Command::Search {
+                fields: Some(fields),
+                ..
+            }) => parse_fields(fields).map_or((false, false), |f| (f.span_id, f.match_id)),
+            _ => (false, false),
+        }
+    }
+
+    /// Whether human output should wrap locations in OSC 8 hyperlinks.
+    pub fn hyperlinks_enabled(&self) -> bool {
+        match self.hyperlinks {
+            HyperlinkMode::Always => true,
+            HyperlinkMode::Never => false,
+            HyperlinkMode::Auto => {
+                use std::io::IsTerminal;
+                std::io::stdout().is_terminal()
+            }
+        }
+    }
+
+    /// Whether symbol search responses are read from and written to the
+    /// on-disk query cache.
+    pub fn cache_enabled(&self) -> bool {
+        self.cache || llmgrep::query::QueryCache::enabled_by_env()
+    }
+
+    /// Whether human output should be colorized with ANSI escapes.
+    pub fn color_enabled(&self) -> bool {
+        match self.color {
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+            ColorMode::Auto => {
+                use std::io::IsTerminal;
+                std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+                    && std::io::stdout().is_terminal()
+            }
+        }
+    }
+
+    /// Whether text output should escape control characters in results.
+    pub fn sanitize_enabled(&self) -> bool {
+        match self.sanitize {
+            SanitizeMode::Always => true,
+            SanitizeMode::Never => false,
+            SanitizeMode::Auto => {
+                use std::io::IsTerminal;
+                std::io::stdout().is_terminal()
+            }
+        }
+    }
+}
+
 #[derive(Subcommand)]
 #[allow(clippy::large_enum_variant)]
 pub enum Command {
@@ -99,15 +265,37 @@ pub enum Command {
         #[arg(long, default_value = ".*")]
         query: String,
 
+        #[arg(long, conflicts_with_all = ["query", "regex"])]
+        all: bool,
+
+        #[arg(
+            long,
+            visible_alias = "queries-from",
+            conflicts_with_all = ["query", "all", "paths_from_stdin"]
+        )]
+        queries_file: Option<PathBuf>,
+
         #[arg(long, value_enum, default_value = "symbols")]
         mode: SearchMode,
 
         #[arg(long)]
         path: Option<PathBuf>,
 
+        #[arg(long = "exclude-path")]
+        exclude_paths: Vec<PathBuf>,
+
+        #[arg(long, requires = "path")]
+        path_glob: bool,
+
+        #[arg(long)]
+        paths_from_stdin: bool,
+
         #[arg(long)]
         kind: Option<String>,
 
+        #[arg(long)]
+        reference_kind: Option<String>,
+
         #[arg(long)]
         language: Option<String>,
 
@@ -117,6 +305,13 @@ pub enum Command {
         #[arg(long, default_value_t = 50, value_parser = ranged_usize(1, 1000))]
         limit: usize,
 
+        #[arg(long, visible_alias = "max-results-per-file", value_parser = ranged_usize(1, 1000))]
+        max_per_file: Option<usize>,
+
+        /// Keep only the best-ranked result per distinct snippet content_hash
+        #[arg(long)]
+        dedupe: bool,
+
         #[arg(long)]
         regex: bool,
 
@@ -126,9 +321,48 @@ pub enum Command {
         #[arg(long)]
         with_context: bool,
 
+        #[arg(long, requires = "with_context")]
+        context_only: bool,
+
+        #[arg(long, requires = "with_context")]
+        context_exclude_self: bool,
+
+        #[arg(long, conflicts_with = "context_only")]
+        distinct_names: bool,
+
+        #[arg(long, conflicts_with_all = ["context_only", "distinct_names"])]
+        files_with_matches: bool,
+
+        #[arg(
+            long,
+            conflicts_with_all = ["context_only", "distinct_names", "files_with_matches"]
+        )]
+        count: bool,
+
+        #[arg(long)]
+        flatten: bool,
+
+        #[arg(long, conflicts_with_all = ["context_only", "distinct_names"])]
+        score_histogram: bool,
+
+        #[arg(
+            long,
+            value_enum,
+            conflicts_with_all = ["context_only", "distinct_names", "score_histogram"]
+        )]
+        partition_by: Option<PartitionBy>,
+
         #[arg(long, default_value_t = 3, value_parser = ranged_usize(1, 100))]
         context_lines: usize,
 
+        /// Lines of context before each match (default: --context-lines)
+        #[arg(long, value_parser = ranged_usize(0, 100))]
+        context_before: Option<usize>,
+
+        /// Lines of context after each match (default: --context-lines)
+        #[arg(long, value_parser = ranged_usize(0, 100))]
+        context_after: Option<usize>,
+
         #[arg(long, default_value_t = 20, value_parser = ranged_usize(1, 500))]
         max_context_lines: usize,
 
@@ -141,6 +375,30 @@ pub enum Command {
         #[arg(long, default_value_t = 200, value_parser = ranged_usize(1, 1_048_576))]
         max_snippet_bytes: usize,
 
+        #[arg(long, value_parser = ranged_usize(1, 10_000))]
+        snippet_lines: Option<usize>,
+
+        #[arg(long)]
+        snippet_line_numbers: bool,
+
+        #[arg(long)]
+        with_line_text: bool,
+
+        #[arg(long)]
+        with_provenance: bool,
+
+        #[arg(long)]
+        resolve_callees: bool,
+
+        #[arg(long)]
+        resolve_defs: bool,
+
+        #[arg(long, value_name = "MS", value_parser = clap::value_parser!(u64).range(1..))]
+        file_io_timeout: Option<u64>,
+
+        #[arg(long)]
+        with_complexity_percentile: bool,
+
         #[arg(long)]
         fields: Option<String>,
 
@@ -159,9 +417,27 @@ pub enum Command {
         #[arg(long, value_parser = ranged_usize(0, 10000))]
         min_fan_in: Option<usize>,
 
+        #[arg(long, value_parser = ranged_usize(0, 10000))]
+        max_fan_in: Option<usize>,
+
         #[arg(long, value_parser = ranged_usize(0, 10000))]
         min_fan_out: Option<usize>,
 
+        #[arg(long, value_parser = ranged_usize(0, 10000))]
+        max_fan_out: Option<usize>,
+
+        #[arg(long, value_parser = ranged_usize(0, 100000))]
+        min_loc: Option<usize>,
+
+        #[arg(long, value_parser = ranged_usize(0, 100000))]
+        max_loc: Option<usize>,
+
+        #[arg(long)]
+        weight_by_calls: bool,
+
+        #[arg(long)]
+        boost_fan_in: bool,
+
         #[arg(long)]
         symbol_id: Option<String>,
 
@@ -171,12 +447,21 @@ pub enum Command {
         #[arg(long)]
         exact_fqn: Option<String>,
 
+        #[arg(long)]
+        normalize_fqn: bool,
+
         #[arg(long, value_name = "KIND")]
         ast_kind: Option<String>,
 
         #[arg(long)]
         with_ast_context: bool,
 
+        #[arg(long)]
+        with_ast_kind: bool,
+
+        #[arg(long)]
+        ts_query: bool,
+
         #[arg(long, value_parser = ranged_usize(0, 100))]
         min_depth: Option<usize>,
 
@@ -192,12 +477,18 @@ pub enum Command {
         #[arg(long, value_name = "FILE")]
         from_symbol_set: Option<String>,
 
+        #[arg(long, value_name = "FILE", conflicts_with = "from_symbol_set")]
+        symbol_ids_file: Option<String>,
+
         #[arg(long, value_name = "SYMBOL")]
         reachable_from: Option<String>,
 
         #[arg(long, value_name = "SYMBOL")]
         dead_code_in: Option<String>,
 
+        #[arg(long, requires = "dead_code_in")]
+        with_callers: bool,
+
         #[arg(long, value_name = "SYMBOL")]
         in_cycle: Option<String>,
 
@@ -251,24 +542,49 @@ pub enum Command {
 
         #[arg(long)]
         tokens: Option<usize>,
+
+        #[arg(long)]
+        skip_corrupt: bool,
+
+        #[arg(long)]
+        strict_complete: bool,
+
+        #[arg(long, value_enum)]
+        visibility: Option<Visibility>,
+
+        #[arg(long, value_enum)]
+        prefer: Option<Prefer>,
     },
 
     #[command(after_help = AST_EXAMPLES)]
     Ast {
-        #[arg(long)]
-        file: PathBuf,
+        #[arg(long, required_unless_present = "fqn")]
+        file: Option<PathBuf>,
 
         #[arg(long)]
         position: Option<usize>,
 
+        /// Symbol FQN whose span selects the AST subtree (instead of --file)
+        #[arg(long)]
+        fqn: Option<String>,
+
         #[arg(long, default_value_t = 10000, value_parser = ranged_usize(1, 100000))]
         limit: usize,
+
+        #[arg(long)]
+        semantic_only: bool,
+
+        #[arg(long, value_parser = ranged_usize(1, 1000))]
+        max_tree_depth: Option<usize>,
     },
 
     #[command(after_help = FIND_AST_EXAMPLES)]
     FindAst {
         #[arg(long)]
         kind: String,
+
+        #[arg(long)]
+        with_path: bool,
     },
 
     #[command(after_help = COMPLETE_EXAMPLES)]
@@ -278,6 +594,9 @@ pub enum Command {
 
         #[arg(long, default_value_t = 50, value_parser = ranged_usize(1, 1000))]
         limit: usize,
+
+        #[arg(long)]
+        segment: bool,
     },
 
     #[command(after_help = LOOKUP_EXAMPLES)]
@@ -286,6 +605,30 @@ pub enum Command {
         fqn: String,
     },
 
+    #[command(after_help = CARD_EXAMPLES)]
+    Card {
+        #[arg(long)]
+        fqn: String,
+    },
+
+    #[command(after_help = EDIT_SCRIPT_EXAMPLES)]
+    EditScript {
+        #[arg(long)]
+        fqn: String,
+
+        #[arg(long)]
+        rename: String,
+    },
+
+    #[command(after_help = ANNOTATE_EXAMPLES)]
+    Annotate {
+        #[arg(long)]
+        file: PathBuf,
+
+        #[arg(long)]
+        heat: bool,
+    },
+
     Explore {
         #[arg(long)]
         intent: String,
@@ -310,12 +653,29 @@ pub enum Command {
         #[arg(long)]
         callees: bool,
 
+        #[arg(long)]
+        caller_tree: bool,
+
         #[arg(long, default_value_t = 1)]
         depth: usize,
     },
 
     Stats,
 
+    Capabilities,
+
+    #[command(after_help = PROBE_EXAMPLES)]
+    Probe,
+
+    #[command(after_help = SCHEMA_EXAMPLES)]
+    Schema,
+
+    #[command(after_help = CACHE_EXAMPLES)]
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+
     Evolve {
         #[arg(long, default_value = ".*")]
         query: String,
@@ -330,6 +690,14 @@ pub enum Command {
         limit: usize,
     },
 
+    Diff {
+        #[arg(long)]
+        baseline: PathBuf,
+
+        #[arg(long, value_enum)]
+        metric: Option<llmgrep::query::DiffMetric>,
+    },
+
     #[cfg(feature = "unstable-watch")]
     #[command(after_help = WATCH_EXAMPLES)]
     Watch {
@@ -350,6 +718,9 @@ pub enum Command {
 
         #[arg(long)]
         regex: bool,
+
+        #[arg(long, value_enum, default_value = "snapshot")]
+        emit: llmgrep::watch_cmd::WatchEmit,
     },
 
     VectorCreate {
@@ -377,6 +748,12 @@ pub enum Command {
     },
 }
 
+#[derive(Subcommand)]
+pub enum CacheAction {
+    /// Remove every cached search response
+    Clear,
+}
+
 #[derive(Clone, Copy, Debug, ValueEnum)]
 pub enum SearchMode {
     Symbols,
@@ -390,6 +767,37 @@ pub enum SearchMode {
     Semantic,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum PartitionBy {
+    Language,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum Visibility {
+    Public,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum HyperlinkMode {
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum ColorMode {
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum SanitizeMode {
+    Auto,
+    Always,
+    Never,
+}
+
 #[derive(Clone, Copy, Debug, ValueEnum)]
 pub enum AutoLimitMode {
     PerMode,
@@ -404,15 +812,30 @@ EXAMPLES:
   # Regex search for pattern matching
   llmgrep --db code.db search --query "^main" --regex
 
+  # Regex capture groups reported per result as `captures`
+  llmgrep --db code.db search --regex --query "get_([a-z_]+)" --output json
+
+  # Case-insensitive regex, filters and scoring
+  llmgrep --db code.db -i search --regex --query "^http"
+
   # JSON output for programmatic use
   llmgrep --db code.db search --query "Parser" --output json
 
   # Search with path filter
   llmgrep --db code.db search --query "Error" --path src/
 
+  # Skip tests and vendored code
+  llmgrep --db code.db search --query "Error" --exclude-path tests --exclude-path vendor
+
+  # Match paths with a glob instead of a prefix
+  llmgrep --db code.db search --query "setup" --path '**/tests/**' --path-glob
+
   # Reference search
   llmgrep --db code.db search --query "Token" --mode references
 
+  # Only the writes to a global
+  llmgrep --db code.db search --query "CONFIG" --mode references --reference-kind write
+
   # Calls search
   llmgrep --db code.db search --query "parse" --mode calls
 
@@ -426,22 +849,190 @@ EXAMPLES:
   # Auto mode (all search modes combined, requires JSON output)
   llmgrep --db code.db search --query "parse" --mode auto --output json
 
+  # Auto mode as one array of items tagged symbol/reference/call
+  llmgrep --db code.db search --query "parse" --mode auto --flatten --output json
+
+  # Fit all modes into a fixed LLM context; see "omitted" for what was dropped
+  llmgrep --db code.db search --query "parse" --mode auto --tokens 2000 --output json
+
+  # Snippets prefixed with source line numbers, matched line marked with '>'
+  llmgrep --db code.db search --query "parse" --with-snippet --snippet-line-numbers --output pretty
+
+  # Snippets capped at the first 5 whole lines of each symbol
+  llmgrep --db code.db search --query "parse" --with-snippet --snippet-lines 5 --output json
+
+  # Give up on a query that runs longer than 10 seconds
+  llmgrep --db code.db search --all --candidates 10000 --timeout 10
+
+  # Wait out a concurrent re-index with up to 5 retries
+  llmgrep --db code.db --busy-retries 5 search --query "parse"
+
+  # One-line preview of each match without full snippets
+  llmgrep --db code.db search --query "parse" --with-line-text --output json
+
+  # Which File entity defines each match (id, path, language, size)
+  llmgrep --db code.db search --query "parse" --with-provenance --output json
+
+  # Attach each callee's definition span to call results
+  llmgrep --db code.db search --mode calls --query "parse" --resolve-callees --output json
+
+  # Find usages and jump to definition: each reference carries its target's span
+  llmgrep --db code.db search --mode references --query "parse" --resolve-defs --output json
+
+  # Call-frequency hotspots: total call sites, not distinct callers
+  llmgrep --db code.db search --all --sort-by fan-in --weight-by-calls --output json
+
+  # Rank widely used symbols first when name scores tie
+  llmgrep --db code.db search --query "parse" --boost-fan-in --output json
+
+  # Collapse vendored/generated copies with identical bodies
+  llmgrep --db code.db search --query "parse" --with-snippet --dedupe --output json
+
+  # Token-lean JSON: spans collapsed to "loc": "file:line:col"
+  llmgrep --db code.db --compact search --query "parse" --output json
+
+  # Only symbols in files changed on this branch
+  git diff --name-only main | llmgrep --db code.db search --all --paths-from-stdin --output json
+
+  # A spread of matches across the codebase: at most 3 per file
+  llmgrep --db code.db search --query "parse" --max-per-file 3
+
+  # How complex is each match relative to the rest? (complexity_percentile)
+  llmgrep --db code.db search --all --kind function --limit 5000 --with-complexity-percentile --output json
+
+  # Don't let one stalled file on a network mount hang the search
+  llmgrep --db code.db search --query "parse" --with-snippet --file-io-timeout 500
+
+  # Hover-card payload: only {name, file, context} per result
+  llmgrep --db code.db search --query "parse" --with-context --context-only --output json
+
+  # Surrounding code only: before/after lines, the match itself left out
+  llmgrep --db code.db search --query "parse" --with-context --context-exclude-self --output json
+
+  # Signature plus what follows: 1 line before, 10 after
+  llmgrep --db code.db search --query "parse" --with-context --context-before 1 --context-after 10 --output json
+
+  # Search several indexes at once, skipping any that are corrupt
+  llmgrep --db a.db --db b.db search --query "parse" --skip-corrupt --output json
+
+  # Fail (exit 3) instead of silently using a candidate-truncated result set
+  llmgrep --db code.db search --all --candidates 5000 --strict-complete --output json
+
+  # Public API surface only (no _private names, no test/private modules)
+  llmgrep --db code.db search --all --kind function --visibility public --output json
+
+  # One definition per name, skipping stale copies in deeper directories
+  llmgrep --db code.db search --query "Config" --prefer shortest-path --output json
+
+  # Sorted, deduplicated symbol names (wordlist / autocomplete dictionary)
+  llmgrep --db code.db search --all --kind function --limit 1000 --distinct-names
+
+  # Files referencing a symbol, like grep -l (--limit caps files)
+  llmgrep --db code.db search --mode references --query "parse_config" --files-with-matches
+
+  # Count matching symbols without fetching rows
+  llmgrep --db code.db search --query "parse" --kind Function --count
+
+  # Most common symbol names across the index
+  llmgrep --db code.db --output frequency search --all --limit 10000
+
+  # Everything at once: location, kind, score, cc, fan-in/out, short id
+  llmgrep --db code.db --output wide search --query "parse" --sort-by fan-in
+
+  # What does src/query/ depend on? External symbols grouped by module
+  llmgrep --db code.db --output resolved-imports search --mode references --all --path src/query/
+
+  # Dead-code baseline for CI: diff against a committed deadcode.csv
+  llmgrep --db code.db --output deadcode-csv search --all --limit 100000 --dead-code-in main
+
+  # Dead code with its callers, to confirm removal is safe
+  llmgrep --db code.db search --all --limit 100000 --dead-code-in main --with-callers --output json
+
+  # One JSON document for a whole query list, entries keyed by input index
+  llmgrep --db code.db --output batch-results-json search --queries-file queries.txt
+
+  # Look up many names over one database connection, one JSON line per query
+  cut -d, -f1 names.csv | llmgrep --db code.db --output json search --queries-from -
+
+  # Dependency cycles grouped per SCC, with the members entered from outside
+  llmgrep --db code.db --output grouped-by-supernode-json search --condense --all
+
+  # Locations first, then snippets/metrics keyed by match_id (two NDJSON passes)
+  llmgrep --db code.db --output progressive-jpeg-style search --query "parse" --with-snippet
+
+  # Declare which optional fields this database can compute (available_fields)
+  llmgrep --db code.db --output min-viable search --query "parse"
+
+  # One JSON object per match line as rows are read, then a summary line
+  llmgrep --db code.db --output ndjson search --query "parse" --sort-by position --limit 5000
+
+  # Sorted, deduplicated canonical FQNs (allowlists, --exact-fqn inputs)
+  llmgrep --db code.db search --query "parse" --limit 1000 --output fqn-list
+
+  # Benchmark a fixed query set: one CSV timing row per line of queries.txt
+  llmgrep --db code.db --output timings-csv search --queries-file queries.txt
+
+  # Context bundle: matched code only, one header comment per snippet
+  llmgrep --db code.db --output snippets search --query "parse" --with-snippet
+
+  # Relevance distribution: a few strong matches or a pile of weak ones?
+  llmgrep --db code.db search --query "parse" --limit 500 --score-histogram
+
+  # Group results by inferred language: {"python": [...], "rust": [...]}
+  llmgrep --db code.db search --query "parse" --partition-by language --output json
+
+  # Neovim quickfix list: :call setqflist(json_decode(system('...')))
+  llmgrep --db code.db search --query "parse" --output quickfix
+
+  # Vim :grep / errorformat lines: file:line:col:text
+  llmgrep --db code.db --output vimgrep search --query "parse" --with-snippet
+
   # Combined filters with regex
   llmgrep --db code.db search --query "^[A-Z]" --regex --kind Function --output pretty
 
+  # Clickable file:line:col links (OSC 8); auto-enabled on a TTY
+  llmgrep --db code.db --hyperlinks search --query "parse"
+
+  # Keep colors when piping human output to a pager
+  llmgrep --db code.db --color=always search --query "parse" | less -R
+
+  # Keep escape sequences from source files inert when piping snippets to a pager
+  llmgrep --db code.db --sanitize search --query "banner" --output snippets | less -R
+
 V1.1 FEATURES:
   # SymbolId lookup (unambiguous reference)
   llmgrep --db code.db search --symbol-id abc123def456789abc123def456789ab
 
+  # Rarely used but not dead: one to three references
+  llmgrep --db code.db search --all --kind function --min-fan-in 1 --max-fan-in 3
+
   # Filter by complexity and language
   llmgrep --db code.db search --query "handler" --min-complexity 10 --language rust
 
   # Sort by fan-in to find hotspots
   llmgrep --db code.db search --query ".*" --sort-by fan-in --limit 20
 
+  # Largest functions first, for refactoring triage
+  llmgrep --db code.db search --all --kind function --sort-by loc --min-loc 100 --limit 20
+
+  # Review order: file by file, top to bottom
+  llmgrep --db code.db search --query "handler" --sort-by review
+
+  # Position-bounded window, reordered by relevance
+  llmgrep --db code.db search --query "handler" --sort-by hybrid --limit 20
+
+  # Alphabetical symbol inventory (stable order for diffing)
+  llmgrep --db code.db search --all --kind function --sort-by name --output json
+
+  # Match every symbol without a name predicate (faster than ".*")
+  llmgrep --db code.db search --all --kind function --min-complexity 10
+
   # FQN pattern matching
   llmgrep --db code.db search --query "test" --fqn "%module::tests::%"
 
+  # Cross-language FQN syntax: matches Python's pkg.parser.Parser.parse
+  llmgrep --db code.db search --all --exact-fqn "pkg::parser::Parser::parse" --normalize-fqn
+
 V2.0 AST FEATURES:
   # Filter by AST node kind using shorthands
   llmgrep --db code.db search --query ".*" --ast-kind loops
@@ -455,6 +1046,9 @@ V2.0 AST FEATURES:
   # Search with enriched AST context (depth, parent_kind, children, decision_points)
   llmgrep --db code.db search --query "process" --with-ast-context --output json
 
+  # Report only the AST node kind at each symbol span (no enrichment)
+  llmgrep --db code.db search --query "process" --with-ast-kind --output json
+
   # Find deeply nested code (complexity hotspots)
   llmgrep --db code.db search --query ".*" --min-depth 5 --output json
 
@@ -470,6 +1064,9 @@ V2.0 AST FEATURES:
   # Find code at specific depth
   llmgrep --db code.db search --query ".*" --min-depth 2 --max-depth 3
 
+  # Print the equivalent tree-sitter query for the AST filters (no database needed)
+  llmgrep search --inside function_item --ast-kind for_expression --ts-query
+
   AST Shorthands (expand to multiple node kinds):
     loops              - Loop constructs (for_expression, while_expression, loop_expression)
     conditionals       - Conditionals (if_expression, match_expression, match_arm)
@@ -507,6 +1104,22 @@ V2.1 PATHS FEATURES:
 
   # Path filtering with other filters
   llmgrep --db code.db search --paths-from main --kind Function --output json
+
+  # Render a backward slice as a Graphviz graph (seed node highlighted)
+  llmgrep --db code.db search --all --slice-backward-from parse --output slice-dot | dot -Tsvg
+
+  # Folded call stacks from an entry point, for flamegraph.pl / inferno
+  llmgrep --db code.db search --all --mode calls --paths-from main --output flamegraph-callgraph | inferno-flamegraph > calls.svg
+
+  # Caller x callee call counts for a dependency heatmap
+  llmgrep --db code.db search --all --mode calls --output matrix
+
+  # Chain stages: collect IDs of parser functions, then rank only those by fan-in
+  llmgrep --db code.db --output entity-ids-only search --query "parse" --kind function > ids.txt
+  llmgrep --db code.db search --all --symbol-ids-file ids.txt --sort-by fan-in
+
+  # Stream symbols, references and calls as NDJSON tagged with "_type"
+  llmgrep --db code.db --output json-lines-typed search --query "parse" --mode auto
 "#;
 
 const AST_EXAMPLES: &str = r#"
@@ -520,8 +1133,17 @@ EXAMPLES:
   # Limit output for large files
   llmgrep --db code.db ast --file src/lib.rs --limit 100
 
+  # AST subtree of one symbol, located by FQN
+  llmgrep --db code.db ast --fqn "crate::parser::Parser::parse"
+
   # Pretty-print AST structure
   llmgrep --db code.db ast --file src/main.rs --output pretty
+
+  # Drop punctuation/tokens, keep declarations, expressions, control flow
+  llmgrep --db code.db ast --file src/main.rs --semantic-only
+
+  # Structural overview: top 3 levels, deeper subtrees summarized
+  llmgrep --db code.db ast --file src/lib.rs --max-tree-depth 3 --output pretty
 "#;
 
 const FIND_AST_EXAMPLES: &str = r#"
@@ -535,6 +1157,9 @@ EXAMPLES:
   # Find all loops as pretty JSON
   llmgrep --db code.db find-ast --kind for_expression --output pretty
 
+  # Include each match's root-to-node kind path (source_file > ... > if_expression)
+  llmgrep --db code.db find-ast --kind if_expression --with-path
+
   # Common node kinds:
     function_item, struct_item, enum_item, impl_item
     if_expression, while_expression, for_expression, match_expression
@@ -554,6 +1179,9 @@ EXAMPLES:
 
   # Use for interactive autocomplete
   llmgrep --db code.db complete --prefix "crate::backend::" --limit 10
+
+  # Only the next path segment, for shell or editor completion
+  llmgrep --db code.db complete --prefix "crate::backend::" --segment
 "#;
 
 const LOOKUP_EXAMPLES: &str = r#"
@@ -571,6 +1199,61 @@ EXAMPLES:
   llmgrep --db code.db lookup --fqn "parse" --output pretty
 "#;
 
+const CARD_EXAMPLES: &str = r#"
+EXAMPLES:
+  # Info panel for a symbol: location, FQNs, metrics, caller/callee counts
+  llmgrep --db code.db card --fqn "crate::query::run_diff"
+
+  # Structured card for an editor hover
+  llmgrep --db code.db card --fqn "parse" --output json
+"#;
+
+const EDIT_SCRIPT_EXAMPLES: &str = r#"
+EXAMPLES:
+  # Byte-range edits renaming a symbol at its definition and every reference
+  llmgrep --db code.db edit-script --fqn "crate::query::run_diff" --rename run_metric_diff
+
+  # Edits as JSON for a codemod tool: [{file, byte_start, byte_end, replacement}]
+  llmgrep --db code.db edit-script --fqn "parse" --rename parse_source --output json
+"#;
+
+const PROBE_EXAMPLES: &str = r#"
+EXAMPLES:
+  # Liveness check: exit 0 and {"status":"ok","backend":"sqlite","symbols":N}
+  llmgrep --db code.db probe
+
+  # Kubernetes exec probe
+  llmgrep --db /data/code.db probe > /dev/null
+"#;
+
+const SCHEMA_EXAMPLES: &str = r#"
+EXAMPLES:
+  # JSON Schema of the --output json search response envelope
+  llmgrep schema > llmgrep-response.schema.json
+
+  # Compact, for piping into a validator
+  llmgrep --output json schema
+"#;
+
+const CACHE_EXAMPLES: &str = r#"
+EXAMPLES:
+  # Cache symbol searches; repeats are served from disk until the db changes
+  llmgrep --db code.db --cache search --query "parse"
+  LLMGREP_CACHE=1 llmgrep --db code.db search --query "parse"
+
+  # Remove every cached response
+  llmgrep cache clear
+"#;
+
+const ANNOTATE_EXAMPLES: &str = r#"
+EXAMPLES:
+  # Source with a [cc:N] complexity gutter per symbol (colored on a TTY)
+  llmgrep --db code.db annotate --file src/main.rs --heat
+
+  # Heat spans only, for an editor overlay
+  llmgrep --db code.db annotate --file src/main.rs --heat --output json
+"#;
+
 #[cfg(feature = "unstable-watch")]
 const WATCH_EXAMPLES: &str = r#"
 EXAMPLES:
@@ -585,6 +1268,12 @@ EXAMPLES:
 
   # Watch with regex pattern
   llmgrep --db code.db watch --query "^test_" --regex
+
+  # Stream NDJSON added/removed events instead of formatted deltas
+  llmgrep --db code.db watch --query "parse" --emit events
+
+  # Status-bar widget: only the match count and its delta
+  llmgrep --db code.db watch --query "parse" --emit count --output json
 "#;
 
 pub fn validate_path(path: &Path, is_database: bool) -> Result<PathBuf, LlmError> {
@@ -609,6 +1298,68 @@ pub fn validate_path(path: &Path, is_database: bool) -> Result<PathBuf, LlmError
         }
     }
 
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    check_sensitive_path(path, &canonical, home.as_deref(), &allowed_path_roots())?;
+
+    Ok(canonical)
+}
+
+/// Validate a `--path-glob` pattern and anchor it to the absolute paths
+/// stored in the database.
+///
+/// The literal part before the first wildcard goes through the same
+/// sensitive-directory check as [`validate_path`]; see [`anchor_path_glob`]
+/// for how relative patterns are resolved.
+pub fn resolve_path_glob(pattern: &Path) -> Result<PathBuf, LlmError> {
+    let anchored = anchor_path_glob(pattern, &std::env::current_dir()?.canonicalize()?);
+    let text = anchored.to_string_lossy();
+    let literal = Path::new(&text[..text.find(['*', '?']).unwrap_or(text.len())]);
+    if !literal.as_os_str().is_empty() {
+        let canonical = literal
+            .canonicalize()
+            .unwrap_or_else(|_| literal.to_path_buf());
+        let home = std::env::var_os("HOME").map(PathBuf::from);
+        check_sensitive_path(pattern, &canonical, home.as_deref(), &allowed_path_roots())?;
+    }
+    Ok(anchored)
+}
+
+/// Join a relative glob onto `cwd`; absolute globs and globs starting with
+/// `*` (which already match any leading directories) are left as they are.
+pub(crate) fn anchor_path_glob(pattern: &Path, cwd: &Path) -> PathBuf {
+    if pattern.is_absolute() || pattern.to_string_lossy().starts_with('*') {
+        pattern.to_path_buf()
+    } else {
+        cwd.join(pattern)
+    }
+}
+
+/// Roots listed in `LLMGREP_ALLOW_PATHS` (colon-separated), canonicalized.
+///
+/// Entries that cannot be resolved are ignored.
+fn allowed_path_roots() -> Vec<PathBuf> {
+    std::env::var_os("LLMGREP_ALLOW_PATHS")
+        .map(|value| {
+            std::env::split_paths(&value)
+                .filter(|root| !root.as_os_str().is_empty())
+                .filter_map(|root| root.canonicalize().ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Reject `canonical` if it lies in a sensitive system or home directory,
+/// unless it is under one of the `allowed` roots.
+pub(crate) fn check_sensitive_path(
+    path: &Path,
+    canonical: &Path,
+    home: Option<&Path>,
+    allowed: &[PathBuf],
+) -> Result<(), LlmError> {
+    if allowed.iter().any(|root| canonical.starts_with(root)) {
+        return Ok(());
+    }
+
     let sensitive_dirs = [
         "/etc", "/root", "/boot", "/sys", "/proc", "/dev", "/run", "/var/run", "/var/tmp",
     ];
@@ -621,8 +1372,7 @@ pub fn validate_path(path: &Path, is_database: bool) -> Result<PathBuf, LlmError
         }
     }
 
-    if let Some(home) = std::env::var_os("HOME") {
-        let home_path = PathBuf::from(&home);
+    if let Some(home_path) = home {
         let ssh_dir = home_path.join(".ssh");
         let config_dir = home_path.join(".config");
         if canonical.starts_with(&ssh_dir) || canonical.starts_with(&config_dir) {
@@ -633,15 +1383,22 @@ pub fn validate_path(path: &Path, is_database: bool) -> Result<PathBuf, LlmError
         }
     }
 
-    Ok(canonical)
+    Ok(())
 }
 
 const DEFAULT_DB_FILENAME: &str = "llmgrep.db";
 const MAGELLAN_DIR: &str = ".magellan";
 
 pub fn resolve_db_path(cli: &Cli) -> Result<PathBuf, LlmError> {
-    if let Some(db_path) = &cli.db {
-        return validate_path(db_path, true);
+    match cli.db.as_slice() {
+        [] => {}
+        [db_path] => return validate_path(db_path, true),
+        _ => {
+            return Err(LlmError::InvalidQuery {
+                query: "multiple --db values are only supported by search --mode symbols"
+                    .to_string(),
+            })
+        }
     }
 
     let cwd = std::env::current_dir().map_err(|e| LlmError::PathValidationFailed {
@@ -676,6 +1433,14 @@ pub fn resolve_db_path(cli: &Cli) -> Result<PathBuf, LlmError> {
     })
 }
 
+/// Resolve every `--db` value, or the single auto-detected database.
+pub fn resolve_db_paths(cli: &Cli) -> Result<Vec<PathBuf>, LlmError> {
+    if cli.db.len() > 1 {
+        return cli.db.iter().map(|db| validate_path(db, true)).collect();
+    }
+    Ok(vec![resolve_db_path(cli)?])
+}
+
 pub fn find_git_root_db(cwd: &Path) -> Option<PathBuf> {
     let mut dir = cwd;
     loop {
@@ -694,6 +1459,8 @@ pub struct FieldFlags {
     pub fqn: bool,
     pub canonical_fqn: bool,
     pub display_fqn: bool,
+    pub span_id: bool,
+    pub match_id: bool,
 }
 
 pub fn parse_fields(value: &str) -> Result<FieldFlags, LlmError> {
@@ -713,6 +1480,8 @@ pub fn parse_fields(value: &str) -> Result<FieldFlags, LlmError> {
                 flags.fqn = true;
                 flags.canonical_fqn = true;
                 flags.display_fqn = true;
+                flags.span_id = true;
+                flags.match_id = true;
             }
             "context" => flags.context = true,
             "snippet" => flags.snippet = true,
@@ -720,6 +1489,8 @@ pub fn parse_fields(value: &str) -> Result<FieldFlags, LlmError> {
             "fqn" => flags.fqn = true,
             "canonical_fqn" => flags.canonical_fqn = true,
             "display_fqn" => flags.display_fqn = true,
+            "span_id" => flags.span_id = true,
+            "match_id" => flags.match_id = true,
             _ => {
                 return Err(LlmError::InvalidField {
                     field: field.to_string(),
@@ -827,14 +1598,31 @@ pub fn looks_like_regex(query: &str) -> bool {
 }
 
 pub fn emit_error(cli: &Cli, err: &LlmError) {
+    // Results are already on stdout; report on stderr so it stays one document.
+    if matches!(err, LlmError::PartialResults { .. }) {
+        eprintln!("ERROR [{}]: {}", err.error_code(), err);
+        if let Some(hint) = err.remediation() {
+            eprintln!("Hint: {}", hint);
+        }
+        return;
+    }
+    if cli.errors_minimal {
+        println!("{}", err.error_code());
+        eprintln!("ERROR: {}", err);
+        if let Some(hint) = err.remediation() {
+            eprintln!("Hint: {}", hint);
+        }
+        return;
+    }
+    // Text formats keep stdout parseable by their consumers (vim, CSV, DOT, ...)
     match cli.output {
-        OutputFormat::Human => {
+        format if !format.is_json() && !cli.errors_json => {
             eprintln!("ERROR [{}]: {}", err.error_code(), err);
             if let Some(hint) = err.remediation() {
                 eprintln!("Hint: {}", hint);
             }
         }
-        OutputFormat::Json | OutputFormat::Pretty => {
+        _ => {
             let error = ErrorResponse {
                 code: err.error_code().to_string(),
                 error: err.severity().to_string(),
@@ -1,4 +1,7 @@
-use crate::cli::{find_git_root_db, resolve_db_path, validate_path, Cli, Command, SearchMode};
+use crate::cli::{
+    anchor_path_glob, check_sensitive_path, find_git_root_db, resolve_db_path, resolve_path_glob,
+    validate_path, Cli, Command, SearchMode,
+};
 use clap::Parser;
 use llmgrep::error::LlmError;
 use llmgrep::output::OutputFormat;
@@ -27,10 +30,7 @@ fn test_basic_search_command() {
     let result = Cli::try_parse_from(args);
     assert!(result.is_ok(), "Should parse basic search command");
     let cli = result.unwrap();
-    assert_eq!(
-        cli.db.as_ref().unwrap().to_str().unwrap(),
-        temp_db.to_str().unwrap()
-    );
+    assert_eq!(cli.db[0].to_str().unwrap(), temp_db.to_str().unwrap());
     match cli.command {
         Some(Command::Search { query, .. }) => {
             assert_eq!(query, "test");
@@ -446,6 +446,60 @@ fn test_path_validation_var_tmp() {
     }
 }
 
+#[test]
+fn test_sensitive_path_allow_list_permits_config_subdir() {
+    let home = tempfile::tempdir().expect("Failed to create fake home");
+    let home = home.path().canonicalize().expect("canonical home");
+    let app_dir = home.join(".config/myapp/.codemcp");
+    let other_dir = home.join(".config/other");
+    let db = app_dir.join("code.db");
+
+    let blocked = check_sensitive_path(&db, &db, Some(&home), &[]);
+    assert!(
+        matches!(blocked, Err(LlmError::PathValidationFailed { .. })),
+        "~/.config should be blocked by default"
+    );
+
+    let allowed = [home.join(".config/myapp")];
+    assert!(check_sensitive_path(&db, &db, Some(&home), &allowed).is_ok());
+    assert!(
+        check_sensitive_path(&other_dir, &other_dir, Some(&home), &allowed).is_err(),
+        "siblings of an allowed root stay blocked"
+    );
+    let passwd = Path::new("/etc/passwd");
+    assert!(
+        check_sensitive_path(passwd, passwd, None, &allowed).is_err(),
+        "the system blocklist still applies outside allowed roots"
+    );
+}
+
+#[test]
+fn test_anchor_path_glob_joins_relative_patterns_onto_cwd() {
+    let cwd = Path::new("/work/project");
+    assert_eq!(
+        anchor_path_glob(Path::new("src/*.rs"), cwd),
+        PathBuf::from("/work/project/src/*.rs")
+    );
+    assert_eq!(
+        anchor_path_glob(Path::new("**/tests/**"), cwd),
+        PathBuf::from("**/tests/**")
+    );
+    assert_eq!(
+        anchor_path_glob(Path::new("/srv/*/lib"), cwd),
+        PathBuf::from("/srv/*/lib")
+    );
+}
+
+#[test]
+fn test_resolve_path_glob_rejects_sensitive_literal_prefix() {
+    let result = resolve_path_glob(Path::new("/etc/ssh/*_config"));
+    assert!(matches!(result, Err(LlmError::PathValidationFailed { .. })));
+    assert_eq!(
+        resolve_path_glob(Path::new("**/tests/**")).unwrap(),
+        PathBuf::from("**/tests/**")
+    );
+}
+
 #[test]
 fn test_path_validation_allowed_path() {
     let temp_db = create_temp_db().expect("Failed to create temp db");
@@ -477,11 +531,17 @@ fn test_ast_command_basic() {
         Some(Command::Ast {
             file,
             position,
+            fqn,
             limit,
+            semantic_only,
+            max_tree_depth,
         }) => {
-            assert_eq!(file, temp_file);
+            assert_eq!(file, Some(temp_file.clone()));
             assert_eq!(position, None);
+            assert_eq!(fqn, None);
             assert_eq!(limit, 10000);
+            assert!(!semantic_only);
+            assert_eq!(max_tree_depth, None);
         }
         _ => panic!("Expected Command::Ast"),
     }
@@ -549,6 +609,34 @@ fn test_ast_command_with_limit() {
     std::fs::remove_file(&temp_file).ok();
 }
 
+#[test]
+fn test_ast_command_with_fqn() {
+    let temp_db = create_temp_db().expect("Failed to create temp db");
+
+    let args = [
+        "llmgrep",
+        "--db",
+        temp_db.to_str().unwrap(),
+        "ast",
+        "--fqn",
+        "crate::parser::parse",
+    ];
+    let cli = Cli::try_parse_from(args).expect("Should parse ast command with --fqn");
+    match cli.command {
+        Some(Command::Ast { file, fqn, .. }) => {
+            assert_eq!(file, None);
+            assert_eq!(fqn.as_deref(), Some("crate::parser::parse"));
+        }
+        _ => panic!("Expected Command::Ast"),
+    }
+
+    let args = ["llmgrep", "--db", temp_db.to_str().unwrap(), "ast"];
+    assert!(
+        Cli::try_parse_from(args).is_err(),
+        "ast should require --file or --fqn"
+    );
+}
+
 #[test]
 fn test_ast_limit_validation_min() {
     let temp_db = create_temp_db().expect("Failed to create temp db");
@@ -616,7 +704,7 @@ fn test_find_ast_command_basic() {
 
     let cli = result.unwrap();
     match cli.command {
-        Some(Command::FindAst { kind }) => {
+        Some(Command::FindAst { kind, .. }) => {
             assert_eq!(kind, "function_item");
         }
         _ => panic!("Expected Command::FindAst"),
@@ -648,7 +736,7 @@ fn test_find_ast_command_with_various_kinds() {
 
         let cli = result.unwrap();
         match cli.command {
-            Some(Command::FindAst { kind: k }) => {
+            Some(Command::FindAst { kind: k, .. }) => {
                 assert_eq!(k, kind);
             }
             _ => panic!("Expected Command::FindAst"),
@@ -0,0 +1,44 @@
+use crate::cli::{resolve_db_path, validate_path, Cli};
+use llmgrep::backend::open_connection;
+use llmgrep::error::LlmError;
+use llmgrep::output::{json_response, OutputFormat};
+use std::io::IsTerminal;
+use std::path::Path;
+
+pub fn run_annotate(cli: &Cli, file: &Path, heat: bool) -> Result<(), LlmError> {
+    if !heat {
+        return Err(LlmError::InvalidQuery {
+            query: "annotate requires an annotation mode; use --heat".to_string(),
+        });
+    }
+
+    let db_path = resolve_db_path(cli)?;
+    let validated_file = validate_path(file, false)?;
+    let file_path = validated_file
+        .to_str()
+        .ok_or_else(|| LlmError::SearchFailed {
+            reason: format!("File path {:?} is not valid UTF-8", validated_file),
+        })?;
+
+    let conn = open_connection(&db_path)?;
+    let spans = llmgrep::query::file_heat_spans(&conn, file_path)?;
+
+    if matches!(cli.output, OutputFormat::Human) {
+        let source = std::fs::read_to_string(&validated_file)?;
+        let color = std::io::stdout().is_terminal();
+        print!("{}", llmgrep::query::annotate_heat(&source, &spans, color));
+    } else {
+        let response = json_response(serde_json::json!({
+            "file": file_path,
+            "spans": spans,
+        }));
+        let json_str = if matches!(cli.output, OutputFormat::Pretty) {
+            serde_json::to_string_pretty(&response)?
+        } else {
+            serde_json::to_string(&response)?
+        };
+        println!("{}", json_str);
+    }
+
+    Ok(())
+}
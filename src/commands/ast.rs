@@ -6,21 +6,35 @@ use std::path::Path;
 
 pub fn run_ast(
     cli: &Cli,
-    file: &Path,
+    file: Option<&Path>,
     position: Option<usize>,
+    fqn: Option<&str>,
     limit: usize,
+    semantic_only: bool,
+    max_tree_depth: Option<usize>,
 ) -> Result<(), LlmError> {
     let db_path = resolve_db_path(cli)?;
 
-    let validated_file = validate_path(file, false)?;
-
-    if !validated_file.exists() {
-        return Err(LlmError::PathValidationFailed {
-            path: file.display().to_string(),
-            reason: "File does not exist".to_string(),
+    if fqn.is_some() && (file.is_some() || position.is_some()) {
+        return Err(LlmError::InvalidQuery {
+            query: "--fqn and --file/--position are mutually exclusive. Use only one.".to_string(),
         });
     }
 
+    let validated_file = match file {
+        Some(file) => {
+            let validated_file = validate_path(file, false)?;
+            if !validated_file.exists() {
+                return Err(LlmError::PathValidationFailed {
+                    path: file.display().to_string(),
+                    reason: "File does not exist".to_string(),
+                });
+            }
+            Some(validated_file)
+        }
+        None => None,
+    };
+
     let total_start = std::time::Instant::now();
 
     let detect_start = std::time::Instant::now();
@@ -28,7 +42,24 @@ pub fn run_ast(
     let backend_detection_ms = detect_start.elapsed().as_millis() as u64;
 
     let query_start = std::time::Instant::now();
-    let json_value = backend.ast(&validated_file, position, limit)?;
+    let mut json_value = match (&validated_file, fqn) {
+        (Some(validated_file), _) => backend.ast(validated_file, position, limit)?,
+        (None, Some(fqn)) => {
+            // Resolve the FQN to its span, then take the nodes inside it
+            let symbol = backend.lookup(fqn, &db_path.to_string_lossy())?;
+            backend.ast_span(
+                &symbol.span.file_path,
+                symbol.span.byte_start,
+                symbol.span.byte_end,
+                limit,
+            )?
+        }
+        (None, None) => {
+            return Err(LlmError::InvalidQuery {
+                query: "ast requires --file or --fqn".to_string(),
+            })
+        }
+    };
     let query_execution_ms = query_start.elapsed().as_millis() as u64;
 
     if position.is_none() {
@@ -45,6 +76,14 @@ pub fn run_ast(
         }
     }
 
+    if semantic_only {
+        llmgrep::ast::retain_semantic_nodes(&mut json_value);
+    }
+    if let Some(max_depth) = max_tree_depth {
+        llmgrep::ast::prune_to_depth(&mut json_value, max_depth);
+    }
+    llmgrep::ast::summarize_ast(&mut json_value);
+
     let format_start = std::time::Instant::now();
     let rendered = if matches!(cli.output, OutputFormat::Pretty) {
         serde_json::to_string_pretty(&json_value)?
@@ -55,6 +94,13 @@ pub fn run_ast(
     let total_ms = total_start.elapsed().as_millis() as u64;
 
     println!("{}", rendered);
+    if matches!(cli.output, OutputFormat::Human) {
+        let summary = &json_value["summary"];
+        println!(
+            "Summary: {} nodes, max depth {}",
+            summary["node_count"], summary["max_depth"]
+        );
+    }
 
     if cli.show_metrics {
         eprintln!("Performance metrics:");
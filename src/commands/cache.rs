@@ -0,0 +1,28 @@
+use crate::cli::{CacheAction, Cli};
+use llmgrep::error::LlmError;
+use llmgrep::output::OutputFormat;
+use llmgrep::query::QueryCache;
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct CacheCleared {
+    removed: usize,
+}
+
+/// Manage the on-disk symbol search cache (`--cache`).
+pub fn run_cache(cli: &Cli, action: &CacheAction) -> Result<(), LlmError> {
+    match action {
+        CacheAction::Clear => {
+            let removed = match QueryCache::default_location() {
+                Some(cache) => cache.clear()?,
+                None => 0,
+            };
+            if matches!(cli.output, OutputFormat::Human) {
+                println!("Removed {} cached search responses", removed);
+            } else {
+                println!("{}", serde_json::to_string(&CacheCleared { removed })?);
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,34 @@
+use crate::cli::{resolve_db_path, Cli};
+use llmgrep::backend::open_connection;
+use llmgrep::error::LlmError;
+use llmgrep::output::{json_response, OutputFormat};
+
+pub fn run_capabilities_cmd(cli: &Cli) -> Result<(), LlmError> {
+    let db_path = resolve_db_path(cli)?;
+    let conn = open_connection(&db_path)?;
+    let capabilities = llmgrep::query::capabilities(&conn)?;
+
+    if matches!(cli.output, OutputFormat::Human) {
+        println!("Backend: {}", capabilities.backend);
+        if let serde_json::Value::Object(fields) = serde_json::to_value(capabilities)? {
+            for (name, supported) in fields.iter().filter(|(name, _)| *name != "backend") {
+                let mark = if supported.as_bool() == Some(true) {
+                    "yes"
+                } else {
+                    "no"
+                };
+                println!("  {}: {}", name, mark);
+            }
+        }
+    } else {
+        let wrapped = json_response(&capabilities);
+        let json_str = if matches!(cli.output, OutputFormat::Pretty) {
+            serde_json::to_string_pretty(&wrapped)?
+        } else {
+            serde_json::to_string(&wrapped)?
+        };
+        println!("{}", json_str);
+    }
+
+    Ok(())
+}
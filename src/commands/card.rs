@@ -0,0 +1,60 @@
+use crate::cli::{resolve_db_path, Cli};
+use llmgrep::backend::{open_connection, Backend};
+use llmgrep::error::LlmError;
+use llmgrep::output::{json_response, OutputFormat};
+
+pub fn run_card(cli: &Cli, fqn: &str) -> Result<(), LlmError> {
+    let db_path = resolve_db_path(cli)?;
+
+    if fqn.trim().is_empty() {
+        return Err(LlmError::InvalidQuery {
+            query: "--fqn cannot be empty".to_string(),
+        });
+    }
+
+    let backend = Backend::detect_and_open(&db_path)?;
+    let symbol = backend.lookup(fqn, &db_path.to_string_lossy())?;
+    let conn = open_connection(&db_path)?;
+    let card = llmgrep::query::symbol_card(&conn, symbol)?;
+
+    if matches!(cli.output, OutputFormat::Human) {
+        println!("{} ({})", card.name, card.kind);
+        println!(
+            "  Location: {}:{}:{}-{}",
+            card.file, card.start_line, card.start_col, card.end_line
+        );
+        for (label, value) in [
+            ("FQN", &card.fqn),
+            ("Canonical FQN", &card.canonical_fqn),
+            ("Display FQN", &card.display_fqn),
+            ("Language", &card.language),
+            ("AST kind", &card.ast_kind),
+        ] {
+            if let Some(value) = value {
+                println!("  {}: {}", label, value);
+            }
+        }
+        if let (Some(cc), Some(fan_in), Some(fan_out), Some(loc)) = (
+            card.cyclomatic_complexity,
+            card.fan_in,
+            card.fan_out,
+            card.loc,
+        ) {
+            println!(
+                "  Metrics: cc={} fan-in={} fan-out={} loc={}",
+                cc, fan_in, fan_out, loc
+            );
+        }
+        println!("  Callers: {}  Callees: {}", card.callers, card.callees);
+    } else {
+        let wrapped = json_response(&card);
+        let json_str = if matches!(cli.output, OutputFormat::Pretty) {
+            serde_json::to_string_pretty(&wrapped)?
+        } else {
+            serde_json::to_string(&wrapped)?
+        };
+        println!("{}", json_str);
+    }
+
+    Ok(())
+}
@@ -2,8 +2,17 @@ use crate::cli::{resolve_db_path, Cli};
 use llmgrep::backend::Backend;
 use llmgrep::error::LlmError;
 use llmgrep::output::OutputFormat;
+use llmgrep::query::{next_segments, rank_completions};
 
-pub fn run_complete(cli: &Cli, prefix: String, limit: usize) -> Result<(), LlmError> {
+/// FQNs scanned when narrowing to segments, since many FQNs share one segment
+const SEGMENT_SCAN_LIMIT: usize = 10_000;
+
+pub fn run_complete(
+    cli: &Cli,
+    prefix: String,
+    limit: usize,
+    segment: bool,
+) -> Result<(), LlmError> {
     let db_path = resolve_db_path(cli)?;
 
     if prefix.trim().is_empty() {
@@ -19,7 +28,13 @@ pub fn run_complete(cli: &Cli, prefix: String, limit: usize) -> Result<(), LlmEr
     let backend_detection_ms = detect_start.elapsed().as_millis() as u64;
 
     let query_start = std::time::Instant::now();
-    let completions = backend.complete(&prefix, limit)?;
+    let mut completions = if segment {
+        next_segments(&backend.complete(&prefix, SEGMENT_SCAN_LIMIT)?, &prefix)
+    } else {
+        backend.complete(&prefix, limit)?
+    };
+    rank_completions(&mut completions);
+    completions.truncate(limit);
     let query_execution_ms = query_start.elapsed().as_millis() as u64;
 
     let format_start = std::time::Instant::now();
@@ -29,7 +44,7 @@ pub fn run_complete(cli: &Cli, prefix: String, limit: usize) -> Result<(), LlmEr
                 println!("{}", completion);
             }
         }
-        OutputFormat::Json | OutputFormat::Pretty => {
+        _ => {
             use serde_json::json;
             let response = json!({
                 "completions": completions,
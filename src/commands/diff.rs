@@ -0,0 +1,80 @@
+use crate::cli::{resolve_db_path, validate_path, Cli};
+use llmgrep::backend::open_connection;
+use llmgrep::error::LlmError;
+use llmgrep::output::{json_response, OutputFormat, Span};
+use llmgrep::query::DiffMetric;
+use std::path::Path;
+
+pub fn run_diff_cmd(
+    cli: &Cli,
+    baseline: &Path,
+    metric: Option<DiffMetric>,
+) -> Result<(), LlmError> {
+    let db_path = resolve_db_path(cli)?;
+    let baseline_path = validate_path(baseline, true)?;
+
+    let current = open_connection(&db_path)?;
+    let old = open_connection(&baseline_path)?;
+    for conn in [&current, &old] {
+        llmgrep::backend::schema_check::check_schema_version(conn)
+            .map_err(|e| LlmError::SchemaMismatch { reason: e })?;
+    }
+
+    let wants_json = !matches!(cli.output, OutputFormat::Human);
+    let Some(metric) = metric else {
+        let diff = llmgrep::query::run_symbol_diff(&old, &current)?;
+        if wants_json {
+            print_json(cli, &diff)?;
+        } else {
+            let loc =
+                |span: &Span| format!("{}:{}:{}", span.file_path, span.start_line, span.start_col);
+            for s in &diff.added {
+                println!("+ {} {}  {}", s.kind, s.name, loc(&s.span));
+            }
+            for s in &diff.removed {
+                println!("- {} {}  {}", s.kind, s.name, loc(&s.span));
+            }
+            for m in &diff.moved {
+                println!(
+                    "~ {} {}  {} -> {}",
+                    m.symbol.kind,
+                    m.symbol.name,
+                    loc(&m.old_span),
+                    loc(&m.new_span)
+                );
+            }
+            eprintln!();
+            eprintln!(
+                "Added: {}, Removed: {}, Moved: {}",
+                diff.added.len(),
+                diff.removed.len(),
+                diff.moved.len()
+            );
+        }
+        return Ok(());
+    };
+
+    let response = llmgrep::query::run_diff(&old, &current, metric)?;
+    if wants_json {
+        print_json(cli, &response)?;
+    } else {
+        for d in &response.deltas {
+            println!("{:+}  {}  ({} -> {})", d.delta, d.fqn, d.old, d.new);
+        }
+        eprintln!();
+        eprintln!("Changed symbols: {}", response.total_count);
+    }
+
+    Ok(())
+}
+
+fn print_json<T: serde::Serialize>(cli: &Cli, data: &T) -> Result<(), LlmError> {
+    let wrapped = json_response(data);
+    let json_str = if matches!(cli.output, OutputFormat::Pretty) {
+        serde_json::to_string_pretty(&wrapped)?
+    } else {
+        serde_json::to_string(&wrapped)?
+    };
+    println!("{}", json_str);
+    Ok(())
+}
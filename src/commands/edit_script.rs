@@ -0,0 +1,48 @@
+use crate::cli::{resolve_db_path, Cli};
+use llmgrep::backend::{open_connection, Backend};
+use llmgrep::error::LlmError;
+use llmgrep::output::{json_response, OutputFormat};
+
+pub fn run_edit_script(cli: &Cli, fqn: &str, rename: &str) -> Result<(), LlmError> {
+    let db_path = resolve_db_path(cli)?;
+
+    if fqn.trim().is_empty() {
+        return Err(LlmError::InvalidQuery {
+            query: "--fqn cannot be empty".to_string(),
+        });
+    }
+    let is_identifier = rename
+        .chars()
+        .next()
+        .is_some_and(|c| c == '_' || c.is_alphabetic())
+        && rename.chars().all(|c| c == '_' || c.is_alphanumeric());
+    if !is_identifier {
+        return Err(LlmError::InvalidQuery {
+            query: format!("--rename must be an identifier, got `{}`", rename),
+        });
+    }
+
+    let backend = Backend::detect_and_open(&db_path)?;
+    let symbol = backend.lookup(fqn, &db_path.to_string_lossy())?;
+    let conn = open_connection(&db_path)?;
+    let edits = llmgrep::query::rename_edits(&conn, &symbol, rename)?;
+
+    if matches!(cli.output, OutputFormat::Human) {
+        for edit in &edits {
+            println!(
+                "{}:{}..{}\t{}",
+                edit.file, edit.byte_start, edit.byte_end, edit.replacement
+            );
+        }
+    } else {
+        let wrapped = json_response(&edits);
+        let json_str = if matches!(cli.output, OutputFormat::Pretty) {
+            serde_json::to_string_pretty(&wrapped)?
+        } else {
+            serde_json::to_string(&wrapped)?
+        };
+        println!("{}", json_str);
+    }
+
+    Ok(())
+}
@@ -1,4 +1,5 @@
 use crate::cli::{resolve_db_path, Cli};
+use llmgrep::backend::open_connection;
 use llmgrep::error::LlmError;
 use llmgrep::output::{json_response, OutputFormat};
 
@@ -10,7 +11,7 @@ pub fn run_evolve_cmd(
     limit: usize,
 ) -> Result<(), LlmError> {
     let db_path = resolve_db_path(cli)?;
-    let conn = rusqlite::Connection::open(&db_path)?;
+    let conn = open_connection(&db_path)?;
     llmgrep::backend::schema_check::check_schema_version(&conn)
         .map_err(|e| LlmError::SchemaMismatch { reason: e })?;
 
@@ -22,7 +23,7 @@ pub fn run_evolve_cmd(
     };
 
     let response = llmgrep::query::run_evolve(&conn, &options)?;
-    let wants_json = matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty);
+    let wants_json = !matches!(cli.output, OutputFormat::Human);
 
     if wants_json {
         let wrapped = json_response(&response);
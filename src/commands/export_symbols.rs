@@ -4,8 +4,8 @@
 //! for fast lookups without database queries.
 
 use crate::cli::Cli;
+use llmgrep::backend::open_connection;
 use llmgrep::error::LlmError;
-use rusqlite::Connection;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs::File;
@@ -38,7 +38,7 @@ pub fn run_export_symbols(cli: &Cli, output_path: PathBuf) -> Result<(), LlmErro
     let db_path = resolve_db_path(cli)?;
 
     // Open magellan database
-    let conn = Connection::open(&db_path)?;
+    let conn = open_connection(&db_path)?;
 
     // Check schema version
     llmgrep::backend::schema_check::check_schema_version(&conn)
@@ -1,9 +1,10 @@
 use crate::cli::{resolve_db_path, Cli};
-use llmgrep::backend::Backend;
+use llmgrep::ast::ast_kind_path;
+use llmgrep::backend::{open_connection, Backend};
 use llmgrep::error::LlmError;
 use llmgrep::output::OutputFormat;
 
-pub fn run_find_ast(cli: &Cli, kind: &str) -> Result<(), LlmError> {
+pub fn run_find_ast(cli: &Cli, kind: &str, with_path: bool) -> Result<(), LlmError> {
     let db_path = resolve_db_path(cli)?;
 
     if kind.trim().is_empty() {
@@ -19,7 +20,23 @@ pub fn run_find_ast(cli: &Cli, kind: &str) -> Result<(), LlmError> {
     let backend_detection_ms = detect_start.elapsed().as_millis() as u64;
 
     let query_start = std::time::Instant::now();
-    let json_value = backend.find_ast(kind)?;
+    let mut json_value = backend.find_ast(kind)?;
+    if with_path {
+        let conn = open_connection(&db_path)?;
+        let nodes = if json_value["data"]["nodes"].is_array() {
+            json_value["data"]["nodes"].as_array_mut()
+        } else {
+            json_value["nodes"].as_array_mut()
+        };
+        for node in nodes.into_iter().flatten() {
+            if let Some(id) = node["id"].as_i64() {
+                let kind_path = ast_kind_path(&conn, id).map_err(|e| LlmError::SearchFailed {
+                    reason: format!("Failed to build AST kind path: {}", e),
+                })?;
+                node["kind_path"] = serde_json::json!(kind_path);
+            }
+        }
+    }
     let query_execution_ms = query_start.elapsed().as_millis() as u64;
 
     let nodes = if json_value["data"]["nodes"].is_array() {
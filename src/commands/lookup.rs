@@ -45,7 +45,7 @@ pub fn run_lookup(cli: &Cli, fqn: &str) -> Result<(), LlmError> {
                 println!("Language: {}", language);
             }
         }
-        OutputFormat::Json | OutputFormat::Pretty => {
+        _ => {
             let response = vec![symbol];
             let rendered = if matches!(cli.output, OutputFormat::Pretty) {
                 serde_json::to_string_pretty(&response)?
@@ -1,9 +1,17 @@
+pub mod annotate;
 pub mod ast;
+pub mod cache;
+pub mod capabilities;
+pub mod card;
 pub mod complete;
+pub mod diff;
+pub mod edit_script;
 pub mod evolve;
 pub mod export_symbols;
 pub mod find_ast;
 pub mod lookup;
+pub mod probe;
+pub mod schema;
 pub mod search;
 pub mod stats;
 pub mod vector;
@@ -11,12 +19,20 @@ pub mod vector;
 #[cfg(feature = "unstable-watch")]
 pub mod watch;
 
+pub use annotate::run_annotate;
 pub use ast::run_ast;
+pub use cache::run_cache;
+pub use capabilities::run_capabilities_cmd;
+pub use card::run_card;
 pub use complete::run_complete;
+pub use diff::run_diff_cmd;
+pub use edit_script::run_edit_script;
 pub use evolve::run_evolve_cmd;
 pub use export_symbols::run_export_symbols;
 pub use find_ast::run_find_ast;
 pub use lookup::run_lookup;
+pub use probe::run_probe;
+pub use schema::run_schema;
 pub use search::dispatch_search;
 pub use stats::run_stats_cmd;
 pub use vector::{run_vector_create, run_vector_search};
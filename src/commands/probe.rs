@@ -0,0 +1,53 @@
+use crate::cli::{resolve_db_path, Cli};
+use llmgrep::backend::{open_connection_with_flags, Backend};
+use llmgrep::error::LlmError;
+use serde::Serialize;
+use std::path::Path;
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// Hard deadline for a probe, so a hung filesystem or locked database fails
+/// the liveness check instead of stalling it.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Serialize)]
+struct ProbeStatus {
+    status: &'static str,
+    backend: &'static str,
+    symbols: u64,
+}
+
+fn probe(db_path: &Path) -> Result<ProbeStatus, LlmError> {
+    let backend = Backend::detect_and_open(db_path)?;
+    let backend_name = match backend {
+        Backend::Sqlite(_) => "sqlite",
+    };
+    let conn = open_connection_with_flags(db_path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    let symbols: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM graph_entities WHERE kind = 'Symbol'",
+        [],
+        |row| row.get(0),
+    )?;
+    Ok(ProbeStatus {
+        status: "ok",
+        backend: backend_name,
+        symbols: symbols as u64,
+    })
+}
+
+pub fn run_probe(cli: &Cli) -> Result<(), LlmError> {
+    let db_path = resolve_db_path(cli)?;
+
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = tx.send(probe(&db_path));
+    });
+    let status = rx
+        .recv_timeout(PROBE_TIMEOUT)
+        .map_err(|_| LlmError::SearchFailed {
+            reason: format!("probe did not finish within {}s", PROBE_TIMEOUT.as_secs()),
+        })??;
+
+    println!("{}", serde_json::to_string(&status)?);
+    Ok(())
+}
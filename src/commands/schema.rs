@@ -0,0 +1,20 @@
+use crate::cli::Cli;
+use llmgrep::error::LlmError;
+use llmgrep::output::OutputFormat;
+use llmgrep::output_schema::search_response_schema;
+
+/// Print the JSON Schema of the search response envelope.
+///
+/// The schema is a standalone document, so it is not wrapped in the
+/// envelope it describes. `--output json` prints it compact; every other
+/// format pretty-prints it.
+pub fn run_schema(cli: &Cli) -> Result<(), LlmError> {
+    let schema = search_response_schema();
+    let json_str = if matches!(cli.output, OutputFormat::Json) {
+        serde_json::to_string(&schema)?
+    } else {
+        serde_json::to_string_pretty(&schema)?
+    };
+    println!("{}", json_str);
+    Ok(())
+}
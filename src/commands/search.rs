@@ -1,61 +1,105 @@
 use crate::cli::{
-    looks_like_regex, normalize_language, parse_fields, parse_kinds, resolve_db_path,
-    split_auto_limit, validate_path, AutoLimitMode, Cli, Command, SearchMode, SearchParams,
+    looks_like_regex, normalize_language, parse_fields, parse_kinds, resolve_db_paths,
+    resolve_path_glob, split_auto_limit, validate_path, AutoLimitMode, Cli, Command, PartitionBy,
+    SearchMode, SearchParams, Visibility,
 };
 use crate::display::{
     output_calls, output_docs, output_facts, output_implements, output_references, output_semantic,
-    output_symbols,
+    output_symbols, render_json,
 };
 use llmgrep::algorithm::AlgorithmOptions;
-use llmgrep::ast::{expand_shorthand_with_language, expand_shorthands};
-use llmgrep::backend::Backend;
+use llmgrep::ast::{expand_shorthand_with_language, expand_shorthands, to_tree_sitter_query};
+use llmgrep::backend::{open_connection, restart_query_clock, Backend};
 use llmgrep::error::LlmError;
 use llmgrep::output::{
-    json_response_with_partial_and_performance, CombinedSearchResponse, OutputFormat,
-    PerformanceMetrics,
+    json_response_with_partial_and_performance, progressive_json_lines, typed_json_lines,
+    write_ndjson_line, BatchQueryResult, CombinedSearchResponse, FilesWithMatches, MatchCount,
+    OutputFormat, PerformanceMetrics, QueryTiming, SearchResponse, SymbolMatch, TypedRecord,
 };
 use llmgrep::query::{
-    AstOptions, ContextOptions, DepthOptions, FqnOptions, MetricsOptions, SearchOptions,
-    SnippetOptions,
+    attach_callers_across, call_site_counts, capabilities, folded_call_stacks, group_supernodes,
+    open_databases, render_slice_dot, resolve_callee_definitions, resolve_reference_definitions,
+    resolved_imports, search_symbols_across, slice_edges, AstOptions, ContextOptions, DepthOptions,
+    CachedSearch, FqnOptions, MetricsOptions, QueryCache, SearchOptions, SnippetMode, SnippetOptions,
+    MAX_FOLDED_DEPTH,
 };
+use llmgrep::SortMode;
+use std::path::{Path, PathBuf};
 
 pub fn dispatch_search(cli: &Cli, cmd: &Command) -> Result<(), LlmError> {
     let params = match cmd {
         Command::Search {
             query,
+            all,
+            queries_file: _,
             mode,
             path,
+            exclude_paths,
+            path_glob,
+            paths_from_stdin,
             kind,
+            reference_kind,
             language,
             label,
             limit,
+            max_per_file,
+            dedupe,
             regex,
             candidates,
             with_context,
+            context_only,
+            context_exclude_self,
+            distinct_names,
+            files_with_matches,
+            count,
+            flatten,
+            score_histogram,
+            partition_by,
             context_lines,
+            context_before,
+            context_after,
             max_context_lines,
             with_snippet,
             with_fqn,
             max_snippet_bytes,
+            snippet_lines,
+            snippet_line_numbers,
+            with_line_text,
+            with_provenance,
+            resolve_callees,
+            resolve_defs,
+            file_io_timeout,
+            with_complexity_percentile,
             fields,
             sort_by,
             auto_limit,
             min_complexity,
             max_complexity,
             min_fan_in,
+            max_fan_in,
             min_fan_out,
+            max_fan_out,
+            min_loc,
+            max_loc,
+            weight_by_calls,
+            boost_fan_in,
             symbol_id,
             fqn,
             exact_fqn,
+            normalize_fqn,
             ast_kind,
             with_ast_context,
+            with_ast_kind,
+            ts_query,
             min_depth,
             max_depth,
             inside,
             contains,
             from_symbol_set,
+            symbol_ids_file,
             reachable_from,
             dead_code_in,
+            with_callers,
             in_cycle,
             slice_backward_from,
             slice_forward_from,
@@ -74,41 +118,86 @@ pub fn dispatch_search(cli: &Cli, cmd: &Command) -> Result<(), LlmError> {
             status,
             subject_type,
             tokens,
+            skip_corrupt,
+            strict_complete,
+            visibility,
+            prefer,
         } => SearchParams {
-            query: query.clone(),
+            // `--all` is an empty query: no name predicate, no regex scan
+            query: if *all { String::new() } else { query.clone() },
+            all: *all,
             mode: *mode,
             path: path.clone(),
+            exclude_paths: exclude_paths.clone(),
+            path_glob: *path_glob,
+            file_set: if *paths_from_stdin {
+                Some(read_path_list(std::io::stdin().lock())?)
+            } else {
+                None
+            },
             kind: kind.clone(),
+            reference_kind: reference_kind.clone(),
             language: language.clone(),
             label: label.clone(),
             limit: *limit,
+            max_per_file: *max_per_file,
+            dedupe: *dedupe,
             regex: *regex,
             candidates: *candidates,
             with_context: *with_context,
+            context_only: *context_only,
+            context_exclude_self: *context_exclude_self,
+            distinct_names: *distinct_names,
+            files_with_matches: *files_with_matches,
+            count: *count,
+            flatten: *flatten,
+            score_histogram: *score_histogram,
+            partition_by: *partition_by,
             context_lines: *context_lines,
+            context_before: *context_before,
+            context_after: *context_after,
             max_context_lines: *max_context_lines,
             with_snippet: *with_snippet,
             with_fqn: *with_fqn,
             max_snippet_bytes: *max_snippet_bytes,
+            snippet_lines: *snippet_lines,
+            snippet_line_numbers: *snippet_line_numbers,
+            with_line_text: *with_line_text,
+            with_provenance: *with_provenance,
+            resolve_callees: *resolve_callees,
+            resolve_defs: *resolve_defs,
+            file_io_timeout: *file_io_timeout,
+            with_complexity_percentile: *with_complexity_percentile,
             fields: fields.clone(),
             sort_by: *sort_by,
             auto_limit: *auto_limit,
             min_complexity: *min_complexity,
             max_complexity: *max_complexity,
             min_fan_in: *min_fan_in,
+            max_fan_in: *max_fan_in,
             min_fan_out: *min_fan_out,
+            max_fan_out: *max_fan_out,
+            min_loc: *min_loc,
+            max_loc: *max_loc,
+            weight_by_calls: *weight_by_calls,
+            boost_fan_in: *boost_fan_in,
             symbol_id: symbol_id.clone(),
             fqn: fqn.clone(),
             exact_fqn: exact_fqn.clone(),
+            normalize_fqn: *normalize_fqn,
             ast_kind: ast_kind.clone(),
             with_ast_context: *with_ast_context,
+            with_ast_kind: *with_ast_kind,
+            ts_query: *ts_query,
             min_depth: *min_depth,
             max_depth: *max_depth,
             inside: inside.clone(),
             contains: contains.clone(),
             from_symbol_set: from_symbol_set.clone(),
+            symbol_ids_file: symbol_ids_file.clone(),
             reachable_from: reachable_from.clone(),
             dead_code_in: dead_code_in.clone(),
+            with_callers: *with_callers,
             in_cycle: in_cycle.clone(),
             slice_backward_from: slice_backward_from.clone(),
             slice_forward_from: slice_forward_from.clone(),
@@ -132,14 +221,220 @@ pub fn dispatch_search(cli: &Cli, cmd: &Command) -> Result<(), LlmError> {
             fact_status_filter: status.clone(),
             subject_type: subject_type.clone(),
             tokens: *tokens,
+            skip_corrupt: *skip_corrupt,
+            strict_complete: *strict_complete,
+            visibility: *visibility,
+            prefer: *prefer,
         },
         _ => unreachable!(),
     };
-    run_search(cli, &params)
+    let queries = match cmd {
+        Command::Search {
+            queries_file: Some(file),
+            ..
+        } => read_queries_file(file)?,
+        _ => vec![params.query.clone()],
+    };
+
+    if matches!(cli.output, OutputFormat::TimingsCsv) {
+        println!("{}", QueryTiming::CSV_HEADER);
+    }
+    let mut batch = Vec::new();
+    let mut opened = None;
+    for (index, query) in queries.into_iter().enumerate() {
+        let params = SearchParams {
+            query,
+            ..params.clone()
+        };
+        restart_query_clock();
+        match run_search(cli, &params, &mut opened)? {
+            Some(Collected::Timing(timing)) => println!("{}", timing.to_csv_row()),
+            Some(Collected::Response(response, partial)) => batch.push(BatchQueryResult {
+                index,
+                query: params.query,
+                total_count: response.total_count,
+                partial,
+                results: response.results,
+            }),
+            None => {}
+        }
+    }
+    if matches!(cli.output, OutputFormat::BatchResultsJson) {
+        println!("{}", serde_json::to_string(&batch)?);
+    }
+    Ok(())
+}
+
+/// Read one query per line, skipping blank lines and `#` comments.
+///
+/// A path of `-` reads the queries from stdin.
+fn read_queries_file(path: &Path) -> Result<Vec<String>, LlmError> {
+    let content = if path == Path::new("-") {
+        std::io::read_to_string(std::io::stdin())?
+    } else {
+        std::fs::read_to_string(path)?
+    };
+    let queries: Vec<String> = content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect();
+    if queries.is_empty() {
+        return Err(LlmError::InvalidQuery {
+            query: format!("--queries-file {} contains no queries", path.display()),
+        });
+    }
+    Ok(queries)
+}
+
+/// Read newline-separated file paths (e.g. `git diff --name-only`) and
+/// canonicalize them to match indexed file paths.
+///
+/// Blank lines are skipped, as are paths that no longer exist (files deleted
+/// in a diff have no symbols to find).
+pub(crate) fn read_path_list(reader: impl std::io::BufRead) -> Result<Vec<String>, LlmError> {
+    let mut files = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        let path = Path::new(line.trim());
+        if path.as_os_str().is_empty() || !path.exists() {
+            continue;
+        }
+        files.push(validate_path(path, false)?.to_string_lossy().into_owned());
+    }
+    Ok(files)
+}
+
+/// Print `--files-with-matches` paths: one per line, or `{"files":[...]}` for JSON.
+fn print_files_with_matches(
+    cli: &Cli,
+    files: &FilesWithMatches,
+    wants_json: bool,
+) -> Result<(), LlmError> {
+    if wants_json {
+        let rendered = render_json(cli, files)?;
+        println!("{}", rendered);
+    } else {
+        for file in &files.files {
+            println!("{}", file);
+        }
+    }
+    Ok(())
+}
+
+/// Opened `--db` values with their backends, in `--db` order.
+type Databases = [(PathBuf, Backend)];
+
+/// Databases for a search, opened by the first query of a batch and reused by
+/// the rest, with the milliseconds spent opening them (zero when reused).
+fn open_backends<'a>(
+    opened: &'a mut Option<Vec<(PathBuf, Backend)>>,
+    db_paths: &[PathBuf],
+    skip_corrupt: bool,
+) -> Result<(&'a Databases, u64), LlmError> {
+    let detect_start = std::time::Instant::now();
+    let backends = match opened {
+        Some(backends) => backends,
+        None => opened.insert(open_databases(
+            db_paths,
+            skip_corrupt,
+            Backend::detect_and_open,
+        )?),
+    };
+    Ok((backends, detect_start.elapsed().as_millis() as u64))
+}
+
+/// Symbol search through the on-disk cache when `--cache` is enabled.
+///
+/// A failed cache write is reported on stderr and otherwise ignored.
+fn search_symbols_cached(
+    cache: Option<&QueryCache>,
+    backend: &Backend,
+    options: SearchOptions,
+) -> Result<(SearchResponse, bool, bool), LlmError> {
+    let Some(cache) = cache else {
+        return backend.search_symbols(options);
+    };
+    if let Some(hit) = cache.get(&options) {
+        return Ok(hit);
+    }
+    let result = backend.search_symbols(options.clone())?;
+    if let Err(e) = cache.put(&options, &result) {
+        eprintln!("Note: query cache write failed: {}", e);
+    }
+    Ok(result)
+}
+
+/// Stream `--output ndjson` symbol rows to stdout from every database in
+/// `--db` order.
+///
+/// `--limit` applies across the databases: each one is searched for what
+/// the earlier ones left.
+fn stream_symbols_ndjson(
+    backends: &Databases,
+    skip_corrupt: bool,
+    options: &SearchOptions,
+) -> Result<(SearchResponse, bool, bool), LlmError> {
+    let multi_db = backends.len() > 1;
+    let mut out = std::io::stdout().lock();
+    let mut written = 0;
+    let mut paths_bounded = false;
+    let (response, partial) = search_symbols_across(backends, skip_corrupt, options, |path, backend| {
+        let limit = options.limit.saturating_sub(written);
+        let source_db = multi_db.then(|| path.display().to_string());
+        let (response, partial, bounded) = backend.stream_symbols(
+            SearchOptions {
+                db_path: path,
+                limit,
+                ..options.clone()
+            },
+            &mut |mut row: SymbolMatch| {
+                row.source_db = source_db.clone();
+                written += 1;
+                Ok(write_ndjson_line(&mut out, &row)?)
+            },
+        )?;
+        paths_bounded |= bounded;
+        Ok((response, partial))
+    })?;
+    Ok((response, partial, paths_bounded))
+}
+
+fn print_match_count(cli: &Cli, count: MatchCount, wants_json: bool) -> Result<(), LlmError> {
+    if !wants_json {
+        println!("{}", count.count);
+        if count.partial {
+            eprintln!(
+                "Warning: count stopped at --candidates; more symbols may match (raise --candidates)"
+            );
+        }
+    } else if matches!(cli.output, OutputFormat::Pretty) {
+        println!("{}", serde_json::to_string_pretty(&count)?);
+    } else {
+        println!("{}", serde_json::to_string(&count)?);
+    }
+    Ok(())
 }
 
+/// Per-query output that the caller collects instead of printing.
+pub enum Collected {
+    /// Timing row for `--output timings-csv`
+    Timing(QueryTiming),
+    /// Symbol results and partial flag for `--output batch-results-json`
+    Response(SearchResponse, bool),
+}
+
+/// Run one search and print its results.
+///
+/// With `--output timings-csv` or `--output batch-results-json` nothing is
+/// printed; the query's timing row or response is returned instead.
 #[allow(clippy::too_many_arguments)]
-pub fn run_search(cli: &Cli, params: &SearchParams) -> Result<(), LlmError> {
+pub fn run_search(
+    cli: &Cli,
+    params: &SearchParams,
+    opened: &mut Option<Vec<(PathBuf, Backend)>>,
+) -> Result<Option<Collected>, LlmError> {
     if let Some(sid) = &params.symbol_id {
         let hex_regex =
             regex::Regex::new(r"^[0-9a-f]{32}$").map_err(|_| LlmError::InvalidQuery {
@@ -175,6 +470,23 @@ pub fn run_search(cli: &Cli, params: &SearchParams) -> Result<(), LlmError> {
         None
     };
 
+    if params.ts_query {
+        if expanded_ast_kind.is_none() && params.inside.is_none() && params.contains.is_none() {
+            return Err(LlmError::InvalidQuery {
+                query: "--ts-query requires --ast-kind, --inside, or --contains".to_string(),
+            });
+        }
+        let kinds: Vec<String> = expanded_ast_kind
+            .as_deref()
+            .map(|k| k.split(',').map(|s| s.to_string()).collect())
+            .unwrap_or_default();
+        println!(
+            "{}",
+            to_tree_sitter_query(&kinds, params.inside.as_deref(), params.contains.as_deref())
+        );
+        return Ok(None);
+    }
+
     let normalized_kind = params.kind.as_ref().map(|k| {
         let kinds = parse_kinds(k);
         if kinds.is_empty() {
@@ -215,6 +527,7 @@ pub fn run_search(cli: &Cli, params: &SearchParams) -> Result<(), LlmError> {
     }
 
     if params.query.trim().is_empty()
+        && !params.all
         && params.symbol_id.is_none()
         && !params.condense
         && params.paths_from.is_none()
@@ -223,21 +536,347 @@ pub fn run_search(cli: &Cli, params: &SearchParams) -> Result<(), LlmError> {
         return Err(LlmError::EmptyQuery);
     }
 
-    let db_path = resolve_db_path(cli)?;
+    let db_paths = resolve_db_paths(cli)?;
+    let multi_db = db_paths.len() > 1;
+    if multi_db && !matches!(params.mode, SearchMode::Symbols) {
+        return Err(LlmError::InvalidQuery {
+            query: "multiple --db values require --mode symbols".to_string(),
+        });
+    }
 
     let total_start = std::time::Instant::now();
-
-    let detect_start = std::time::Instant::now();
-    let backend = Backend::detect_and_open(&db_path)?;
-    let backend_detection_ms = detect_start.elapsed().as_millis() as u64;
+    let mut collected = None;
+    let mut partial_results = false;
+    let db_path = db_paths[0].clone();
 
     let validated_path = if let Some(p) = &params.path {
-        Some(validate_path(p, false)?)
+        if params.path_glob {
+            Some(resolve_path_glob(p)?)
+        } else {
+            Some(validate_path(p, false)?)
+        }
     } else {
         None
     };
-    let wants_json = matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty);
+    let exclude_paths = params
+        .exclude_paths
+        .iter()
+        .map(|p| validate_path(p, false))
+        .collect::<Result<Vec<_>, _>>()?;
+    let json_lines_typed = matches!(cli.output, OutputFormat::JsonLinesTyped);
+    let progressive = matches!(cli.output, OutputFormat::ProgressiveJpegStyle);
+    let min_viable = matches!(cli.output, OutputFormat::MinViable);
+    let ndjson = matches!(cli.output, OutputFormat::Ndjson);
+    let wants_json = json_lines_typed
+        || progressive
+        || min_viable
+        || ndjson
+        || matches!(
+            cli.output,
+            OutputFormat::Json | OutputFormat::Pretty | OutputFormat::BatchResultsJson
+        );
+    if params.distinct_names && !matches!(params.mode, SearchMode::Symbols) {
+        return Err(LlmError::InvalidQuery {
+            query: "--distinct-names requires --mode symbols".to_string(),
+        });
+    }
+    if params.flatten && !matches!(params.mode, SearchMode::Auto) {
+        return Err(LlmError::InvalidQuery {
+            query: "--flatten requires --mode auto".to_string(),
+        });
+    }
+    if params.score_histogram && !matches!(params.mode, SearchMode::Symbols) {
+        return Err(LlmError::InvalidQuery {
+            query: "--score-histogram requires --mode symbols".to_string(),
+        });
+    }
+    if params.partition_by.is_some() && !matches!(params.mode, SearchMode::Symbols) {
+        return Err(LlmError::InvalidQuery {
+            query: "--partition-by requires --mode symbols".to_string(),
+        });
+    }
+    if params.weight_by_calls
+        && (params.sort_by != SortMode::FanIn || !matches!(params.mode, SearchMode::Symbols))
+    {
+        return Err(LlmError::InvalidQuery {
+            query: "--weight-by-calls requires --mode symbols with --sort-by fan-in".to_string(),
+        });
+    }
+    if params.boost_fan_in
+        && (params.sort_by != SortMode::Relevance || !matches!(params.mode, SearchMode::Symbols))
+    {
+        return Err(LlmError::InvalidQuery {
+            query: "--boost-fan-in requires --mode symbols with --sort-by relevance".to_string(),
+        });
+    }
+    if params.file_set.is_some() && !matches!(params.mode, SearchMode::Symbols) {
+        return Err(LlmError::InvalidQuery {
+            query: "--paths-from-stdin requires --mode symbols".to_string(),
+        });
+    }
+    if params.reference_kind.is_some() && !matches!(params.mode, SearchMode::References) {
+        return Err(LlmError::InvalidQuery {
+            query: "--reference-kind requires --mode references".to_string(),
+        });
+    }
+    if !params.exclude_paths.is_empty()
+        && !matches!(
+            params.mode,
+            SearchMode::Symbols | SearchMode::References | SearchMode::Calls | SearchMode::Auto
+        )
+    {
+        return Err(LlmError::InvalidQuery {
+            query: "--exclude-path requires --mode symbols, references, calls or auto".to_string(),
+        });
+    }
+    if params.path_glob
+        && !matches!(
+            params.mode,
+            SearchMode::Symbols | SearchMode::References | SearchMode::Calls | SearchMode::Auto
+        )
+    {
+        return Err(LlmError::InvalidQuery {
+            query: "--path-glob requires --mode symbols, references, calls or auto".to_string(),
+        });
+    }
+    if params.max_per_file.is_some()
+        && !matches!(
+            params.mode,
+            SearchMode::Symbols | SearchMode::References | SearchMode::Calls | SearchMode::Auto
+        )
+    {
+        return Err(LlmError::InvalidQuery {
+            query: "--max-per-file requires --mode symbols, references, calls or auto".to_string(),
+        });
+    }
+    if params.dedupe && !matches!(params.mode, SearchMode::Symbols | SearchMode::Auto) {
+        return Err(LlmError::InvalidQuery {
+            query: "--dedupe requires --mode symbols or auto".to_string(),
+        });
+    }
+    if params.snippet_lines.is_some()
+        && !matches!(params.mode, SearchMode::Symbols | SearchMode::Auto)
+    {
+        return Err(LlmError::InvalidQuery {
+            query: "--snippet-lines requires --mode symbols or auto".to_string(),
+        });
+    }
+    if params.with_complexity_percentile && !matches!(params.mode, SearchMode::Symbols) {
+        return Err(LlmError::InvalidQuery {
+            query: "--with-complexity-percentile requires --mode symbols".to_string(),
+        });
+    }
+    if params.visibility.is_some() && !matches!(params.mode, SearchMode::Symbols) {
+        return Err(LlmError::InvalidQuery {
+            query: "--visibility requires --mode symbols".to_string(),
+        });
+    }
+    if params.prefer.is_some() && !matches!(params.mode, SearchMode::Symbols) {
+        return Err(LlmError::InvalidQuery {
+            query: "--prefer requires --mode symbols".to_string(),
+        });
+    }
+    let slice_seed = params
+        .slice_backward_from
+        .as_deref()
+        .or(params.slice_forward_from.as_deref());
+    if matches!(cli.output, OutputFormat::SliceDot)
+        && (slice_seed.is_none() || !matches!(params.mode, SearchMode::Symbols))
+    {
+        return Err(LlmError::InvalidQuery {
+            query: "--output slice-dot requires --mode symbols with --slice-backward-from or --slice-forward-from".to_string(),
+        });
+    }
+    if matches!(cli.output, OutputFormat::TimingsCsv) && !matches!(params.mode, SearchMode::Symbols)
+    {
+        return Err(LlmError::InvalidQuery {
+            query: "--output timings-csv requires --mode symbols".to_string(),
+        });
+    }
+    if matches!(cli.output, OutputFormat::BatchResultsJson)
+        && !matches!(params.mode, SearchMode::Symbols)
+    {
+        return Err(LlmError::InvalidQuery {
+            query: "--output batch-results-json requires --mode symbols".to_string(),
+        });
+    }
+    if matches!(cli.output, OutputFormat::GroupedBySupernodeJson)
+        && (!params.condense || !matches!(params.mode, SearchMode::Symbols))
+    {
+        return Err(LlmError::InvalidQuery {
+            query: "--output grouped-by-supernode-json requires --mode symbols with --condense"
+                .to_string(),
+        });
+    }
+    if matches!(cli.output, OutputFormat::Snippets)
+        && (!params.with_snippet || !matches!(params.mode, SearchMode::Symbols))
+    {
+        return Err(LlmError::InvalidQuery {
+            query: "--output snippets requires --mode symbols and --with-snippet".to_string(),
+        });
+    }
+    if matches!(cli.output, OutputFormat::EntityIdsOnly)
+        && !matches!(params.mode, SearchMode::Symbols)
+    {
+        return Err(LlmError::InvalidQuery {
+            query: "--output entity-ids-only requires --mode symbols".to_string(),
+        });
+    }
+    if json_lines_typed
+        && !matches!(
+            params.mode,
+            SearchMode::Symbols | SearchMode::References | SearchMode::Calls | SearchMode::Auto
+        )
+    {
+        return Err(LlmError::InvalidQuery {
+            query: "--output json-lines-typed requires --mode symbols, references, calls or auto"
+                .to_string(),
+        });
+    }
+    if matches!(cli.output, OutputFormat::Frequency) && !matches!(params.mode, SearchMode::Symbols)
+    {
+        return Err(LlmError::InvalidQuery {
+            query: "--output frequency requires --mode symbols".to_string(),
+        });
+    }
+    if matches!(cli.output, OutputFormat::DeadcodeCsv)
+        && (params.dead_code_in.is_none() || !matches!(params.mode, SearchMode::Symbols))
+    {
+        return Err(LlmError::InvalidQuery {
+            query: "--output deadcode-csv requires --mode symbols with --dead-code-in".to_string(),
+        });
+    }
+    if progressive && !matches!(params.mode, SearchMode::Symbols) {
+        return Err(LlmError::InvalidQuery {
+            query: "--output progressive-jpeg-style requires --mode symbols".to_string(),
+        });
+    }
+    if ndjson
+        && !matches!(
+            params.mode,
+            SearchMode::Symbols | SearchMode::References | SearchMode::Calls
+        )
+    {
+        return Err(LlmError::InvalidQuery {
+            query: "--output ndjson requires --mode symbols, references or calls".to_string(),
+        });
+    }
+    // ndjson writes each match as its row is read, unless another flag
+    // replaces the per-match output altogether
+    let stream_rows = ndjson
+        && !params.files_with_matches
+        && params.partition_by.is_none()
+        && !params.score_histogram
+        && !params.distinct_names
+        && !params.context_only;
+    if stream_rows && (params.with_complexity_percentile || params.with_callers) {
+        return Err(LlmError::InvalidQuery {
+            query: "--output ndjson writes each match as it is read and cannot be combined with --with-complexity-percentile or --with-callers".to_string(),
+        });
+    }
+    if min_viable && !matches!(params.mode, SearchMode::Symbols) {
+        return Err(LlmError::InvalidQuery {
+            query: "--output min-viable requires --mode symbols".to_string(),
+        });
+    }
+    if matches!(cli.output, OutputFormat::Wide) && !matches!(params.mode, SearchMode::Symbols) {
+        return Err(LlmError::InvalidQuery {
+            query: "--output wide requires --mode symbols".to_string(),
+        });
+    }
+    if matches!(cli.output, OutputFormat::FqnList) && !matches!(params.mode, SearchMode::Symbols) {
+        return Err(LlmError::InvalidQuery {
+            query: "--output fqn-list requires --mode symbols".to_string(),
+        });
+    }
+    if matches!(cli.output, OutputFormat::Matrix) && !matches!(params.mode, SearchMode::Calls) {
+        return Err(LlmError::InvalidQuery {
+            query: "--output matrix requires --mode calls".to_string(),
+        });
+    }
+    if matches!(cli.output, OutputFormat::FlamegraphCallgraph)
+        && (params.paths_from.is_none() || !matches!(params.mode, SearchMode::Calls))
+    {
+        return Err(LlmError::InvalidQuery {
+            query: "--output flamegraph-callgraph requires --mode calls with --paths-from"
+                .to_string(),
+        });
+    }
+    if matches!(cli.output, OutputFormat::ResolvedImports)
+        && (params.path.is_none() || !matches!(params.mode, SearchMode::References))
+    {
+        return Err(LlmError::InvalidQuery {
+            query: "--output resolved-imports requires --mode references with --path".to_string(),
+        });
+    }
+    // These read one call graph for the whole result set, which only makes
+    // sense within a single database
+    if multi_db
+        && (matches!(
+            cli.output,
+            OutputFormat::SliceDot | OutputFormat::GroupedBySupernodeJson
+        ) || min_viable)
+    {
+        return Err(LlmError::InvalidQuery {
+            query: format!("--output {} requires a single --db", cli.output),
+        });
+    }
+    if params.with_callers && (!wants_json || !matches!(params.mode, SearchMode::Symbols)) {
+        return Err(LlmError::InvalidQuery {
+            query: "--with-callers requires --mode symbols and --output json or pretty".to_string(),
+        });
+    }
+    if params.resolve_callees && (!wants_json || !matches!(params.mode, SearchMode::Calls)) {
+        return Err(LlmError::InvalidQuery {
+            query: "--resolve-callees requires --mode calls and --output json or pretty"
+                .to_string(),
+        });
+    }
+    if params.resolve_defs && (!wants_json || !matches!(params.mode, SearchMode::References)) {
+        return Err(LlmError::InvalidQuery {
+            query: "--resolve-defs requires --mode references and --output json or pretty"
+                .to_string(),
+        });
+    }
+    if params.context_only && (!wants_json || !matches!(params.mode, SearchMode::Symbols)) {
+        return Err(LlmError::InvalidQuery {
+            query: "--context-only requires --mode symbols and --output json or pretty".to_string(),
+        });
+    }
+    if params.files_with_matches
+        && !matches!(
+            params.mode,
+            SearchMode::Symbols | SearchMode::References | SearchMode::Calls
+        )
+    {
+        return Err(LlmError::InvalidQuery {
+            query: "--files-with-matches requires --mode symbols, references or calls".to_string(),
+        });
+    }
+    if params.count && !matches!(params.mode, SearchMode::Symbols) {
+        return Err(LlmError::InvalidQuery {
+            query: "--count requires --mode symbols".to_string(),
+        });
+    }
+    // These filters run after SQL, so the COUNT(*) fast path cannot honour them
+    if params.count
+        && (params.visibility.is_some()
+            || params.prefer.is_some()
+            || params.max_per_file.is_some()
+            || params.dedupe)
+    {
+        return Err(LlmError::InvalidQuery {
+            query: "--count cannot be combined with --visibility, --prefer, --max-per-file or --dedupe"
+                .to_string(),
+        });
+    }
     let candidates = params.candidates.max(params.limit);
+    // --files-with-matches caps distinct files, not matches: fetch every candidate
+    let match_limit = if params.files_with_matches {
+        candidates
+    } else {
+        params.limit
+    };
     let fields = if wants_json {
         params
             .fields
@@ -249,24 +888,52 @@ pub fn run_search(cli: &Cli, params: &SearchParams) -> Result<(), LlmError> {
     };
 
     let include_context = wants_json && fields.as_ref().map_or(params.with_context, |f| f.context);
-    let include_snippet = wants_json && fields.as_ref().map_or(params.with_snippet, |f| f.snippet);
+    let snippets_only = matches!(cli.output, OutputFormat::Snippets);
+    // --output vimgrep uses the snippet's first line as the match text
+    let vimgrep = matches!(cli.output, OutputFormat::Vimgrep);
+    let include_snippet = snippets_only
+        || (vimgrep && params.with_snippet)
+        || (wants_json && fields.as_ref().map_or(params.with_snippet, |f| f.snippet));
+    let snippet_mode = if params.snippet_lines.is_some() {
+        SnippetMode::Lines
+    } else {
+        SnippetMode::Bytes
+    };
     let include_score = if wants_json {
         fields.as_ref().is_none_or(|f| f.score)
     } else {
         true
     };
 
+    let include_line_text = wants_json && params.with_line_text;
+    let include_defined_in = wants_json && params.with_provenance;
+    let read_timeout = params.file_io_timeout.map(std::time::Duration::from_millis);
+
+    let fqn_list = matches!(cli.output, OutputFormat::FqnList);
     let include_fqn = wants_json && fields.as_ref().map_or(params.with_fqn, |f| f.fqn);
-    let include_canonical_fqn =
-        wants_json && fields.as_ref().map_or(params.with_fqn, |f| f.canonical_fqn);
-    let include_display_fqn =
-        wants_json && fields.as_ref().map_or(params.with_fqn, |f| f.display_fqn);
+    let deadcode_csv = matches!(cli.output, OutputFormat::DeadcodeCsv);
+    let include_canonical_fqn = fqn_list
+        || deadcode_csv
+        || (wants_json && fields.as_ref().map_or(params.with_fqn, |f| f.canonical_fqn));
+    let include_display_fqn = fqn_list
+        || deadcode_csv
+        || (wants_json && fields.as_ref().map_or(params.with_fqn, |f| f.display_fqn));
 
     let metrics = MetricsOptions {
         min_complexity: params.min_complexity,
         max_complexity: params.max_complexity,
         min_fan_in: params.min_fan_in,
+        max_fan_in: params.max_fan_in,
         min_fan_out: params.min_fan_out,
+        max_fan_out: params.max_fan_out,
+        min_loc: params.min_loc,
+        max_loc: params.max_loc,
+        weight_by_calls: params.weight_by_calls,
+        boost_fan_in: params.boost_fan_in,
+        with_fan_in: matches!(
+            cli.output,
+            OutputFormat::Wide | OutputFormat::ProgressiveJpegStyle
+        ),
     };
 
     match params.mode {
@@ -275,26 +942,42 @@ pub fn run_search(cli: &Cli, params: &SearchParams) -> Result<(), LlmError> {
                 db_path: &db_path,
                 query: &params.query,
                 path_filter: validated_path.as_ref(),
+                exclude_paths: &exclude_paths,
+                path_glob: params.path_glob,
                 kind_filter: normalized_kind.as_deref(),
                 language_filter: normalized_language.as_deref(),
-                limit: params.limit,
+                limit: match_limit,
                 use_regex,
+                ignore_case: cli.ignore_case,
                 candidates,
                 context: ContextOptions {
                     include: include_context,
                     lines: params.context_lines,
+                    before: params.context_before,
+                    after: params.context_after,
                     max_lines: params.max_context_lines,
+                    exclude_self: params.context_exclude_self,
                 },
                 snippet: SnippetOptions {
                     include: include_snippet,
                     max_bytes: params.max_snippet_bytes,
+                    mode: snippet_mode,
+                    max_lines: params.snippet_lines.unwrap_or(0),
+                    line_numbers: params.snippet_line_numbers,
+                    line_text: include_line_text,
+                    dedupe: params.dedupe,
+                    read_timeout,
                 },
                 fqn: FqnOptions {
                     fqn: include_fqn,
                     canonical_fqn: include_canonical_fqn,
                     display_fqn: include_display_fqn,
+                    normalize: params.normalize_fqn,
+                    defined_in: include_defined_in,
                 },
-                include_score,
+                // Merging several databases by relevance needs every score
+                include_score: include_score
+                    || (multi_db && matches!(params.sort_by, SortMode::Relevance | SortMode::Hybrid)),
                 sort_by: params.sort_by,
                 metrics,
                 ast: AstOptions {
@@ -303,6 +986,7 @@ pub fn run_search(cli: &Cli, params: &SearchParams) -> Result<(), LlmError> {
                         .map(|k| k.split(',').map(|s| s.trim().to_string()).collect())
                         .unwrap_or_default(),
                     with_ast_context: params.with_ast_context,
+                    with_ast_kind: params.with_ast_kind,
                     _phantom: std::marker::PhantomData,
                 },
                 depth: DepthOptions {
@@ -313,6 +997,7 @@ pub fn run_search(cli: &Cli, params: &SearchParams) -> Result<(), LlmError> {
                 },
                 algorithm: AlgorithmOptions {
                     from_symbol_set: params.from_symbol_set.as_deref(),
+                    symbol_ids_file: params.symbol_ids_file.as_deref(),
                     reachable_from: params.reachable_from.as_deref(),
                     dead_code_in: params.dead_code_in.as_deref(),
                     in_cycle: params.in_cycle.as_deref(),
@@ -326,11 +1011,120 @@ pub fn run_search(cli: &Cli, params: &SearchParams) -> Result<(), LlmError> {
                 fqn_pattern: params.fqn.as_deref(),
                 exact_fqn: params.exact_fqn.as_deref(),
                 coverage_filter: None,
+                file_set: params.file_set.as_deref(),
+                max_per_file: params.max_per_file,
+                public_only: params.visibility == Some(Visibility::Public),
+                prefer: params.prefer,
             };
 
+            if params.count {
+                let (backends, _) = open_backends(opened, &db_paths, params.skip_corrupt)?;
+                let mut count = MatchCount {
+                    count: 0,
+                    partial: false,
+                };
+                for (path, backend) in backends {
+                    let (db_count, partial) = backend.count_symbols(SearchOptions {
+                        db_path: path,
+                        ..options.clone()
+                    })?;
+                    count.count += db_count;
+                    count.partial |= partial;
+                }
+                print_match_count(cli, count, wants_json)?;
+                if params.strict_complete && count.partial {
+                    return Err(LlmError::PartialResults { candidates });
+                }
+                return Ok(None);
+            }
+
             let query_start = std::time::Instant::now();
-            let (mut response, partial, paths_bounded) = backend.search_symbols(options)?;
-            let query_execution_ms = query_start.elapsed().as_millis() as u64;
+            let cache = if cli.cache_enabled() && !stream_rows {
+                QueryCache::default_location()
+            } else {
+                None
+            };
+            // The databases stay closed only when every one of them hits the cache
+            let cached: Option<Vec<(PathBuf, CachedSearch)>> = cache.as_ref().and_then(|cache| {
+                db_paths
+                    .iter()
+                    .map(|path| {
+                        let hit = cache.get(&SearchOptions {
+                            db_path: path,
+                            ..options.clone()
+                        })?;
+                        Some((path.clone(), hit))
+                    })
+                    .collect()
+            });
+            let mut backend_detection_ms = 0;
+            let (mut response, partial, paths_bounded) = match cached {
+                Some(hits) if multi_db => {
+                    let (response, partial) = search_symbols_across(
+                        &hits,
+                        params.skip_corrupt,
+                        &options,
+                        |_, (response, partial, _)| Ok((response.clone(), *partial)),
+                    )?;
+                    (response, partial, false)
+                }
+                Some(mut hits) => hits.remove(0).1,
+                None => {
+                    let (backends, detection_ms) =
+                        open_backends(opened, &db_paths, params.skip_corrupt)?;
+                    backend_detection_ms = detection_ms;
+                    if stream_rows {
+                        stream_symbols_ndjson(backends, params.skip_corrupt, &options)?
+                    } else if multi_db {
+                        let (response, partial) = search_symbols_across(
+                            backends,
+                            params.skip_corrupt,
+                            &options,
+                            |path, backend| {
+                                let (response, partial, _) = search_symbols_cached(
+                                    cache.as_ref(),
+                                    backend,
+                                    SearchOptions {
+                                        db_path: path,
+                                        ..options.clone()
+                                    },
+                                )?;
+                                Ok((response, partial))
+                            },
+                        )?;
+                        (response, partial, false)
+                    } else {
+                        search_symbols_cached(cache.as_ref(), &backends[0].1, options)?
+                    }
+                }
+            };
+            partial_results = partial;
+            if !include_score {
+                for result in &mut response.results {
+                    result.score = None;
+                }
+            }
+            if !wants_json {
+                if let Some(notice) = response.notice.take() {
+                    eprintln!("Warning: {notice}");
+                }
+            }
+            if !wants_json && cli.sanitize_enabled() {
+                response.escape_control_chars();
+            }
+            if params.with_complexity_percentile {
+                response.assign_complexity_percentiles();
+            }
+            if params.with_callers {
+                let (backends, _) = open_backends(opened, &db_paths, params.skip_corrupt)?;
+                attach_callers_across(backends, &mut response.results)?;
+            }
+            if min_viable {
+                let conn = open_connection(&db_path)?;
+                response.project_available_fields(capabilities(&conn)?.available_symbol_fields());
+            }
+            let query_execution_ms =
+                query_start.elapsed().as_millis() as u64 - backend_detection_ms;
 
             let scc_count: usize = response
                 .results
@@ -370,7 +1164,125 @@ pub fn run_search(cli: &Cli, params: &SearchParams) -> Result<(), LlmError> {
                 None
             };
 
-            output_symbols(cli, response, partial, scc_count, metrics.as_ref(), params.tokens)?;
+            if params.files_with_matches {
+                let files = FilesWithMatches::from_spans(
+                    response.results.iter().map(|r| &r.span),
+                    params.limit,
+                );
+                print_files_with_matches(cli, &files, wants_json)?;
+            } else if matches!(cli.output, OutputFormat::TimingsCsv) {
+                // Formatting cost is the JSON serialization of the response.
+                let _rendered = serde_json::to_string(&response)?;
+                collected = Some(Collected::Timing(QueryTiming {
+                    query: params.query.clone(),
+                    metrics: PerformanceMetrics {
+                        backend_detection_ms,
+                        query_execution_ms,
+                        output_formatting_ms: format_start.elapsed().as_millis() as u64,
+                        total_ms: total_start.elapsed().as_millis() as u64,
+                    },
+                    result_count: response.results.len(),
+                }));
+            } else if matches!(cli.output, OutputFormat::BatchResultsJson) {
+                collected = Some(Collected::Response(response, partial));
+            } else if let (OutputFormat::SliceDot, Some(seed)) = (cli.output, slice_seed) {
+                let symbol_ids: Vec<String> = response
+                    .results
+                    .iter()
+                    .filter_map(|r| r.symbol_id.clone())
+                    .collect();
+                let conn = open_connection(&db_path)?;
+                let edges = slice_edges(&conn, &symbol_ids)?;
+                print!("{}", render_slice_dot(&response.results, seed, &edges));
+            } else if matches!(cli.output, OutputFormat::GroupedBySupernodeJson) {
+                let conn = open_connection(&db_path)?;
+                let groups = group_supernodes(&conn, &response.results)?;
+                println!("{}", serde_json::to_string(&groups)?);
+            } else if snippets_only {
+                print!("{}", response.snippet_bundle());
+            } else if matches!(cli.output, OutputFormat::EntityIdsOnly) {
+                for symbol_id in response.entity_ids() {
+                    println!("{}", symbol_id);
+                }
+            } else if deadcode_csv {
+                print!("{}", response.deadcode_csv());
+            } else if fqn_list {
+                for fqn in response.fqn_list() {
+                    println!("{}", fqn);
+                }
+            } else if json_lines_typed {
+                print!(
+                    "{}",
+                    typed_json_lines(response.results.iter().map(TypedRecord::Symbol))?
+                );
+            } else if progressive {
+                print!("{}", progressive_json_lines(&response.results)?);
+            } else if matches!(cli.output, OutputFormat::Frequency) {
+                for (name, count) in response.name_frequency() {
+                    println!("{:>6} {}", count, name);
+                }
+            } else if matches!(cli.output, OutputFormat::Wide) {
+                print!("{}", response.wide_table());
+            } else if params.partition_by == Some(PartitionBy::Language) {
+                let groups = response.partition_by_language();
+                if wants_json {
+                    let rendered = render_json(cli, &groups)?;
+                    println!("{}", rendered);
+                } else {
+                    for (language, items) in &groups {
+                        println!("{} ({}):", language, items.len());
+                        for item in items {
+                            println!(
+                                "  {}:{}:{} {} {}",
+                                item.span.file_path,
+                                item.span.start_line,
+                                item.span.start_col,
+                                item.name,
+                                item.kind
+                            );
+                        }
+                    }
+                }
+            } else if params.score_histogram {
+                let histogram = response.score_histogram();
+                if wants_json {
+                    let map: serde_json::Map<String, serde_json::Value> = histogram
+                        .iter()
+                        .map(|(range, count)| (range.to_string(), (*count).into()))
+                        .collect();
+                    let rendered = render_json(cli, &map)?;
+                    println!("{}", rendered);
+                } else {
+                    let max = histogram.iter().map(|(_, c)| *c).max().unwrap_or(0).max(1);
+                    for (range, count) in &histogram {
+                        let bar = "#".repeat((count * 40).div_ceil(max));
+                        println!("{:>6} | {:<40} {}", range, bar, count);
+                    }
+                }
+            } else if params.distinct_names {
+                let names = response.distinct_names();
+                if wants_json {
+                    let rendered = render_json(cli, &names)?;
+                    println!("{}", rendered);
+                } else {
+                    for name in &names {
+                        println!("{}", name);
+                    }
+                }
+            } else if params.context_only {
+                let cards = response.context_cards();
+                let rendered = render_json(cli, &cards)?;
+                println!("{}", rendered);
+            } else {
+                output_symbols(
+                    cli,
+                    response,
+                    partial,
+                    scc_count,
+                    metrics.as_ref(),
+                    params.tokens,
+                )?;
+            }
 
             let output_formatting_ms = format_start.elapsed().as_millis() as u64;
             let total_ms = total_start.elapsed().as_millis() as u64;
@@ -384,23 +1296,51 @@ pub fn run_search(cli: &Cli, params: &SearchParams) -> Result<(), LlmError> {
             }
         }
         SearchMode::References => {
+            let (backends, backend_detection_ms) =
+                open_backends(opened, &db_paths, params.skip_corrupt)?;
+            let backend = &backends[0].1;
+            if let (OutputFormat::ResolvedImports, Some(path)) =
+                (cli.output, validated_path.as_ref())
+            {
+                let conn = open_connection(&db_path)?;
+                for (module, symbols) in resolved_imports(&conn, path)? {
+                    println!("{}", module);
+                    for symbol in symbols {
+                        println!("  {}", symbol);
+                    }
+                }
+                return Ok(None);
+            }
+
             let options = SearchOptions {
                 db_path: &db_path,
                 query: &params.query,
                 path_filter: validated_path.as_ref(),
-                kind_filter: None,
+                exclude_paths: &exclude_paths,
+                path_glob: params.path_glob,
+                kind_filter: params.reference_kind.as_deref(),
                 language_filter: None,
-                limit: params.limit,
+                limit: match_limit,
                 use_regex,
+                ignore_case: cli.ignore_case,
                 candidates,
                 context: ContextOptions {
                     include: include_context,
                     lines: params.context_lines,
+                    before: params.context_before,
+                    after: params.context_after,
                     max_lines: params.max_context_lines,
+                    exclude_self: params.context_exclude_self,
                 },
                 snippet: SnippetOptions {
                     include: include_snippet,
                     max_bytes: params.max_snippet_bytes,
+                    mode: snippet_mode,
+                    max_lines: params.snippet_lines.unwrap_or(0),
+                    line_numbers: params.snippet_line_numbers,
+                    line_text: false,
+                    dedupe: false,
+                    read_timeout,
                 },
                 fqn: FqnOptions::default(),
                 include_score,
@@ -413,10 +1353,34 @@ pub fn run_search(cli: &Cli, params: &SearchParams) -> Result<(), LlmError> {
                 fqn_pattern: None,
                 exact_fqn: None,
                 coverage_filter: None,
+                file_set: None,
+                max_per_file: params.max_per_file,
+                public_only: false,
+                prefer: None,
             };
 
             let query_start = std::time::Instant::now();
-            let (response, partial) = backend.search_references(options)?;
+            let (mut response, partial) = if stream_rows {
+                let conn = params.resolve_defs.then(|| open_connection(&db_path)).transpose()?;
+                let mut out = std::io::stdout().lock();
+                backend.stream_references(options, &mut |mut row| {
+                    if let Some(conn) = &conn {
+                        resolve_reference_definitions(conn, std::slice::from_mut(&mut row))?;
+                    }
+                    Ok(write_ndjson_line(&mut out, &row)?)
+                })?
+            } else {
+                let (mut response, partial) = backend.search_references(options)?;
+                if params.resolve_defs {
+                    let conn = open_connection(&db_path)?;
+                    resolve_reference_definitions(&conn, &mut response.results)?;
+                }
+                (response, partial)
+            };
+            partial_results = partial;
+            if !wants_json && cli.sanitize_enabled() {
+                response.escape_control_chars();
+            }
             let query_execution_ms = query_start.elapsed().as_millis() as u64;
 
             let format_start = std::time::Instant::now();
@@ -431,7 +1395,20 @@ pub fn run_search(cli: &Cli, params: &SearchParams) -> Result<(), LlmError> {
                 None
             };
 
-            output_references(cli, response, partial, metrics.as_ref(), params.tokens)?;
+            if params.files_with_matches {
+                let files = FilesWithMatches::from_spans(
+                    response.results.iter().map(|r| &r.span),
+                    params.limit,
+                );
+                print_files_with_matches(cli, &files, wants_json)?;
+            } else if json_lines_typed {
+                print!(
+                    "{}",
+                    typed_json_lines(response.results.iter().map(TypedRecord::Reference))?
+                );
+            } else {
+                output_references(cli, response, partial, metrics.as_ref(), params.tokens)?;
+            }
 
             let output_formatting_ms = format_start.elapsed().as_millis() as u64;
             let total_ms = total_start.elapsed().as_millis() as u64;
@@ -445,23 +1422,49 @@ pub fn run_search(cli: &Cli, params: &SearchParams) -> Result<(), LlmError> {
             }
         }
         SearchMode::Calls => {
+            let (backends, backend_detection_ms) =
+                open_backends(opened, &db_paths, params.skip_corrupt)?;
+            let backend = &backends[0].1;
+            if let (OutputFormat::FlamegraphCallgraph, Some(entry)) =
+                (cli.output, params.paths_from.as_deref())
+            {
+                let conn = open_connection(&db_path)?;
+                let calls = call_site_counts(&conn, validated_path.as_ref())?;
+                for (stack, count) in folded_call_stacks(&calls, entry, MAX_FOLDED_DEPTH) {
+                    println!("{} {}", stack, count);
+                }
+                return Ok(None);
+            }
+
             let options = SearchOptions {
                 db_path: &db_path,
                 query: &params.query,
                 path_filter: validated_path.as_ref(),
+                exclude_paths: &exclude_paths,
+                path_glob: params.path_glob,
                 kind_filter: None,
                 language_filter: None,
-                limit: params.limit,
+                limit: match_limit,
                 use_regex,
+                ignore_case: cli.ignore_case,
                 candidates,
                 context: ContextOptions {
                     include: include_context,
                     lines: params.context_lines,
+                    before: params.context_before,
+                    after: params.context_after,
                     max_lines: params.max_context_lines,
+                    exclude_self: params.context_exclude_self,
                 },
                 snippet: SnippetOptions {
                     include: include_snippet,
                     max_bytes: params.max_snippet_bytes,
+                    mode: snippet_mode,
+                    max_lines: params.snippet_lines.unwrap_or(0),
+                    line_numbers: params.snippet_line_numbers,
+                    line_text: false,
+                    dedupe: false,
+                    read_timeout,
                 },
                 fqn: FqnOptions::default(),
                 include_score,
@@ -474,10 +1477,34 @@ pub fn run_search(cli: &Cli, params: &SearchParams) -> Result<(), LlmError> {
                 fqn_pattern: None,
                 exact_fqn: None,
                 coverage_filter: None,
+                file_set: None,
+                max_per_file: params.max_per_file,
+                public_only: false,
+                prefer: None,
             };
 
             let query_start = std::time::Instant::now();
-            let (response, partial) = backend.search_calls(options)?;
+            let (mut response, partial) = if stream_rows {
+                let conn = params.resolve_callees.then(|| open_connection(&db_path)).transpose()?;
+                let mut out = std::io::stdout().lock();
+                backend.stream_calls(options, &mut |mut row| {
+                    if let Some(conn) = &conn {
+                        resolve_callee_definitions(conn, std::slice::from_mut(&mut row))?;
+                    }
+                    Ok(write_ndjson_line(&mut out, &row)?)
+                })?
+            } else {
+                let (mut response, partial) = backend.search_calls(options)?;
+                if params.resolve_callees {
+                    let conn = open_connection(&db_path)?;
+                    resolve_callee_definitions(&conn, &mut response.results)?;
+                }
+                (response, partial)
+            };
+            partial_results = partial;
+            if !wants_json && cli.sanitize_enabled() {
+                response.escape_control_chars();
+            }
             let query_execution_ms = query_start.elapsed().as_millis() as u64;
 
             let format_start = std::time::Instant::now();
@@ -492,7 +1519,22 @@ pub fn run_search(cli: &Cli, params: &SearchParams) -> Result<(), LlmError> {
                 None
             };
 
-            output_calls(cli, response, partial, metrics.as_ref(), params.tokens)?;
+            if params.files_with_matches {
+                let files = FilesWithMatches::from_spans(
+                    response.results.iter().map(|r| &r.span),
+                    params.limit,
+                );
+                print_files_with_matches(cli, &files, wants_json)?;
+            } else if json_lines_typed {
+                print!(
+                    "{}",
+                    typed_json_lines(response.results.iter().map(TypedRecord::Call))?
+                );
+            } else if matches!(cli.output, OutputFormat::Matrix) {
+                println!("{}", serde_json::to_string(&response.call_matrix())?);
+            } else {
+                output_calls(cli, response, partial, metrics.as_ref(), params.tokens)?;
+            }
 
             let output_formatting_ms = format_start.elapsed().as_millis() as u64;
             let total_ms = total_start.elapsed().as_millis() as u64;
@@ -506,6 +1548,9 @@ pub fn run_search(cli: &Cli, params: &SearchParams) -> Result<(), LlmError> {
             }
         }
         SearchMode::Auto => {
+            let (backends, backend_detection_ms) =
+                open_backends(opened, &db_paths, params.skip_corrupt)?;
+            let backend = &backends[0].1;
             if !wants_json {
                 return Err(LlmError::InvalidQuery {
                     query: "auto mode requires JSON output".to_string(),
@@ -516,28 +1561,44 @@ pub fn run_search(cli: &Cli, params: &SearchParams) -> Result<(), LlmError> {
                 AutoLimitMode::Global => split_auto_limit(params.limit),
             };
 
-            let (symbols, symbols_partial, _) = backend.search_symbols(SearchOptions {
+            // All three queries run against the backend opened above, so auto mode
+            // reuses one connection instead of reopening the database per mode.
+            let (mut symbols, symbols_partial, _) = backend.search_symbols(SearchOptions {
                 db_path: &db_path,
                 query: &params.query,
                 path_filter: validated_path.as_ref(),
+                exclude_paths: &exclude_paths,
+                path_glob: params.path_glob,
                 kind_filter: normalized_kind.as_deref(),
                 language_filter: normalized_language.as_deref(),
                 limit: symbols_limit,
                 use_regex,
+                ignore_case: cli.ignore_case,
                 candidates,
                 context: ContextOptions {
                     include: include_context,
                     lines: params.context_lines,
+                    before: params.context_before,
+                    after: params.context_after,
                     max_lines: params.max_context_lines,
+                    exclude_self: params.context_exclude_self,
                 },
                 snippet: SnippetOptions {
                     include: include_snippet,
                     max_bytes: params.max_snippet_bytes,
+                    mode: snippet_mode,
+                    max_lines: params.snippet_lines.unwrap_or(0),
+                    line_numbers: params.snippet_line_numbers,
+                    line_text: include_line_text,
+                    dedupe: params.dedupe,
+                    read_timeout,
                 },
                 fqn: FqnOptions {
                     fqn: include_fqn,
                     canonical_fqn: include_canonical_fqn,
                     display_fqn: include_display_fqn,
+                    normalize: params.normalize_fqn,
+                    defined_in: include_defined_in,
                 },
                 include_score,
                 sort_by: params.sort_by,
@@ -548,6 +1609,7 @@ pub fn run_search(cli: &Cli, params: &SearchParams) -> Result<(), LlmError> {
                         .map(|k| k.split(',').map(|s| s.trim().to_string()).collect())
                         .unwrap_or_default(),
                     with_ast_context: params.with_ast_context,
+                    with_ast_kind: params.with_ast_kind,
                     _phantom: std::marker::PhantomData,
                 },
                 depth: DepthOptions {
@@ -561,24 +1623,40 @@ pub fn run_search(cli: &Cli, params: &SearchParams) -> Result<(), LlmError> {
                 fqn_pattern: params.fqn.as_deref(),
                 exact_fqn: params.exact_fqn.as_deref(),
                 coverage_filter: None,
+                file_set: None,
+                max_per_file: params.max_per_file,
+                public_only: false,
+                prefer: None,
             })?;
-            let (references, refs_partial) = backend.search_references(SearchOptions {
+            let (mut references, refs_partial) = backend.search_references(SearchOptions {
                 db_path: &db_path,
                 query: &params.query,
                 path_filter: validated_path.as_ref(),
+                exclude_paths: &exclude_paths,
+                path_glob: params.path_glob,
                 kind_filter: None,
                 language_filter: None,
                 limit: references_limit,
                 use_regex,
+                ignore_case: cli.ignore_case,
                 candidates,
                 context: ContextOptions {
                     include: include_context,
                     lines: params.context_lines,
+                    before: params.context_before,
+                    after: params.context_after,
                     max_lines: params.max_context_lines,
+                    exclude_self: params.context_exclude_self,
                 },
                 snippet: SnippetOptions {
                     include: include_snippet,
                     max_bytes: params.max_snippet_bytes,
+                    mode: snippet_mode,
+                    max_lines: params.snippet_lines.unwrap_or(0),
+                    line_numbers: params.snippet_line_numbers,
+                    line_text: false,
+                    dedupe: false,
+                    read_timeout,
                 },
                 fqn: FqnOptions::default(),
                 include_score,
@@ -591,24 +1669,40 @@ pub fn run_search(cli: &Cli, params: &SearchParams) -> Result<(), LlmError> {
                 fqn_pattern: None,
                 exact_fqn: None,
                 coverage_filter: None,
+                file_set: None,
+                max_per_file: params.max_per_file,
+                public_only: false,
+                prefer: None,
             })?;
-            let (calls, calls_partial) = backend.search_calls(SearchOptions {
+            let (mut calls, calls_partial) = backend.search_calls(SearchOptions {
                 db_path: &db_path,
                 query: &params.query,
                 path_filter: validated_path.as_ref(),
+                exclude_paths: &exclude_paths,
+                path_glob: params.path_glob,
                 kind_filter: None,
                 language_filter: None,
                 limit: calls_limit,
                 use_regex,
+                ignore_case: cli.ignore_case,
                 candidates,
                 context: ContextOptions {
                     include: include_context,
                     lines: params.context_lines,
+                    before: params.context_before,
+                    after: params.context_after,
                     max_lines: params.max_context_lines,
+                    exclude_self: params.context_exclude_self,
                 },
                 snippet: SnippetOptions {
                     include: include_snippet,
                     max_bytes: params.max_snippet_bytes,
+                    mode: snippet_mode,
+                    max_lines: params.snippet_lines.unwrap_or(0),
+                    line_numbers: params.snippet_line_numbers,
+                    line_text: false,
+                    dedupe: false,
+                    read_timeout,
                 },
                 fqn: FqnOptions::default(),
                 include_score,
@@ -621,9 +1715,18 @@ pub fn run_search(cli: &Cli, params: &SearchParams) -> Result<(), LlmError> {
                 fqn_pattern: None,
                 exact_fqn: None,
                 coverage_filter: None,
+                file_set: None,
+                max_per_file: params.max_per_file,
+                public_only: false,
+                prefer: None,
             })?;
+            if !wants_json && cli.sanitize_enabled() {
+                symbols.escape_control_chars();
+                references.escape_control_chars();
+                calls.escape_control_chars();
+            }
             let total_count = symbols.total_count + references.total_count + calls.total_count;
-            let combined = CombinedSearchResponse {
+            let mut combined = CombinedSearchResponse {
                 query: params.query.to_string(),
                 path_filter: validated_path
                     .as_ref()
@@ -636,8 +1739,10 @@ pub fn run_search(cli: &Cli, params: &SearchParams) -> Result<(), LlmError> {
                     AutoLimitMode::PerMode => "per-mode".to_string(),
                     AutoLimitMode::Global => "global".to_string(),
                 },
+                omitted: None,
             };
             let partial = symbols_partial || refs_partial || calls_partial;
+            partial_results = partial;
 
             let query_execution_ms =
                 total_start.elapsed().as_millis() as u64 - backend_detection_ms;
@@ -654,13 +1759,40 @@ pub fn run_search(cli: &Cli, params: &SearchParams) -> Result<(), LlmError> {
                 None
             };
 
-            let payload = json_response_with_partial_and_performance(combined, partial, metrics);
-            let rendered = if matches!(cli.output, OutputFormat::Pretty) {
-                serde_json::to_string_pretty(&payload)
+            // Budget the combined result set, shrinking every mode proportionally
+            let tokens_estimated = params.tokens.filter(|&limit| limit > 0).map(|limit| {
+                combined.truncate_to_tokens(limit, |c| {
+                    let payload = json_response_with_partial_and_performance(
+                        c.clone(),
+                        partial,
+                        metrics.clone(),
+                    );
+                    render_json(cli, &payload).unwrap_or_default()
+                })
+            });
+            let truncated = combined.omitted.map(|_| true);
+
+            if json_lines_typed {
+                print!("{}", typed_json_lines(combined.typed_records())?);
             } else {
-                serde_json::to_string(&payload)
-            }?;
-            println!("{}", rendered);
+                let rendered = if params.flatten {
+                    let mut payload = json_response_with_partial_and_performance(
+                        combined.flatten(),
+                        partial,
+                        metrics,
+                    );
+                    payload.tokens_estimated = tokens_estimated;
+                    payload.truncated = truncated;
+                    render_json(cli, &payload)?
+                } else {
+                    let mut payload =
+                        json_response_with_partial_and_performance(combined, partial, metrics);
+                    payload.tokens_estimated = tokens_estimated;
+                    payload.truncated = truncated;
+                    render_json(cli, &payload)?
+                };
+                println!("{}", rendered);
+            }
 
             let output_formatting_ms = format_start.elapsed().as_millis() as u64;
             let total_ms = total_start.elapsed().as_millis() as u64;
@@ -674,6 +1806,9 @@ pub fn run_search(cli: &Cli, params: &SearchParams) -> Result<(), LlmError> {
             }
         }
         SearchMode::Labels => {
+            let (backends, backend_detection_ms) =
+                open_backends(opened, &db_paths, params.skip_corrupt)?;
+            let backend = &backends[0].1;
             let label_name = params.label.clone().unwrap_or("test".to_string());
 
             let query_start = std::time::Instant::now();
@@ -682,6 +1817,7 @@ pub fn run_search(cli: &Cli, params: &SearchParams) -> Result<(), LlmError> {
             })?;
             let (response, partial, _paths_bounded) =
                 backend.search_by_label(&label_name, params.limit, db_path_str)?;
+            partial_results = partial;
             let query_execution_ms = query_start.elapsed().as_millis() as u64;
 
             let format_start = std::time::Instant::now();
@@ -710,23 +1846,38 @@ pub fn run_search(cli: &Cli, params: &SearchParams) -> Result<(), LlmError> {
             }
         }
         SearchMode::Implements => {
+            let (backends, backend_detection_ms) =
+                open_backends(opened, &db_paths, params.skip_corrupt)?;
+            let backend = &backends[0].1;
             let options = SearchOptions {
                 db_path: &db_path,
                 query: &params.query,
                 path_filter: validated_path.as_ref(),
+                exclude_paths: &[],
+                path_glob: params.path_glob,
                 kind_filter: None,
                 language_filter: None,
                 limit: params.limit,
                 use_regex,
+                ignore_case: cli.ignore_case,
                 candidates,
                 context: ContextOptions {
                     include: include_context,
                     lines: params.context_lines,
+                    before: params.context_before,
+                    after: params.context_after,
                     max_lines: params.max_context_lines,
+                    exclude_self: params.context_exclude_self,
                 },
                 snippet: SnippetOptions {
                     include: include_snippet,
                     max_bytes: params.max_snippet_bytes,
+                    mode: snippet_mode,
+                    max_lines: params.snippet_lines.unwrap_or(0),
+                    line_numbers: params.snippet_line_numbers,
+                    line_text: false,
+                    dedupe: false,
+                    read_timeout,
                 },
                 fqn: FqnOptions::default(),
                 include_score,
@@ -739,10 +1890,15 @@ pub fn run_search(cli: &Cli, params: &SearchParams) -> Result<(), LlmError> {
                 fqn_pattern: None,
                 exact_fqn: None,
                 coverage_filter: None,
+                file_set: None,
+                max_per_file: None,
+                public_only: false,
+                prefer: None,
             };
 
             let query_start = std::time::Instant::now();
             let (response, partial) = backend.search_implements(options)?;
+            partial_results = partial;
             let query_execution_ms = query_start.elapsed().as_millis() as u64;
 
             let format_start = std::time::Instant::now();
@@ -771,6 +1927,9 @@ pub fn run_search(cli: &Cli, params: &SearchParams) -> Result<(), LlmError> {
             }
         }
         SearchMode::Docs => {
+            let (backends, backend_detection_ms) =
+                open_backends(opened, &db_paths, params.skip_corrupt)?;
+            let backend = &backends[0].1;
             let docs_options = llmgrep::query::DocsSearchOptions {
                 db_path: &db_path,
                 limit: params.limit,
@@ -811,6 +1970,9 @@ pub fn run_search(cli: &Cli, params: &SearchParams) -> Result<(), LlmError> {
             }
         }
         SearchMode::Facts => {
+            let (backends, backend_detection_ms) =
+                open_backends(opened, &db_paths, params.skip_corrupt)?;
+            let backend = &backends[0].1;
             let facts_options = llmgrep::query::FactsSearchOptions {
                 db_path: &db_path,
                 limit: params.limit,
@@ -851,6 +2013,7 @@ pub fn run_search(cli: &Cli, params: &SearchParams) -> Result<(), LlmError> {
             }
         }
         SearchMode::Semantic => {
+            let (_, backend_detection_ms) = open_backends(opened, &db_paths, params.skip_corrupt)?;
             let semantic_options = llmgrep::query::SemanticSearchOptions {
                 db_path: &db_path,
                 query: &params.query,
@@ -889,5 +2052,8 @@ pub fn run_search(cli: &Cli, params: &SearchParams) -> Result<(), LlmError> {
         }
     }
 
-    Ok(())
+    if params.strict_complete && partial_results {
+        return Err(LlmError::PartialResults { candidates });
+    }
+    Ok(collected)
 }
@@ -1,15 +1,16 @@
 use crate::cli::{resolve_db_path, Cli};
+use llmgrep::backend::open_connection;
 use llmgrep::error::LlmError;
 use llmgrep::output::{json_response, OutputFormat};
 
 pub fn run_stats_cmd(cli: &Cli) -> Result<(), LlmError> {
     let db_path = resolve_db_path(cli)?;
-    let conn = rusqlite::Connection::open(&db_path)?;
+    let conn = open_connection(&db_path)?;
     llmgrep::backend::schema_check::check_schema_version(&conn)
         .map_err(|e| LlmError::SchemaMismatch { reason: e })?;
 
     let response = llmgrep::query::run_stats(&conn, &db_path)?;
-    let wants_json = matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty);
+    let wants_json = !matches!(cli.output, OutputFormat::Human);
 
     if wants_json {
         let wrapped = json_response(&response);
@@ -25,12 +26,30 @@ pub fn run_stats_cmd(cli: &Cli) -> Result<(), LlmError> {
             println!("Schema version: {}", v);
         }
         println!();
-        println!("Symbols: {}", response.symbols.total);
-        if !response.symbols.by_kind.is_empty() {
-            for (kind, count) in &response.symbols.by_kind {
-                println!("  {}: {}", kind, count);
+        let composition = &response.composition;
+        println!("Composition:");
+        println!("  Files: {}", composition.files);
+        println!("  References: {}", composition.references);
+        println!("  Calls: {}", composition.calls);
+        if !composition.symbols_by_language.is_empty() {
+            println!("  Symbols by language:");
+            for (language, count) in &composition.symbols_by_language {
+                println!("    {}: {}", language, count);
             }
         }
+        let present = |exists: bool| if exists { "present" } else { "missing" };
+        println!(
+            "  symbol_metrics: {} rows, ast_nodes: {}, code_chunks: {}",
+            composition.metrics_rows,
+            present(composition.has_ast_nodes),
+            present(composition.has_code_chunks)
+        );
+        println!();
+        // Counted from the graph, so symbols without metrics rows are included
+        println!("Symbols: {}", composition.symbols);
+        for (kind, count) in &composition.symbols_by_kind {
+            println!("  {}: {}", kind, count);
+        }
         println!("  Avg fan-in: {:.1}", response.symbols.avg_fan_in);
         println!("  Avg complexity: {:.1}", response.symbols.avg_complexity);
         println!();
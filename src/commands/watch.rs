@@ -1,11 +1,11 @@
 use crate::cli::{resolve_db_path, validate_path, Cli, SearchMode};
 use llmgrep::algorithm::AlgorithmOptions;
 use llmgrep::error::LlmError;
-use llmgrep::output::OutputFormat;
 use llmgrep::query::{
     AstOptions, ContextOptions, DepthOptions, FqnOptions, MetricsOptions, SearchOptions,
     SnippetOptions,
 };
+use llmgrep::watch_cmd::WatchEmit;
 use std::path::PathBuf;
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
@@ -19,6 +19,7 @@ pub fn run_watch(
     kind: &Option<String>,
     limit: usize,
     regex: bool,
+    emit: WatchEmit,
 ) -> Result<(), LlmError> {
     let db_path = resolve_db_path(cli)?;
 
@@ -43,10 +44,13 @@ pub fn run_watch(
         db_path: &db_path,
         query,
         path_filter: validated_path.as_ref(),
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: kind.as_deref(),
         language_filter: None,
         limit,
         use_regex: regex,
+        ignore_case: cli.ignore_case,
         candidates: 1000,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -57,6 +61,7 @@ pub fn run_watch(
         ast: AstOptions {
             ast_kinds: Vec::new(),
             with_ast_context: false,
+            with_ast_kind: false,
             _phantom: std::marker::PhantomData,
         },
         depth: DepthOptions {
@@ -67,6 +72,7 @@ pub fn run_watch(
         },
         algorithm: AlgorithmOptions {
             from_symbol_set: None,
+            symbol_ids_file: None,
             reachable_from: None,
             dead_code_in: None,
             in_cycle: None,
@@ -80,6 +86,10 @@ pub fn run_watch(
         fqn_pattern: None,
         exact_fqn: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let shutdown = Arc::new(AtomicBool::new(false));
@@ -95,7 +105,7 @@ pub fn run_watch(
         let _ = sig_flag;
     }
 
-    llmgrep::watch_cmd::run_watch(db_path.clone(), options, cli.output, shutdown).map_err(|e| {
+    llmgrep::watch_cmd::run_watch(db_path.clone(), options, cli.output, emit, shutdown).map_err(|e| {
         LlmError::SearchFailed {
             reason: e.to_string(),
         }
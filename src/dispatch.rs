@@ -12,10 +12,18 @@ pub fn command_name(cli: &Cli) -> &str {
             Command::FindAst { .. } => "find-ast",
             Command::Complete { .. } => "complete",
             Command::Lookup { .. } => "lookup",
+            Command::Card { .. } => "card",
+            Command::EditScript { .. } => "edit-script",
+            Command::Annotate { .. } => "annotate",
             Command::Explore { .. } => "explore",
             Command::Navigate { .. } => "navigate",
             Command::Stats => "stats",
+            Command::Capabilities => "capabilities",
+            Command::Probe => "probe",
+            Command::Schema => "schema",
+            Command::Cache { .. } => "cache",
             Command::Evolve { .. } => "evolve",
+            Command::Diff { .. } => "diff",
             #[cfg(feature = "unstable-watch")]
             Command::Watch { .. } => "watch",
             Command::VectorCreate { .. } => "vector-create",
@@ -40,7 +48,7 @@ pub fn dispatch(cli: &Cli) -> Result<(), LlmError> {
             BackendFormat::Sqlite => "sqlite",
         };
 
-        if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+        if !matches!(cli.output, OutputFormat::Human) {
             use serde_json::json;
             let output = json!({
                 "backend": backend_str,
@@ -61,23 +69,41 @@ pub fn dispatch(cli: &Cli) -> Result<(), LlmError> {
             Command::Ast {
                 file,
                 position,
+                fqn,
                 limit,
-            } => commands::run_ast(cli, file, *position, *limit),
+                semantic_only,
+                max_tree_depth,
+            } => commands::run_ast(
+                cli,
+                file.as_deref(),
+                *position,
+                fqn.as_deref(),
+                *limit,
+                *semantic_only,
+                *max_tree_depth,
+            ),
+
+            Command::FindAst { kind, with_path } => commands::run_find_ast(cli, kind, *with_path),
+
+            Command::Complete {
+                prefix,
+                limit,
+                segment,
+            } => commands::run_complete(cli, prefix.clone(), *limit, *segment),
 
-            Command::FindAst { kind } => commands::run_find_ast(cli, kind),
+            Command::Lookup { fqn } => commands::run_lookup(cli, fqn),
 
-            Command::Complete { prefix, limit } => {
-                commands::run_complete(cli, prefix.clone(), *limit)
-            }
+            Command::Card { fqn } => commands::run_card(cli, fqn),
 
-            Command::Lookup { fqn } => commands::run_lookup(cli, fqn),
+            Command::EditScript { fqn, rename } => commands::run_edit_script(cli, fqn, rename),
+
+            Command::Annotate { file, heat } => commands::run_annotate(cli, file, *heat),
 
             Command::Explore { intent, limit } => {
                 let validated_db = resolve_db_path(cli)?;
                 let output = match cli.output {
-                    OutputFormat::Human => llmgrep::output::OutputFormat::Human,
-                    OutputFormat::Json => llmgrep::output::OutputFormat::Json,
-                    OutputFormat::Pretty => llmgrep::output::OutputFormat::Pretty,
+                    OutputFormat::Human | OutputFormat::Pretty => cli.output,
+                    _ => OutputFormat::Json,
                 };
                 llmgrep::query::run_explore(&validated_db, intent, *limit, output).map_err(|e| {
                     LlmError::InvalidQuery {
@@ -92,13 +118,13 @@ pub fn dispatch(cli: &Cli) -> Result<(), LlmError> {
                 edges,
                 callers,
                 callees,
+                caller_tree,
                 depth,
             } => {
                 let validated_db = resolve_db_path(cli)?;
                 let output = match cli.output {
-                    OutputFormat::Human => llmgrep::output::OutputFormat::Human,
-                    OutputFormat::Json => llmgrep::output::OutputFormat::Json,
-                    OutputFormat::Pretty => llmgrep::output::OutputFormat::Pretty,
+                    OutputFormat::Human | OutputFormat::Pretty => cli.output,
+                    _ => OutputFormat::Json,
                 };
                 llmgrep::query::navigate::run_navigate(
                     &validated_db,
@@ -107,6 +133,7 @@ pub fn dispatch(cli: &Cli) -> Result<(), LlmError> {
                     *edges,
                     *callers,
                     *callees,
+                    *caller_tree,
                     *depth,
                     output,
                 )
@@ -119,6 +146,12 @@ pub fn dispatch(cli: &Cli) -> Result<(), LlmError> {
 
             Command::Stats => commands::run_stats_cmd(cli),
 
+            Command::Capabilities => commands::run_capabilities_cmd(cli),
+
+            Command::Probe => commands::run_probe(cli),
+            Command::Schema => commands::run_schema(cli),
+            Command::Cache { action } => commands::run_cache(cli, action),
+
             Command::Evolve {
                 query,
                 min_score,
@@ -126,6 +159,8 @@ pub fn dispatch(cli: &Cli) -> Result<(), LlmError> {
                 limit,
             } => commands::run_evolve_cmd(cli, query, *min_score, *dry_run, *limit),
 
+            Command::Diff { baseline, metric } => commands::run_diff_cmd(cli, baseline, *metric),
+
             #[cfg(feature = "unstable-watch")]
             Command::Watch {
                 query,
@@ -134,7 +169,8 @@ pub fn dispatch(cli: &Cli) -> Result<(), LlmError> {
                 kind,
                 limit,
                 regex,
-            } => commands::run_watch(cli, query, *mode, path, kind, *limit, *regex),
+                emit,
+            } => commands::run_watch(cli, query, mode, path, kind, *limit, *regex, *emit),
             Command::VectorCreate { name, dim } => commands::run_vector_create(name, *dim),
             Command::VectorSearch {
                 query,
@@ -1,12 +1,16 @@
 use crate::cli::Cli;
 use llmgrep::error::LlmError;
 use llmgrep::output::{
-    json_response_with_partial_and_performance, CallMatch, CallSearchResponse, DocsMatch,
-    DocsSearchResponse, FactMatch, FactsSearchResponse, ImplementsMatch,
-    ImplementsSearchResponse, OutputFormat, PerformanceMetrics, ReferenceMatch,
-    ReferenceSearchResponse, SearchResponse, SemanticMatch, SemanticSearchResponse, SymbolMatch,
+    compact_spans, json_response_with_partial_and_performance, vimgrep_line, write_ndjson, CallMatch,
+    CallSearchResponse, DocsMatch, DocsSearchResponse, FactMatch, FactsSearchResponse,
+    ImplementsMatch, ImplementsSearchResponse, NdjsonSummary, OutputFormat, PerformanceMetrics,
+    QuickfixItem, ReferenceMatch, ReferenceSearchResponse, SearchResponse, SemanticMatch,
+    SemanticSearchResponse, SymbolMatch,
+};
+use llmgrep::output_common::{
+    format_location, format_location_styled, format_partial_footer, format_total_header, paint,
+    Style,
 };
-use llmgrep::output_common::{format_partial_footer, format_total_header};
 
 pub fn format_scc_summary(count: usize, supernode_count: usize) -> String {
     if supernode_count == 1 {
@@ -16,6 +20,36 @@ pub fn format_scc_summary(count: usize, supernode_count: usize) -> String {
     }
 }
 
+/// Print a Neovim quickfix list as a single-line JSON array.
+fn print_quickfix(items: &[QuickfixItem]) -> Result<(), LlmError> {
+    println!("{}", serde_json::to_string(items)?);
+    Ok(())
+}
+
+/// Serialize a JSON payload, pretty-printed for `--output pretty` and with
+/// spans rewritten by [`compact_spans`] under `--compact`.
+pub(crate) fn render_json<T: serde::Serialize>(
+    cli: &Cli,
+    value: &T,
+) -> Result<String, serde_json::Error> {
+    let pretty = matches!(cli.output, OutputFormat::Pretty);
+    if !cli.compact {
+        return if pretty {
+            serde_json::to_string_pretty(value)
+        } else {
+            serde_json::to_string(value)
+        };
+    }
+    let mut json = serde_json::to_value(value)?;
+    let (keep_span_id, keep_match_id) = cli.compact_keeps_ids();
+    compact_spans(&mut json, keep_span_id, keep_match_id);
+    if pretty {
+        serde_json::to_string_pretty(&json)
+    } else {
+        serde_json::to_string(&json)
+    }
+}
+
 /// Generic helper to prune results vector to fit token budget
 pub(crate) fn truncate_response<T: Clone, F>(
     mut results: Vec<T>,
@@ -64,7 +98,9 @@ pub fn output_symbols(
 
     match cli.output {
         OutputFormat::Human => {
-            let format_fn = |items: &[SymbolMatch]| {
+            let hyperlinks = cli.hyperlinks_enabled();
+            let color = cli.color_enabled();
+            let format_with = |items: &[SymbolMatch], color: bool| {
                 let mut human_out = String::new();
                 if scc_count > 0 {
                     human_out.push_str(&format_scc_summary(response.total_count as usize, scc_count));
@@ -86,15 +122,25 @@ pub fn output_symbols(
                             )
                         })
                         .unwrap_or_default();
+                    let source_db_str = item
+                        .source_db
+                        .as_ref()
+                        .map(|db| format!(" [db={}]", db))
+                        .unwrap_or_default();
                     human_out.push_str(&format!(
-                        "{}:{}:{} {} {} score={}{}\n",
-                        item.span.file_path,
-                        item.span.start_line,
-                        item.span.start_col,
-                        item.name,
+                        "{} {} {} {}{}{}\n",
+                        format_location_styled(
+                            &item.span.file_path,
+                            item.span.start_line,
+                            item.span.start_col,
+                            hyperlinks,
+                            color,
+                        ),
+                        paint(&item.name, Style::Name, color),
                         item.kind,
-                        item.score.unwrap_or(0),
-                        coverage_str
+                        paint(&format!("score={}", item.score.unwrap_or(0)), Style::Dim, color),
+                        coverage_str,
+                        source_db_str
                     ));
                 }
                 if partial {
@@ -104,8 +150,10 @@ pub fn output_symbols(
                 human_out
             };
 
-            let (pruned_results, _tokens_est, truncated) = truncate_response(results, tokens, format_fn);
-            let final_output = format_fn(&pruned_results);
+            // Budget tokens on the plain text so escapes never cost results
+            let (pruned_results, _tokens_est, truncated) =
+                truncate_response(results, tokens, |items| format_with(items, false));
+            let final_output = format_with(&pruned_results, color);
             print!("{}", final_output);
             if truncated {
                 if let Some(limit) = tokens {
@@ -113,16 +161,27 @@ pub fn output_symbols(
                 }
             }
         }
-        OutputFormat::Json | OutputFormat::Pretty => {
+        OutputFormat::Quickfix => print_quickfix(&response.quickfix_items())?,
+        OutputFormat::Vimgrep => {
+            for r in &response.results {
+                let label = format!("{} {}", r.name, r.kind);
+                println!("{}", vimgrep_line(&r.span, r.snippet.as_deref(), &label));
+            }
+        }
+        OutputFormat::Ndjson => write_ndjson(
+            &mut std::io::stdout().lock(),
+            &response.results,
+            NdjsonSummary {
+                total_count: response.total_count,
+                partial,
+            },
+        )?,
+        _ => {
             let format_fn = |items: &[SymbolMatch]| {
                 let mut temp_resp = response.clone();
                 temp_resp.results = items.to_vec();
                 let json_response = json_response_with_partial_and_performance(temp_resp, partial, metrics.cloned());
-                if matches!(cli.output, OutputFormat::Pretty) {
-                    serde_json::to_string_pretty(&json_response).unwrap_or_default()
-                } else {
-                    serde_json::to_string(&json_response).unwrap_or_default()
-                }
+                render_json(cli, &json_response).unwrap_or_default()
             };
 
             let (pruned_results, tokens_est, truncated) = truncate_response(results, tokens, format_fn);
@@ -135,11 +194,7 @@ pub fn output_symbols(
                 json_response.truncated = Some(true);
             }
 
-            let rendered = if matches!(cli.output, OutputFormat::Pretty) {
-                serde_json::to_string_pretty(&json_response)?
-            } else {
-                serde_json::to_string(&json_response)?
-            };
+            let rendered = render_json(cli, &json_response)?;
             println!("{}", rendered);
         }
     }
@@ -157,18 +212,24 @@ pub fn output_references(
 
     match cli.output {
         OutputFormat::Human => {
-            let format_fn = |items: &[ReferenceMatch]| {
+            let hyperlinks = cli.hyperlinks_enabled();
+            let color = cli.color_enabled();
+            let format_with = |items: &[ReferenceMatch], color: bool| {
                 let mut human_out = String::new();
                 human_out.push_str(&format_total_header(response.total_count));
                 human_out.push('\n');
                 for item in items {
                     human_out.push_str(&format!(
-                        "{}:{}:{} {} score={}\n",
-                        item.span.file_path,
-                        item.span.start_line,
-                        item.span.start_col,
-                        item.referenced_symbol,
-                        item.score.unwrap_or(0)
+                        "{} {} {}\n",
+                        format_location_styled(
+                            &item.span.file_path,
+                            item.span.start_line,
+                            item.span.start_col,
+                            hyperlinks,
+                            color,
+                        ),
+                        paint(&item.referenced_symbol, Style::Name, color),
+                        paint(&format!("score={}", item.score.unwrap_or(0)), Style::Dim, color)
                     ));
                 }
                 if partial {
@@ -178,8 +239,9 @@ pub fn output_references(
                 human_out
             };
 
-            let (pruned_results, _tokens_est, truncated) = truncate_response(results, tokens, format_fn);
-            let final_output = format_fn(&pruned_results);
+            let (pruned_results, _tokens_est, truncated) =
+                truncate_response(results, tokens, |items| format_with(items, false));
+            let final_output = format_with(&pruned_results, color);
             print!("{}", final_output);
             if truncated {
                 if let Some(limit) = tokens {
@@ -187,16 +249,34 @@ pub fn output_references(
                 }
             }
         }
-        OutputFormat::Json | OutputFormat::Pretty => {
+        OutputFormat::Quickfix => {
+            let items: Vec<QuickfixItem> = response
+                .results
+                .iter()
+                .map(|r| QuickfixItem::new(&r.span, &r.referenced_symbol, "reference"))
+                .collect();
+            print_quickfix(&items)?;
+        }
+        OutputFormat::Vimgrep => {
+            for r in &response.results {
+                let label = format!("{} reference", r.referenced_symbol);
+                println!("{}", vimgrep_line(&r.span, r.snippet.as_deref(), &label));
+            }
+        }
+        OutputFormat::Ndjson => write_ndjson(
+            &mut std::io::stdout().lock(),
+            &response.results,
+            NdjsonSummary {
+                total_count: response.total_count,
+                partial,
+            },
+        )?,
+        _ => {
             let format_fn = |items: &[ReferenceMatch]| {
                 let mut temp_resp = response.clone();
                 temp_resp.results = items.to_vec();
                 let json_response = json_response_with_partial_and_performance(temp_resp, partial, metrics.cloned());
-                if matches!(cli.output, OutputFormat::Pretty) {
-                    serde_json::to_string_pretty(&json_response).unwrap_or_default()
-                } else {
-                    serde_json::to_string(&json_response).unwrap_or_default()
-                }
+                render_json(cli, &json_response).unwrap_or_default()
             };
 
             let (pruned_results, tokens_est, truncated) = truncate_response(results, tokens, format_fn);
@@ -209,11 +289,7 @@ pub fn output_references(
                 json_response.truncated = Some(true);
             }
 
-            let rendered = if matches!(cli.output, OutputFormat::Pretty) {
-                serde_json::to_string_pretty(&json_response)?
-            } else {
-                serde_json::to_string(&json_response)?
-            };
+            let rendered = render_json(cli, &json_response)?;
             println!("{}", rendered);
         }
     }
@@ -231,19 +307,25 @@ pub fn output_calls(
 
     match cli.output {
         OutputFormat::Human => {
-            let format_fn = |items: &[CallMatch]| {
+            let hyperlinks = cli.hyperlinks_enabled();
+            let color = cli.color_enabled();
+            let format_with = |items: &[CallMatch], color: bool| {
                 let mut human_out = String::new();
                 human_out.push_str(&format_total_header(response.total_count));
                 human_out.push('\n');
                 for item in items {
                     human_out.push_str(&format!(
-                        "{}:{}:{} {} -> {} score={}\n",
-                        item.span.file_path,
-                        item.span.start_line,
-                        item.span.start_col,
-                        item.caller,
-                        item.callee,
-                        item.score.unwrap_or(0)
+                        "{} {} -> {} {}\n",
+                        format_location_styled(
+                            &item.span.file_path,
+                            item.span.start_line,
+                            item.span.start_col,
+                            hyperlinks,
+                            color,
+                        ),
+                        paint(&item.caller, Style::Name, color),
+                        paint(&item.callee, Style::Name, color),
+                        paint(&format!("score={}", item.score.unwrap_or(0)), Style::Dim, color)
                     ));
                 }
                 if partial {
@@ -253,8 +335,9 @@ pub fn output_calls(
                 human_out
             };
 
-            let (pruned_results, _tokens_est, truncated) = truncate_response(results, tokens, format_fn);
-            let final_output = format_fn(&pruned_results);
+            let (pruned_results, _tokens_est, truncated) =
+                truncate_response(results, tokens, |items| format_with(items, false));
+            let final_output = format_with(&pruned_results, color);
             print!("{}", final_output);
             if truncated {
                 if let Some(limit) = tokens {
@@ -262,16 +345,36 @@ pub fn output_calls(
                 }
             }
         }
-        OutputFormat::Json | OutputFormat::Pretty => {
+        OutputFormat::Quickfix => {
+            let items: Vec<QuickfixItem> = response
+                .results
+                .iter()
+                .map(|c| {
+                    QuickfixItem::new(&c.span, &format!("{} -> {}", c.caller, c.callee), "call")
+                })
+                .collect();
+            print_quickfix(&items)?;
+        }
+        OutputFormat::Vimgrep => {
+            for c in &response.results {
+                let label = format!("{} -> {} call", c.caller, c.callee);
+                println!("{}", vimgrep_line(&c.span, c.snippet.as_deref(), &label));
+            }
+        }
+        OutputFormat::Ndjson => write_ndjson(
+            &mut std::io::stdout().lock(),
+            &response.results,
+            NdjsonSummary {
+                total_count: response.total_count,
+                partial,
+            },
+        )?,
+        _ => {
             let format_fn = |items: &[CallMatch]| {
                 let mut temp_resp = response.clone();
                 temp_resp.results = items.to_vec();
                 let json_response = json_response_with_partial_and_performance(temp_resp, partial, metrics.cloned());
-                if matches!(cli.output, OutputFormat::Pretty) {
-                    serde_json::to_string_pretty(&json_response).unwrap_or_default()
-                } else {
-                    serde_json::to_string(&json_response).unwrap_or_default()
-                }
+                render_json(cli, &json_response).unwrap_or_default()
             };
 
             let (pruned_results, tokens_est, truncated) = truncate_response(results, tokens, format_fn);
@@ -284,11 +387,7 @@ pub fn output_calls(
                 json_response.truncated = Some(true);
             }
 
-            let rendered = if matches!(cli.output, OutputFormat::Pretty) {
-                serde_json::to_string_pretty(&json_response)?
-            } else {
-                serde_json::to_string(&json_response)?
-            };
+            let rendered = render_json(cli, &json_response)?;
             println!("{}", rendered);
         }
     }
@@ -306,16 +405,20 @@ pub fn output_implements(
 
     match cli.output {
         OutputFormat::Human => {
+            let hyperlinks = cli.hyperlinks_enabled();
             let format_fn = |items: &[ImplementsMatch]| {
                 let mut human_out = String::new();
                 human_out.push_str(&format_total_header(response.total_count));
                 human_out.push('\n');
                 for item in items {
                     human_out.push_str(&format!(
-                        "{}:{}:{} {} impl {} score={}\n",
-                        item.span.file_path,
-                        item.span.start_line,
-                        item.span.start_col,
+                        "{} {} impl {} score={}\n",
+                        format_location(
+                            &item.span.file_path,
+                            item.span.start_line,
+                            item.span.start_col,
+                            hyperlinks,
+                        ),
                         item.type_name,
                         item.trait_name,
                         item.score.unwrap_or(0)
@@ -337,16 +440,29 @@ pub fn output_implements(
                 }
             }
         }
-        OutputFormat::Json | OutputFormat::Pretty => {
+        OutputFormat::Quickfix => {
+            let items: Vec<QuickfixItem> = response
+                .results
+                .iter()
+                .map(|i| {
+                    let name = format!("{}: {}", i.type_name, i.trait_name);
+                    QuickfixItem::new(&i.span, &name, "impl")
+                })
+                .collect();
+            print_quickfix(&items)?;
+        }
+        OutputFormat::Vimgrep => {
+            for i in &response.results {
+                let label = format!("{}: {} impl", i.type_name, i.trait_name);
+                println!("{}", vimgrep_line(&i.span, i.snippet.as_deref(), &label));
+            }
+        }
+        _ => {
             let format_fn = |items: &[ImplementsMatch]| {
                 let mut temp_resp = response.clone();
                 temp_resp.results = items.to_vec();
                 let json_response = json_response_with_partial_and_performance(temp_resp, partial, metrics.cloned());
-                if matches!(cli.output, OutputFormat::Pretty) {
-                    serde_json::to_string_pretty(&json_response).unwrap_or_default()
-                } else {
-                    serde_json::to_string(&json_response).unwrap_or_default()
-                }
+                render_json(cli, &json_response).unwrap_or_default()
             };
 
             let (pruned_results, tokens_est, truncated) = truncate_response(results, tokens, format_fn);
@@ -359,11 +475,7 @@ pub fn output_implements(
                 json_response.truncated = Some(true);
             }
 
-            let rendered = if matches!(cli.output, OutputFormat::Pretty) {
-                serde_json::to_string_pretty(&json_response)?
-            } else {
-                serde_json::to_string(&json_response)?
-            };
+            let rendered = render_json(cli, &json_response)?;
             println!("{}", rendered);
         }
     }
@@ -406,16 +518,12 @@ pub fn output_docs(
                 }
             }
         }
-        OutputFormat::Json | OutputFormat::Pretty => {
+        _ => {
             let format_fn = |items: &[DocsMatch]| {
                 let mut temp_resp = response.clone();
                 temp_resp.results = items.to_vec();
                 let json_response = json_response_with_partial_and_performance(temp_resp, false, metrics.cloned());
-                if matches!(cli.output, OutputFormat::Pretty) {
-                    serde_json::to_string_pretty(&json_response).unwrap_or_default()
-                } else {
-                    serde_json::to_string(&json_response).unwrap_or_default()
-                }
+                render_json(cli, &json_response).unwrap_or_default()
             };
 
             let (pruned_results, tokens_est, truncated) = truncate_response(results, tokens, format_fn);
@@ -428,11 +536,7 @@ pub fn output_docs(
                 json_response.truncated = Some(true);
             }
 
-            let rendered = if matches!(cli.output, OutputFormat::Pretty) {
-                serde_json::to_string_pretty(&json_response)?
-            } else {
-                serde_json::to_string(&json_response)?
-            };
+            let rendered = render_json(cli, &json_response)?;
             println!("{}", rendered);
         }
     }
@@ -449,16 +553,20 @@ pub fn output_semantic(
 
     match cli.output {
         OutputFormat::Human => {
+            let hyperlinks = cli.hyperlinks_enabled();
             let format_fn = |items: &[SemanticMatch]| {
                 let mut human_out = String::new();
                 human_out.push_str(&format!("{} semantic matches\n", response.total_count));
                 for item in items {
                     let lang = item.language.as_deref().unwrap_or("?");
                     human_out.push_str(&format!(
-                        "{}:{}:{} {} [{}] {} score={} distance={:.4}\n",
-                        item.span.file_path,
-                        item.span.start_line,
-                        item.span.start_col,
+                        "{} {} [{}] {} score={} distance={:.4}\n",
+                        format_location(
+                            &item.span.file_path,
+                            item.span.start_line,
+                            item.span.start_col,
+                            hyperlinks,
+                        ),
                         item.name,
                         item.kind,
                         lang,
@@ -478,16 +586,26 @@ pub fn output_semantic(
                 }
             }
         }
-        OutputFormat::Json | OutputFormat::Pretty => {
+        OutputFormat::Quickfix => {
+            let items: Vec<QuickfixItem> = response
+                .results
+                .iter()
+                .map(|m| QuickfixItem::new(&m.span, &m.name, &m.kind))
+                .collect();
+            print_quickfix(&items)?;
+        }
+        OutputFormat::Vimgrep => {
+            for m in &response.results {
+                let label = format!("{} {}", m.name, m.kind);
+                println!("{}", vimgrep_line(&m.span, None, &label));
+            }
+        }
+        _ => {
             let format_fn = |items: &[SemanticMatch]| {
                 let mut temp_resp = response.clone();
                 temp_resp.results = items.to_vec();
                 let json_response = json_response_with_partial_and_performance(temp_resp, false, metrics.cloned());
-                if matches!(cli.output, OutputFormat::Pretty) {
-                    serde_json::to_string_pretty(&json_response).unwrap_or_default()
-                } else {
-                    serde_json::to_string(&json_response).unwrap_or_default()
-                }
+                render_json(cli, &json_response).unwrap_or_default()
             };
 
             let (pruned_results, tokens_est, truncated) = truncate_response(results, tokens, format_fn);
@@ -500,11 +618,7 @@ pub fn output_semantic(
                 json_response.truncated = Some(true);
             }
 
-            let rendered = if matches!(cli.output, OutputFormat::Pretty) {
-                serde_json::to_string_pretty(&json_response)?
-            } else {
-                serde_json::to_string(&json_response)?
-            };
+            let rendered = render_json(cli, &json_response)?;
             println!("{}", rendered);
         }
     }
@@ -549,16 +663,12 @@ pub fn output_facts(
                 }
             }
         }
-        OutputFormat::Json | OutputFormat::Pretty => {
+        _ => {
             let format_fn = |items: &[FactMatch]| {
                 let mut temp_resp = response.clone();
                 temp_resp.results = items.to_vec();
                 let json_response = json_response_with_partial_and_performance(temp_resp, false, metrics.cloned());
-                if matches!(cli.output, OutputFormat::Pretty) {
-                    serde_json::to_string_pretty(&json_response).unwrap_or_default()
-                } else {
-                    serde_json::to_string(&json_response).unwrap_or_default()
-                }
+                render_json(cli, &json_response).unwrap_or_default()
             };
 
             let (pruned_results, tokens_est, truncated) = truncate_response(results, tokens, format_fn);
@@ -571,11 +681,7 @@ pub fn output_facts(
                 json_response.truncated = Some(true);
             }
 
-            let rendered = if matches!(cli.output, OutputFormat::Pretty) {
-                serde_json::to_string_pretty(&json_response)?
-            } else {
-                serde_json::to_string(&json_response)?
-            };
+            let rendered = render_json(cli, &json_response)?;
             println!("{}", rendered);
         }
     }
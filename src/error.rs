@@ -35,6 +35,11 @@ pub enum LlmError {
     #[error("Schema mismatch: {reason}")]
     SchemaMismatch { reason: String },
 
+    /// Database stayed locked by another writer (e.g. a Magellan re-index)
+    /// through every `--busy-retries` attempt.
+    #[error("Database busy after {attempts} attempts")]
+    DatabaseBusy { attempts: u32 },
+
     /// Invalid query string provided.
     #[error("Invalid query: {query}")]
     InvalidQuery { query: String },
@@ -63,6 +68,14 @@ pub enum LlmError {
     #[error("Search failed: {reason}")]
     SearchFailed { reason: String },
 
+    /// Results were cut off by the candidate limit under `--strict-complete`.
+    #[error("Partial results: more matches than --candidates {candidates}")]
+    PartialResults { candidates: usize },
+
+    /// A query ran past the `--timeout` budget and was interrupted.
+    #[error("Query timed out after {seconds}s")]
+    QueryTimeout { seconds: u64 },
+
     /// Invalid file path provided.
     #[error("Invalid path: {path}")]
     InvalidPath { path: String },
@@ -165,12 +178,15 @@ impl LlmError {
             LlmError::DatabaseNotFound { .. } => "LLM-E001",
             LlmError::DatabaseCorrupted { .. } => "LLM-E002",
             LlmError::SchemaMismatch { .. } => "LLM-E003",
+            LlmError::DatabaseBusy { .. } => "LLM-E004",
             LlmError::InvalidQuery { .. } => "LLM-E011",
             LlmError::EmptyQuery => "LLM-E012",
             LlmError::RegexRejected { .. } => "LLM-E101",
             LlmError::ResourceLimitExceeded { .. } => "LLM-E102",
             LlmError::PathValidationFailed { .. } => "LLM-E103",
             LlmError::SearchFailed { .. } => "LLM-E021",
+            LlmError::PartialResults { .. } => "LLM-E022",
+            LlmError::QueryTimeout { .. } => "LLM-E023",
             LlmError::InvalidPath { .. } => "LLM-E031",
             LlmError::InvalidField { .. } => "LLM-E032",
             LlmError::IoError(_) => "LLM-E901",
@@ -188,6 +204,57 @@ impl LlmError {
         }
     }
 
+    /// Process exit code for this error.
+    ///
+    /// `PartialResults` exits with 3 so scripts can tell an incomplete scan
+    /// from a failed one; every other error exits with 1.
+    pub const fn exit_code(&self) -> i32 {
+        match self {
+            LlmError::PartialResults { .. } => 3,
+            _ => 1,
+        }
+    }
+
+    /// Whether this error means a database file cannot be read at all.
+    ///
+    /// Covers corrupt or non-SQLite files and incompatible schemas, the cases
+    /// `--skip-corrupt` tolerates when searching several databases.
+    pub fn is_unreadable_database(&self) -> bool {
+        match self {
+            LlmError::DatabaseCorrupted { .. }
+            | LlmError::SchemaMismatch { .. }
+            | LlmError::BackendDetectionFailed { .. } => true,
+            LlmError::SqliteError(rusqlite::Error::SqliteFailure(err, _)) => matches!(
+                err.code,
+                rusqlite::ErrorCode::DatabaseCorrupt | rusqlite::ErrorCode::NotADatabase
+            ),
+            _ => false,
+        }
+    }
+
+    /// Whether this error is SQLite reporting the database as busy or locked,
+    /// the transient failure [`crate::backend::retry_busy`] retries.
+    pub fn is_busy(&self) -> bool {
+        matches!(
+            self,
+            LlmError::SqliteError(rusqlite::Error::SqliteFailure(err, _))
+                if matches!(
+                    err.code,
+                    rusqlite::ErrorCode::DatabaseBusy | rusqlite::ErrorCode::DatabaseLocked
+                )
+        )
+    }
+
+    /// Whether this error is SQLite reporting an interrupted statement, as
+    /// raised when a [`crate::backend::set_query_timeout`] deadline passes.
+    pub fn is_interrupted(&self) -> bool {
+        matches!(
+            self,
+            LlmError::SqliteError(rusqlite::Error::SqliteFailure(err, _))
+                if err.code == rusqlite::ErrorCode::OperationInterrupted
+        )
+    }
+
     /// Returns the severity level for this error.
     pub const fn severity(&self) -> &'static str {
         match self {
@@ -208,11 +275,14 @@ impl LlmError {
         }
     }
 
-    /// Returns remediation hints for this error, if available.
+    /// Returns remediation hints for this error.
+    ///
+    /// Every variant has a hint, so the structured `ErrorResponse` always
+    /// carries `remediation`; the `Option` is kept for API compatibility.
     pub const fn remediation(&self) -> Option<&'static str> {
         match self {
             LlmError::DatabaseNotFound { .. } => {
-                Some("Ensure the database path is correct and the file exists.")
+                Some("Pass --db with the path to a Magellan database, or create one with: magellan watch --root . --db .magellan/llmgrep.db --scan-initial")
             }
             LlmError::DatabaseCorrupted { .. } => {
                 Some("The database file may be corrupted. Try reindexing your codebase.")
@@ -220,23 +290,34 @@ impl LlmError {
             LlmError::SchemaMismatch { .. } => {
                 Some("The database schema version is incompatible. Try reindexing with a compatible Magellan version or upgrade llmgrep.")
             }
+            LlmError::DatabaseBusy { .. } => Some(
+                "Another process (usually a Magellan re-index) is writing the database. Retry once it finishes, or raise --busy-retries.",
+            ),
             LlmError::InvalidQuery { .. } => {
-                Some("Check that your query is properly formatted and valid.")
+                Some("Check the query and flag combination against the command's --help.")
             }
-            LlmError::EmptyQuery => Some("Provide a non-empty query string using --query."),
+            LlmError::EmptyQuery => Some(
+                "Provide a non-empty --query, use --all to match every symbol, or --queries-file to read queries from a file.",
+            ),
             LlmError::SearchFailed { .. } => {
                 Some("Check that the database is valid and the query is supported.")
             }
+            LlmError::PartialResults { .. } => Some(
+                "Raise --candidates above the match count, or drop --strict-complete to accept partial results.",
+            ),
+            LlmError::QueryTimeout { .. } => Some(
+                "Narrow the query (--path, --kind, a simpler regex or a lower --candidates) or raise --timeout.",
+            ),
             LlmError::InvalidPath { .. } => Some("Ensure the path is valid and accessible."),
             LlmError::InvalidField { .. } => {
-                Some("Valid fields: context, snippet, score, fqn, canonical_fqn, display_fqn, all")
+                Some("Valid fields: context, snippet, score, fqn, canonical_fqn, display_fqn, span_id, match_id, all")
             }
             LlmError::IoError(_) => Some("Check file permissions and disk space."),
             LlmError::SqliteError(_) => {
                 Some("The database may be locked or corrupted. Try reopening the database.")
             }
             LlmError::JsonError(_) => {
-                Some("JSON serialization error. This may indicate corrupted data.")
+                Some("JSON serialization error. This may indicate corrupted data; try reindexing your codebase.")
             }
             LlmError::RegexError(_) => Some("Invalid regular expression. Check your query syntax."),
             LlmError::RegexRejected { .. } => Some(
@@ -246,7 +327,7 @@ impl LlmError {
                 Some("Reduce the resource value to within the allowed maximum")
             }
             LlmError::PathValidationFailed { .. } => {
-                Some("Check that the path exists and is within the allowed directory structure")
+                Some("Check that the path exists and is within the allowed directory structure, or list a trusted root in LLMGREP_ALLOW_PATHS (colon-separated)")
             }
             LlmError::MagellanNotFound => {
                 Some("Install magellan v2.1.0+: cargo install magellan")
@@ -270,7 +351,7 @@ impl LlmError {
                 Some("Reindex with chunking enabled: magellan watch --root . --db code.geo --chunk")
             }
             LlmError::FeatureNotAvailable { .. } => {
-                Some("This feature is not supported by the current backend. Use a different backend or check available features.")
+                Some("This feature is not supported by the current backend. Run llmgrep --detect-backend to see the database format, and reindex with a Magellan version that supports it.")
             }
         }
     }
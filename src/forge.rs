@@ -63,10 +63,13 @@ pub fn search_symbols(
         db_path,
         query,
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         language_filter: None,
         limit,
         use_regex: false,
+        ignore_case: false,
         candidates: limit * 10,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -74,6 +77,8 @@ pub fn search_symbols(
             fqn: true,
             canonical_fqn: true,
             display_fqn: false,
+            normalize: false,
+            defined_in: false,
         },
         include_score: false,
         sort_by: SortMode::default(),
@@ -85,6 +90,10 @@ pub fn search_symbols(
         fqn_pattern: None,
         exact_fqn: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
     let (response, _, _) = backend.search_symbols(options)?;
     Ok(response.results)
@@ -103,10 +112,13 @@ pub fn search_symbols_regex(
         db_path,
         query: pattern,
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         language_filter: None,
         limit,
         use_regex: true,
+        ignore_case: false,
         candidates: limit * 10,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -114,6 +126,8 @@ pub fn search_symbols_regex(
             fqn: true,
             canonical_fqn: true,
             display_fqn: false,
+            normalize: false,
+            defined_in: false,
         },
         include_score: false,
         sort_by: SortMode::default(),
@@ -125,6 +139,10 @@ pub fn search_symbols_regex(
         fqn_pattern: None,
         exact_fqn: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
     let (response, _, _) = backend.search_symbols(options)?;
     Ok(response.results)
@@ -154,10 +172,13 @@ pub fn search_references(
         db_path,
         query: symbol_name,
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         language_filter: None,
         limit,
         use_regex: false,
+        ignore_case: false,
         candidates: limit * 10,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -172,6 +193,10 @@ pub fn search_references(
         fqn_pattern: None,
         exact_fqn: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
     let (response, _) = backend.search_references(options)?;
     Ok(response.results)
@@ -200,10 +225,13 @@ pub fn search_calls(
         db_path,
         query: symbol_name,
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         language_filter: None,
         limit,
         use_regex: false,
+        ignore_case: false,
         candidates: limit * 10,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -218,6 +246,10 @@ pub fn search_calls(
         fqn_pattern: None,
         exact_fqn: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
     let (response, _) = backend.search_calls(options)?;
     Ok(response.results)
@@ -254,10 +286,13 @@ pub fn search_symbols_by_language(
         db_path,
         query,
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         language_filter: Some(language),
         limit,
         use_regex: false,
+        ignore_case: false,
         candidates: limit * 10,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -265,6 +300,8 @@ pub fn search_symbols_by_language(
             fqn: true,
             canonical_fqn: true,
             display_fqn: false,
+            normalize: false,
+            defined_in: false,
         },
         include_score: false,
         sort_by: SortMode::default(),
@@ -276,6 +313,10 @@ pub fn search_symbols_by_language(
         fqn_pattern: None,
         exact_fqn: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
     let (response, _, _) = backend.search_symbols(options)?;
     Ok(response.results)
@@ -39,6 +39,7 @@
 //! - [`error`] - Error types
 //! - [`forge`] - High-level API for external consumers
 //! - [`output`] - Response types and formatting
+//! - [`output_schema`] - JSON Schema of the search response envelope
 //! - [`query`] - Search functions and options
 //! - [`safe_extraction`] - Safe UTF-8 extraction
 
@@ -49,6 +50,7 @@ pub mod error;
 pub mod forge;
 pub mod output;
 pub mod output_common;
+pub mod output_schema;
 pub mod platform;
 pub mod query;
 pub mod safe_extraction;
@@ -74,7 +76,7 @@ pub use algorithm::{
 pub use ast::{
     calculate_decision_depth, check_ast_table_exists, expand_shorthand,
     expand_shorthand_with_language, expand_shorthands, get_node_kinds_for_language,
-    get_supported_languages, AstContext,
+    get_supported_languages, to_tree_sitter_query, AstContext,
 };
 
 // Re-export backend types for external use
@@ -108,8 +110,20 @@ pub enum SortMode {
     FanOut,
     /// Sort by cyclomatic complexity descending
     Complexity,
+    /// Sort by lines of code (`symbol_metrics.loc`) descending
+    Loc,
     /// Sort by AST nesting depth descending (deepest first)
     NestingDepth,
     /// Sort by AST complexity (decision points) descending
     AstComplexity,
+    /// Sort by containing file's modification time descending (missing files last)
+    LastModified,
+    /// Reading order for code review: file path, then position (no scoring)
+    Review,
+    /// Position-ordered fetch, then the `--limit` window reordered by relevance
+    Hybrid,
+    /// Symbol name A-Z (case-insensitive), then file path and line (no scoring)
+    Name,
+    /// Symbol name Z-A (case-insensitive), then file path and line (no scoring)
+    NameDesc,
 }
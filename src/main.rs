@@ -18,7 +18,14 @@ fn main() {
     let tel = llmgrep::query::telemetry::TelemetryGuard::new(cmd_name);
     let tel = if cli.record { tel.with_record() } else { tel };
 
-    let result = dispatch(&cli);
+    if let Some(seconds) = cli.timeout {
+        llmgrep::backend::set_query_timeout(std::time::Duration::from_secs(seconds));
+    }
+    llmgrep::backend::set_busy_retries(cli.busy_retries);
+    let result = dispatch(&cli).map_err(|err| match cli.timeout {
+        Some(seconds) if err.is_interrupted() => llmgrep::error::LlmError::QueryTimeout { seconds },
+        _ => err,
+    });
 
     match &result {
         Ok(()) => tel.record("ok", 0),
@@ -27,6 +34,6 @@ fn main() {
 
     if let Err(err) = result {
         emit_error(&cli, &err);
-        std::process::exit(1);
+        std::process::exit(err.exit_code());
     }
 }
@@ -4,12 +4,20 @@
 //! in various formats (human-readable, JSON, pretty-printed JSON).
 
 use crate::ast::AstContext;
+use crate::output_common::escape_control_chars;
 use chrono::Utc;
 use clap::ValueEnum;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fmt;
+use std::time::SystemTime;
 
-const SCHEMA_VERSION: &str = "1.0.0";
+/// Version of the [`JsonResponse`] envelope, emitted as `schema_version`.
+///
+/// A monotonic integer, incremented on breaking changes to the envelope or
+/// to existing `data` fields; additive fields leave it alone. Version 2 is
+/// the first integer one, following the string `"1.0.0"`.
+pub const SCHEMA_VERSION: u32 = 2;
 
 /// Output format for search results.
 ///
@@ -22,6 +30,64 @@ pub enum OutputFormat {
     Json,
     /// Pretty-printed JSON with indentation
     Pretty,
+    /// Neovim quickfix list (JSON array of `setqflist()` items)
+    Quickfix,
+    /// Graphviz DOT graph of a `--slice-*-from` slice
+    SliceDot,
+    /// Folded call stacks (`a;b;c N`) for flamegraph tooling
+    FlamegraphCallgraph,
+    /// Sorted, deduplicated FQNs, one per line
+    FqnList,
+    /// Per-query timing rows (CSV) for benchmarking query batches
+    TimingsCsv,
+    /// Concatenated snippet bodies under file/symbol header comments
+    Snippets,
+    /// Matched symbol IDs, one per line, for `--symbol-ids-file` chaining
+    EntityIdsOnly,
+    /// NDJSON, one match per line tagged with `_type` (symbol, reference, call)
+    JsonLinesTyped,
+    /// Caller to callee call-count adjacency map (calls mode)
+    Matrix,
+    /// Symbol names ranked by how many definitions share them
+    Frequency,
+    /// Aligned human table with every populated metadata column
+    Wide,
+    /// External symbols referenced from --path, grouped by defining module
+    ResolvedImports,
+    /// Dead-code CSV rows (fqn,file,line,kind) for --dead-code-in baselines
+    DeadcodeCsv,
+    /// JSON array of per-query results keyed by input index (`--queries-file`)
+    BatchResultsJson,
+    /// SCC groups with members and entry points (`--condense`)
+    GroupedBySupernodeJson,
+    /// Two-pass NDJSON: coarse name/location records, then enrichment records keyed by match_id
+    ProgressiveJpegStyle,
+    /// JSON with optional fields projected to what the database can compute, declared in available_fields
+    MinViable,
+    /// One JSON object per match line as rows are read (`--sort-by position`), then a `_type: summary` line
+    Ndjson,
+    /// file:line:col:text lines for vim/neovim quickfix (:grep, errorformat)
+    Vimgrep,
+}
+
+impl OutputFormat {
+    /// Whether stdout carries JSON, a document or JSON lines, so errors go
+    /// there as a JSON envelope too; text formats report them on stderr.
+    pub const fn is_json(self) -> bool {
+        matches!(
+            self,
+            OutputFormat::Json
+                | OutputFormat::Pretty
+                | OutputFormat::Quickfix
+                | OutputFormat::JsonLinesTyped
+                | OutputFormat::Matrix
+                | OutputFormat::BatchResultsJson
+                | OutputFormat::GroupedBySupernodeJson
+                | OutputFormat::ProgressiveJpegStyle
+                | OutputFormat::MinViable
+                | OutputFormat::Ndjson
+        )
+    }
 }
 
 impl fmt::Display for OutputFormat {
@@ -30,11 +96,41 @@ impl fmt::Display for OutputFormat {
             OutputFormat::Human => "human",
             OutputFormat::Json => "json",
             OutputFormat::Pretty => "pretty",
+            OutputFormat::Quickfix => "quickfix",
+            OutputFormat::SliceDot => "slice-dot",
+            OutputFormat::FlamegraphCallgraph => "flamegraph-callgraph",
+            OutputFormat::FqnList => "fqn-list",
+            OutputFormat::TimingsCsv => "timings-csv",
+            OutputFormat::Snippets => "snippets",
+            OutputFormat::EntityIdsOnly => "entity-ids-only",
+            OutputFormat::JsonLinesTyped => "json-lines-typed",
+            OutputFormat::Matrix => "matrix",
+            OutputFormat::Frequency => "frequency",
+            OutputFormat::Wide => "wide",
+            OutputFormat::ResolvedImports => "resolved-imports",
+            OutputFormat::DeadcodeCsv => "deadcode-csv",
+            OutputFormat::BatchResultsJson => "batch-results-json",
+            OutputFormat::GroupedBySupernodeJson => "grouped-by-supernode-json",
+            OutputFormat::ProgressiveJpegStyle => "progressive-jpeg-style",
+            OutputFormat::MinViable => "min-viable",
+            OutputFormat::Ndjson => "ndjson",
+            OutputFormat::Vimgrep => "vimgrep",
         };
         write!(f, "{}", value)
     }
 }
 
+/// Policy for keeping one result per symbol name (`--prefer`).
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum Prefer {
+    /// Most recently modified file (unreadable files lose)
+    Newest,
+    /// Highest cyclomatic complexity (unknown complexity loses)
+    HighestComplexity,
+    /// Shallowest file path, then shortest
+    ShortestPath,
+}
+
 /// Performance metrics for search operations.
 ///
 /// Tracks timing breakdown for different phases of search execution.
@@ -51,6 +147,62 @@ pub struct PerformanceMetrics {
     pub total_ms: u64,
 }
 
+/// Quote a CSV field when it contains `,`, `"`, or a newline.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Timing of one query in a `--output timings-csv` run.
+#[derive(Clone, Debug)]
+pub struct QueryTiming {
+    /// The query string as run
+    pub query: String,
+    /// Timing breakdown for the query
+    pub metrics: PerformanceMetrics,
+    /// Number of results returned
+    pub result_count: usize,
+}
+
+impl QueryTiming {
+    /// CSV header matching [`QueryTiming::to_csv_row`].
+    pub const CSV_HEADER: &'static str =
+        "query,backend_ms,query_ms,format_ms,total_ms,result_count";
+
+    /// Render as one CSV row; the query is quoted when it contains `,`, `"`, or a newline.
+    pub fn to_csv_row(&self) -> String {
+        format!(
+            "{},{},{},{},{},{}",
+            csv_field(&self.query),
+            self.metrics.backend_detection_ms,
+            self.metrics.query_execution_ms,
+            self.metrics.output_formatting_ms,
+            self.metrics.total_ms,
+            self.result_count
+        )
+    }
+}
+
+/// One query's results in a `--output batch-results-json` run.
+///
+/// Entries are keyed by input position, so duplicated queries stay distinct.
+#[derive(Serialize, Clone, Debug)]
+pub struct BatchQueryResult {
+    /// Position of the query in the input list (0-based)
+    pub index: usize,
+    /// The query string as run
+    pub query: String,
+    /// Total number of matches (before limit)
+    pub total_count: u64,
+    /// Whether the candidate limit cut this query's results short
+    pub partial: bool,
+    /// The matches returned for this query
+    pub results: Vec<SymbolMatch>,
+}
+
 impl PerformanceMetrics {
     /// Create a new PerformanceMetrics instance with all fields set to zero.
     pub fn new() -> Self {
@@ -71,7 +223,7 @@ impl PerformanceMetrics {
 ///
 /// ```json
 /// {
-///   "schema_version": "1.0.0",
+///   "schema_version": 2,
 ///   "execution_id": "1234567890-abcd",
 ///   "tool": "llmgrep",
 ///   "timestamp": "2024-01-15T10:30:00Z",
@@ -82,7 +234,7 @@ impl PerformanceMetrics {
 #[derive(Serialize, Clone, Debug)]
 pub struct JsonResponse<T> {
     /// Schema version for response structure compatibility
-    pub schema_version: &'static str,
+    pub schema_version: u32,
     /// Unique execution identifier (timestamp + process ID)
     pub execution_id: String,
     /// Tool name (always "llmgrep")
@@ -125,7 +277,7 @@ pub struct ErrorResponse {
 ///
 /// Represents a contiguous span of source code with line/column information
 /// for display and navigation.
-#[derive(Serialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Span {
     /// Unique span identifier
     pub span_id: String,
@@ -152,7 +304,7 @@ pub struct Span {
 ///
 /// Provides before/after/selected lines for displaying search results
 /// with surrounding code context.
-#[derive(Serialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct SpanContext {
     /// Lines before the matched span
     pub before: Vec<String>,
@@ -168,7 +320,7 @@ pub struct SpanContext {
 ///
 /// Represents a single symbol (function, struct, enum, etc.) that matched
 /// the search criteria, with all available metadata.
-#[derive(Serialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct SymbolMatch {
     /// Unique match identifier
     pub match_id: String,
@@ -185,6 +337,10 @@ pub struct SymbolMatch {
     /// Relevance score (higher = more relevant)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub score: Option<u64>,
+    /// Capture groups of a `--regex` query matched against the name
+    /// (unmatched optional groups are empty strings)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub captures: Option<Vec<String>>,
     /// Fully-qualified name
     #[serde(skip_serializing_if = "Option::is_none")]
     pub fqn: Option<String>,
@@ -206,6 +362,12 @@ pub struct SymbolMatch {
     /// Whether the snippet was truncated due to size limits
     #[serde(skip_serializing_if = "Option::is_none")]
     pub snippet_truncated: Option<bool>,
+    /// Source text of `start_line`, without the line terminator
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line_text: Option<String>,
+    /// Visibility recorded by the indexer (e.g. `pub`, `export`), when available
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub visibility: Option<String>,
     // Label fields (language and normalized kind)
     /// Programming language (rust, python, etc.)
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -223,13 +385,22 @@ pub struct SymbolMatch {
     /// Number of outgoing calls (fan-out)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub fan_out: Option<u64>,
+    /// Total call sites targeting this symbol (`--weight-by-calls`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub call_count: Option<u64>,
     /// Cyclomatic complexity
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cyclomatic_complexity: Option<u64>,
+    /// Share of matched results (0-100) with cyclomatic complexity at or below this one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub complexity_percentile: Option<u8>,
     // AST fields (from ast_nodes table)
     /// AST context (depth, parent_kind, children, decision_points)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ast_context: Option<AstContext>,
+    /// Kind of the AST node exactly matching the symbol span (--with-ast-kind)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ast_node_kind: Option<String>,
     // Condense fields (SCC membership from magellan condense)
     /// Supernode ID for strongly-connected component members
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -237,6 +408,42 @@ pub struct SymbolMatch {
     /// Coverage information for CFG-backed symbols (functions/methods)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub coverage: Option<CoverageInfo>,
+    /// Database the match came from (multi-`--db` search only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_db: Option<String>,
+    /// File entity that defines the symbol (`--with-provenance`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub defined_in: Option<DefinedIn>,
+    /// Symbols with a call to this one (`--with-callers`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub callers: Option<Vec<CallerRef>>,
+}
+
+/// A caller of a dead-code candidate.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CallerRef {
+    /// Caller name as recorded on the `Call` entity
+    pub name: String,
+    /// Caller symbol ID, when the call was resolved
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol_id: Option<String>,
+    /// Whether the caller is itself among the dead-code results
+    pub dead: bool,
+}
+
+/// Provenance of a symbol: the File entity at the source of its `DEFINES` edge.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DefinedIn {
+    /// `graph_entities` id of the File entity
+    pub file_id: i64,
+    /// Path recorded on the File entity
+    pub path: String,
+    /// Language recorded on the File entity, else inferred from the extension
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// File size in bytes, as recorded by the indexer or read from disk
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size_bytes: Option<u64>,
 }
 
 /// Coverage information for a symbol.
@@ -244,7 +451,7 @@ pub struct SymbolMatch {
 /// Aggregated from Magellan's `cfg_block_coverage` and `cfg_edge_coverage`
 /// side tables. Only populated for symbols backed by CFG data (functions,
 /// methods) when coverage was recorded during test execution.
-#[derive(Serialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct CoverageInfo {
     /// Total number of CFG basic blocks for this symbol
     pub total_blocks: u64,
@@ -279,6 +486,9 @@ pub struct ReferenceMatch {
     pub reference_kind: Option<String>,
     /// Symbol ID of the referenced symbol
     pub target_symbol_id: Option<String>,
+    /// Definition span of the referenced symbol, resolved via `target_symbol_id` (`--resolve-defs`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub definition: Option<Span>,
     /// Relevance score
     #[serde(skip_serializing_if = "Option::is_none")]
     pub score: Option<u64>,
@@ -314,6 +524,9 @@ pub struct CallMatch {
     pub caller_symbol_id: Option<String>,
     /// Symbol ID of the callee
     pub callee_symbol_id: Option<String>,
+    /// Definition span of the callee, resolved via `callee_symbol_id` (`--resolve-callees`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub callee_definition: Option<Span>,
     /// Relevance score
     #[serde(skip_serializing_if = "Option::is_none")]
     pub score: Option<u64>,
@@ -334,7 +547,7 @@ pub struct CallMatch {
 /// Response from a symbol search operation.
 ///
 /// Contains all matching symbols along with search metadata.
-#[derive(Serialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct SearchResponse {
     /// List of matching symbols
     pub results: Vec<SymbolMatch>,
@@ -351,6 +564,530 @@ pub struct SearchResponse {
     /// Optional notice (e.g., results truncated, algorithm applied)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub notice: Option<String>,
+    /// Optional symbol fields the database can compute (`--output min-viable`)
+    #[serde(skip_serializing_if = "Option::is_none", skip_deserializing)]
+    pub available_fields: Option<Vec<&'static str>>,
+}
+
+/// Trimmed projection of a symbol match for hover-card rendering.
+///
+/// Emitted by `--context-only`: just the name, file, and context block,
+/// without scoring, metrics, or AST fields.
+#[derive(Serialize, Clone, Debug)]
+pub struct ContextCard {
+    /// Symbol name
+    pub name: String,
+    /// File containing the symbol
+    pub file: String,
+    /// Context lines around the symbol span
+    pub context: SpanContext,
+}
+
+/// One entry of a Neovim quickfix list, as accepted by `setqflist()`.
+///
+/// Emitted by `--output quickfix`. `col` is 1-based; `type` is the
+/// uppercased first letter of the symbol kind (e.g. `F` for functions).
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct QuickfixItem {
+    /// File containing the match
+    pub filename: String,
+    /// 1-based line number
+    pub lnum: u64,
+    /// 1-based column number
+    pub col: u64,
+    /// Display text: name followed by kind
+    pub text: String,
+    /// Single-letter type derived from the kind
+    #[serde(rename = "type")]
+    pub item_type: String,
+}
+
+impl QuickfixItem {
+    /// Build an entry for `name` of `kind` at the start of `span`.
+    ///
+    /// Span columns are stored 0-based by the indexer, so one is added.
+    pub fn new(span: &Span, name: &str, kind: &str) -> Self {
+        Self {
+            filename: span.file_path.clone(),
+            lnum: span.start_line,
+            col: span.start_col + 1,
+            text: format!("{} {}", name, kind),
+            item_type: kind
+                .chars()
+                .next()
+                .map(|c| c.to_uppercase().to_string())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// One `--output vimgrep` line: `file:line:col:text`.
+///
+/// `col` is 1-based, as vim's `%f:%l:%c:%m` errorformat expects. The text is
+/// the snippet's first non-blank line when a snippet was fetched, otherwise
+/// `label`.
+pub fn vimgrep_line(span: &Span, snippet: Option<&str>, label: &str) -> String {
+    let text = snippet
+        .and_then(|s| s.lines().map(str::trim).find(|line| !line.is_empty()))
+        .unwrap_or(label.trim_end());
+    format!(
+        "{}:{}:{}:{}",
+        span.file_path,
+        span.start_line,
+        span.start_col + 1,
+        text
+    )
+}
+
+/// Rewrite serialized results for `--compact`: every `span` object becomes a
+/// `"loc": "file:line:col"` string (the location human output prints) and
+/// `match_id` is dropped. Resolved `definition` and `callee_definition` spans
+/// are replaced in place by the same `file:line:col` string.
+///
+/// A span's `context` moves up next to `loc`. `keep_span_id` and
+/// `keep_match_id` retain those IDs for callers that asked for them.
+pub fn compact_spans(value: &mut serde_json::Value, keep_span_id: bool, keep_match_id: bool) {
+    match value {
+        serde_json::Value::Object(map) => {
+            let span = match map.get("span") {
+                Some(serde_json::Value::Object(_)) => map.remove("span"),
+                _ => None,
+            };
+            if let Some(serde_json::Value::Object(mut span)) = span {
+                map.insert("loc".to_string(), span_loc(&span).into());
+                if let Some(context) = span.remove("context") {
+                    map.entry("context").or_insert(context);
+                }
+                if keep_span_id {
+                    if let Some(span_id) = span.remove("span_id") {
+                        map.insert("span_id".to_string(), span_id);
+                    }
+                }
+                if !keep_match_id {
+                    map.remove("match_id");
+                }
+            }
+            for key in ["definition", "callee_definition"] {
+                if let Some(serde_json::Value::Object(definition)) = map.get(key) {
+                    let loc = span_loc(definition);
+                    map.insert(key.to_string(), loc.into());
+                }
+            }
+            for nested in map.values_mut() {
+                compact_spans(nested, keep_span_id, keep_match_id);
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for item in items {
+                compact_spans(item, keep_span_id, keep_match_id);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// `file:line:col` for a serialized [`Span`].
+fn span_loc(span: &serde_json::Map<String, serde_json::Value>) -> String {
+    let field = |key: &str| span.get(key).cloned().unwrap_or_default();
+    format!(
+        "{}:{}:{}",
+        field("file_path").as_str().unwrap_or_default(),
+        field("start_line"),
+        field("start_col")
+    )
+}
+
+impl SearchResponse {
+    /// Drop optional metric, AST and coverage fields not in `available` and
+    /// record `available` as `available_fields`.
+    ///
+    /// A field missing from a result then means "not computed for this
+    /// symbol", never "the database cannot compute it".
+    pub fn project_available_fields(&mut self, available: Vec<&'static str>) {
+        let has = |field: &str| available.contains(&field);
+        for result in &mut self.results {
+            if !has("fan_in") {
+                result.fan_in = None;
+            }
+            if !has("fan_out") {
+                result.fan_out = None;
+            }
+            if !has("cyclomatic_complexity") {
+                result.cyclomatic_complexity = None;
+                result.complexity_score = None;
+                result.complexity_percentile = None;
+            }
+            if !has("ast_context") {
+                result.ast_context = None;
+            }
+            if !has("ast_node_kind") {
+                result.ast_node_kind = None;
+            }
+            if !has("coverage") {
+                result.coverage = None;
+            }
+        }
+        self.available_fields = Some(available);
+    }
+
+    /// Project results to context cards, skipping results without context.
+    pub fn context_cards(&self) -> Vec<ContextCard> {
+        self.results
+            .iter()
+            .filter_map(|r| {
+                Some(ContextCard {
+                    name: r.name.clone(),
+                    file: r.span.file_path.clone(),
+                    context: r.span.context.clone()?,
+                })
+            })
+            .collect()
+    }
+
+    /// Count results per relevance-score range, highest range first.
+    ///
+    /// Ranges follow `score_match` tiers: exact (90-100), prefix (70-89),
+    /// contains (50-69), and weak (0-49). Unscored results count as 0.
+    pub fn score_histogram(&self) -> Vec<(&'static str, usize)> {
+        let mut buckets = vec![("90-100", 0), ("70-89", 0), ("50-69", 0), ("0-49", 0)];
+        for r in &self.results {
+            let idx = match r.score.unwrap_or(0) {
+                90.. => 0,
+                70..=89 => 1,
+                50..=69 => 2,
+                _ => 3,
+            };
+            buckets[idx].1 += 1;
+        }
+        buckets
+    }
+
+    /// Group results by lowercased inferred language; results without one go under `"unknown"`.
+    pub fn partition_by_language(&self) -> std::collections::BTreeMap<String, Vec<&SymbolMatch>> {
+        let mut groups: std::collections::BTreeMap<String, Vec<&SymbolMatch>> =
+            std::collections::BTreeMap::new();
+        for r in &self.results {
+            let language = r
+                .language
+                .as_deref()
+                .map_or_else(|| "unknown".to_string(), str::to_lowercase);
+            groups.entry(language).or_default().push(r);
+        }
+        groups
+    }
+
+    /// Project results to Neovim quickfix entries.
+    pub fn quickfix_items(&self) -> Vec<QuickfixItem> {
+        self.results
+            .iter()
+            .map(|r| QuickfixItem::new(&r.span, &r.name, &r.kind))
+            .collect()
+    }
+
+    /// Snippet bodies of all results, concatenated in result order.
+    ///
+    /// Each snippet is preceded by a `// file:start-end name (kind)` header
+    /// and followed by a blank line; results without a snippet are skipped.
+    pub fn snippet_bundle(&self) -> String {
+        let mut bundle = String::new();
+        for result in &self.results {
+            let Some(snippet) = result.snippet.as_deref() else {
+                continue;
+            };
+            bundle.push_str(&format!(
+                "// {}:{}-{} {} ({})\n",
+                result.span.file_path,
+                result.span.start_line,
+                result.span.end_line,
+                result.name,
+                result.kind
+            ));
+            bundle.push_str(snippet);
+            if !snippet.ends_with('\n') {
+                bundle.push('\n');
+            }
+            bundle.push('\n');
+        }
+        bundle
+    }
+
+    /// Resolved `symbol_id`s of the results, in result order.
+    ///
+    /// Duplicates and IDs that are not 32 hex characters are skipped, so the
+    /// output always loads with `--symbol-ids-file`.
+    pub fn entity_ids(&self) -> Vec<String> {
+        let mut seen = std::collections::HashSet::new();
+        self.results
+            .iter()
+            .filter_map(|r| r.symbol_id.as_deref())
+            .filter(|id| id.len() == 32 && id.chars().all(|c| c.is_ascii_hexdigit()))
+            .filter(|id| seen.insert(*id))
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Sorted, deduplicated FQNs from the results.
+    ///
+    /// Uses `canonical_fqn`, falling back to `display_fqn`; results with
+    /// neither are skipped.
+    pub fn fqn_list(&self) -> Vec<String> {
+        self.results
+            .iter()
+            .filter_map(|r| r.canonical_fqn.clone().or_else(|| r.display_fqn.clone()))
+            .collect::<std::collections::BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    /// Dead-code baseline CSV: a `fqn,file,line,kind` header, then one row per
+    /// result sorted by FQN, file and line so runs diff cleanly.
+    ///
+    /// The FQN is the canonical FQN, falling back to the display FQN and then
+    /// the symbol name.
+    pub fn deadcode_csv(&self) -> String {
+        let mut rows: Vec<(&str, &str, u64, &str)> = self
+            .results
+            .iter()
+            .map(|r| {
+                let fqn = r
+                    .canonical_fqn
+                    .as_deref()
+                    .or(r.display_fqn.as_deref())
+                    .unwrap_or(&r.name);
+                (
+                    fqn,
+                    r.span.file_path.as_str(),
+                    r.span.start_line,
+                    r.kind.as_str(),
+                )
+            })
+            .collect();
+        rows.sort_unstable();
+        rows.dedup();
+
+        let mut out = String::from("fqn,file,line,kind\n");
+        for (fqn, file, line, kind) in rows {
+            out.push_str(&format!(
+                "{},{},{},{}\n",
+                csv_field(fqn),
+                csv_field(file),
+                line,
+                csv_field(kind)
+            ));
+        }
+        out
+    }
+
+    /// Sorted, deduplicated symbol names from the results.
+    pub fn distinct_names(&self) -> Vec<String> {
+        self.results
+            .iter()
+            .map(|r| r.name.clone())
+            .collect::<std::collections::BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    /// Number of definitions per symbol name, most frequent first.
+    ///
+    /// Ties are ordered by name.
+    pub fn name_frequency(&self) -> Vec<(String, usize)> {
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for result in &self.results {
+            *counts.entry(result.name.as_str()).or_default() += 1;
+        }
+        let mut ranked: Vec<(String, usize)> = counts
+            .into_iter()
+            .map(|(name, count)| (name.to_string(), count))
+            .collect();
+        ranked.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        ranked
+    }
+
+    /// Render results as an aligned table with every metadata column.
+    ///
+    /// Columns are `LOCATION NAME KIND SCORE CC FAN_IN FAN_OUT ID`; missing
+    /// values print as `-` and the symbol id is shortened to 8 characters.
+    pub fn wide_table(&self) -> String {
+        let number = |value: Option<u64>| value.map_or_else(|| "-".to_string(), |v| v.to_string());
+        let header = [
+            "LOCATION", "NAME", "KIND", "SCORE", "CC", "FAN_IN", "FAN_OUT", "ID",
+        ];
+        let rows: Vec<[String; 8]> = self
+            .results
+            .iter()
+            .map(|r| {
+                [
+                    format!(
+                        "{}:{}:{}",
+                        r.span.file_path, r.span.start_line, r.span.start_col
+                    ),
+                    r.name.clone(),
+                    r.kind.clone(),
+                    number(r.score),
+                    number(r.cyclomatic_complexity),
+                    number(r.fan_in),
+                    number(r.fan_out),
+                    r.symbol_id
+                        .as_deref()
+                        .map_or_else(|| "-".to_string(), |id| id.chars().take(8).collect()),
+                ]
+            })
+            .collect();
+
+        let mut widths = header.map(str::len);
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let mut out = String::new();
+        for cells in std::iter::once(header.map(str::to_string)).chain(rows) {
+            let line: Vec<String> = cells
+                .iter()
+                .zip(widths)
+                .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                .collect();
+            out.push_str(line.join("  ").trim_end());
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Set `complexity_percentile` on every result with a cyclomatic complexity.
+    ///
+    /// The percentile is the share of results with a known complexity that are
+    /// at or below this one, so the most complex result is always 100.
+    pub fn assign_complexity_percentiles(&mut self) {
+        let mut complexities: Vec<u64> = self
+            .results
+            .iter()
+            .filter_map(|r| r.cyclomatic_complexity)
+            .collect();
+        complexities.sort_unstable();
+        let total = complexities.len();
+        for result in &mut self.results {
+            result.complexity_percentile = result.cyclomatic_complexity.map(|cc| {
+                let at_or_below = complexities.partition_point(|&c| c <= cc);
+                ((at_or_below * 100 + total / 2) / total) as u8
+            });
+        }
+    }
+
+    /// Escape terminal control characters in names, snippets, context, and
+    /// line text so printing results cannot drive the terminal.
+    ///
+    /// See [`escape_control_chars`].
+    pub fn escape_control_chars(&mut self) {
+        for result in &mut self.results {
+            result.name = escape_control_chars(&result.name);
+            for text in [&mut result.snippet, &mut result.line_text]
+                .into_iter()
+                .flatten()
+            {
+                *text = escape_control_chars(text);
+            }
+            escape_context_lines(&mut result.span);
+        }
+    }
+}
+
+/// Escape control characters in the context lines attached to `span`.
+fn escape_context_lines(span: &mut Span) {
+    if let Some(context) = &mut span.context {
+        for line in context
+            .before
+            .iter_mut()
+            .chain(&mut context.selected)
+            .chain(&mut context.after)
+        {
+            *line = escape_control_chars(line);
+        }
+    }
+}
+
+impl SymbolMatch {
+    /// Whether this symbol belongs to the public API surface.
+    ///
+    /// Uses the recorded visibility when the indexer stores one (`pub`,
+    /// `public`, `export`, `exported`). Otherwise falls back to a heuristic:
+    /// names starting with `_` and symbols under `test`/`tests`/`private`
+    /// paths or modules are treated as non-public.
+    pub fn is_public(&self) -> bool {
+        if let Some(visibility) = &self.visibility {
+            return matches!(
+                visibility.to_ascii_lowercase().as_str(),
+                "pub" | "public" | "export" | "exported"
+            );
+        }
+        let hidden = |segment: &str| {
+            segment.starts_with('_') || matches!(segment, "test" | "tests" | "private")
+        };
+        if self.name.starts_with('_') {
+            return false;
+        }
+        let in_hidden_dir = std::path::Path::new(&self.span.file_path)
+            .parent()
+            .is_some_and(|dir| {
+                dir.components()
+                    .any(|c| matches!(c.as_os_str().to_str(), Some("test" | "tests" | "private")))
+            });
+        let fqn = self.fqn.as_deref().or(self.display_fqn.as_deref());
+        let in_hidden_module = fqn.is_some_and(|fqn| {
+            let mut segments: Vec<&str> = fqn.split("::").flat_map(|s| s.split('.')).collect();
+            segments.pop(); // the symbol's own name
+            segments.into_iter().any(hidden)
+        });
+        !in_hidden_dir && !in_hidden_module
+    }
+}
+
+/// Keep one result per symbol name, chosen by `policy`.
+///
+/// Ties keep the earliest result, and survivors stay in their original
+/// order.
+pub fn dedupe_by_name(results: &mut Vec<SymbolMatch>, policy: Prefer) {
+    let mut mtimes: HashMap<&str, Option<SystemTime>> = HashMap::new();
+    if policy == Prefer::Newest {
+        for result in results.iter() {
+            let path = result.span.file_path.as_str();
+            mtimes
+                .entry(path)
+                .or_insert_with(|| std::fs::metadata(path).and_then(|m| m.modified()).ok());
+        }
+    }
+    let path_rank = |r: &SymbolMatch| {
+        let path = &r.span.file_path;
+        let depth = path.split('/').filter(|c| !c.is_empty()).count();
+        (depth, path.len())
+    };
+    // Option orders None first, so unknown mtimes and complexities lose
+    let better = |a: &SymbolMatch, b: &SymbolMatch| match policy {
+        Prefer::Newest => mtimes[a.span.file_path.as_str()] > mtimes[b.span.file_path.as_str()],
+        Prefer::HighestComplexity => a.cyclomatic_complexity > b.cyclomatic_complexity,
+        Prefer::ShortestPath => path_rank(a) < path_rank(b),
+    };
+
+    let mut winners: HashMap<&str, usize> = HashMap::new();
+    for (index, result) in results.iter().enumerate() {
+        winners
+            .entry(result.name.as_str())
+            .and_modify(|best| {
+                if better(result, &results[*best]) {
+                    *best = index;
+                }
+            })
+            .or_insert(index);
+    }
+    let keep: HashSet<usize> = winners.into_values().collect();
+
+    *results = std::mem::take(results)
+        .into_iter()
+        .enumerate()
+        .filter(|(index, _)| keep.contains(index))
+        .map(|(_, result)| result)
+        .collect();
 }
 
 /// Response from a reference search operation.
@@ -369,6 +1106,22 @@ pub struct ReferenceSearchResponse {
     pub total_count: u64,
 }
 
+impl ReferenceSearchResponse {
+    /// Escape terminal control characters in referenced names, snippets and
+    /// context so printing results cannot drive the terminal.
+    ///
+    /// See [`escape_control_chars`].
+    pub fn escape_control_chars(&mut self) {
+        for result in &mut self.results {
+            result.referenced_symbol = escape_control_chars(&result.referenced_symbol);
+            if let Some(snippet) = &mut result.snippet {
+                *snippet = escape_control_chars(snippet);
+            }
+            escape_context_lines(&mut result.span);
+        }
+    }
+}
+
 /// Response from a call search operation.
 ///
 /// Contains all function call relationships matching the search.
@@ -385,6 +1138,36 @@ pub struct CallSearchResponse {
     pub total_count: u64,
 }
 
+impl CallSearchResponse {
+    /// Escape terminal control characters in caller and callee names,
+    /// snippets and context so printing results cannot drive the terminal.
+    ///
+    /// See [`escape_control_chars`].
+    pub fn escape_control_chars(&mut self) {
+        for result in &mut self.results {
+            result.caller = escape_control_chars(&result.caller);
+            result.callee = escape_control_chars(&result.callee);
+            if let Some(snippet) = &mut result.snippet {
+                *snippet = escape_control_chars(snippet);
+            }
+            escape_context_lines(&mut result.span);
+        }
+    }
+
+    /// Count calls per caller and callee: `{"main": {"parse": 2}}`.
+    pub fn call_matrix(&self) -> BTreeMap<String, BTreeMap<String, u64>> {
+        let mut matrix: BTreeMap<String, BTreeMap<String, u64>> = BTreeMap::new();
+        for call in &self.results {
+            *matrix
+                .entry(call.caller.clone())
+                .or_default()
+                .entry(call.callee.clone())
+                .or_default() += 1;
+        }
+        matrix
+    }
+}
+
 /// An implements match from an implements search operation.
 ///
 /// Represents a type implementing a trait relationship.
@@ -565,6 +1348,295 @@ pub struct CombinedSearchResponse {
     pub total_count: u64,
     /// Description of the limit mode applied (e.g., "unlimited", "per-mode")
     pub limit_mode: String,
+    /// Results dropped from each section to fit a `--tokens` budget
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub omitted: Option<OmittedCounts>,
+}
+
+/// Number of results dropped per auto-mode section to fit a token budget.
+#[derive(Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OmittedCounts {
+    /// Symbol results dropped
+    pub symbols: usize,
+    /// Reference results dropped
+    pub references: usize,
+    /// Call results dropped
+    pub calls: usize,
+}
+
+impl CombinedSearchResponse {
+    /// Shrink all sections until `render` fits in `limit` tokens.
+    ///
+    /// Tokens are estimated as rendered length / 4, as for single-mode
+    /// output. Each pass halves every section, so their relative sizes are
+    /// preserved; the per-section drop counts are recorded in `omitted`.
+    /// Returns the estimated token count of the final rendering.
+    pub fn truncate_to_tokens(&mut self, limit: usize, render: impl Fn(&Self) -> String) -> usize {
+        let original = (
+            self.symbols.results.len(),
+            self.references.results.len(),
+            self.calls.results.len(),
+        );
+        let mut tokens = render(self).len() / 4;
+        while tokens > limit
+            && !(self.symbols.results.is_empty()
+                && self.references.results.is_empty()
+                && self.calls.results.is_empty())
+        {
+            self.symbols
+                .results
+                .truncate(self.symbols.results.len() / 2);
+            self.references
+                .results
+                .truncate(self.references.results.len() / 2);
+            self.calls.results.truncate(self.calls.results.len() / 2);
+            self.omitted = Some(OmittedCounts {
+                symbols: original.0 - self.symbols.results.len(),
+                references: original.1 - self.references.results.len(),
+                calls: original.2 - self.calls.results.len(),
+            });
+            tokens = render(self).len() / 4;
+        }
+        tokens
+    }
+
+    /// Merge symbols, references and calls into one list, in that order.
+    pub fn flatten(self) -> FlatSearchResponse {
+        let results = self
+            .symbols
+            .results
+            .into_iter()
+            .map(FlatResult::Symbol)
+            .chain(
+                self.references
+                    .results
+                    .into_iter()
+                    .map(FlatResult::Reference),
+            )
+            .chain(self.calls.results.into_iter().map(FlatResult::Call))
+            .collect();
+        FlatSearchResponse {
+            query: self.query,
+            path_filter: self.path_filter,
+            results,
+            total_count: self.total_count,
+            limit_mode: self.limit_mode,
+            omitted: self.omitted,
+        }
+    }
+
+    /// Symbols, then references, then calls, as `_type`-tagged records.
+    pub fn typed_records(&self) -> Vec<TypedRecord<'_>> {
+        self.symbols
+            .results
+            .iter()
+            .map(TypedRecord::Symbol)
+            .chain(self.references.results.iter().map(TypedRecord::Reference))
+            .chain(self.calls.results.iter().map(TypedRecord::Call))
+            .collect()
+    }
+}
+
+/// One item of a flattened auto-mode response.
+///
+/// Serializes as the underlying match with a `result_type` field of
+/// `symbol`, `reference` or `call` added.
+#[derive(Serialize, Clone, Debug)]
+#[serde(tag = "result_type", rename_all = "snake_case")]
+#[allow(clippy::large_enum_variant)]
+pub enum FlatResult {
+    Symbol(SymbolMatch),
+    Reference(ReferenceMatch),
+    Call(CallMatch),
+}
+
+/// One `--output json-lines-typed` record.
+///
+/// Serializes as the underlying match with a `_type` field of `symbol`,
+/// `reference` or `call` added, so one NDJSON consumer can dispatch on it.
+#[derive(Serialize, Clone, Copy, Debug)]
+#[serde(tag = "_type", rename_all = "snake_case")]
+pub enum TypedRecord<'a> {
+    Symbol(&'a SymbolMatch),
+    Reference(&'a ReferenceMatch),
+    Call(&'a CallMatch),
+}
+
+/// Render records as NDJSON, one `_type`-tagged object per line.
+pub fn typed_json_lines<'a>(
+    records: impl IntoIterator<Item = TypedRecord<'a>>,
+) -> Result<String, serde_json::Error> {
+    let mut out = String::new();
+    for record in records {
+        out.push_str(&serde_json::to_string(&record)?);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// Distinct files containing matches (`--files-with-matches`).
+#[derive(Serialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct FilesWithMatches {
+    /// Sorted, deduplicated file paths
+    pub files: Vec<String>,
+}
+
+impl FilesWithMatches {
+    /// Sorted, deduplicated `file_path`s of `spans`, capped at `limit` files.
+    pub fn from_spans<'a>(spans: impl IntoIterator<Item = &'a Span>, limit: usize) -> Self {
+        let files: BTreeSet<&str> = spans.into_iter().map(|s| s.file_path.as_str()).collect();
+        Self {
+            files: files.into_iter().take(limit).map(str::to_string).collect(),
+        }
+    }
+}
+
+/// Number of matching symbols (`--count`).
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MatchCount {
+    pub count: u64,
+    /// The count stopped at `--candidates` and more symbols may match
+    pub partial: bool,
+}
+
+/// Trailing `--output ndjson` record, written after one line per match.
+///
+/// Serializes with `"_type": "summary"`, which no match object carries.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(tag = "_type", rename = "summary")]
+pub struct NdjsonSummary {
+    pub total_count: u64,
+    pub partial: bool,
+}
+
+/// Write `results` as NDJSON, one match per line, followed by `summary`.
+///
+/// `search` streams its rows through [`write_ndjson_line`] as they are read
+/// and passes an empty `results` here, so only the summary is left to write.
+pub fn write_ndjson<W: std::io::Write, T: Serialize>(
+    out: &mut W,
+    results: &[T],
+    summary: NdjsonSummary,
+) -> Result<(), serde_json::Error> {
+    for result in results {
+        write_ndjson_line(out, result)?;
+    }
+    write_ndjson_line(out, &summary)
+}
+
+/// Write one NDJSON line.
+pub fn write_ndjson_line<W: std::io::Write, T: Serialize>(
+    out: &mut W,
+    value: &T,
+) -> Result<(), serde_json::Error> {
+    serde_json::to_writer(&mut *out, value)?;
+    out.write_all(b"\n").map_err(serde_json::Error::io)
+}
+
+/// One `--output progressive-jpeg-style` record.
+///
+/// Serializes with a `_phase` field of `coarse` or `enrichment`. Every coarse
+/// record is written before the first enrichment record, which carries the
+/// same `match_id` as the coarse record it fills in.
+#[derive(Serialize, Clone, Debug)]
+#[serde(tag = "_phase", rename_all = "snake_case")]
+pub enum ProgressiveRecord<'a> {
+    Coarse {
+        match_id: &'a str,
+        name: &'a str,
+        kind: &'a str,
+        file_path: &'a str,
+        start_line: u64,
+        start_col: u64,
+    },
+    Enrichment {
+        match_id: &'a str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        snippet: Option<&'a str>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        snippet_truncated: Option<bool>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        complexity_score: Option<u64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        fan_in: Option<u64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        fan_out: Option<u64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        cyclomatic_complexity: Option<u64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        ast_context: Option<&'a AstContext>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        ast_node_kind: Option<&'a str>,
+    },
+}
+
+impl<'a> ProgressiveRecord<'a> {
+    fn coarse(symbol: &'a SymbolMatch) -> Self {
+        ProgressiveRecord::Coarse {
+            match_id: &symbol.match_id,
+            name: &symbol.name,
+            kind: &symbol.kind,
+            file_path: &symbol.span.file_path,
+            start_line: symbol.span.start_line,
+            start_col: symbol.span.start_col,
+        }
+    }
+
+    /// Enrichment for `symbol`, or `None` when it has no snippet, metrics or AST data.
+    fn enrichment(symbol: &'a SymbolMatch) -> Option<Self> {
+        let has_detail = symbol.snippet.is_some()
+            || symbol.complexity_score.is_some()
+            || symbol.fan_in.is_some()
+            || symbol.fan_out.is_some()
+            || symbol.cyclomatic_complexity.is_some()
+            || symbol.ast_context.is_some()
+            || symbol.ast_node_kind.is_some();
+        has_detail.then(|| ProgressiveRecord::Enrichment {
+            match_id: &symbol.match_id,
+            snippet: symbol.snippet.as_deref(),
+            snippet_truncated: symbol.snippet_truncated,
+            complexity_score: symbol.complexity_score,
+            fan_in: symbol.fan_in,
+            fan_out: symbol.fan_out,
+            cyclomatic_complexity: symbol.cyclomatic_complexity,
+            ast_context: symbol.ast_context.as_ref(),
+            ast_node_kind: symbol.ast_node_kind.as_deref(),
+        })
+    }
+}
+
+/// Render symbols as two NDJSON passes: coarse records for every match, then
+/// enrichment records for the matches that have snippet, metrics or AST data.
+pub fn progressive_json_lines(results: &[SymbolMatch]) -> Result<String, serde_json::Error> {
+    let records = results
+        .iter()
+        .map(ProgressiveRecord::coarse)
+        .chain(results.iter().filter_map(ProgressiveRecord::enrichment));
+    let mut out = String::new();
+    for record in records {
+        out.push_str(&serde_json::to_string(&record)?);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// Auto-mode response with all result kinds in a single array (`--flatten`).
+#[derive(Serialize, Clone, Debug)]
+pub struct FlatSearchResponse {
+    /// The search query string
+    pub query: String,
+    /// Path filter that was applied (if any)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path_filter: Option<String>,
+    /// Symbols, then references, then calls, each tagged with `result_type`
+    pub results: Vec<FlatResult>,
+    /// Total count across all search modes
+    pub total_count: u64,
+    /// Description of the limit mode applied (e.g., "unlimited", "per-mode")
+    pub limit_mode: String,
+    /// Results dropped from each section to fit a `--tokens` budget
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub omitted: Option<OmittedCounts>,
 }
 
 /// Create a JSON response with the provided data.
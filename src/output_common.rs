@@ -26,6 +26,105 @@ pub fn format_total_header(total: u64) -> String {
     format!("total: {}", total)
 }
 
+/// Format a `file:line:col` location for human-readable output.
+///
+/// When `hyperlink` is set, the location is wrapped in an OSC 8 escape
+/// pointing at `file://<absolute path>#<line>` so supporting terminals
+/// render it as a clickable link. Otherwise the plain text is returned.
+pub fn format_location(file_path: &str, line: u64, col: u64, hyperlink: bool) -> String {
+    format_location_styled(file_path, line, col, hyperlink, false)
+}
+
+/// [`format_location`] with the path and `line:col` colorized when `color`
+/// is set (`--color`).
+pub fn format_location_styled(
+    file_path: &str,
+    line: u64,
+    col: u64,
+    hyperlink: bool,
+    color: bool,
+) -> String {
+    let text = format!(
+        "{}:{}",
+        paint(file_path, Style::Path, color),
+        paint(&format!("{}:{}", line, col), Style::Position, color)
+    );
+    if !hyperlink {
+        return text;
+    }
+    let path = std::path::Path::new(file_path);
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir()
+            .map(|cwd| cwd.join(path))
+            .unwrap_or_else(|_| path.to_path_buf())
+    };
+    format!(
+        "\x1b]8;;file://{}#{}\x1b\\{}\x1b]8;;\x1b\\",
+        absolute.display(),
+        line,
+        text
+    )
+}
+
+/// ANSI styles for colorized human output.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Style {
+    /// File paths (cyan)
+    Path,
+    /// `line:col` positions (green)
+    Position,
+    /// Symbol names (bold)
+    Name,
+    /// Secondary details such as scores (dim)
+    Dim,
+}
+
+impl Style {
+    fn sgr(self) -> &'static str {
+        match self {
+            Style::Path => "36",
+            Style::Position => "32",
+            Style::Name => "1",
+            Style::Dim => "2",
+        }
+    }
+}
+
+/// Wrap `text` in the SGR escape for `style` when `color` is set.
+pub fn paint(text: &str, style: Style, color: bool) -> String {
+    if color {
+        format!("\x1b[{}m{}\x1b[0m", style.sgr(), text)
+    } else {
+        text.to_string()
+    }
+}
+
+/// Replace terminal control characters with visible escapes.
+///
+/// C0 controls and DEL are shown in caret notation (`ESC` becomes `^[`),
+/// C1 controls as `\u{9b}`. Tabs, newlines, and the `\r` of a `\r\n` pair
+/// are kept so snippets keep their layout.
+pub fn escape_control_chars(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\t' | '\n' => out.push(c),
+            '\r' if chars.peek() == Some(&'\n') => out.push(c),
+            '\u{0}'..='\u{1f}' => {
+                out.push('^');
+                out.push((c as u8 + 0x40) as char);
+            }
+            '\u{7f}' => out.push_str("^?"),
+            '\u{80}'..='\u{9f}' => out.push_str(&format!("\\u{{{:x}}}", c as u32)),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
 /// Format the "partial: true" footer for human-readable output
 pub fn format_partial_footer() -> &'static str {
     "partial: true"
@@ -35,3 +134,41 @@ pub fn format_partial_footer() -> &'static str {
 pub fn is_json_format(format: OutputFormat) -> bool {
     matches!(format, OutputFormat::Json | OutputFormat::Pretty)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_location_plain_when_disabled() {
+        assert_eq!(
+            format_location("/src/lib.rs", 3, 4, false),
+            "/src/lib.rs:3:4"
+        );
+    }
+
+    #[test]
+    fn test_format_location_wraps_osc8_when_forced() {
+        assert_eq!(
+            format_location("/src/lib.rs", 3, 4, true),
+            "\x1b]8;;file:///src/lib.rs#3\x1b\\/src/lib.rs:3:4\x1b]8;;\x1b\\"
+        );
+    }
+
+    #[test]
+    fn test_format_location_styled_colors_path_and_position() {
+        assert_eq!(
+            format_location_styled("/src/lib.rs", 3, 4, false, true),
+            "\x1b[36m/src/lib.rs\x1b[0m:\x1b[32m3:4\x1b[0m"
+        );
+        assert_eq!(paint("parse", Style::Name, false), "parse");
+    }
+
+    #[test]
+    fn test_escape_control_chars_neutralizes_terminal_escapes() {
+        let snippet = "let s = \"\x1b]0;pwned\x07\x1b[2J\";\r\n\tok\u{9b}\x7f\n";
+        let escaped = escape_control_chars(snippet);
+        assert_eq!(escaped, "let s = \"^[]0;pwned^G^[[2J\";\r\n\tok\\u{9b}^?\n");
+        assert!(!escaped.contains('\x1b'));
+    }
+}
@@ -0,0 +1,320 @@
+//! JSON Schema for `--output json` search responses.
+//!
+//! The schema describes the [`JsonResponse`](crate::output::JsonResponse)
+//! envelope whose `data` is one of [`SearchResponse`](crate::output::SearchResponse),
+//! [`CombinedSearchResponse`](crate::output::CombinedSearchResponse),
+//! [`ReferenceSearchResponse`](crate::output::ReferenceSearchResponse) or
+//! [`CallSearchResponse`](crate::output::CallSearchResponse). It is written by
+//! hand and must be updated alongside those structs: fields serialized with
+//! `skip_serializing_if` are optional, other `Option` fields are nullable, and
+//! every object rejects unknown properties so drift fails validation.
+//!
+//! Only `--output json`, `pretty` and `min-viable` produce this envelope;
+//! `--fields`, `--tokens` and the `--with-*` flags only add or drop fields
+//! the schema already lists as optional. Everything below is outside the
+//! schema and fails validation by design:
+//!
+//! - `--output ndjson`: one bare match object per line, then a `_type: summary` line
+//! - `--output json-lines-typed`: one bare match per line tagged with `_type`
+//! - `--output progressive-jpeg-style`: coarse records, then enrichment records
+//! - `--output batch-results-json`: an array of per-query results keyed by index
+//! - `--output grouped-by-supernode-json`: an array of SCC groups
+//! - `--output matrix`: a caller to callee count map
+//! - `--output quickfix`: an array of `setqflist()` items
+//! - `--compact`: replaces `span` with a `loc` string, flattens
+//!   `definition`/`callee_definition` to strings, and drops `match_id` and
+//!   `span_id`
+//! - `--flatten` (auto mode): one merged `results` list instead of
+//!   `symbols`/`references`/`calls`
+//! - `--count`: `{"count": N, "partial": bool}`
+//! - `--files-with-matches`: `{"files": [...]}`
+//! - `--partition-by language`: matches keyed by language
+//! - `--score-histogram`: counts keyed by score range
+//! - `--distinct-names`: an array of names
+//! - `--context-only`: an array of context cards
+//!
+//! The remaining output formats are not JSON.
+
+use serde_json::{json, Map, Value};
+
+use crate::output::SCHEMA_VERSION;
+
+/// JSON Schema dialect the document is written against.
+pub const JSON_SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+fn reference(name: &str) -> Value {
+    json!({ "$ref": format!("#/$defs/{}", name) })
+}
+
+fn nullable(ty: &str) -> Value {
+    json!({ "type": [ty, "null"] })
+}
+
+fn array_of(items: Value) -> Value {
+    json!({ "type": "array", "items": items })
+}
+
+/// Closed object schema: `required` fields always present, `optional` ones
+/// omitted when unset.
+fn object(required: &[(&str, Value)], optional: &[(&str, Value)]) -> Value {
+    let properties: Map<String, Value> = required
+        .iter()
+        .chain(optional)
+        .map(|(name, schema)| (name.to_string(), schema.clone()))
+        .collect();
+    let required: Vec<&str> = required.iter().map(|(name, _)| *name).collect();
+    json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "additionalProperties": false,
+    })
+}
+
+fn definitions() -> Value {
+    let string = json!({ "type": "string" });
+    let count = json!({ "type": "integer", "minimum": 0 });
+    let boolean = json!({ "type": "boolean" });
+    let strings = array_of(string.clone());
+
+    let snippet_fields = [
+        ("score", count.clone()),
+        ("content_hash", string.clone()),
+        ("symbol_kind_from_chunk", string.clone()),
+        ("snippet", string.clone()),
+        ("snippet_truncated", boolean.clone()),
+    ];
+    let mut reference_optional = vec![("definition", reference("Span"))];
+    reference_optional.extend(snippet_fields.iter().cloned());
+    let mut call_optional = vec![("callee_definition", reference("Span"))];
+    call_optional.extend(snippet_fields.iter().cloned());
+
+    json!({
+        "PerformanceMetrics": object(
+            &[
+                ("backend_detection_ms", count.clone()),
+                ("query_execution_ms", count.clone()),
+                ("output_formatting_ms", count.clone()),
+                ("total_ms", count.clone()),
+            ],
+            &[],
+        ),
+        "SpanContext": object(
+            &[
+                ("before", strings.clone()),
+                ("selected", strings.clone()),
+                ("after", strings.clone()),
+                ("truncated", boolean.clone()),
+            ],
+            &[],
+        ),
+        "Span": object(
+            &[
+                ("span_id", string.clone()),
+                ("file_path", string.clone()),
+                ("byte_start", count.clone()),
+                ("byte_end", count.clone()),
+                ("start_line", count.clone()),
+                ("start_col", count.clone()),
+                ("end_line", count.clone()),
+                ("end_col", count.clone()),
+            ],
+            &[("context", reference("SpanContext"))],
+        ),
+        "AstContext": object(
+            &[
+                ("ast_id", json!({ "type": "integer" })),
+                ("kind", string.clone()),
+                ("parent_id", nullable("integer")),
+                ("byte_start", count.clone()),
+                ("byte_end", count.clone()),
+            ],
+            &[
+                ("depth", count.clone()),
+                ("parent_kind", string.clone()),
+                (
+                    "children_count_by_kind",
+                    json!({ "type": "object", "additionalProperties": count.clone() }),
+                ),
+                ("decision_points", count.clone()),
+            ],
+        ),
+        "CoverageInfo": object(
+            &[
+                ("total_blocks", count.clone()),
+                ("covered_blocks", count.clone()),
+                ("block_percentage", json!({ "type": "number" })),
+                ("total_edges", count.clone()),
+                ("covered_edges", count.clone()),
+                ("edge_percentage", json!({ "type": "number" })),
+            ],
+            &[("recorded_at", string.clone())],
+        ),
+        "DefinedIn": object(
+            &[("file_id", json!({ "type": "integer" })), ("path", string.clone())],
+            &[("language", string.clone()), ("size_bytes", count.clone())],
+        ),
+        "CallerRef": object(
+            &[("name", string.clone()), ("dead", boolean.clone())],
+            &[("symbol_id", string.clone())],
+        ),
+        "SymbolMatch": object(
+            &[
+                ("match_id", string.clone()),
+                ("span", reference("Span")),
+                ("name", string.clone()),
+                ("kind", string.clone()),
+                ("parent", nullable("string")),
+                ("symbol_id", nullable("string")),
+            ],
+            &[
+                ("score", count.clone()),
+                ("captures", strings.clone()),
+                ("fqn", string.clone()),
+                ("canonical_fqn", string.clone()),
+                ("display_fqn", string.clone()),
+                ("content_hash", string.clone()),
+                ("symbol_kind_from_chunk", string.clone()),
+                ("snippet", string.clone()),
+                ("snippet_truncated", boolean.clone()),
+                ("line_text", string.clone()),
+                ("visibility", string.clone()),
+                ("language", string.clone()),
+                ("kind_normalized", string.clone()),
+                ("complexity_score", count.clone()),
+                ("fan_in", count.clone()),
+                ("fan_out", count.clone()),
+                ("call_count", count.clone()),
+                ("cyclomatic_complexity", count.clone()),
+                (
+                    "complexity_percentile",
+                    json!({ "type": "integer", "minimum": 0, "maximum": 100 }),
+                ),
+                ("ast_context", reference("AstContext")),
+                ("ast_node_kind", string.clone()),
+                ("supernode_id", string.clone()),
+                ("coverage", reference("CoverageInfo")),
+                ("source_db", string.clone()),
+                ("defined_in", reference("DefinedIn")),
+                ("callers", array_of(reference("CallerRef"))),
+            ],
+        ),
+        "ReferenceMatch": object(
+            &[
+                ("match_id", string.clone()),
+                ("span", reference("Span")),
+                ("referenced_symbol", string.clone()),
+                ("reference_kind", nullable("string")),
+                ("target_symbol_id", nullable("string")),
+            ],
+            &reference_optional,
+        ),
+        "CallMatch": object(
+            &[
+                ("match_id", string.clone()),
+                ("span", reference("Span")),
+                ("caller", string.clone()),
+                ("callee", string.clone()),
+                ("caller_symbol_id", nullable("string")),
+                ("callee_symbol_id", nullable("string")),
+            ],
+            &call_optional,
+        ),
+        "SearchResponse": object(
+            &[
+                ("results", array_of(reference("SymbolMatch"))),
+                ("query", string.clone()),
+                ("total_count", count.clone()),
+            ],
+            &[
+                ("path_filter", string.clone()),
+                ("kind_filter", string.clone()),
+                ("notice", string.clone()),
+                ("available_fields", strings.clone()),
+            ],
+        ),
+        "ReferenceSearchResponse": object(
+            &[
+                ("results", array_of(reference("ReferenceMatch"))),
+                ("query", string.clone()),
+                ("total_count", count.clone()),
+            ],
+            &[("path_filter", string.clone())],
+        ),
+        "CallSearchResponse": object(
+            &[
+                ("results", array_of(reference("CallMatch"))),
+                ("query", string.clone()),
+                ("total_count", count.clone()),
+            ],
+            &[("path_filter", string.clone())],
+        ),
+        "OmittedCounts": object(
+            &[
+                ("symbols", count.clone()),
+                ("references", count.clone()),
+                ("calls", count.clone()),
+            ],
+            &[],
+        ),
+        "CombinedSearchResponse": object(
+            &[
+                ("query", string.clone()),
+                ("symbols", reference("SearchResponse")),
+                ("references", reference("ReferenceSearchResponse")),
+                ("calls", reference("CallSearchResponse")),
+                ("total_count", count.clone()),
+                ("limit_mode", string.clone()),
+            ],
+            &[
+                ("path_filter", string.clone()),
+                ("omitted", reference("OmittedCounts")),
+            ],
+        ),
+    })
+}
+
+/// JSON Schema of the `--output json` search response envelope.
+///
+/// `data` is matched with `anyOf` rather than `oneOf`: with no results, a
+/// references response is structurally identical to a calls response.
+pub fn search_response_schema() -> Value {
+    let mut schema = object(
+        &[
+            ("schema_version", json!({ "const": SCHEMA_VERSION })),
+            ("execution_id", json!({ "type": "string" })),
+            ("tool", json!({ "const": "llmgrep" })),
+            (
+                "timestamp",
+                json!({ "type": "string", "format": "date-time" }),
+            ),
+            ("partial", json!({ "type": "boolean" })),
+            (
+                "data",
+                json!({
+                    "anyOf": [
+                        reference("SearchResponse"),
+                        reference("CombinedSearchResponse"),
+                        reference("ReferenceSearchResponse"),
+                        reference("CallSearchResponse"),
+                    ]
+                }),
+            ),
+        ],
+        &[
+            ("performance", reference("PerformanceMetrics")),
+            (
+                "tokens_estimated",
+                json!({ "type": "integer", "minimum": 0 }),
+            ),
+            ("truncated", json!({ "type": "boolean" })),
+        ],
+    );
+    let root = schema
+        .as_object_mut()
+        .expect("object() builds a JSON object");
+    root.insert("$schema".to_string(), json!(JSON_SCHEMA_DIALECT));
+    root.insert("title".to_string(), json!("llmgrep search response"));
+    root.insert("$defs".to_string(), definitions());
+    schema
+}
@@ -0,0 +1,115 @@
+//! Source annotation with per-symbol complexity ("heat").
+//!
+//! Joins the symbols defined in one file with `symbol_metrics` and prefixes
+//! each source line covered by a symbol with its cyclomatic complexity, so
+//! high-complexity functions stand out in a review overlay.
+
+use rusqlite::Connection;
+
+use crate::error::LlmError;
+
+/// Line range of one symbol in a file, with its cyclomatic complexity.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct HeatSpan {
+    pub name: String,
+    pub start_line: u64,
+    pub end_line: u64,
+    pub cyclomatic_complexity: i64,
+}
+
+/// Load heat spans for symbols defined in `file_path` that have metrics.
+pub fn file_heat_spans(conn: &Connection, file_path: &str) -> Result<Vec<HeatSpan>, LlmError> {
+    let metrics_exist: bool = conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type='table' AND name='symbol_metrics'",
+            [],
+            |_| Ok(true),
+        )
+        .unwrap_or(false);
+
+    if !metrics_exist {
+        return Err(LlmError::InvalidQuery {
+            query: "symbol_metrics table not found. Re-index with 'magellan watch --scan-initial' to populate metrics.".to_string(),
+        });
+    }
+
+    let mut stmt = conn.prepare(
+        "SELECT json_extract(s.data, '$.name'),
+                json_extract(s.data, '$.start_line'),
+                json_extract(s.data, '$.end_line'),
+                sm.cyclomatic_complexity
+         FROM graph_entities f
+         JOIN graph_edges e ON e.from_id = f.id AND e.edge_type = 'DEFINES'
+         JOIN graph_entities s ON s.id = e.to_id AND s.kind = 'Symbol'
+         JOIN symbol_metrics sm ON sm.symbol_id = s.id
+         WHERE f.kind = 'File' AND json_extract(f.data, '$.path') = ?1
+         ORDER BY 2, 3",
+    )?;
+    let rows = stmt.query_map([file_path], |row| {
+        Ok(HeatSpan {
+            name: row.get(0)?,
+            start_line: row.get(1)?,
+            end_line: row.get(2)?,
+            cyclomatic_complexity: row.get(3)?,
+        })
+    })?;
+
+    let mut spans = Vec::new();
+    for row in rows {
+        spans.push(row?);
+    }
+    Ok(spans)
+}
+
+/// ANSI color for a complexity value: green up to 5, yellow up to 10, red above.
+fn heat_color(cc: i64) -> &'static str {
+    match cc {
+        ..=5 => "\x1b[32m",
+        6..=10 => "\x1b[33m",
+        _ => "\x1b[31m",
+    }
+}
+
+/// Render `source` with a `[cc:N]` gutter on every line covered by a span.
+///
+/// Lines covered by several (nested) spans use the innermost one. Lines
+/// outside every span get a blank gutter of the same width. With `color`, the
+/// gutter is wrapped in an ANSI color chosen by severity.
+pub fn annotate_heat(source: &str, spans: &[HeatSpan], color: bool) -> String {
+    let gutters: Vec<String> = spans
+        .iter()
+        .map(|s| format!("[cc:{}]", s.cyclomatic_complexity))
+        .collect();
+    let gutter_width = gutters.iter().map(String::len).max().unwrap_or(0);
+    let line_count = source.lines().count();
+    let number_width = line_count.max(1).to_string().len();
+
+    let mut out = String::new();
+    for (idx, line) in source.lines().enumerate() {
+        let line_no = idx as u64 + 1;
+        let innermost = spans
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| s.start_line <= line_no && line_no <= s.end_line)
+            .min_by_key(|(_, s)| s.end_line - s.start_line);
+
+        let gutter = match innermost {
+            Some((i, span)) if color => format!(
+                "{}{:<width$}\x1b[0m",
+                heat_color(span.cyclomatic_complexity),
+                gutters[i],
+                width = gutter_width
+            ),
+            Some((i, _)) => format!("{:<width$}", gutters[i], width = gutter_width),
+            None => " ".repeat(gutter_width),
+        };
+        out.push_str(&format!(
+            "{} {:>width$} | {}\n",
+            gutter,
+            line_no,
+            line,
+            width = number_width
+        ));
+    }
+    out
+}
@@ -5,7 +5,9 @@
 
 use crate::algorithm::{symbol_set_filter_strategy, SymbolSetStrategy};
 use crate::query::options::{CoverageFilter, MetricsOptions};
-use crate::query::util::{like_pattern, like_prefix};
+use crate::query::util::{
+    like_path, like_pattern, like_prefix, normalize_fqn, NORMALIZED_CANONICAL_FQN_SQL,
+};
 use crate::SortMode;
 use rusqlite::{Connection, ToSql};
 use std::collections::HashSet;
@@ -18,6 +20,13 @@ pub(crate) fn check_symbol_fts_exists(conn: &Connection) -> Result<bool, rusqlit
     stmt.exists([])
 }
 
+/// Check if the `symbol_metrics` table exists in the database.
+pub(crate) fn check_metrics_table_exists(conn: &Connection) -> Result<bool, rusqlite::Error> {
+    let mut stmt = conn
+        .prepare("SELECT name FROM sqlite_master WHERE type='table' AND name='symbol_metrics'")?;
+    stmt.exists([])
+}
+
 /// Convert a user search query into an FTS5 MATCH expression with OR + prefix semantics.
 ///
 /// FTS5 defaults to AND for space-separated terms, which causes multi-word
@@ -128,6 +137,9 @@ fn expand_kind_aliases(kind: &str) -> Vec<String> {
 pub(crate) fn build_search_query(
     query: &str,
     path_filter: Option<&PathBuf>,
+    path_glob: bool,
+    exclude_paths: &[PathBuf],
+    file_set: Option<&[String]>,
     kind_filter: Option<&str>,
     language_filter: Option<&str>,
     use_regex: bool,
@@ -138,7 +150,9 @@ pub(crate) fn build_search_query(
     symbol_id: Option<&str>,
     fqn_pattern: Option<&str>,
     exact_fqn: Option<&str>,
+    normalize_fqn_separators: bool,
     has_ast_table: bool,
+    has_metrics_table: bool,
     ast_kinds: &[String],
     _min_depth: Option<usize>,
     _max_depth: Option<usize>,
@@ -156,8 +170,9 @@ pub(crate) fn build_search_query(
     if let Some(sid) = symbol_id {
         where_clauses.push("json_extract(s.data, '$.symbol_id') = ?".to_string());
         params.push(Box::new(sid.to_string()));
-    } else if !use_regex {
-        // Standard name-based search (only if not using symbol_id)
+    } else if !use_regex && !query.is_empty() {
+        // Standard name-based search (only if not using symbol_id).
+        // An empty query (`--all`) adds no name predicate at all.
         if use_fts5 && !query.trim().is_empty() {
             // FTS5 with OR semantics for multi-word queries
             let fts_query = fts5_or_query(query);
@@ -178,24 +193,48 @@ pub(crate) fn build_search_query(
         }
     }
 
+    // FQN filters compare separator-normalized forms when requested
+    let (canonical_fqn_col, fqn_arg): (&str, fn(&str) -> String) = if normalize_fqn_separators {
+        (NORMALIZED_CANONICAL_FQN_SQL, normalize_fqn)
+    } else {
+        ("json_extract(s.data, '$.canonical_fqn')", str::to_string)
+    };
+
     // FQN pattern filter (LIKE match on canonical_fqn)
     if let Some(pattern) = fqn_pattern {
-        where_clauses
-            .push("json_extract(s.data, '$.canonical_fqn') LIKE ? ESCAPE '\\'".to_string());
-        params.push(Box::new(pattern.to_string()));
+        where_clauses.push(format!("{} LIKE ? ESCAPE '\\'", canonical_fqn_col));
+        params.push(Box::new(fqn_arg(pattern)));
     }
 
     // Exact FQN filter (exact match on canonical_fqn)
     if let Some(exact) = exact_fqn {
-        where_clauses.push("json_extract(s.data, '$.canonical_fqn') = ?".to_string());
-        params.push(Box::new(exact.to_string()));
+        where_clauses.push(format!("{} = ?", canonical_fqn_col));
+        params.push(Box::new(fqn_arg(exact)));
     }
 
     if let Some(path) = path_filter {
         where_clauses.push("f.file_path LIKE ? ESCAPE '\\'".to_string());
+        params.push(Box::new(like_path(path, path_glob)));
+    }
+    for path in exclude_paths {
+        where_clauses.push("f.file_path NOT LIKE ? ESCAPE '\\'".to_string());
         params.push(Box::new(like_prefix(path)));
     }
 
+    if let Some(files) = file_set {
+        if files.is_empty() {
+            where_clauses.push("0".to_string());
+        } else {
+            let placeholders = files.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+            where_clauses.push(format!("f.file_path IN ({})", placeholders));
+            params.extend(
+                files
+                    .iter()
+                    .map(|file| Box::new(file.clone()) as Box<dyn ToSql>),
+            );
+        }
+    }
+
     if let Some(kind) = kind_filter {
         let raw_kinds: Vec<&str> = kind
             .split(',')
@@ -304,10 +343,31 @@ pub(crate) fn build_search_query(
         where_clauses.push("(sm.fan_in IS NOT NULL AND sm.fan_in >= ?)".to_string());
         params.push(Box::new(min_fi as i64));
     }
+    if let Some(max_fi) = metrics.max_fan_in {
+        // Derived fan-in has no row for unreferenced symbols; those count as 0
+        if has_metrics_table {
+            where_clauses.push("(sm.fan_in IS NOT NULL AND sm.fan_in <= ?)".to_string());
+        } else {
+            where_clauses.push("COALESCE(sm.fan_in, 0) <= ?".to_string());
+        }
+        params.push(Box::new(max_fi as i64));
+    }
     if let Some(min_fo) = metrics.min_fan_out {
         where_clauses.push("(sm.fan_out IS NOT NULL AND sm.fan_out >= ?)".to_string());
         params.push(Box::new(min_fo as i64));
     }
+    if let Some(max_fo) = metrics.max_fan_out {
+        where_clauses.push("(sm.fan_out IS NOT NULL AND sm.fan_out <= ?)".to_string());
+        params.push(Box::new(max_fo as i64));
+    }
+    if let Some(min_loc) = metrics.min_loc {
+        where_clauses.push("(sm.loc IS NOT NULL AND sm.loc >= ?)".to_string());
+        params.push(Box::new(min_loc as i64));
+    }
+    if let Some(max_loc) = metrics.max_loc {
+        where_clauses.push("(sm.loc IS NOT NULL AND sm.loc <= ?)".to_string());
+        params.push(Box::new(max_loc as i64));
+    }
 
     // Structural search: --inside KIND (find descendants within any ancestor of type KIND)
     if let Some(inside_kind) = inside_kind {
@@ -413,6 +473,14 @@ pub(crate) fn build_search_query(
         ]
     };
 
+    select_cols.push("f.id AS file_id");
+    select_cols.push("f.data AS file_data");
+
+    if metrics.weight_by_calls {
+        select_cols
+            .push("COALESCE(cw_id.call_count, 0) + COALESCE(cw_name.call_count, 0) AS call_count");
+    }
+
     if has_coverage {
         select_cols.push("cov.total_blocks");
         select_cols.push("cov.covered_blocks");
@@ -420,6 +488,12 @@ pub(crate) fn build_search_query(
         select_cols.push("edge_cov.covered_edges");
     }
 
+    let needs_fan_in = sort_by == SortMode::FanIn
+        || metrics.min_fan_in.is_some()
+        || metrics.max_fan_in.is_some()
+        || metrics.boost_fan_in
+        || metrics.with_fan_in;
+
     let select_clause = if count_only {
         "SELECT COUNT(*)".to_string()
     } else {
@@ -446,16 +520,28 @@ FROM (
 ) s
 JOIN graph_edges e ON e.to_id = s.id AND e.edge_type = 'DEFINES'
 JOIN (
-    SELECT id, json_extract(data, '$.path') AS file_path
+    SELECT id, data, json_extract(data, '$.path') AS file_path
     FROM graph_entities
     WHERE kind = 'File'
 ) f ON f.id = e.from_id
-LEFT JOIN symbol_metrics sm ON s.id = sm.symbol_id
+{metrics_join}
 {ast_join}
+{call_weight_join}
 {coverage_join}
 {edge_coverage_join}
 WHERE {where_clause}",
         select_clause = select_clause,
+        metrics_join = if has_metrics_table {
+            "LEFT JOIN symbol_metrics sm ON s.id = sm.symbol_id".to_string()
+        } else if !needs_fan_in {
+            // No metrics and nothing asks for fan-in: keep the sm columns NULL
+            // without scanning graph_edges
+            "LEFT JOIN (\n            SELECT NULL AS symbol_id,\n                   NULL AS fan_in,\n                   NULL AS fan_out,\n                   NULL AS cyclomatic_complexity,\n                   NULL AS loc\n            WHERE 0\n        ) sm ON s.id = sm.symbol_id".to_string()
+        } else {
+            // Without precomputed metrics, derive fan-in from REFERENCES edges so
+            // --sort-by fan-in and --min-fan-in still work on older databases.
+            "LEFT JOIN (\n            SELECT to_id AS symbol_id,\n                   COUNT(DISTINCT from_id) AS fan_in,\n                   NULL AS fan_out,\n                   NULL AS cyclomatic_complexity,\n                   NULL AS loc\n            FROM graph_edges\n            WHERE edge_type = 'REFERENCES'\n            GROUP BY to_id\n        ) sm ON s.id = sm.symbol_id".to_string()
+        },
         ast_join = if has_ast_table {
             // Use a correlated subquery to pick exactly one ast_node per symbol byte span.
             // This avoids duplicate rows when multiple AST nodes overlap the same span.
@@ -463,6 +549,13 @@ WHERE {where_clause}",
         } else {
             "".to_string()
         },
+        call_weight_join = if metrics.weight_by_calls {
+            // Calls resolved to a symbol id count toward that symbol; older
+            // calls without one fall back to matching the callee name.
+            "LEFT JOIN (\n            SELECT json_extract(data, '$.callee_symbol_id') AS symbol_id, COUNT(*) AS call_count\n            FROM graph_entities\n            WHERE kind = 'Call' AND json_extract(data, '$.callee_symbol_id') IS NOT NULL\n            GROUP BY symbol_id\n        ) cw_id ON cw_id.symbol_id = json_extract(s.data, '$.symbol_id')\nLEFT JOIN (\n            SELECT json_extract(data, '$.callee') AS callee, COUNT(*) AS call_count\n            FROM graph_entities\n            WHERE kind = 'Call' AND json_extract(data, '$.callee_symbol_id') IS NULL\n            GROUP BY callee\n        ) cw_name ON cw_name.callee = s.name".to_string()
+        } else {
+            "".to_string()
+        },
         coverage_join = if has_coverage {
             "LEFT JOIN (\n            SELECT b.function_id,\n                   COUNT(b.id) as total_blocks,\n                   COUNT(CASE WHEN COALESCE(bc.hit_count, 0) > 0 THEN 1 END) as covered_blocks\n            FROM cfg_blocks b\n            LEFT JOIN cfg_block_coverage bc ON b.id = bc.block_id\n            GROUP BY b.function_id\n        ) cov ON cov.function_id = s.id".to_string()
         } else {
@@ -483,6 +576,10 @@ WHERE {where_clause}",
     if !count_only {
         // Determine ORDER BY clause based on sort mode
         let order_by = match sort_by {
+            SortMode::FanIn if metrics.weight_by_calls => {
+                // Sort by total call sites descending
+                "call_count DESC, s.start_line, s.start_col, s.byte_start, s.byte_end, s.id"
+            }
             SortMode::FanIn => {
                 // Sort by fan_in descending, NULLs last
                 "COALESCE(sm.fan_in, 0) DESC, s.start_line, s.start_col, s.byte_start, s.byte_end, s.id"
@@ -495,16 +592,35 @@ WHERE {where_clause}",
                 // Sort by cyclomatic_complexity descending, NULLs last
                 "COALESCE(sm.cyclomatic_complexity, 0) DESC, s.start_line, s.start_col, s.byte_start, s.byte_end, s.id"
             }
+            SortMode::Loc => {
+                // Sort by lines of code descending, NULLs last
+                "COALESCE(sm.loc, 0) DESC, s.start_line, s.start_col, s.byte_start, s.byte_end, s.id"
+            }
             SortMode::AstComplexity => {
                 // Sort by AST complexity (cyclomatic_complexity), same as Complexity mode
                 "COALESCE(sm.cyclomatic_complexity, 0) DESC, s.start_line, s.start_col, s.byte_start, s.byte_end, s.id"
             }
             SortMode::NestingDepth => {
-                // Nesting depth is calculated post-query via batch CTE and sorted in-memory
+                // Decision depth is calculated post-query and sorted in-memory
+                "s.start_line, s.start_col, s.byte_start, s.byte_end, s.id"
+            }
+            SortMode::LastModified => {
+                // File mtime is not in the graph; sorted in-memory after collection
                 "s.start_line, s.start_col, s.byte_start, s.byte_end, s.id"
             }
-            SortMode::Position => {
-                // Position-based ordering (faster, pure SQL ORDER BY)
+            SortMode::Review => {
+                // Reading order: files alphabetically, then position within each file
+                "f.file_path, s.start_line, s.start_col, s.byte_start, s.byte_end, s.id"
+            }
+            SortMode::Name => {
+                // Alphabetical inventory; file and position break ties deterministically
+                "s.name COLLATE NOCASE ASC, f.file_path, s.start_line, s.start_col, s.byte_start, s.byte_end, s.id"
+            }
+            SortMode::NameDesc => {
+                "s.name COLLATE NOCASE DESC, f.file_path, s.start_line, s.start_col, s.byte_start, s.byte_end, s.id"
+            }
+            SortMode::Position | SortMode::Hybrid => {
+                // Position-based ordering; Hybrid rescores the --limit window in-memory
                 "s.start_line, s.start_col, s.byte_start, s.byte_end, s.id"
             }
             SortMode::Relevance => {
@@ -520,9 +636,13 @@ WHERE {where_clause}",
     (sql, params, symbol_set_strategy)
 }
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn build_reference_query(
     query: &str,
     path_filter: Option<&PathBuf>,
+    path_glob: bool,
+    exclude_paths: &[PathBuf],
+    reference_kind: Option<&str>,
     use_regex: bool,
     count_only: bool,
     limit: usize,
@@ -538,9 +658,31 @@ pub(crate) fn build_reference_query(
 
     if let Some(path) = path_filter {
         where_clauses.push("json_extract(r.data, '$.file') LIKE ? ESCAPE '\\'".to_string());
+        params.push(Box::new(like_path(path, path_glob)));
+    }
+    for path in exclude_paths {
+        where_clauses.push("json_extract(r.data, '$.file') NOT LIKE ? ESCAPE '\\'".to_string());
         params.push(Box::new(like_prefix(path)));
     }
 
+    if let Some(kinds) = reference_kind {
+        let kinds: Vec<&str> = kinds
+            .split(',')
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .collect();
+        if !kinds.is_empty() {
+            let placeholders = kinds.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+            where_clauses.push(format!(
+                "json_extract(r.data, '$.kind') IN ({})",
+                placeholders
+            ));
+            for kind in kinds {
+                params.push(Box::new(kind.to_string()));
+            }
+        }
+    }
+
     let select_clause = if count_only {
         "SELECT COUNT(*)"
     } else {
@@ -571,6 +713,8 @@ WHERE {where_clause}",
 pub(crate) fn build_call_query(
     query: &str,
     path_filter: Option<&PathBuf>,
+    path_glob: bool,
+    exclude_paths: &[PathBuf],
     use_regex: bool,
     count_only: bool,
     limit: usize,
@@ -590,6 +734,10 @@ pub(crate) fn build_call_query(
 
     if let Some(path) = path_filter {
         where_clauses.push("json_extract(c.data, '$.file') LIKE ? ESCAPE '\\'".to_string());
+        params.push(Box::new(like_path(path, path_glob)));
+    }
+    for path in exclude_paths {
+        where_clauses.push("json_extract(c.data, '$.file') NOT LIKE ? ESCAPE '\\'".to_string());
         params.push(Box::new(like_prefix(path)));
     }
 
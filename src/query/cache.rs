@@ -0,0 +1,132 @@
+//! Opt-in on-disk cache of symbol search responses.
+//!
+//! Enabled with `--cache` or `LLMGREP_CACHE=1`. Each entry lives in
+//! `~/.magellan/llmgrep-cache/<sha256>.json`, keyed by the full
+//! [`SearchOptions`] (database path included). An entry records the
+//! database's modification time and only counts as a hit while that time is
+//! unchanged; a stale entry is overwritten by the next search with the same
+//! options. `llmgrep cache clear` removes every entry. Without `HOME` the
+//! cache is disabled.
+
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::error::LlmError;
+use crate::output::SearchResponse;
+use crate::query::options::SearchOptions;
+
+/// Symbol search result as returned by the backend:
+/// (response, partial_results, paths_bounded).
+pub type CachedSearch = (SearchResponse, bool, bool);
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    db_mtime_ns: u64,
+    partial: bool,
+    paths_bounded: bool,
+    response: SearchResponse,
+}
+
+/// Directory of cached search responses.
+#[derive(Debug, Clone)]
+pub struct QueryCache {
+    dir: PathBuf,
+}
+
+impl QueryCache {
+    /// Cache rooted at `dir` (created on first write).
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Cache under `~/.magellan/llmgrep-cache`, or `None` when `HOME` is unset
+    /// (a shared fallback such as `/tmp` would let other users plant entries).
+    pub fn default_location() -> Option<Self> {
+        let home = std::env::var_os("HOME").filter(|home| !home.is_empty())?;
+        Some(Self::new(
+            PathBuf::from(home).join(".magellan").join("llmgrep-cache"),
+        ))
+    }
+
+    /// Whether caching was requested through the environment.
+    pub fn enabled_by_env() -> bool {
+        std::env::var("LLMGREP_CACHE").is_ok_and(|v| v == "1")
+    }
+
+    fn entry_path(&self, options: &SearchOptions) -> PathBuf {
+        let mut hasher = Sha256::new();
+        hasher.update(env!("CARGO_PKG_VERSION"));
+        hasher.update(format!("{:?}", options));
+        self.dir
+            .join(format!("{}.json", hex::encode(hasher.finalize())))
+    }
+
+    /// Cached result for `options`, if one was stored against the database's
+    /// current modification time.
+    pub fn get(&self, options: &SearchOptions) -> Option<CachedSearch> {
+        let db_mtime_ns = db_mtime_ns(options.db_path)?;
+        let contents = std::fs::read(self.entry_path(options)).ok()?;
+        let entry: CacheEntry = serde_json::from_slice(&contents).ok()?;
+        (entry.db_mtime_ns == db_mtime_ns).then_some((
+            entry.response,
+            entry.partial,
+            entry.paths_bounded,
+        ))
+    }
+
+    /// Store `result` for `options` against the database's current
+    /// modification time.
+    pub fn put(&self, options: &SearchOptions, result: &CachedSearch) -> Result<(), LlmError> {
+        let Some(db_mtime_ns) = db_mtime_ns(options.db_path) else {
+            return Ok(());
+        };
+        let (response, partial, paths_bounded) = result;
+        let entry = CacheEntry {
+            db_mtime_ns,
+            partial: *partial,
+            paths_bounded: *paths_bounded,
+            response: response.clone(),
+        };
+        std::fs::create_dir_all(&self.dir)?;
+        // Write then rename so a concurrent reader never sees a partial entry
+        let path = self.entry_path(options);
+        let tmp = path.with_extension(format!("tmp{}", std::process::id()));
+        std::fs::write(&tmp, serde_json::to_vec(&entry)?)?;
+        std::fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+
+    /// Remove every cached entry, returning how many were removed.
+    pub fn clear(&self) -> Result<usize, LlmError> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e.into()),
+        };
+        let mut removed = 0;
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                std::fs::remove_file(&path)?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+}
+
+/// Latest modification time of the database and its WAL file, which takes
+/// writes without touching the main file until a checkpoint.
+fn db_mtime_ns(db_path: &Path) -> Option<u64> {
+    let mtime_ns = |path: &Path| -> Option<u64> {
+        let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+        Some(modified.duration_since(UNIX_EPOCH).ok()?.as_nanos() as u64)
+    };
+    let mut wal = db_path.as_os_str().to_owned();
+    wal.push("-wal");
+    let db = mtime_ns(db_path)?;
+    Some(mtime_ns(Path::new(&wal)).map_or(db, |wal| wal.max(db)))
+}
@@ -0,0 +1,98 @@
+//! Backend capability report.
+//!
+//! Commands and search modes are gated on the tables the indexer wrote
+//! (`ast_nodes`, `symbol_metrics`, `hnsw_indexes`, ...), each checked where
+//! it is used. This module runs the same checks up front so a client can
+//! ask which commands the opened database supports before calling them.
+
+use rusqlite::Connection;
+use serde::Serialize;
+
+use crate::backend::schema_check::check_coverage_tables_exist;
+use crate::error::LlmError;
+
+/// Which commands and search modes the opened database supports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Capabilities {
+    /// Detected backend variant
+    pub backend: &'static str,
+    /// `search` symbols/references/calls modes (`graph_entities`)
+    pub search: bool,
+    pub complete: bool,
+    pub lookup: bool,
+    pub search_by_label: bool,
+    pub card: bool,
+    /// `edit-script` (`graph_entities` plus `REFERENCES` edges in `graph_edges`)
+    pub edit_script: bool,
+    /// `ast` and `find-ast` (`ast_nodes`)
+    pub ast: bool,
+    pub find_ast: bool,
+    /// `evolve`, `annotate --heat` and the `--min-complexity`/fan filters (`symbol_metrics`)
+    pub metrics: bool,
+    /// `--coverage` filters (all three `cfg_*_coverage` tables)
+    pub coverage: bool,
+    /// `--mode semantic` (`hnsw_indexes`)
+    pub semantic: bool,
+    /// `--mode docs` (`source_documents`)
+    pub docs: bool,
+    /// `--mode facts` (`candidate_facts`)
+    pub facts: bool,
+    /// Chunk-backed snippets (`code_chunks`)
+    pub chunks: bool,
+    /// Full-text symbol prefilter (`symbol_fts`)
+    pub fts: bool,
+}
+
+impl Capabilities {
+    /// Optional symbol result fields this database can populate.
+    ///
+    /// `fan_in` falls back to counting `REFERENCES` edges when there is no
+    /// `symbol_metrics` table, so it only needs the graph edges.
+    pub fn available_symbol_fields(&self) -> Vec<&'static str> {
+        let mut fields = Vec::new();
+        if self.metrics || self.edit_script {
+            fields.push("fan_in");
+        }
+        if self.metrics {
+            fields.extend(["fan_out", "cyclomatic_complexity"]);
+        }
+        if self.ast {
+            fields.extend(["ast_context", "ast_node_kind"]);
+        }
+        if self.coverage {
+            fields.push("coverage");
+        }
+        fields
+    }
+}
+
+fn table_exists(conn: &Connection, table: &str) -> Result<bool, LlmError> {
+    let mut stmt = conn.prepare("SELECT 1 FROM sqlite_master WHERE type='table' AND name=?1")?;
+    Ok(stmt.exists([table])?)
+}
+
+/// Report the capabilities of a SQLite database.
+pub fn capabilities(conn: &Connection) -> Result<Capabilities, LlmError> {
+    let graph = table_exists(conn, "graph_entities")?;
+    let edges = graph && table_exists(conn, "graph_edges")?;
+    let ast = table_exists(conn, "ast_nodes")?;
+
+    Ok(Capabilities {
+        backend: "sqlite",
+        search: graph,
+        complete: graph,
+        lookup: graph,
+        search_by_label: graph,
+        card: graph,
+        edit_script: edges,
+        ast,
+        find_ast: ast,
+        metrics: table_exists(conn, "symbol_metrics")?,
+        coverage: check_coverage_tables_exist(conn),
+        semantic: table_exists(conn, "hnsw_indexes")?,
+        docs: table_exists(conn, "source_documents")?,
+        facts: table_exists(conn, "candidate_facts")?,
+        chunks: table_exists(conn, "code_chunks")?,
+        fts: table_exists(conn, "symbol_fts")?,
+    })
+}
@@ -0,0 +1,133 @@
+//! Symbol card: one consolidated detail view of a single symbol.
+//!
+//! Composes an FQN lookup with cheap count queries (immediate callers and
+//! callees), the `symbol_metrics` join, and the exact-span AST kind, so an
+//! editor info panel can be filled in one call.
+
+use rusqlite::{Connection, OptionalExtension};
+
+use crate::error::LlmError;
+use crate::output::SymbolMatch;
+
+/// Consolidated detail view of one symbol.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct SymbolCard {
+    pub name: String,
+    pub kind: String,
+    pub file: String,
+    pub start_line: u64,
+    pub start_col: u64,
+    pub end_line: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fqn: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub canonical_fqn: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_fqn: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cyclomatic_complexity: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fan_in: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fan_out: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub loc: Option<i64>,
+    /// Distinct symbols with a call to this one
+    pub callers: u64,
+    /// Distinct symbols this one calls
+    pub callees: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ast_kind: Option<String>,
+}
+
+fn table_exists(conn: &Connection, table: &str) -> Result<bool, LlmError> {
+    let mut stmt = conn.prepare("SELECT 1 FROM sqlite_master WHERE type='table' AND name=?1")?;
+    Ok(stmt.exists([table])?)
+}
+
+/// Count distinct counterparts of `symbol` on one side of `Call` entities.
+///
+/// Calls carrying `<side>_symbol_id` match by ID; older calls without one
+/// fall back to matching by name.
+fn count_call_partners(
+    conn: &Connection,
+    symbol: &SymbolMatch,
+    side: &str,
+    partner: &str,
+) -> Result<u64, LlmError> {
+    let sql = format!(
+        "SELECT COUNT(DISTINCT json_extract(data, '$.{partner}'))
+         FROM graph_entities
+         WHERE kind = 'Call'
+           AND (json_extract(data, '$.{side}_symbol_id') = ?1
+                OR (json_extract(data, '$.{side}_symbol_id') IS NULL
+                    AND json_extract(data, '$.{side}') = ?2))"
+    );
+    let count: i64 = conn.query_row(
+        &sql,
+        rusqlite::params![symbol.symbol_id, symbol.name],
+        |row| row.get(0),
+    )?;
+    Ok(count as u64)
+}
+
+/// Build the card for a symbol already resolved by FQN lookup.
+///
+/// Metrics and AST kind are left empty when the `symbol_metrics` or
+/// `ast_nodes` tables are missing, or hold no row for the symbol.
+pub fn symbol_card(conn: &Connection, symbol: SymbolMatch) -> Result<SymbolCard, LlmError> {
+    let callers = count_call_partners(conn, &symbol, "callee", "caller")?;
+    let callees = count_call_partners(conn, &symbol, "caller", "callee")?;
+
+    let metrics = match (&symbol.symbol_id, table_exists(conn, "symbol_metrics")?) {
+        (Some(symbol_id), true) => conn
+            .query_row(
+                "SELECT sm.cyclomatic_complexity, sm.fan_in, sm.fan_out, sm.loc
+                 FROM symbol_metrics sm
+                 JOIN graph_entities e ON e.id = sm.symbol_id
+                 WHERE e.kind = 'Symbol' AND json_extract(e.data, '$.symbol_id') = ?1
+                 LIMIT 1",
+                [symbol_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .optional()?,
+        _ => None,
+    };
+    let (cyclomatic_complexity, fan_in, fan_out, loc) = match metrics {
+        Some((cc, fan_in, fan_out, loc)) => (Some(cc), Some(fan_in), Some(fan_out), Some(loc)),
+        None => (None, None, None, None),
+    };
+
+    let ast_kind = if table_exists(conn, "ast_nodes")? {
+        conn.query_row(
+            "SELECT kind FROM ast_nodes WHERE byte_start = ?1 AND byte_end = ?2 ORDER BY id LIMIT 1",
+            rusqlite::params![symbol.span.byte_start, symbol.span.byte_end],
+            |row| row.get(0),
+        )
+        .optional()?
+    } else {
+        None
+    };
+
+    Ok(SymbolCard {
+        name: symbol.name,
+        kind: symbol.kind,
+        file: symbol.span.file_path,
+        start_line: symbol.span.start_line,
+        start_col: symbol.span.start_col,
+        end_line: symbol.span.end_line,
+        language: symbol.language,
+        fqn: symbol.fqn,
+        canonical_fqn: symbol.canonical_fqn,
+        display_fqn: symbol.display_fqn,
+        cyclomatic_complexity,
+        fan_in,
+        fan_out,
+        loc,
+        callers,
+        callees,
+        ast_kind,
+    })
+}
@@ -0,0 +1,36 @@
+//! Ranking and segment narrowing for FQN completions.
+//!
+//! The backend returns matching FQNs; these helpers turn them into something
+//! a shell or editor completion source can use directly.
+
+/// FQN path separator used to split completions into segments
+const SEGMENT_SEPARATOR: &str = "::";
+
+/// Order completions closest-to-the-prefix first: shortest, then lexically.
+pub fn rank_completions(completions: &mut [String]) {
+    completions.sort_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)));
+}
+
+/// Narrow full FQN completions to the segment being completed.
+///
+/// For prefix `crate::backend::`, `crate::backend::sqlite::SqliteBackend`
+/// yields `sqlite`; for `crate::back` it yields `backend`. Completions that
+/// do not start with `prefix` are skipped and duplicates are dropped.
+pub fn next_segments(completions: &[String], prefix: &str) -> Vec<String> {
+    let start = prefix
+        .rfind(SEGMENT_SEPARATOR)
+        .map_or(0, |i| i + SEGMENT_SEPARATOR.len());
+    let mut segments: Vec<String> = Vec::new();
+    for completion in completions {
+        if !completion.starts_with(prefix) {
+            continue;
+        }
+        let rest = &completion[prefix.len()..];
+        let end = rest.find(SEGMENT_SEPARATOR).unwrap_or(rest.len());
+        let segment = &completion[start..prefix.len() + end];
+        if !segment.is_empty() && !segments.iter().any(|s| s == segment) {
+            segments.push(segment.to_string());
+        }
+    }
+    segments
+}
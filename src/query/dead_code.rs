@@ -0,0 +1,53 @@
+//! Callers of dead-code candidates.
+//!
+//! `--dead-code-in` yields symbols unreachable from an entry point. Before
+//! removing one it is worth confirming nothing still calls it: a caller that
+//! is itself dead means the two can go together, while a live caller means
+//! the reachability data and the call graph disagree.
+
+use std::collections::HashSet;
+
+use rusqlite::Connection;
+
+use crate::error::LlmError;
+use crate::output::{CallerRef, SymbolMatch};
+
+/// Attach `callers` to every result from its incoming `Call` entities.
+///
+/// Calls carrying `callee_symbol_id` match by ID; older calls without one
+/// fall back to matching by callee name. A caller is `dead` when its symbol
+/// ID is among `results`. Callers are distinct and ordered by name.
+pub fn attach_dead_code_callers(
+    conn: &Connection,
+    results: &mut [SymbolMatch],
+) -> Result<(), LlmError> {
+    let dead: HashSet<String> = results.iter().filter_map(|r| r.symbol_id.clone()).collect();
+
+    let mut stmt = conn.prepare_cached(
+        "SELECT DISTINCT json_extract(data, '$.caller'), json_extract(data, '$.caller_symbol_id')
+         FROM graph_entities
+         WHERE kind = 'Call'
+           AND (json_extract(data, '$.callee_symbol_id') = ?1
+                OR (json_extract(data, '$.callee_symbol_id') IS NULL
+                    AND json_extract(data, '$.callee') = ?2))
+         ORDER BY 1, 2",
+    )?;
+
+    for result in results.iter_mut() {
+        let rows = stmt.query_map(rusqlite::params![result.symbol_id, result.name], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?))
+        })?;
+        let mut callers = Vec::new();
+        for row in rows {
+            let (name, symbol_id) = row?;
+            let is_dead = symbol_id.as_ref().is_some_and(|id| dead.contains(id));
+            callers.push(CallerRef {
+                name,
+                symbol_id,
+                dead: is_dead,
+            });
+        }
+        result.callers = Some(callers);
+    }
+    Ok(())
+}
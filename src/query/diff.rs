@@ -0,0 +1,248 @@
+//! Diff command: compare two index builds.
+//!
+//! Metric diffs join symbols across databases by `canonical_fqn`, so a symbol
+//! that moved between entity IDs in a re-index still lines up with its
+//! baseline. Symbol diffs key on the stable `symbol_id` instead, which is what
+//! lets a symbol whose span changed be reported as moved rather than as a
+//! removal plus an addition.
+
+use std::collections::{BTreeMap, HashMap};
+
+use rusqlite::Connection;
+
+use crate::error::LlmError;
+use crate::output::{Span, SymbolMatch};
+use crate::query::util::{infer_language, match_id, span_id, SymbolNodeData};
+
+/// Metric compared by the diff command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffMetric {
+    /// Cyclomatic complexity from `symbol_metrics`
+    Complexity,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct MetricDelta {
+    pub fqn: String,
+    pub old: i64,
+    pub new: i64,
+    pub delta: i64,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DiffResponse {
+    pub metric: DiffMetric,
+    pub deltas: Vec<MetricDelta>,
+    pub total_count: usize,
+}
+
+/// Compare `metric` between a baseline and a current database.
+///
+/// Only symbols present in both databases whose value changed are reported,
+/// sorted by delta descending (largest regression first).
+pub fn run_diff(
+    baseline: &Connection,
+    current: &Connection,
+    metric: DiffMetric,
+) -> Result<DiffResponse, LlmError> {
+    let old = load_metric(baseline, metric)?;
+    let new = load_metric(current, metric)?;
+
+    let mut deltas: Vec<MetricDelta> = new
+        .into_iter()
+        .filter_map(|(fqn, new)| {
+            let old = *old.get(&fqn)?;
+            (old != new).then(|| MetricDelta {
+                fqn,
+                old,
+                new,
+                delta: new - old,
+            })
+        })
+        .collect();
+    deltas.sort_by(|a, b| b.delta.cmp(&a.delta).then_with(|| a.fqn.cmp(&b.fqn)));
+
+    let total_count = deltas.len();
+    Ok(DiffResponse {
+        metric,
+        deltas,
+        total_count,
+    })
+}
+
+fn load_metric(conn: &Connection, metric: DiffMetric) -> Result<HashMap<String, i64>, LlmError> {
+    let metrics_exist: bool = conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type='table' AND name='symbol_metrics'",
+            [],
+            |_| Ok(true),
+        )
+        .unwrap_or(false);
+
+    if !metrics_exist {
+        return Err(LlmError::InvalidQuery {
+            query: "symbol_metrics table not found. Re-index with 'magellan watch --scan-initial' to populate metrics.".to_string(),
+        });
+    }
+
+    let column = match metric {
+        DiffMetric::Complexity => "sm.cyclomatic_complexity",
+    };
+    let sql = format!(
+        "SELECT json_extract(e.data, '$.canonical_fqn'), {}
+         FROM symbol_metrics sm
+         JOIN graph_entities e ON e.id = sm.symbol_id
+         WHERE json_extract(e.data, '$.canonical_fqn') IS NOT NULL",
+        column
+    );
+
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map([], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+    })?;
+
+    let mut values = HashMap::new();
+    for row in rows {
+        let (fqn, value) = row?;
+        values.insert(fqn, value);
+    }
+    Ok(values)
+}
+
+/// A symbol present in both databases whose span changed.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct MovedSymbol {
+    /// The symbol as it appears in the current database
+    pub symbol: SymbolMatch,
+    pub old_span: Span,
+    pub new_span: Span,
+}
+
+/// Symbol-level difference between a baseline and a current database.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SymbolDiff {
+    pub added: Vec<SymbolMatch>,
+    pub removed: Vec<SymbolMatch>,
+    pub moved: Vec<MovedSymbol>,
+}
+
+/// Compare the symbols of two databases by `symbol_id`.
+///
+/// Symbols only in `current` are added, symbols only in `baseline` are
+/// removed, and symbols in both whose file, byte range, or line/column range
+/// differ are moved. Symbols without a `symbol_id` cannot be matched and are
+/// skipped. Each list is ordered by `symbol_id`.
+pub fn run_symbol_diff(
+    baseline: &Connection,
+    current: &Connection,
+) -> Result<SymbolDiff, LlmError> {
+    let mut old = load_symbols(baseline)?;
+    let new = load_symbols(current)?;
+
+    let mut added = Vec::new();
+    let mut moved = Vec::new();
+    for (symbol_id, symbol) in new {
+        match old.remove(&symbol_id) {
+            None => added.push(symbol),
+            Some(previous) if span_key(&previous.span) != span_key(&symbol.span) => {
+                moved.push(MovedSymbol {
+                    old_span: previous.span,
+                    new_span: symbol.span.clone(),
+                    symbol,
+                });
+            }
+            Some(_) => {}
+        }
+    }
+
+    Ok(SymbolDiff {
+        added,
+        removed: old.into_values().collect(),
+        moved,
+    })
+}
+
+fn span_key(span: &Span) -> (&str, u64, u64, u64, u64, u64, u64) {
+    (
+        &span.file_path,
+        span.byte_start,
+        span.byte_end,
+        span.start_line,
+        span.start_col,
+        span.end_line,
+        span.end_col,
+    )
+}
+
+fn load_symbols(conn: &Connection) -> Result<BTreeMap<String, SymbolMatch>, LlmError> {
+    let mut stmt = conn.prepare(
+        "SELECT s.data, json_extract(f.data, '$.path')
+         FROM graph_entities s
+         LEFT JOIN graph_edges e ON e.to_id = s.id AND e.edge_type = 'DEFINES'
+         LEFT JOIN graph_entities f ON f.id = e.from_id AND f.kind = 'File'
+         WHERE s.kind = 'Symbol'",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?))
+    })?;
+
+    let mut symbols = BTreeMap::new();
+    for row in rows {
+        let (data, file_path) = row?;
+        let symbol: SymbolNodeData = serde_json::from_str(&data)?;
+        let Some(symbol_id) = symbol.symbol_id.clone() else {
+            continue;
+        };
+        let file_path = file_path.unwrap_or_default();
+        let name = symbol.name.unwrap_or_default();
+        symbols.insert(
+            symbol_id.clone(),
+            SymbolMatch {
+                match_id: match_id(&file_path, symbol.byte_start, symbol.byte_end, &name),
+                span: Span {
+                    span_id: span_id(&file_path, symbol.byte_start, symbol.byte_end),
+                    file_path: file_path.clone(),
+                    byte_start: symbol.byte_start,
+                    byte_end: symbol.byte_end,
+                    start_line: symbol.start_line,
+                    start_col: symbol.start_col,
+                    end_line: symbol.end_line,
+                    end_col: symbol.end_col,
+                    context: None,
+                },
+                name,
+                kind: symbol.kind,
+                parent: None,
+                symbol_id: Some(symbol_id),
+                score: None,
+                fqn: symbol.fqn,
+                canonical_fqn: symbol.canonical_fqn,
+                display_fqn: symbol.display_fqn,
+                content_hash: None,
+                symbol_kind_from_chunk: None,
+                snippet: None,
+                snippet_truncated: None,
+                language: infer_language(&file_path).map(|s| s.to_string()),
+                kind_normalized: symbol.kind_normalized,
+                complexity_score: None,
+                fan_in: None,
+                fan_out: None,
+                cyclomatic_complexity: None,
+                ast_context: None,
+                ast_node_kind: None,
+                supernode_id: None,
+                coverage: None,
+                line_text: None,
+                visibility: None,
+                complexity_percentile: None,
+                call_count: None,
+                source_db: None,
+                captures: None,
+                defined_in: None,
+                callers: None,
+            },
+        );
+    }
+    Ok(symbols)
+}
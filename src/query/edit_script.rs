@@ -0,0 +1,116 @@
+//! Rename edit scripts.
+//!
+//! Composes an FQN lookup with the symbol's incoming `REFERENCES` edges and
+//! turns every occurrence into a byte-range replacement of the name. Spans
+//! from the index cover whole items (a definition span includes the body),
+//! so each span is narrowed to the first whole-identifier occurrence of the
+//! name inside it by reading the source file.
+
+use std::collections::HashMap;
+
+use rusqlite::Connection;
+use serde::Serialize;
+
+use crate::error::LlmError;
+use crate::output::SymbolMatch;
+use crate::query::util::ReferenceNodeData;
+
+/// One byte-range replacement.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TextEdit {
+    pub file: String,
+    pub byte_start: u64,
+    pub byte_end: u64,
+    pub replacement: String,
+}
+
+fn is_ident_byte(byte: u8) -> bool {
+    byte == b'_' || byte.is_ascii_alphanumeric() || !byte.is_ascii()
+}
+
+/// Absolute byte range of the first whole-identifier `name` within
+/// `source[start..end]`.
+fn find_identifier(source: &[u8], start: usize, end: usize, name: &str) -> Option<(u64, u64)> {
+    let end = end.min(source.len());
+    let needle = name.as_bytes();
+    if needle.is_empty() || start >= end {
+        return None;
+    }
+    (start..end)
+        .filter(|&pos| source[pos..end].starts_with(needle))
+        .find(|&pos| {
+            let after = pos + needle.len();
+            (pos == 0 || !is_ident_byte(source[pos - 1]))
+                && (after == source.len() || !is_ident_byte(source[after]))
+        })
+        .map(|pos| (pos as u64, (pos + needle.len()) as u64))
+}
+
+/// Spans of the `Reference` entities with a `REFERENCES` edge to `symbol_id`.
+fn reference_spans(conn: &Connection, symbol_id: &str) -> Result<Vec<ReferenceNodeData>, LlmError> {
+    let mut stmt = conn.prepare(
+        "SELECT r.data
+         FROM graph_entities r
+         JOIN graph_edges e ON e.from_id = r.id AND e.edge_type = 'REFERENCES'
+         JOIN graph_entities s ON s.id = e.to_id AND s.kind = 'Symbol'
+         WHERE r.kind = 'Reference' AND json_extract(s.data, '$.symbol_id') = ?1
+         ORDER BY r.id",
+    )?;
+    let rows = stmt.query_map([symbol_id], |row| row.get::<_, String>(0))?;
+
+    let mut spans = Vec::new();
+    for row in rows {
+        spans.push(serde_json::from_str(&row?)?);
+    }
+    Ok(spans)
+}
+
+/// Edits renaming `symbol` to `new_name` at its definition and every reference.
+///
+/// Edits are sorted by file and byte offset, with duplicate ranges removed.
+/// Fails with `SearchFailed` when a span no longer contains the name, which
+/// means the index is stale and the edits would not be safe to apply.
+pub fn rename_edits(
+    conn: &Connection,
+    symbol: &SymbolMatch,
+    new_name: &str,
+) -> Result<Vec<TextEdit>, LlmError> {
+    let mut occurrences = vec![(
+        symbol.span.file_path.clone(),
+        symbol.span.byte_start,
+        symbol.span.byte_end,
+    )];
+    if let Some(symbol_id) = &symbol.symbol_id {
+        occurrences.extend(
+            reference_spans(conn, symbol_id)?
+                .into_iter()
+                .map(|r| (r.file, r.byte_start, r.byte_end)),
+        );
+    }
+
+    let mut sources: HashMap<String, Vec<u8>> = HashMap::new();
+    let mut edits = Vec::with_capacity(occurrences.len());
+    for (file, start, end) in occurrences {
+        if !sources.contains_key(&file) {
+            sources.insert(file.clone(), std::fs::read(&file)?);
+        }
+        let (byte_start, byte_end) =
+            find_identifier(&sources[&file], start as usize, end as usize, &symbol.name)
+                .ok_or_else(|| LlmError::SearchFailed {
+                    reason: format!(
+                        "`{}` not found in {} bytes {}..{}; re-index before renaming",
+                        symbol.name, file, start, end
+                    ),
+                })?;
+        edits.push(TextEdit {
+            file,
+            byte_start,
+            byte_end,
+            replacement: new_name.to_string(),
+        });
+    }
+
+    edits.sort_by(|a, b| (&a.file, a.byte_start).cmp(&(&b.file, b.byte_start)));
+    edits.dedup();
+    Ok(edits)
+}
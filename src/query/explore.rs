@@ -4,6 +4,7 @@
 //! graph, ranks candidates by graph signals (fan-in, module clustering), and
 //! returns grouped results. No embeddings — just graph traversal.
 
+use crate::backend::open_connection;
 use anyhow::Result;
 use rusqlite::Connection;
 use serde::Serialize;
@@ -384,7 +385,7 @@ pub fn run_explore(
     limit: usize,
     output: crate::output::OutputFormat,
 ) -> Result<()> {
-    let conn = Connection::open(db_path)?;
+    let conn = open_connection(db_path)?;
 
     let tokens = tokenize_intent(intent);
     if tokens.is_empty() {
@@ -408,14 +409,6 @@ pub fn run_explore(
     };
 
     match output {
-        crate::output::OutputFormat::Json | crate::output::OutputFormat::Pretty => {
-            let wrapped = crate::output::json_response(&response);
-            let json_str = match output {
-                crate::output::OutputFormat::Pretty => serde_json::to_string_pretty(&wrapped)?,
-                _ => serde_json::to_string(&wrapped)?,
-            };
-            println!("{}", json_str);
-        }
         crate::output::OutputFormat::Human => {
             println!("Exploring: \"{}\"", intent);
             println!();
@@ -439,6 +432,14 @@ pub fn run_explore(
                 response.total_modules, response.total_symbols
             );
         }
+        _ => {
+            let wrapped = crate::output::json_response(&response);
+            let json_str = match output {
+                crate::output::OutputFormat::Pretty => serde_json::to_string_pretty(&wrapped)?,
+                _ => serde_json::to_string(&wrapped)?,
+            };
+            println!("{}", json_str);
+        }
     }
 
     Ok(())
@@ -0,0 +1,99 @@
+//! Folded-stack export of the call graph for flamegraph tooling.
+//!
+//! Every call path from an entry symbol down to a leaf becomes one line of
+//! `entry;callee;...;leaf <count>`, the format consumed by `flamegraph.pl` and
+//! `inferno-flamegraph`. The count is the number of distinct call-site chains
+//! producing that path, so paths reached through many call sites dominate.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+
+use rusqlite::Connection;
+
+use crate::error::LlmError;
+use crate::query::util::like_prefix;
+
+/// Maximum stack depth explored from the entry symbol.
+pub const MAX_FOLDED_DEPTH: usize = 32;
+
+/// Load `caller -> callee -> call-site count` from `Call` entities.
+pub fn call_site_counts(
+    conn: &Connection,
+    path_filter: Option<&PathBuf>,
+) -> Result<HashMap<String, BTreeMap<String, u64>>, LlmError> {
+    let mut sql = "SELECT json_extract(data, '$.caller'), json_extract(data, '$.callee')
+         FROM graph_entities
+         WHERE kind = 'Call'
+           AND json_extract(data, '$.caller') IS NOT NULL
+           AND json_extract(data, '$.callee') IS NOT NULL"
+        .to_string();
+    let mut params: Vec<String> = Vec::new();
+    if let Some(path) = path_filter {
+        sql.push_str(" AND json_extract(data, '$.file') LIKE ? ESCAPE '\\'");
+        params.push(like_prefix(path));
+    }
+
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(rusqlite::params_from_iter(params), |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+    })?;
+
+    let mut calls: HashMap<String, BTreeMap<String, u64>> = HashMap::new();
+    for row in rows {
+        let (caller, callee) = row?;
+        *calls.entry(caller).or_default().entry(callee).or_default() += 1;
+    }
+    Ok(calls)
+}
+
+/// Expand all call paths from `entry` into folded stacks, sorted by stack.
+///
+/// A callee already on the current path ends the stack (recursion is folded
+/// into its first frame), as does reaching `max_depth` frames.
+pub fn folded_call_stacks(
+    calls: &HashMap<String, BTreeMap<String, u64>>,
+    entry: &str,
+    max_depth: usize,
+) -> Vec<(String, u64)> {
+    let mut folded: BTreeMap<String, u64> = BTreeMap::new();
+    let mut stack = vec![entry.to_string()];
+    fold_from(calls, &mut stack, 1, max_depth.max(1), &mut folded);
+    folded.into_iter().collect()
+}
+
+fn fold_from(
+    calls: &HashMap<String, BTreeMap<String, u64>>,
+    stack: &mut Vec<String>,
+    weight: u64,
+    max_depth: usize,
+    folded: &mut BTreeMap<String, u64>,
+) {
+    let current = stack.last().expect("stack always holds the entry");
+    let callees: Vec<(&String, u64)> = calls
+        .get(current)
+        .map(|callees| {
+            callees
+                .iter()
+                .filter(|(callee, _)| !stack.contains(callee))
+                .map(|(callee, count)| (callee, *count))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if callees.is_empty() || stack.len() >= max_depth {
+        *folded.entry(stack.join(";")).or_default() += weight;
+        return;
+    }
+
+    for (callee, count) in callees {
+        stack.push(callee.clone());
+        fold_from(
+            calls,
+            stack,
+            weight.saturating_mul(count),
+            max_depth,
+            folded,
+        );
+        stack.pop();
+    }
+}
@@ -3,20 +3,21 @@
 //! This module provides implements search functionality for finding
 //! type-trait implementation relationships.
 
+use crate::backend::retry_busy;
 use crate::error::LlmError;
 use crate::output::{ImplementsMatch, ImplementsSearchResponse};
 use crate::query::builder::build_implements_query;
 use crate::query::chunks::search_chunks_by_span;
 use crate::query::options::SearchOptions;
 use crate::query::util::{
-    json_extract, match_id, score_match, snippet_from_file, span_context_from_file, span_id,
+    content_hash_from_file, json_extract, match_id, name_contains, open_readonly_validated,
+    score_match_case, snippet_from_file, span_context_from_file, span_id, SourceCache,
     MAX_REGEX_SIZE,
 };
 use crate::safe_extraction::extract_symbol_content_safe;
 use crate::SortMode;
 use regex::RegexBuilder;
-use rusqlite::{params_from_iter, Connection, ErrorCode, OpenFlags};
-use std::collections::HashMap;
+use rusqlite::{params_from_iter, Connection};
 
 /// Internal implementation of search_implements that takes an explicit Connection.
 pub(crate) fn search_implements_impl(
@@ -35,6 +36,7 @@ pub(crate) fn search_implements_impl(
     let regex = if options.use_regex {
         Some(
             RegexBuilder::new(options.query)
+                .case_insensitive(options.ignore_case)
                 .size_limit(MAX_REGEX_SIZE)
                 .build()
                 .map_err(|e| LlmError::RegexRejected {
@@ -44,10 +46,10 @@ pub(crate) fn search_implements_impl(
     } else {
         None
     };
-    let mut file_cache = HashMap::new();
+    let mut file_cache = SourceCache::new(options.snippet.read_timeout);
     let mut results = Vec::new();
 
-    let compute_scores = options.sort_by == SortMode::Relevance;
+    let compute_scores = matches!(options.sort_by, SortMode::Relevance | SortMode::Hybrid);
 
     while let Some(row) = rows.next()? {
         let type_data: String = row.get(0)?;
@@ -74,7 +76,9 @@ pub(crate) fn search_implements_impl(
             if !pattern.is_match(&type_name) && !pattern.is_match(&trait_name) {
                 continue;
             }
-        } else if !type_name.contains(options.query) && !trait_name.contains(options.query) {
+        } else if !name_contains(&type_name, options.query, options.ignore_case)
+            && !name_contains(&trait_name, options.query, options.ignore_case)
+        {
             continue;
         }
 
@@ -89,22 +93,37 @@ pub(crate) fn search_implements_impl(
         }
 
         let score = if compute_scores {
-            let type_score = score_match(options.query, &type_name, "", "", regex.as_ref());
-            let trait_score = score_match(options.query, &trait_name, "", "", regex.as_ref());
+            let type_score = score_match_case(
+                options.query,
+                &type_name,
+                "",
+                "",
+                regex.as_ref(),
+                options.ignore_case,
+            );
+            let trait_score = score_match_case(
+                options.query,
+                &trait_name,
+                "",
+                "",
+                regex.as_ref(),
+                options.ignore_case,
+            );
             type_score.max(trait_score)
         } else {
             0
         };
 
         let context = if options.context.include {
-            let capped = options.context.lines > options.context.max_lines;
-            let effective_lines = options.context.lines.min(options.context.max_lines);
+            let (before_lines, after_lines, capped) = options.context.window();
             span_context_from_file(
                 &type_file_path,
                 type_start_line,
                 type_end_line,
-                effective_lines,
+                before_lines,
+                after_lines,
                 capped,
+                options.context.exclude_self,
                 &mut file_cache,
             )
         } else {
@@ -143,7 +162,15 @@ pub(crate) fn search_implements_impl(
                             options.snippet.max_bytes,
                             &mut file_cache,
                         );
-                        (snippet, truncated, None, None)
+                        let content_hash = snippet.as_ref().and_then(|_| {
+                            content_hash_from_file(
+                                &type_file_path,
+                                type_byte_start,
+                                type_byte_end,
+                                &mut file_cache,
+                            )
+                        });
+                        (snippet, truncated, content_hash, None)
                     }
                 }
             } else {
@@ -203,6 +230,10 @@ pub(crate) fn search_implements_impl(
         count
     };
 
+    // Hybrid scores only the position-ordered window that survives --limit
+    if options.sort_by == SortMode::Hybrid {
+        results.truncate(options.limit);
+    }
     if compute_scores {
         results.sort_by(|a, b| {
             b.score
@@ -232,44 +263,7 @@ pub(crate) fn search_implements_impl(
 pub fn search_implements(
     options: SearchOptions,
 ) -> Result<(ImplementsSearchResponse, bool), LlmError> {
-    let conn = match Connection::open_with_flags(options.db_path, OpenFlags::SQLITE_OPEN_READ_ONLY)
-    {
-        Ok(conn) => conn,
-        Err(rusqlite::Error::SqliteFailure(err, msg)) => match err.code {
-            ErrorCode::DatabaseCorrupt | ErrorCode::NotADatabase => {
-                return Err(LlmError::DatabaseCorrupted {
-                    reason: msg
-                        .unwrap_or_else(|| "Database file is invalid or corrupted".to_string()),
-                });
-            }
-            ErrorCode::CannotOpen => {
-                return Err(LlmError::DatabaseNotFound {
-                    path: options.db_path.display().to_string(),
-                });
-            }
-            _ => return Err(LlmError::from(rusqlite::Error::SqliteFailure(err, msg))),
-        },
-        Err(e) => return Err(LlmError::from(e)),
-    };
-
-    conn.query_row(
-        "SELECT name FROM sqlite_master WHERE type='table' LIMIT 1",
-        [],
-        |_| Ok(()),
-    )
-    .map_err(|e| match e {
-        rusqlite::Error::SqliteFailure(err, ref msg) => match err.code {
-            ErrorCode::DatabaseCorrupt | ErrorCode::NotADatabase => LlmError::DatabaseCorrupted {
-                reason: msg
-                    .as_ref()
-                    .map(|s| s.as_str())
-                    .unwrap_or("Database file is invalid or corrupted")
-                    .to_string(),
-            },
-            _ => LlmError::from(e),
-        },
-        other => LlmError::from(other),
-    })?;
+    let conn = retry_busy(|| open_readonly_validated(options.db_path))?;
 
     search_implements_impl(&conn, &options)
 }
@@ -0,0 +1,70 @@
+//! External dependencies of a path, resolved through reference edges.
+//!
+//! Every `Reference` inside the path is followed along its `REFERENCES` edge
+//! to the target symbol. Targets defined outside the path are the path's
+//! external dependencies; they are grouped by the module prefix of their FQN
+//! to answer "what does this module depend on?".
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use rusqlite::Connection;
+
+use crate::error::LlmError;
+use crate::query::util::like_prefix;
+
+/// Split an FQN into its module prefix and final segment.
+///
+/// Both `::` and `.` separators are recognised; an FQN without either has no
+/// module prefix.
+fn split_module(fqn: &str) -> Option<(&str, &str)> {
+    fqn.rsplit_once("::").or_else(|| fqn.rsplit_once('.'))
+}
+
+/// Distinct external symbols referenced from `path`, grouped by defining module.
+///
+/// Symbols whose FQN has no module prefix are grouped under the file that
+/// defines them. Modules and symbol names are sorted.
+pub fn resolved_imports(
+    conn: &Connection,
+    path: &Path,
+) -> Result<BTreeMap<String, Vec<String>>, LlmError> {
+    let prefix = like_prefix(path);
+    let mut stmt = conn.prepare(
+        "SELECT DISTINCT json_extract(s.data, '$.name'),
+                COALESCE(json_extract(s.data, '$.fqn'), json_extract(s.data, '$.display_fqn')),
+                json_extract(f.data, '$.path')
+         FROM graph_entities r
+         JOIN graph_edges e ON e.from_id = r.id AND e.edge_type = 'REFERENCES'
+         JOIN graph_entities s ON s.id = e.to_id AND s.kind = 'Symbol'
+         JOIN graph_edges d ON d.to_id = s.id AND d.edge_type = 'DEFINES'
+         JOIN graph_entities f ON f.id = d.from_id AND f.kind = 'File'
+         WHERE r.kind = 'Reference'
+           AND json_extract(r.data, '$.file') LIKE ?1 ESCAPE '\\'
+           AND json_extract(f.data, '$.path') NOT LIKE ?1 ESCAPE '\\'",
+    )?;
+    let rows = stmt.query_map([prefix], |row| {
+        Ok((
+            row.get::<_, Option<String>>(0)?,
+            row.get::<_, Option<String>>(1)?,
+            row.get::<_, String>(2)?,
+        ))
+    })?;
+
+    let mut imports: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for row in rows {
+        let (name, fqn, file) = row?;
+        let (module, symbol) = match fqn.as_deref().and_then(split_module) {
+            Some((module, last)) => (module.to_string(), name.unwrap_or_else(|| last.to_string())),
+            None => match name.or(fqn) {
+                Some(symbol) => (file, symbol),
+                None => continue,
+            },
+        };
+        imports.entry(module).or_default().insert(symbol);
+    }
+    Ok(imports
+        .into_iter()
+        .map(|(module, symbols)| (module, symbols.into_iter().collect()))
+        .collect())
+}
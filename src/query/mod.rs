@@ -20,19 +20,32 @@
 //! used for labeling symbols with their source language.
 
 // Module declarations
+mod annotate;
 mod backend;
 mod builder;
+mod cache;
 mod calls;
+mod capabilities;
+mod card;
 mod chunks;
+mod complete;
+mod dead_code;
+mod diff;
 mod docs;
+mod edit_script;
 mod evolve;
 mod explore;
 mod facts;
+mod flamegraph;
 mod implements;
+mod imports;
+mod multi_db;
 pub mod navigate;
 mod options;
 mod references;
 mod semantic;
+mod slice;
+mod supernodes;
 mod symbols;
 pub(crate) mod util;
 
@@ -40,7 +53,7 @@ pub(crate) mod util;
 // Options
 pub use options::{
     AstOptions, ContextOptions, CoverageFilter, DepthOptions, FqnOptions, MetricsOptions,
-    SearchOptions, SnippetOptions,
+    SearchOptions, SnippetMode, SnippetOptions,
 };
 
 // Backend
@@ -50,15 +63,18 @@ pub use backend::{detect_backend_format, BackendFormat};
 pub use chunks::{search_chunks_by_span, search_chunks_by_symbol_name, CodeChunk};
 
 // Search functions (public wrappers)
-pub use calls::search_calls;
+pub use cache::{CachedSearch, QueryCache};
+pub use calls::{resolve_callee_definitions, search_calls};
 pub use implements::search_implements;
-pub use references::search_references;
+pub use multi_db::{attach_callers_across, open_databases, search_symbols_across};
+pub use references::{resolve_reference_definitions, search_references};
 pub use semantic::{search_semantic, SemanticSearchOptions};
-pub use symbols::search_symbols;
+pub use symbols::{search_symbols, AST_FILTER_IGNORED_NOTICE};
 
 mod stats;
 pub use stats::{
-    run_stats, CoverageStats, DeadCodeStats, HotspotSymbol, StatsResponse, SymbolStats,
+    run_stats, CompositionStats, CoverageStats, DeadCodeStats, HotspotSymbol, StatsResponse,
+    SymbolStats,
 };
 pub mod telemetry;
 
@@ -66,18 +82,39 @@ pub mod telemetry;
 pub(crate) use calls::search_calls_impl;
 pub(crate) use docs::search_docs_impl;
 pub use docs::DocsSearchOptions;
+pub use annotate::{annotate_heat, file_heat_spans, HeatSpan};
+pub use capabilities::{capabilities, Capabilities};
+pub use card::{symbol_card, SymbolCard};
+pub use complete::{next_segments, rank_completions};
+pub use dead_code::attach_dead_code_callers;
+pub use diff::{
+    run_diff, run_symbol_diff, DiffMetric, DiffResponse, MetricDelta, MovedSymbol, SymbolDiff,
+};
+pub use edit_script::{rename_edits, TextEdit};
 pub use evolve::{run_evolve, EvolveCandidate, EvolveOptions, EvolveResponse};
 pub(crate) use facts::search_facts_impl;
 pub use facts::FactsSearchOptions;
 pub(crate) use implements::search_implements_impl;
 pub(crate) use references::search_references_impl;
-pub(crate) use symbols::search_symbols_impl;
+pub(crate) use symbols::{count_symbols_impl, search_symbols_impl};
 
 // Explore
 pub use explore::run_explore;
 
+// Folded call stacks
+pub use flamegraph::{call_site_counts, folded_call_stacks, MAX_FOLDED_DEPTH};
+
+// External dependencies of a path
+pub use imports::resolved_imports;
+
+// Slice visualization
+pub use slice::{render_slice_dot, slice_edges, SliceEdge};
+
+// SCC grouping
+pub use supernodes::{group_supernodes, Supernode, SupernodeGroups, SupernodeMember};
+
 // Utilities
-pub use util::infer_language;
+pub use util::{infer_language, RowSink, FAN_IN_BOOST_CAP};
 
 // Internal exports for tests
 
@@ -0,0 +1,192 @@
+//! Symbol search across several databases.
+//!
+//! With repeated `--db` values, each database is opened and searched in turn
+//! and the symbol results are merged into one `--sort-by` order, each tagged
+//! with the `source_db` it came from. By default the first unreadable database aborts the run; with
+//! `--skip-corrupt` it is reported on stderr and skipped.
+
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
+
+use crate::backend::open_connection;
+use crate::error::LlmError;
+use crate::output::{SearchResponse, SymbolMatch};
+use crate::query::dead_code::attach_dead_code_callers;
+use crate::query::util::{sort_by_file_mtime, sort_by_name};
+use crate::query::SearchOptions;
+use crate::SortMode;
+
+/// Apply the skip policy to one database failure.
+///
+/// Returns the error back unless it is an unreadable-database error and
+/// `skip_corrupt` is set, in which case a warning is logged.
+fn skip_or_fail(path: &Path, err: LlmError, skip_corrupt: bool) -> Result<(), LlmError> {
+    if skip_corrupt && err.is_unreadable_database() {
+        eprintln!(
+            "Warning: skipping unreadable database {}: {}",
+            path.display(),
+            err
+        );
+        Ok(())
+    } else {
+        Err(err)
+    }
+}
+
+/// Open every database with `open`, skipping unreadable ones when requested.
+///
+/// Fails if no database could be opened.
+pub fn open_databases<T, F>(
+    db_paths: &[PathBuf],
+    skip_corrupt: bool,
+    mut open: F,
+) -> Result<Vec<(PathBuf, T)>, LlmError>
+where
+    F: FnMut(&Path) -> Result<T, LlmError>,
+{
+    let mut opened = Vec::new();
+    for path in db_paths {
+        match open(path) {
+            Ok(handle) => opened.push((path.clone(), handle)),
+            Err(err) => skip_or_fail(path, err, skip_corrupt)?,
+        }
+    }
+    if opened.is_empty() {
+        return Err(no_readable_database(db_paths.len()));
+    }
+    Ok(opened)
+}
+
+fn no_readable_database(count: usize) -> LlmError {
+    LlmError::DatabaseCorrupted {
+        reason: format!("no readable database among {} --db values", count),
+    }
+}
+
+/// Search every opened database and merge the symbol results.
+///
+/// Each result gets `source_db` set to its database path. The merged results
+/// are re-sorted by `options.sort_by` (see [`sort_merged`]) and cut to
+/// `options.limit`, so `--limit` caps the whole run rather than each
+/// database. `total_count` is summed and the merged response is partial if
+/// any database's was.
+pub fn search_symbols_across<T, F>(
+    databases: &[(PathBuf, T)],
+    skip_corrupt: bool,
+    options: &SearchOptions,
+    mut search: F,
+) -> Result<(SearchResponse, bool), LlmError>
+where
+    F: FnMut(&Path, &T) -> Result<(SearchResponse, bool), LlmError>,
+{
+    let mut merged: Option<SearchResponse> = None;
+    let mut partial = false;
+    for (path, handle) in databases {
+        let (mut response, db_partial) = match search(path, handle) {
+            Ok(found) => found,
+            Err(err) => {
+                skip_or_fail(path, err, skip_corrupt)?;
+                continue;
+            }
+        };
+        let source_db = path.display().to_string();
+        for result in &mut response.results {
+            result.source_db = Some(source_db.clone());
+        }
+        partial |= db_partial;
+        match merged.as_mut() {
+            Some(merged) => {
+                merged.total_count += response.total_count;
+                merged.notice = merged.notice.take().or(response.notice);
+                merged.results.append(&mut response.results);
+            }
+            None => merged = Some(response),
+        }
+    }
+    let mut merged = merged.ok_or_else(|| no_readable_database(databases.len()))?;
+    if databases.len() > 1 {
+        sort_merged(&mut merged.results, options);
+    }
+    merged.results.truncate(options.limit);
+    Ok((merged, partial))
+}
+
+/// Order results merged from several databases the way one database orders
+/// its own under `options.sort_by`.
+///
+/// Relevance and hybrid sort by `score`, which the caller must request.
+/// Nesting depth and lines of code are not carried on results, so those keep
+/// `--db` order. The sort is stable, so ties keep `--db` order too.
+fn sort_merged(results: &mut [SymbolMatch], options: &SearchOptions) {
+    let by_position = |a: &SymbolMatch, b: &SymbolMatch| {
+        a.span
+            .start_line
+            .cmp(&b.span.start_line)
+            .then_with(|| a.span.start_col.cmp(&b.span.start_col))
+            .then_with(|| a.span.byte_start.cmp(&b.span.byte_start))
+            .then_with(|| a.span.byte_end.cmp(&b.span.byte_end))
+    };
+    let descending = |key: fn(&SymbolMatch) -> Option<u64>| {
+        move |a: &SymbolMatch, b: &SymbolMatch| -> Ordering {
+            key(b)
+                .unwrap_or(0)
+                .cmp(&key(a).unwrap_or(0))
+                .then_with(|| by_position(a, b))
+        }
+    };
+    match options.sort_by {
+        SortMode::Relevance | SortMode::Hybrid => results.sort_by(descending(|r| r.score)),
+        SortMode::Position => results.sort_by(by_position),
+        SortMode::FanIn if options.metrics.weight_by_calls => {
+            results.sort_by(descending(|r| r.call_count))
+        }
+        SortMode::FanIn => results.sort_by(descending(|r| r.fan_in)),
+        SortMode::FanOut => results.sort_by(descending(|r| r.fan_out)),
+        SortMode::Complexity | SortMode::AstComplexity => {
+            results.sort_by(descending(|r| r.cyclomatic_complexity))
+        }
+        SortMode::Review => results.sort_by(|a, b| {
+            a.span
+                .file_path
+                .cmp(&b.span.file_path)
+                .then_with(|| by_position(a, b))
+        }),
+        SortMode::Name | SortMode::NameDesc => {
+            sort_by_name(results, options.sort_by == SortMode::NameDesc, |r| {
+                (r.name.as_str(), &r.span)
+            })
+        }
+        SortMode::LastModified => sort_by_file_mtime(results, |r| r.span.file_path.as_str()),
+        SortMode::NestingDepth | SortMode::Loc => {}
+    }
+}
+
+/// Attach `--with-callers` to each result from the database it came from.
+///
+/// Results are grouped by `source_db` (unset for a single `--db`, which means
+/// the first database) so every group is resolved against its own call graph.
+pub fn attach_callers_across<T>(
+    databases: &[(PathBuf, T)],
+    results: &mut [SymbolMatch],
+) -> Result<(), LlmError> {
+    let db_index = |result: &SymbolMatch| {
+        databases
+            .iter()
+            .position(|(path, _)| result.source_db == Some(path.display().to_string()))
+            .unwrap_or(0)
+    };
+    for (index, (path, _)) in databases.iter().enumerate() {
+        let positions: Vec<usize> = (0..results.len())
+            .filter(|&i| db_index(&results[i]) == index)
+            .collect();
+        if positions.is_empty() {
+            continue;
+        }
+        let mut group: Vec<SymbolMatch> = positions.iter().map(|&i| results[i].clone()).collect();
+        attach_dead_code_callers(&open_connection(path)?, &mut group)?;
+        for (i, result) in positions.into_iter().zip(group) {
+            results[i] = result;
+        }
+    }
+    Ok(())
+}
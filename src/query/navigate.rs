@@ -27,6 +27,17 @@ pub struct EdgeInfo {
     pub target: NavSymbol,
 }
 
+/// Nested caller hierarchy rooted at a target symbol.
+#[derive(Clone, Serialize)]
+pub struct CallerTree {
+    pub symbol: NavSymbol,
+    pub callers: Vec<CallerTree>,
+    /// Set when this caller already appears on the path to the root;
+    /// its callers are not expanded again.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub cycle: bool,
+}
+
 #[derive(Serialize)]
 pub struct NavigateResponse {
     pub node: Option<NavSymbol>,
@@ -34,6 +45,7 @@ pub struct NavigateResponse {
     pub edges: Option<Vec<EdgeInfo>>,
     pub callers: Option<Vec<DepthNavSymbol>>,
     pub callees: Option<Vec<DepthNavSymbol>>,
+    pub caller_tree: Option<CallerTree>,
 }
 
 impl From<magellan::graph::navigator::SymbolInfo> for NavSymbol {
@@ -64,6 +76,54 @@ fn direction_str(d: &BackendDirection) -> &'static str {
     }
 }
 
+/// Build a callers-of-callers tree up to `depth` levels below `root`.
+///
+/// `direct_callers` returns the immediate callers of a symbol id. A caller
+/// already on the current path is emitted with `cycle: true` and not expanded.
+pub fn build_caller_tree<F>(
+    root: NavSymbol,
+    depth: usize,
+    direct_callers: &mut F,
+) -> Result<CallerTree>
+where
+    F: FnMut(i64) -> Result<Vec<NavSymbol>>,
+{
+    let mut path = vec![root.id];
+    expand_callers(root, depth, direct_callers, &mut path)
+}
+
+fn expand_callers<F>(
+    symbol: NavSymbol,
+    depth: usize,
+    direct_callers: &mut F,
+    path: &mut Vec<i64>,
+) -> Result<CallerTree>
+where
+    F: FnMut(i64) -> Result<Vec<NavSymbol>>,
+{
+    let mut callers = Vec::new();
+    if depth > 0 {
+        for caller in direct_callers(symbol.id)? {
+            if path.contains(&caller.id) {
+                callers.push(CallerTree {
+                    symbol: caller,
+                    callers: Vec::new(),
+                    cycle: true,
+                });
+                continue;
+            }
+            path.push(caller.id);
+            callers.push(expand_callers(caller, depth - 1, direct_callers, path)?);
+            path.pop();
+        }
+    }
+    Ok(CallerTree {
+        symbol,
+        callers,
+        cycle: false,
+    })
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn run_navigate(
     db_path: &Path,
@@ -72,6 +132,7 @@ pub fn run_navigate(
     show_edges: bool,
     show_callers: bool,
     show_callees: bool,
+    show_caller_tree: bool,
     depth: usize,
     output: crate::output::OutputFormat,
 ) -> Result<()> {
@@ -84,12 +145,12 @@ pub fn run_navigate(
         let resolved = nav.resolve(symbol)?;
         if resolved.is_empty() {
             match output {
-                crate::output::OutputFormat::Json | crate::output::OutputFormat::Pretty => {
-                    println!(r#"{{"error":"no symbols found for '{}'"}}"#, symbol);
-                }
                 crate::output::OutputFormat::Human => {
                     eprintln!("error: no symbols found for '{}'", symbol);
                 }
+                _ => {
+                    println!(r#"{{"error":"no symbols found for '{}'"}}"#, symbol);
+                }
             }
             return Ok(());
         }
@@ -143,16 +204,29 @@ pub fn run_navigate(
         None
     };
 
+    let caller_tree = match (&node, show_caller_tree) {
+        (Some(root), true) => Some(build_caller_tree(root.clone(), depth, &mut |id| {
+            Ok(nav
+                .k_hop_callers(id, 1)?
+                .into_iter()
+                .map(|ds| ds.info.into())
+                .collect())
+        })?),
+        _ => None,
+    };
+
     let response = NavigateResponse {
         node,
         resolve,
         edges,
         callers,
         callees,
+        caller_tree,
     };
 
     match output {
-        crate::output::OutputFormat::Json | crate::output::OutputFormat::Pretty => {
+        crate::output::OutputFormat::Human => print_human(&response),
+        _ => {
             let wrapped = crate::output::json_response(&response);
             let json_str = match output {
                 crate::output::OutputFormat::Pretty => serde_json::to_string_pretty(&wrapped)?,
@@ -160,7 +234,6 @@ pub fn run_navigate(
             };
             println!("{}", json_str);
         }
-        crate::output::OutputFormat::Human => print_human(&response),
     }
 
     Ok(())
@@ -211,6 +284,10 @@ fn print_human(resp: &NavigateResponse) {
             );
         }
     }
+    if let Some(ref tree) = resp.caller_tree {
+        println!("  caller tree:");
+        print_caller_tree(tree, 2);
+    }
     if let Some(ref edges) = resp.edges {
         println!("  edges:");
         for e in edges {
@@ -224,3 +301,18 @@ fn print_human(resp: &NavigateResponse) {
         }
     }
 }
+
+fn print_caller_tree(tree: &CallerTree, indent: usize) {
+    for c in &tree.callers {
+        println!(
+            "{:indent$}{} id={} ({}){}",
+            "",
+            c.symbol.name,
+            c.symbol.id,
+            c.symbol.file.as_deref().unwrap_or("?"),
+            if c.cycle { " [cycle]" } else { "" },
+            indent = indent * 2
+        );
+        print_caller_tree(c, indent + 1);
+    }
+}
@@ -3,6 +3,7 @@
 //! This module defines all the option structs used for configuring search operations.
 
 use crate::algorithm::AlgorithmOptions;
+use crate::output::Prefer;
 use crate::SortMode;
 use std::path::PathBuf;
 
@@ -27,7 +28,12 @@ pub struct SearchOptions<'a> {
     pub query: &'a str,
     /// Optional path filter
     pub path_filter: Option<&'a PathBuf>,
-    /// Optional kind filter (symbols only) - comma-separated values
+    /// Path prefixes to leave out of the results
+    pub exclude_paths: &'a [PathBuf],
+    /// Treat `path_filter` as a shell-style glob instead of a path prefix
+    pub path_glob: bool,
+    /// Optional kind filter - comma-separated values. Matches symbol kinds in
+    /// symbol search and the Reference entity's `kind` in reference search
     pub kind_filter: Option<&'a str>,
     /// Optional language filter (symbols only)
     pub language_filter: Option<&'a str>,
@@ -35,6 +41,8 @@ pub struct SearchOptions<'a> {
     pub limit: usize,
     /// Use regex matching
     pub use_regex: bool,
+    /// Match names case-insensitively (regex, in-memory filters and scoring)
+    pub ignore_case: bool,
     /// Candidate limit for filtering
     pub candidates: usize,
     /// Context options
@@ -63,6 +71,19 @@ pub struct SearchOptions<'a> {
     pub exact_fqn: Option<&'a str>,
     /// Coverage filter (covered/uncovered symbols only)
     pub coverage_filter: Option<CoverageFilter>,
+    /// Restrict to symbols defined in exactly these files (symbols only)
+    pub file_set: Option<&'a [String]>,
+    /// Keep at most this many results from any one file, applied after
+    /// sorting and before `limit`
+    pub max_per_file: Option<usize>,
+    /// Keep only public API symbols (see [`SymbolMatch::is_public`]), applied
+    /// before `limit` (symbols only)
+    ///
+    /// [`SymbolMatch::is_public`]: crate::output::SymbolMatch::is_public
+    pub public_only: bool,
+    /// Keep one result per symbol name, chosen by this policy, applied before
+    /// `limit` (symbols only)
+    pub prefer: Option<Prefer>,
 }
 
 /// Context extraction options
@@ -72,8 +93,39 @@ pub struct ContextOptions {
     pub include: bool,
     /// Lines of context before/after
     pub lines: usize,
+    /// Lines of context before the span, overriding `lines`
+    pub before: Option<usize>,
+    /// Lines of context after the span, overriding `lines`
+    pub after: Option<usize>,
     /// Maximum context lines
     pub max_lines: usize,
+    /// Leave `selected` empty, returning only the surrounding lines
+    pub exclude_self: bool,
+}
+
+impl ContextOptions {
+    /// Effective `(before, after)` line counts, each capped at `max_lines`,
+    /// and whether either side was capped.
+    pub fn window(&self) -> (usize, usize, bool) {
+        let before = self.before.unwrap_or(self.lines);
+        let after = self.after.unwrap_or(self.lines);
+        let capped = before > self.max_lines || after > self.max_lines;
+        (
+            before.min(self.max_lines),
+            after.min(self.max_lines),
+            capped,
+        )
+    }
+}
+
+/// How a snippet is capped
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SnippetMode {
+    /// Cap at `max_bytes`, which can cut a line in half
+    #[default]
+    Bytes,
+    /// Keep at most `max_lines` whole lines (symbols only)
+    Lines,
 }
 
 /// Snippet extraction options
@@ -83,6 +135,20 @@ pub struct SnippetOptions {
     pub include: bool,
     /// Maximum snippet bytes
     pub max_bytes: usize,
+    /// Whether the snippet is capped by bytes or by lines
+    pub mode: SnippetMode,
+    /// Maximum snippet lines, used in [`SnippetMode::Lines`]
+    pub max_lines: usize,
+    /// Prefix snippet lines with source line numbers (symbols only)
+    pub line_numbers: bool,
+    /// Include the text of the symbol's first line as `line_text` (symbols only)
+    pub line_text: bool,
+    /// Keep only the best-ranked result per `content_hash` (symbols only);
+    /// results without a hash are always kept
+    pub dedupe: bool,
+    /// Upper bound for reading one source file for snippets, context or line
+    /// text; a file that misses it is treated as unreadable
+    pub read_timeout: Option<std::time::Duration>,
 }
 
 /// FQN inclusion options (symbols only)
@@ -94,6 +160,10 @@ pub struct FqnOptions {
     pub canonical_fqn: bool,
     /// Include display FQN
     pub display_fqn: bool,
+    /// Normalize FQN separators to `::` for display and `--fqn`/`--exact-fqn` matching
+    pub normalize: bool,
+    /// Include the defining File entity as `defined_in`
+    pub defined_in: bool,
 }
 
 /// Metrics-based filtering options
@@ -105,8 +175,28 @@ pub struct MetricsOptions {
     pub max_complexity: Option<usize>,
     /// Minimum fan-in (incoming references)
     pub min_fan_in: Option<usize>,
+    /// Maximum fan-in (incoming references)
+    pub max_fan_in: Option<usize>,
     /// Minimum fan-out (outgoing calls)
     pub min_fan_out: Option<usize>,
+    /// Maximum fan-out (outgoing calls)
+    pub max_fan_out: Option<usize>,
+    /// Minimum lines of code
+    pub min_loc: Option<usize>,
+    /// Maximum lines of code
+    pub max_loc: Option<usize>,
+    /// Rank `--sort-by fan-in` by total call sites (`Call` entities) instead
+    /// of distinct references
+    pub weight_by_calls: bool,
+    /// Add a bounded fan-in bonus (at most [`FAN_IN_BOOST_CAP`]) to relevance
+    /// scores, so widely used symbols win name-score ties
+    ///
+    /// [`FAN_IN_BOOST_CAP`]: crate::query::FAN_IN_BOOST_CAP
+    pub boost_fan_in: bool,
+    /// Populate `fan_in` for output even when nothing sorts or filters on it;
+    /// only matters without a `symbol_metrics` table, where fan-in is counted
+    /// from `REFERENCES` edges on demand
+    pub with_fan_in: bool,
 }
 
 /// AST-based filtering options
@@ -118,6 +208,8 @@ pub struct AstOptions<'a> {
     pub ast_kinds: Vec<String>,
     /// Enable enriched AST context calculation (depth, parent_kind, children, decision_points)
     pub with_ast_context: bool,
+    /// Populate `ast_node_kind` from the exact-span AST node without enrichment
+    pub with_ast_kind: bool,
     /// Phantom data for lifetime parameter (for future use if needed)
     pub _phantom: std::marker::PhantomData<&'a ()>,
 }
@@ -128,6 +220,7 @@ impl<'a> AstOptions<'a> {
         Self {
             ast_kinds: Vec::new(),
             with_ast_context: false,
+            with_ast_kind: false,
             _phantom: std::marker::PhantomData,
         }
     }
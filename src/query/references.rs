@@ -3,33 +3,44 @@
 //! This module provides reference search functionality for finding
 //! incoming references to symbols.
 
+use crate::backend::retry_busy;
 use crate::error::LlmError;
 use crate::output::{ReferenceMatch, ReferenceSearchResponse};
 use crate::query::builder::build_reference_query;
 use crate::query::chunks::search_chunks_by_span;
 use crate::query::options::SearchOptions;
 use crate::query::util::{
-    match_id, referenced_symbol_from_name, score_match, snippet_from_file, span_context_from_file,
-    span_id, ReferenceNodeData, MAX_REGEX_SIZE,
+    cap_per_file, content_hash_from_file, definition_spans, match_id, name_contains,
+    open_readonly_validated, referenced_symbol_from_name, score_match_case, snippet_from_file,
+    sort_by_name, span_context_from_file, span_id, check_streamable, next_row, warn_timed_out, RowSink, RowStream, ReferenceNodeData, SourceCache, MAX_REGEX_SIZE,
 };
 use crate::safe_extraction::extract_symbol_content_safe;
 use crate::SortMode;
 use regex::RegexBuilder;
-use rusqlite::{params_from_iter, Connection, ErrorCode, OpenFlags};
-use std::collections::HashMap;
+use rusqlite::{params_from_iter, Connection};
 
 /// Internal implementation of search_references that takes an explicit Connection.
 ///
 /// This function contains the core SQL query logic for searching references.
 /// It is separated from the public `search_references()` to enable reuse
 /// within the SqliteBackend trait implementation.
+///
+/// With a `sink`, each match is handed over as its row is read and the
+/// returned response carries no results (see [`check_streamable`]).
 pub(crate) fn search_references_impl(
     conn: &Connection,
     options: &SearchOptions,
+    sink: Option<RowSink<'_, ReferenceMatch>>,
 ) -> Result<(ReferenceSearchResponse, bool), LlmError> {
+    if sink.is_some() {
+        check_streamable(options)?;
+    }
     let (sql, params) = build_reference_query(
         options.query,
         options.path_filter,
+        options.path_glob,
+        options.exclude_paths,
+        options.kind_filter,
         options.use_regex,
         false,
         options.candidates,
@@ -39,6 +50,7 @@ pub(crate) fn search_references_impl(
     let regex = if options.use_regex {
         Some(
             RegexBuilder::new(options.query)
+                .case_insensitive(options.ignore_case)
                 .size_limit(MAX_REGEX_SIZE)
                 .build()
                 .map_err(|e| LlmError::RegexRejected {
@@ -48,13 +60,23 @@ pub(crate) fn search_references_impl(
     } else {
         None
     };
-    let mut file_cache = HashMap::new();
+    let mut file_cache = SourceCache::new(options.snippet.read_timeout);
     let mut results = Vec::new();
 
-    // Only compute scores for Relevance mode (Position mode skips scoring for performance)
-    let compute_scores = options.sort_by == SortMode::Relevance;
+    // Only compute scores for Relevance/Hybrid modes (Position mode skips scoring for performance)
+    let compute_scores = matches!(options.sort_by, SortMode::Relevance | SortMode::Hybrid);
 
-    while let Some(row) = rows.next()? {
+    let mut stream = sink.map(|sink| {
+        RowStream::new(sink, options.limit, options.max_per_file, None, |r: &ReferenceMatch| {
+            r.span.file_path.as_str()
+        })
+    });
+    // Rows matching the regex, counted while streaming
+    let mut kept: u64 = 0;
+    // Set when --timeout interrupts the scan
+    let mut timed_out = false;
+
+    while let Some(row) = next_row(&mut rows, &mut timed_out)? {
         let data: String = row.get(0)?;
         let name: String = row.get(1)?;
         let target_symbol_id: Option<String> = row.get(2)?;
@@ -65,25 +87,45 @@ pub(crate) fn search_references_impl(
             if !pattern.is_match(&referenced_symbol) {
                 continue;
             }
-        } else if !referenced_symbol.contains(options.query) {
+        } else if !name_contains(&referenced_symbol, options.query, options.ignore_case) {
             continue;
         }
 
-        // Only compute scores in Relevance mode (Position mode skips scoring for performance)
+        // Past the limit a stream only needs rows for total_count, which SQL
+        // counts itself unless the regex decides membership
+        if let Some(stream) = &stream {
+            if stream.is_full() {
+                if !options.use_regex {
+                    break;
+                }
+                kept += 1;
+                continue;
+            }
+        }
+
+        // Only compute scores in Relevance/Hybrid modes (Position mode skips scoring for performance)
         let score = if compute_scores {
-            score_match(options.query, &referenced_symbol, "", "", regex.as_ref())
+            score_match_case(
+                options.query,
+                &referenced_symbol,
+                "",
+                "",
+                regex.as_ref(),
+                options.ignore_case,
+            )
         } else {
             0
         };
         let context = if options.context.include {
-            let capped = options.context.lines > options.context.max_lines;
-            let effective_lines = options.context.lines.min(options.context.max_lines);
+            let (before_lines, after_lines, capped) = options.context.window();
             span_context_from_file(
                 &reference.file,
                 reference.start_line,
                 reference.end_line,
-                effective_lines,
+                before_lines,
+                after_lines,
                 capped,
+                options.context.exclude_self,
                 &mut file_cache,
             )
         } else {
@@ -130,7 +172,15 @@ pub(crate) fn search_references_impl(
                             options.snippet.max_bytes,
                             &mut file_cache,
                         );
-                        (snippet, truncated, None, None)
+                        let content_hash = snippet.as_ref().and_then(|_| {
+                            content_hash_from_file(
+                                &reference.file,
+                                reference.byte_start,
+                                reference.byte_end,
+                                &mut file_cache,
+                            )
+                        });
+                        (snippet, truncated, content_hash, None)
                     }
                 }
             } else {
@@ -154,12 +204,13 @@ pub(crate) fn search_references_impl(
             reference.byte_end,
             &referenced_symbol,
         );
-        results.push(ReferenceMatch {
+        let result = ReferenceMatch {
             match_id,
             span,
             referenced_symbol,
-            reference_kind: None,
+            reference_kind: reference.kind,
             target_symbol_id,
+            definition: None,
             score: if options.include_score {
                 Some(score)
             } else {
@@ -169,19 +220,37 @@ pub(crate) fn search_references_impl(
             symbol_kind_from_chunk,
             snippet,
             snippet_truncated,
-        });
+        };
+        match stream.as_mut() {
+            Some(stream) => {
+                kept += 1;
+                stream.push(result)?;
+            }
+            None => results.push(result),
+        }
+    }
+    if stream.is_none() {
+        kept = results.len() as u64;
     }
 
     let mut partial = false;
-    let total_count = if options.use_regex {
-        if results.len() >= options.candidates {
+    let total_count = if timed_out {
+        // The count query would be interrupted too; report what was read
+        warn_timed_out(kept);
+        partial = true;
+        kept
+    } else if options.use_regex {
+        if kept as usize >= options.candidates {
             partial = true;
         }
-        results.len() as u64
+        kept
     } else {
         let (count_sql, count_params) = build_reference_query(
             options.query,
             options.path_filter,
+            options.path_glob,
+            options.exclude_paths,
+            options.kind_filter,
             options.use_regex,
             true,
             0,
@@ -193,7 +262,11 @@ pub(crate) fn search_references_impl(
         count
     };
 
-    // Only sort by score in Relevance mode (Position mode relies on SQL ORDER BY)
+    // Hybrid scores only the position-ordered window that survives --limit
+    if options.sort_by == SortMode::Hybrid {
+        results.truncate(options.limit);
+    }
+    // Only sort by score in Relevance/Hybrid modes (Position mode relies on SQL ORDER BY)
     if compute_scores {
         results.sort_by(|a, b| {
             b.score
@@ -204,6 +277,14 @@ pub(crate) fn search_references_impl(
                 .then_with(|| a.span.byte_start.cmp(&b.span.byte_start))
         });
     }
+    if matches!(options.sort_by, SortMode::Name | SortMode::NameDesc) {
+        sort_by_name(&mut results, options.sort_by == SortMode::NameDesc, |r| {
+            (r.referenced_symbol.as_str(), &r.span)
+        });
+    }
+    if let Some(max) = options.max_per_file {
+        cap_per_file(&mut results, max, |r| r.span.file_path.as_str());
+    }
     results.truncate(options.limit);
 
     Ok((
@@ -219,55 +300,43 @@ pub(crate) fn search_references_impl(
     ))
 }
 
+/// Attach each reference's `definition` span by resolving `target_symbol_id`.
+///
+/// The definition file comes from the `DEFINES` edge of the target's `File`
+/// entity. References with no `target_symbol_id`, or whose target is not
+/// indexed, are left unresolved.
+pub fn resolve_reference_definitions(
+    conn: &Connection,
+    results: &mut [ReferenceMatch],
+) -> Result<(), LlmError> {
+    let definitions = definition_spans(
+        conn,
+        results
+            .iter()
+            .filter_map(|reference| reference.target_symbol_id.as_deref()),
+    )?;
+    for reference in results.iter_mut() {
+        reference.definition = reference
+            .target_symbol_id
+            .as_ref()
+            .and_then(|id| definitions.get(id).cloned());
+    }
+    Ok(())
+}
+
 /// Public wrapper for search_references that handles connection opening and validation.
 ///
 /// This function opens the database connection, validates it, and delegates to
 /// search_references_impl() for the actual query logic. This maintains backward
 /// compatibility while enabling trait method implementation.
+///
+/// Each call opens a fresh connection; callers issuing several searches should
+/// open a `SqliteBackend` once and use its trait methods instead.
 pub fn search_references(
     options: SearchOptions,
 ) -> Result<(ReferenceSearchResponse, bool), LlmError> {
-    let conn = match Connection::open_with_flags(options.db_path, OpenFlags::SQLITE_OPEN_READ_ONLY)
-    {
-        Ok(conn) => conn,
-        Err(rusqlite::Error::SqliteFailure(err, msg)) => match err.code {
-            ErrorCode::DatabaseCorrupt | ErrorCode::NotADatabase => {
-                return Err(LlmError::DatabaseCorrupted {
-                    reason: msg
-                        .unwrap_or_else(|| "Database file is invalid or corrupted".to_string()),
-                });
-            }
-            ErrorCode::CannotOpen => {
-                return Err(LlmError::DatabaseNotFound {
-                    path: options.db_path.display().to_string(),
-                });
-            }
-            _ => return Err(LlmError::from(rusqlite::Error::SqliteFailure(err, msg))),
-        },
-        Err(e) => return Err(LlmError::from(e)),
-    };
-
-    // Force database validation by checking if schema exists
-    // This catches "not a database" errors that occur lazily
-    conn.query_row(
-        "SELECT name FROM sqlite_master WHERE type='table' LIMIT 1",
-        [],
-        |_| Ok(()),
-    )
-    .map_err(|e| match e {
-        rusqlite::Error::SqliteFailure(err, ref msg) => match err.code {
-            ErrorCode::DatabaseCorrupt | ErrorCode::NotADatabase => LlmError::DatabaseCorrupted {
-                reason: msg
-                    .as_ref()
-                    .map(|s| s.as_str())
-                    .unwrap_or("Database file is invalid or corrupted")
-                    .to_string(),
-            },
-            _ => LlmError::from(e),
-        },
-        other => LlmError::from(other),
-    })?;
+    let conn = retry_busy(|| open_readonly_validated(options.db_path))?;
 
     // Call the implementation
-    search_references_impl(&conn, &options)
+    search_references_impl(&conn, &options, None)
 }
@@ -4,6 +4,7 @@
 //! via a local Ollama instance and searching the persisted HNSW index in the
 //! Magellan database.
 
+use crate::backend::open_connection;
 use crate::error::LlmError;
 use crate::output::{SemanticMatch, SemanticSearchResponse, Span};
 use rusqlite::{Connection, OptionalExtension};
@@ -44,7 +45,7 @@ pub fn search_semantic(options: SemanticSearchOptions) -> Result<SemanticSearchR
     // ------------------------------------------------------------------
     // 1. Open DB and verify HNSW index exists (fail fast before embedding)
     // ------------------------------------------------------------------
-    let conn = Connection::open(options.db_path).map_err(LlmError::SqliteError)?;
+    let conn = open_connection(options.db_path).map_err(LlmError::SqliteError)?;
 
     let hnsw_exists: bool = conn
         .query_row(
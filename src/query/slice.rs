@@ -0,0 +1,111 @@
+//! DOT rendering of dependency slices.
+//!
+//! `--slice-backward-from`/`--slice-forward-from` narrow symbol search to the
+//! slice computed by magellan. This module recovers the graph edges among the
+//! sliced symbols (keyed by `symbol_id`) and renders them as a Graphviz digraph
+//! with the seed symbol highlighted.
+
+use rusqlite::Connection;
+
+use crate::error::LlmError;
+use crate::output::SymbolMatch;
+
+/// A directed edge between two symbols of a slice.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct SliceEdge {
+    pub from: String,
+    pub to: String,
+    pub edge_type: String,
+}
+
+/// Load all edges whose endpoints are both in `symbol_ids`.
+///
+/// `DEFINES` edges are skipped since they connect files to symbols, never
+/// two symbols.
+pub fn slice_edges(conn: &Connection, symbol_ids: &[String]) -> Result<Vec<SliceEdge>, LlmError> {
+    if symbol_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let placeholders = symbol_ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+    let sql = format!(
+        "SELECT DISTINCT json_extract(a.data, '$.symbol_id'), json_extract(b.data, '$.symbol_id'), e.edge_type
+         FROM graph_edges e
+         JOIN graph_entities a ON a.id = e.from_id
+         JOIN graph_entities b ON b.id = e.to_id
+         WHERE e.edge_type != 'DEFINES'
+           AND json_extract(a.data, '$.symbol_id') IN ({0})
+           AND json_extract(b.data, '$.symbol_id') IN ({0})
+         ORDER BY 1, 2, 3",
+        placeholders
+    );
+
+    let params: Vec<&dyn rusqlite::ToSql> = symbol_ids
+        .iter()
+        .chain(symbol_ids.iter())
+        .map(|id| id as &dyn rusqlite::ToSql)
+        .collect();
+
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(params.as_slice(), |row| {
+        Ok(SliceEdge {
+            from: row.get(0)?,
+            to: row.get(1)?,
+            edge_type: row.get(2)?,
+        })
+    })?;
+
+    let mut edges = Vec::new();
+    for row in rows {
+        edges.push(row?);
+    }
+    Ok(edges)
+}
+
+/// Whether `symbol` is the one the user named as the slice seed.
+fn is_seed(symbol: &SymbolMatch, seed: &str) -> bool {
+    symbol.symbol_id.as_deref() == Some(seed)
+        || symbol.name == seed
+        || symbol.fqn.as_deref() == Some(seed)
+        || symbol.display_fqn.as_deref() == Some(seed)
+        || symbol.canonical_fqn.as_deref() == Some(seed)
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Render slice symbols and their edges as a DOT digraph.
+///
+/// Nodes are keyed by `symbol_id`; symbols without one are omitted. The seed
+/// (matched by symbol ID, name, or any FQN form) is filled gold.
+pub fn render_slice_dot(nodes: &[SymbolMatch], seed: &str, edges: &[SliceEdge]) -> String {
+    let mut dot = String::from("digraph slice {\n  rankdir=LR;\n  node [shape=box];\n");
+    for node in nodes {
+        let Some(id) = node.symbol_id.as_deref() else {
+            continue;
+        };
+        let highlight = if is_seed(node, seed) {
+            ", style=filled, fillcolor=gold, penwidth=2"
+        } else {
+            ""
+        };
+        dot.push_str(&format!(
+            "  \"{}\" [label=\"{}\\n{}\"{}];\n",
+            escape(id),
+            escape(&node.name),
+            escape(&node.kind),
+            highlight
+        ));
+    }
+    for edge in edges {
+        dot.push_str(&format!(
+            "  \"{}\" -> \"{}\" [label=\"{}\"];\n",
+            escape(&edge.from),
+            escape(&edge.to),
+            escape(&edge.edge_type)
+        ));
+    }
+    dot.push_str("}\n");
+    dot
+}
@@ -1,23 +1,44 @@
 //! Stats command: code health summary from Magellan metrics.
 //!
-//! Reports dead code symbols, high fan-in hotspots, CFG complexity
+//! Reports database composition (entity, language and kind counts, optional
+//! tables), dead code symbols, high fan-in hotspots, CFG complexity
 //! distribution, and coverage gaps. Supports JSON output.
 
 use rusqlite::Connection;
+use std::collections::HashMap;
 use std::path::Path;
 
 use crate::error::LlmError;
+use crate::query::util::infer_language;
 
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct StatsResponse {
     pub database: String,
     pub schema_version: Option<i64>,
+    pub composition: CompositionStats,
     pub symbols: SymbolStats,
     pub dead_code: DeadCodeStats,
     pub hotspots: Vec<HotspotSymbol>,
     pub coverage: CoverageStats,
 }
 
+/// What the database holds, independent of `symbol_metrics`.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct CompositionStats {
+    pub files: usize,
+    pub symbols: usize,
+    pub references: usize,
+    pub calls: usize,
+    /// Symbol count per language, inferred from the defining file's extension
+    pub symbols_by_language: Vec<(String, usize)>,
+    /// Symbol count per `kind` as recorded by the indexer
+    pub symbols_by_kind: Vec<(String, usize)>,
+    /// Rows in `symbol_metrics` (0 when the table is missing)
+    pub metrics_rows: usize,
+    pub has_ast_nodes: bool,
+    pub has_code_chunks: bool,
+}
+
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct SymbolStats {
     pub total: usize,
@@ -59,6 +80,7 @@ pub fn run_stats(conn: &Connection, db_path: &Path) -> Result<StatsResponse, Llm
         )
         .ok();
 
+    let composition = gather_composition(conn)?;
     let symbols = gather_symbol_stats(conn)?;
     let dead_code = gather_dead_code(conn)?;
     let hotspots = gather_hotspots(conn, 10)?;
@@ -67,6 +89,7 @@ pub fn run_stats(conn: &Connection, db_path: &Path) -> Result<StatsResponse, Llm
     Ok(StatsResponse {
         database: db_path.display().to_string(),
         schema_version,
+        composition,
         symbols,
         dead_code,
         hotspots,
@@ -74,6 +97,79 @@ pub fn run_stats(conn: &Connection, db_path: &Path) -> Result<StatsResponse, Llm
     })
 }
 
+fn table_exists(conn: &Connection, table: &str) -> bool {
+    conn.query_row(
+        "SELECT 1 FROM sqlite_master WHERE type='table' AND name = ?1 LIMIT 1",
+        [table],
+        |_| Ok(true),
+    )
+    .unwrap_or(false)
+}
+
+fn gather_composition(conn: &Connection) -> Result<CompositionStats, LlmError> {
+    let mut entity_counts = HashMap::new();
+    let mut stmt = conn.prepare("SELECT kind, COUNT(*) FROM graph_entities GROUP BY kind")?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        entity_counts.insert(row.get::<_, String>(0)?, row.get::<_, usize>(1)?);
+    }
+    let entity_count = |kind: &str| entity_counts.get(kind).copied().unwrap_or(0);
+
+    let mut stmt = conn.prepare(
+        "SELECT COALESCE(json_extract(data, '$.kind'), 'unknown') AS k, COUNT(*) AS cnt \
+         FROM graph_entities WHERE kind = 'Symbol' \
+         GROUP BY k ORDER BY cnt DESC, k",
+    )?;
+    let mut rows = stmt.query([])?;
+    let mut symbols_by_kind = Vec::new();
+    while let Some(row) = rows.next()? {
+        symbols_by_kind.push((row.get(0)?, row.get(1)?));
+    }
+
+    // Group per file in SQL, then fold files into languages by extension
+    let mut stmt = conn.prepare(
+        "SELECT json_extract(f.data, '$.path'), COUNT(*) \
+         FROM graph_entities s \
+         JOIN graph_edges e ON e.to_id = s.id AND e.edge_type = 'DEFINES' \
+         JOIN graph_entities f ON f.id = e.from_id AND f.kind = 'File' \
+         WHERE s.kind = 'Symbol' \
+         GROUP BY f.id",
+    )?;
+    let mut rows = stmt.query([])?;
+    let mut by_language: HashMap<&str, usize> = HashMap::new();
+    while let Some(row) = rows.next()? {
+        let path: Option<String> = row.get(0)?;
+        let language = path
+            .as_deref()
+            .and_then(infer_language)
+            .unwrap_or("unknown");
+        *by_language.entry(language).or_default() += row.get::<_, usize>(1)?;
+    }
+    let mut symbols_by_language: Vec<(String, usize)> = by_language
+        .into_iter()
+        .map(|(language, count)| (language.to_string(), count))
+        .collect();
+    symbols_by_language.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    let metrics_rows: usize = if table_exists(conn, "symbol_metrics") {
+        conn.query_row("SELECT COUNT(*) FROM symbol_metrics", [], |row| row.get(0))?
+    } else {
+        0
+    };
+
+    Ok(CompositionStats {
+        files: entity_count("File"),
+        symbols: entity_count("Symbol"),
+        references: entity_count("Reference"),
+        calls: entity_count("Call"),
+        symbols_by_language,
+        symbols_by_kind,
+        metrics_rows,
+        has_ast_nodes: table_exists(conn, "ast_nodes"),
+        has_code_chunks: table_exists(conn, "code_chunks"),
+    })
+}
+
 fn gather_symbol_stats(conn: &Connection) -> Result<SymbolStats, LlmError> {
     let metrics_exist: bool = conn
         .query_row(
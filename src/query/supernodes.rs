@@ -0,0 +1,140 @@
+//! SCC grouping of `--condense` results.
+//!
+//! `--condense` tags each symbol with the `supernode_id` of its strongly
+//! connected component. This module groups the tagged results per supernode
+//! and marks the members that are entered from outside their component:
+//! those with an incoming edge or `Call` from a symbol in another SCC (or in
+//! none).
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use rusqlite::Connection;
+use serde::Serialize;
+
+use crate::error::LlmError;
+use crate::output::SymbolMatch;
+
+/// A symbol belonging to a supernode.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SupernodeMember {
+    pub symbol_id: String,
+    pub name: String,
+    pub file_path: String,
+    pub start_line: u64,
+}
+
+/// One strongly connected component and its entry points.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Supernode {
+    pub id: String,
+    pub size: usize,
+    pub members: Vec<SupernodeMember>,
+    /// Members referenced from outside the component, in member order
+    pub entry_points: Vec<SupernodeMember>,
+}
+
+/// `--output grouped-by-supernode-json` document.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SupernodeGroups {
+    pub supernodes: Vec<Supernode>,
+}
+
+/// Symbol-to-symbol pairs `(from, to)` whose target is in `symbol_ids`.
+///
+/// Covers direct graph edges between symbols (`DEFINES` excluded) and `Call`
+/// entities resolved on both ends.
+fn incoming_pairs(
+    conn: &Connection,
+    symbol_ids: &[&str],
+) -> Result<Vec<(String, String)>, LlmError> {
+    if symbol_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let placeholders = symbol_ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+    let sql = format!(
+        "SELECT json_extract(a.data, '$.symbol_id'), json_extract(b.data, '$.symbol_id')
+         FROM graph_edges e
+         JOIN graph_entities a ON a.id = e.from_id
+         JOIN graph_entities b ON b.id = e.to_id
+         WHERE e.edge_type != 'DEFINES'
+           AND json_extract(a.data, '$.symbol_id') IS NOT NULL
+           AND json_extract(b.data, '$.symbol_id') IN ({0})
+         UNION
+         SELECT json_extract(data, '$.caller_symbol_id'), json_extract(data, '$.callee_symbol_id')
+         FROM graph_entities
+         WHERE kind = 'Call'
+           AND json_extract(data, '$.caller_symbol_id') IS NOT NULL
+           AND json_extract(data, '$.callee_symbol_id') IN ({0})",
+        placeholders
+    );
+
+    let params: Vec<&dyn rusqlite::ToSql> = symbol_ids
+        .iter()
+        .chain(symbol_ids.iter())
+        .map(|id| id as &dyn rusqlite::ToSql)
+        .collect();
+
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(params.as_slice(), |row| Ok((row.get(0)?, row.get(1)?)))?;
+
+    let mut pairs = Vec::new();
+    for row in rows {
+        pairs.push(row?);
+    }
+    Ok(pairs)
+}
+
+/// Group `--condense` results by `supernode_id`, ordered by supernode id.
+///
+/// Results without a `supernode_id` or `symbol_id` are skipped. Membership is
+/// taken from the results, so an entry point is a member with an incoming
+/// edge from any symbol not among its supernode's returned members.
+pub fn group_supernodes(
+    conn: &Connection,
+    results: &[SymbolMatch],
+) -> Result<SupernodeGroups, LlmError> {
+    let mut groups: BTreeMap<&str, Vec<SupernodeMember>> = BTreeMap::new();
+    let mut supernode_of: HashMap<&str, &str> = HashMap::new();
+    for result in results {
+        let (Some(supernode_id), Some(symbol_id)) = (&result.supernode_id, &result.symbol_id)
+        else {
+            continue;
+        };
+        if supernode_of.insert(symbol_id, supernode_id).is_some() {
+            continue;
+        }
+        groups
+            .entry(supernode_id)
+            .or_default()
+            .push(SupernodeMember {
+                symbol_id: symbol_id.clone(),
+                name: result.name.clone(),
+                file_path: result.span.file_path.clone(),
+                start_line: result.span.start_line,
+            });
+    }
+
+    let member_ids: Vec<&str> = supernode_of.keys().copied().collect();
+    let mut entered: HashSet<String> = HashSet::new();
+    for (from, to) in incoming_pairs(conn, &member_ids)? {
+        if supernode_of.get(from.as_str()) != supernode_of.get(to.as_str()) {
+            entered.insert(to);
+        }
+    }
+
+    let supernodes = groups
+        .into_iter()
+        .map(|(id, members)| Supernode {
+            id: id.to_string(),
+            size: members.len(),
+            entry_points: members
+                .iter()
+                .filter(|m| entered.contains(&m.symbol_id))
+                .cloned()
+                .collect(),
+            members,
+        })
+        .collect();
+    Ok(SupernodeGroups { supernodes })
+}
@@ -5,20 +5,26 @@
 
 use crate::algorithm::{apply_algorithm_filters, create_symbol_set_temp_table, SymbolSetStrategy};
 use crate::ast::check_ast_table_exists;
+use crate::backend::retry_busy;
 use crate::backend::schema_check::check_coverage_tables_exist;
 use crate::error::LlmError;
-use crate::output::{SearchResponse, SymbolMatch};
-use crate::query::builder::{build_search_query, check_symbol_fts_exists};
+use crate::output::{dedupe_by_name, SearchResponse, SymbolMatch};
+use crate::query::builder::{
+    build_search_query, check_metrics_table_exists, check_symbol_fts_exists,
+};
 use crate::query::chunks::search_chunks_by_span;
-use crate::query::options::SearchOptions;
+use crate::query::options::{SearchOptions, SnippetMode};
 use crate::query::util::{
-    infer_language, match_id, normalize_kind_label, score_match, snippet_from_file,
-    span_context_from_file, span_id, SymbolNodeData, MAX_REGEX_SIZE,
+    cap_per_file, cap_snippet_lines, content_hash_from_file, dedupe_by_content_hash, defined_in,
+    infer_language, line_text_from_file, match_id, normalize_fqn, normalize_kind_label,
+    number_snippet_lines, open_readonly_validated, score_match_case, snippet_from_file,
+    snippet_lines_from_file, sort_by_file_mtime, span_context_from_file, span_id, check_streamable, next_row, warn_timed_out,
+    RowSink, RowStream, SourceCache, SymbolNodeData, FAN_IN_BOOST_CAP, MAX_REGEX_SIZE,
 };
 use crate::safe_extraction::extract_symbol_content_safe;
 use crate::SortMode;
 use regex::RegexBuilder;
-use rusqlite::{params_from_iter, Connection, ErrorCode, OpenFlags};
+use rusqlite::{params_from_iter, Connection};
 use std::collections::HashMap;
 use std::path::Path;
 
@@ -27,11 +33,18 @@ use std::path::Path;
 /// This function contains the core SQL query logic for searching symbols.
 /// It is separated from search_symbols() to enable trait method implementation
 /// while maintaining backward-compatible wrapper.
+///
+/// With a `sink`, each match is handed over as its row is read and the
+/// returned response carries no results (see [`check_streamable`]).
 pub(crate) fn search_symbols_impl(
     conn: &Connection,
     db_path: &Path,
     options: &SearchOptions,
+    sink: Option<RowSink<'_, SymbolMatch>>,
 ) -> Result<(SearchResponse, bool, bool), LlmError> {
+    if sink.is_some() {
+        check_streamable(options)?;
+    }
     // Apply algorithm filters (pre-computed or one-shot execution)
     let (algorithm_symbol_ids, supernode_map, paths_bounded) = if options.algorithm.is_active() {
         apply_algorithm_filters(db_path, &options.algorithm)?
@@ -48,6 +61,7 @@ pub(crate) fn search_symbols_impl(
 
     let has_coverage = check_coverage_tables_exist(conn);
     let has_symbol_fts = check_symbol_fts_exists(conn).unwrap_or(false);
+    let has_metrics_table = check_metrics_table_exists(conn).unwrap_or(false);
 
     // Warn if coverage filter requested but tables don't exist
     if options.coverage_filter.is_some() && !has_coverage {
@@ -57,6 +71,9 @@ pub(crate) fn search_symbols_impl(
     let (sql, params, symbol_set_strategy) = build_search_query(
         options.query,
         options.path_filter,
+        options.path_glob,
+        options.exclude_paths,
+        options.file_set,
         options.kind_filter,
         options.language_filter,
         options.use_regex,
@@ -67,12 +84,14 @@ pub(crate) fn search_symbols_impl(
         options.symbol_id,
         options.fqn_pattern,
         options.exact_fqn,
+        options.fqn.normalize,
         false, // has_ast_table - set to false for now, will check properly below
-        &[],   // ast_kinds - set to empty for now, will use options.ast.ast_kinds below
-        None,  // min_depth
-        None,  // max_depth
-        None,  // inside_kind
-        None,  // contains_kind
+        has_metrics_table,
+        &[],  // ast_kinds - set to empty for now, will use options.ast.ast_kinds below
+        None, // min_depth
+        None, // max_depth
+        None, // inside_kind
+        None, // contains_kind
         symbol_set_filter,
         has_coverage,
         options.coverage_filter,
@@ -84,6 +103,15 @@ pub(crate) fn search_symbols_impl(
         reason: format!("Failed to check ast_nodes table: {}", e),
     })?;
 
+    // Without ast_nodes the AST clauses are dropped from the query; say so
+    // rather than return unfiltered results as if the filter had applied
+    let ast_filter_ignored = !has_ast_table
+        && (!options.ast.ast_kinds.is_empty()
+            || options.depth.min_depth.is_some()
+            || options.depth.max_depth.is_some()
+            || options.depth.inside.is_some()
+            || options.depth.contains.is_some());
+
     // If we have AST options, rebuild query with correct AST settings
     let (sql, params, symbol_set_strategy) = if !options.ast.ast_kinds.is_empty()
         || has_ast_table
@@ -95,6 +123,9 @@ pub(crate) fn search_symbols_impl(
         build_search_query(
             options.query,
             options.path_filter,
+            options.path_glob,
+            options.exclude_paths,
+            options.file_set,
             options.kind_filter,
             options.language_filter,
             options.use_regex,
@@ -105,7 +136,9 @@ pub(crate) fn search_symbols_impl(
             options.symbol_id,
             options.fqn_pattern,
             options.exact_fqn,
+            options.fqn.normalize,
             has_ast_table,
+            has_metrics_table,
             &options.ast.ast_kinds,
             options.depth.min_depth,
             options.depth.max_depth,
@@ -138,6 +171,7 @@ pub(crate) fn search_symbols_impl(
     let regex = if options.use_regex {
         Some(
             RegexBuilder::new(options.query)
+                .case_insensitive(options.ignore_case)
                 .size_limit(MAX_REGEX_SIZE)
                 .build()
                 .map_err(|e| LlmError::RegexRejected {
@@ -147,21 +181,54 @@ pub(crate) fn search_symbols_impl(
     } else {
         None
     };
-    let mut file_cache = HashMap::new();
+    let mut file_cache = SourceCache::new(options.snippet.read_timeout);
 
-    // Only compute scores for Relevance mode (Position mode skips scoring for performance)
-    let compute_scores = options.sort_by == SortMode::Relevance;
+    // Only compute scores for Relevance/Hybrid modes (Position mode skips scoring for performance)
+    let compute_scores = matches!(options.sort_by, SortMode::Relevance | SortMode::Hybrid);
 
     // Check if depth filtering is active (needed for ast_context enrichment)
     let has_depth_filter = options.depth.min_depth.is_some() || options.depth.max_depth.is_some();
 
-    while let Some(row) = rows.next()? {
+    // Decision depth per ast_id, shared by depth filtering and nesting-depth sorting
+    let mut decision_depths: HashMap<i64, Option<u64>> = HashMap::new();
+
+    let dedupe_key: fn(&SymbolMatch) -> Option<&str> = |r| r.content_hash.as_deref();
+    let mut stream = sink.map(|sink| {
+        RowStream::new(
+            sink,
+            options.limit,
+            options.max_per_file,
+            options.snippet.dedupe.then_some(dedupe_key),
+            |r| r.span.file_path.as_str(),
+        )
+    });
+    // Rows surviving the depth and visibility filters, counted while streaming
+    let mut kept: u64 = 0;
+    // Set when --timeout interrupts the scan
+    let mut timed_out = false;
+
+    while let Some(row) = next_row(&mut rows, &mut timed_out)? {
         let data: String = row.get(0)?;
         let file_path: String = row.get(1)?;
         // Read metrics columns (may be NULL)
         let fan_in: Option<i64> = row.get(2).ok();
         let fan_out: Option<i64> = row.get(3).ok();
         let cyclomatic_complexity: Option<i64> = row.get(4).ok();
+        // Read call-site count (only present with --weight-by-calls)
+        let call_count: Option<u64> = if options.metrics.weight_by_calls {
+            row.get::<_, i64>("call_count").ok().map(|v| v as u64)
+        } else {
+            None
+        };
+        // Read defining File entity (only with --with-provenance)
+        let defined_in = if options.fqn.defined_in {
+            row.get::<_, i64>("file_id").ok().map(|file_id| {
+                let file_data: Option<String> = row.get("file_data").ok();
+                defined_in(file_id, &file_path, file_data.as_deref())
+            })
+        } else {
+            None
+        };
         // Read symbol_id column (may be NULL)
         let symbol_id_from_query: Option<String> = row.get(5).ok();
 
@@ -241,11 +308,45 @@ pub(crate) fn search_symbols_impl(
                 continue;
             }
         }
+        // Surface the regex's capture groups, when it has any and the name matches
+        let captures = regex
+            .as_ref()
+            .filter(|pattern| pattern.captures_len() > 1)
+            .and_then(|pattern| pattern.captures(&name))
+            .map(|groups| {
+                groups
+                    .iter()
+                    .skip(1)
+                    .map(|group| group.map_or_else(String::new, |m| m.as_str().to_string()))
+                    .collect()
+            });
+
+        // Past the limit a stream only needs rows for total_count, which SQL
+        // counts itself unless the regex or --visibility decides membership
+        if stream.as_ref().is_some_and(RowStream::is_full) {
+            if !options.use_regex && !options.public_only {
+                break;
+            }
+            if !has_depth_filter && !options.public_only {
+                kept += 1;
+                continue;
+            }
+        }
 
         let (snippet, snippet_truncated, content_hash, symbol_kind_from_chunk) =
             if options.snippet.include {
                 // Try chunks table first for faster, pre-validated content
                 match search_chunks_by_span(conn, &file_path, symbol.byte_start, symbol.byte_end) {
+                    Ok(Some(chunk)) if options.snippet.mode == SnippetMode::Lines => {
+                        let (snippet_content, truncated) =
+                            cap_snippet_lines(&chunk.content, options.snippet.max_lines);
+                        (
+                            Some(snippet_content),
+                            Some(truncated),
+                            Some(chunk.content_hash),
+                            chunk.symbol_kind,
+                        )
+                    }
                     Ok(Some(chunk)) => {
                         // Apply max_bytes limit to chunk content
                         let content_bytes = chunk.content.as_bytes();
@@ -279,14 +380,31 @@ pub(crate) fn search_symbols_impl(
                             "Chunk fallback: {}:{}-{}",
                             file_path, symbol.byte_start, symbol.byte_end
                         );
-                        let (snippet, truncated) = snippet_from_file(
-                            &file_path,
-                            symbol.byte_start,
-                            symbol.byte_end,
-                            options.snippet.max_bytes,
-                            &mut file_cache,
-                        );
-                        (snippet, truncated, None, None)
+                        let (snippet, truncated) = match options.snippet.mode {
+                            SnippetMode::Bytes => snippet_from_file(
+                                &file_path,
+                                symbol.byte_start,
+                                symbol.byte_end,
+                                options.snippet.max_bytes,
+                                &mut file_cache,
+                            ),
+                            SnippetMode::Lines => snippet_lines_from_file(
+                                &file_path,
+                                symbol.byte_start,
+                                symbol.byte_end,
+                                options.snippet.max_lines,
+                                &mut file_cache,
+                            ),
+                        };
+                        let content_hash = snippet.as_ref().and_then(|_| {
+                            content_hash_from_file(
+                                &file_path,
+                                symbol.byte_start,
+                                symbol.byte_end,
+                                &mut file_cache,
+                            )
+                        });
+                        (snippet, truncated, content_hash, None)
                     }
                     Err(e) => {
                         // Error querying chunks, fall back to file I/O
@@ -294,28 +412,56 @@ pub(crate) fn search_symbols_impl(
                             "Chunk query error for {}:{}-{}: {}, using file I/O",
                             file_path, symbol.byte_start, symbol.byte_end, e
                         );
-                        let (snippet, truncated) = snippet_from_file(
-                            &file_path,
-                            symbol.byte_start,
-                            symbol.byte_end,
-                            options.snippet.max_bytes,
-                            &mut file_cache,
-                        );
-                        (snippet, truncated, None, None)
+                        let (snippet, truncated) = match options.snippet.mode {
+                            SnippetMode::Bytes => snippet_from_file(
+                                &file_path,
+                                symbol.byte_start,
+                                symbol.byte_end,
+                                options.snippet.max_bytes,
+                                &mut file_cache,
+                            ),
+                            SnippetMode::Lines => snippet_lines_from_file(
+                                &file_path,
+                                symbol.byte_start,
+                                symbol.byte_end,
+                                options.snippet.max_lines,
+                                &mut file_cache,
+                            ),
+                        };
+                        let content_hash = snippet.as_ref().and_then(|_| {
+                            content_hash_from_file(
+                                &file_path,
+                                symbol.byte_start,
+                                symbol.byte_end,
+                                &mut file_cache,
+                            )
+                        });
+                        (snippet, truncated, content_hash, None)
                     }
                 }
             } else {
                 (None, None, None, None)
             };
+        let snippet = if options.snippet.line_numbers {
+            snippet.map(|s| number_snippet_lines(&s, symbol.start_line, &name))
+        } else {
+            snippet
+        };
+        let line_text = if options.snippet.line_text {
+            line_text_from_file(&file_path, symbol.start_line, &mut file_cache)
+        } else {
+            None
+        };
         let context = if options.context.include {
-            let capped = options.context.lines > options.context.max_lines;
-            let effective_lines = options.context.lines.min(options.context.max_lines);
+            let (before_lines, after_lines, capped) = options.context.window();
             span_context_from_file(
                 &file_path,
                 symbol.start_line,
                 symbol.end_line,
-                effective_lines,
+                before_lines,
+                after_lines,
                 capped,
+                options.context.exclude_self,
                 &mut file_cache,
             )
         } else {
@@ -335,9 +481,21 @@ pub(crate) fn search_symbols_impl(
         };
 
         let match_id = match_id(&file_path, symbol.byte_start, symbol.byte_end, &name);
-        // Only compute scores in Relevance mode (Position mode skips scoring for performance)
+        // Only compute scores in Relevance/Hybrid modes (Position mode skips scoring for performance)
         let score = if compute_scores {
-            score_match(options.query, &name, &display_fqn, &fqn, regex.as_ref())
+            let fan_in_bonus = if options.metrics.boost_fan_in {
+                fan_in.map_or(0, |v| v.max(0) as u64).min(FAN_IN_BOOST_CAP)
+            } else {
+                0
+            };
+            score_match_case(
+                options.query,
+                &name,
+                &display_fqn,
+                &fqn,
+                regex.as_ref(),
+                options.ignore_case,
+            ) + fan_in_bonus
         } else {
             0
         };
@@ -352,6 +510,16 @@ pub(crate) fn search_symbols_impl(
         } else {
             None
         };
+        let (fqn, canonical_fqn, display_fqn) = if options.fqn.normalize {
+            let normalize = |value: Option<String>| value.map(|v| normalize_fqn(&v));
+            (
+                normalize(fqn),
+                normalize(canonical_fqn),
+                normalize(display_fqn),
+            )
+        } else {
+            (fqn, canonical_fqn, display_fqn)
+        };
 
         // Convert metrics from Option<i64> to Option<u64>
         let complexity_score = None; // Not available in symbol_metrics
@@ -369,6 +537,13 @@ pub(crate) fn search_symbols_impl(
             .clone()
             .unwrap_or_else(|| normalize_kind_label(&symbol.kind));
 
+        // Capture the exact-span AST kind before enrichment may swap in a preferred node
+        let ast_node_kind = if options.ast.with_ast_kind {
+            ast_context.as_ref().map(|ctx| ctx.kind.clone())
+        } else {
+            None
+        };
+
         // Enrich ast_context if --with-ast-context flag is set OR depth filtering is active
         let needs_ast_enrichment = options.ast.with_ast_context || has_depth_filter;
         // Check if we have an active ast_kinds filter that should override the exact-match JOIN result
@@ -495,7 +670,7 @@ pub(crate) fn search_symbols_impl(
             ast_context
         };
 
-        results.push(SymbolMatch {
+        let result = SymbolMatch {
             match_id,
             span,
             name,
@@ -514,6 +689,10 @@ pub(crate) fn search_symbols_impl(
             symbol_kind_from_chunk,
             snippet,
             snippet_truncated,
+            line_text,
+            visibility: symbol.visibility.clone(),
+            complexity_percentile: None,
+            call_count,
             language,
             kind_normalized: Some(kind_normalized),
             complexity_score,
@@ -521,6 +700,7 @@ pub(crate) fn search_symbols_impl(
             fan_out,
             cyclomatic_complexity,
             ast_context,
+            ast_node_kind,
             supernode_id: symbol_id
                 .as_ref()
                 .and_then(|id| supernode_map.get(id).cloned()),
@@ -551,48 +731,65 @@ pub(crate) fn search_symbols_impl(
             } else {
                 None
             },
-        });
+            source_db: None,
+            defined_in,
+            captures,
+            callers: None,
+        };
+        match stream.as_mut() {
+            Some(stream) => {
+                if (has_depth_filter
+                    && !within_depth(conn, &mut decision_depths, options, &result))
+                    || (options.public_only && !result.is_public())
+                {
+                    continue;
+                }
+                kept += 1;
+                stream.push(result)?;
+            }
+            None => results.push(result),
+        }
     }
+    let streaming = stream.is_some();
+    let streamed_dedupes = stream.map_or(0, |stream| stream.deduped);
 
     // Apply depth filtering if min_depth or max_depth specified
     // This is done post-query due to SQLite recursive CTE limitations
     if has_depth_filter {
-        // Filter results by decision depth
-        results.retain(|result| {
-            // Only filter if we have AST context with ast_id
-            if let Some(ref ast_ctx) = result.ast_context {
-                match crate::ast::calculate_decision_depth(conn, ast_ctx.ast_id) {
-                    Ok(Some(depth)) => {
-                        // Check min/max bounds
-                        let min_ok = options
-                            .depth
-                            .min_depth
-                            .is_none_or(|m| (depth as usize) >= m);
-                        let max_ok = options
-                            .depth
-                            .max_depth
-                            .is_none_or(|m| (depth as usize) <= m);
-                        min_ok && max_ok
-                    }
-                    Ok(None) => true, // No depth data, keep the result
-                    Err(_) => true,   // Error calculating depth, keep the result
-                }
-            } else {
-                true // No AST context, keep the result
-            }
-        });
+        results.retain(|result| within_depth(conn, &mut decision_depths, options, result));
+    }
+
+    // --visibility and --prefer run before any truncation to `limit`, so
+    // results past the window can still survive or win
+    let post_filtered = options.public_only || options.prefer.is_some();
+    if options.public_only {
+        results.retain(SymbolMatch::is_public);
+    }
+    if let Some(policy) = options.prefer {
+        dedupe_by_name(&mut results, policy);
+    }
+    if !streaming {
+        kept = results.len() as u64;
     }
 
     let mut partial = false;
-    let total_count = if options.use_regex {
-        if results.len() >= options.candidates {
+    let total_count = if timed_out {
+        // The count query would be interrupted too; report what was read
+        warn_timed_out(kept);
+        partial = true;
+        kept
+    } else if options.use_regex {
+        if kept as usize >= options.candidates {
             partial = true;
         }
-        results.len() as u64
+        kept
     } else {
         let (count_sql, count_params, _symbol_set_strategy) = build_search_query(
             options.query,
             options.path_filter,
+            options.path_glob,
+            options.exclude_paths,
+            options.file_set,
             options.kind_filter,
             options.language_filter,
             options.use_regex,
@@ -603,7 +800,9 @@ pub(crate) fn search_symbols_impl(
             options.symbol_id,
             options.fqn_pattern,
             options.exact_fqn,
+            options.fqn.normalize,
             has_ast_table,
+            has_metrics_table,
             &options.ast.ast_kinds,
             options.depth.min_depth,
             options.depth.max_depth,
@@ -618,10 +817,19 @@ pub(crate) fn search_symbols_impl(
         if options.candidates < count as usize {
             partial = true;
         }
-        count
+        // SQL cannot see the post-filters, so count what survived them instead
+        if post_filtered {
+            kept
+        } else {
+            count
+        }
     };
 
-    // Only sort by score in Relevance mode (Position mode relies on SQL ORDER BY)
+    // Hybrid scores only the position-ordered window that survives --limit
+    if options.sort_by == SortMode::Hybrid {
+        results.truncate(options.limit);
+    }
+    // Only sort by score in Relevance/Hybrid modes (Position mode relies on SQL ORDER BY)
     if compute_scores {
         results.sort_by(|a, b| {
             b.score
@@ -633,66 +841,40 @@ pub(crate) fn search_symbols_impl(
         });
     }
 
-    // Sort by nesting depth when requested (requires batch depth calculation)
+    // Sort by decision depth when requested, deepest first; symbols without
+    // AST data count as depth 0
     if options.sort_by == SortMode::NestingDepth {
-        let ast_ids: Vec<i64> = results
-            .iter()
-            .filter_map(|r| r.ast_context.as_ref().map(|ctx| ctx.ast_id))
-            .collect();
-
-        if !ast_ids.is_empty() {
-            let placeholders = ast_ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
-            let depth_sql = format!(
-                "WITH RECURSIVE node_ancestry AS (
-                    SELECT id, parent_id, 0 as depth
-                    FROM ast_nodes
-                    WHERE parent_id IS NULL
-                    UNION ALL
-                    SELECT a.id, a.parent_id, na.depth + 1
-                    FROM ast_nodes a
-                    JOIN node_ancestry na ON a.parent_id = na.id
-                )
-                SELECT id, depth FROM node_ancestry WHERE id IN ({})",
-                placeholders
-            );
-            let depth_params: Vec<Box<dyn rusqlite::ToSql>> = ast_ids
-                .iter()
-                .map(|id| Box::new(*id) as Box<dyn rusqlite::ToSql>)
-                .collect();
-
-            let mut depth_map = std::collections::HashMap::new();
-            if let Ok(mut stmt) = conn.prepare(&depth_sql) {
-                let rows = stmt.query_map(
-                    rusqlite::params_from_iter(depth_params.iter().map(|p| p.as_ref())),
-                    |row| Ok((row.get::<_, i64>(0)?, row.get::<_, u64>(1)?)),
-                );
-                if let Ok(rows) = rows {
-                    for (id, depth) in rows.flatten() {
-                        depth_map.insert(id, depth);
-                    }
-                }
-            }
+        let mut depth_of = |result: &SymbolMatch| {
+            result
+                .ast_context
+                .as_ref()
+                .and_then(|ctx| cached_decision_depth(conn, &mut decision_depths, ctx.ast_id))
+                .unwrap_or(0)
+        };
+        let mut keyed: Vec<(u64, SymbolMatch)> =
+            results.drain(..).map(|r| (depth_of(&r), r)).collect();
+        keyed.sort_by(|(depth_a, a), (depth_b, b)| {
+            depth_b
+                .cmp(depth_a)
+                .then_with(|| a.span.start_line.cmp(&b.span.start_line))
+                .then_with(|| a.span.start_col.cmp(&b.span.start_col))
+                .then_with(|| a.span.byte_start.cmp(&b.span.byte_start))
+        });
+        results.extend(keyed.into_iter().map(|(_, r)| r));
+    }
 
-            results.sort_by(|a, b| {
-                let depth_a = a
-                    .ast_context
-                    .as_ref()
-                    .and_then(|ctx| depth_map.get(&ctx.ast_id).copied())
-                    .unwrap_or(0);
-                let depth_b = b
-                    .ast_context
-                    .as_ref()
-                    .and_then(|ctx| depth_map.get(&ctx.ast_id).copied())
-                    .unwrap_or(0);
-                depth_b
-                    .cmp(&depth_a)
-                    .then_with(|| a.span.start_line.cmp(&b.span.start_line))
-                    .then_with(|| a.span.start_col.cmp(&b.span.start_col))
-                    .then_with(|| a.span.byte_start.cmp(&b.span.byte_start))
-            });
-        }
+    if options.sort_by == SortMode::LastModified {
+        sort_by_file_mtime(&mut results, |r| r.span.file_path.as_str());
     }
 
+    let deduped = if options.snippet.dedupe {
+        dedupe_by_content_hash(&mut results, |r| r.content_hash.as_deref()) + streamed_dedupes
+    } else {
+        0
+    };
+    if let Some(max) = options.max_per_file {
+        cap_per_file(&mut results, max, |r| r.span.file_path.as_str());
+    }
     results.truncate(options.limit);
 
     // Ambiguity detection: warn if multiple symbols have the same name
@@ -745,60 +927,158 @@ pub(crate) fn search_symbols_impl(
                 .map(|path| path.to_string_lossy().to_string()),
             kind_filter: options.kind_filter.map(|value| value.to_string()),
             total_count,
-            notice: None,
+            notice: search_notice(ast_filter_ignored, deduped),
+            available_fields: None,
         },
         partial,
         paths_bounded,
     ))
 }
 
+/// Whether `result` falls inside `--min-depth`/`--max-depth`.
+///
+/// Results without AST context or depth data are kept.
+fn within_depth(
+    conn: &Connection,
+    decision_depths: &mut HashMap<i64, Option<u64>>,
+    options: &SearchOptions,
+    result: &SymbolMatch,
+) -> bool {
+    let Some(ast_ctx) = &result.ast_context else {
+        return true;
+    };
+    match cached_decision_depth(conn, decision_depths, ast_ctx.ast_id) {
+        Some(depth) => {
+            let min_ok = options
+                .depth
+                .min_depth
+                .is_none_or(|m| (depth as usize) >= m);
+            let max_ok = options
+                .depth
+                .max_depth
+                .is_none_or(|m| (depth as usize) <= m);
+            min_ok && max_ok
+        }
+        None => true,
+    }
+}
+
+/// Decision depth of `ast_id`, computed once per search.
+///
+/// Missing nodes and query errors are cached as `None` so they are not retried.
+fn cached_decision_depth(
+    conn: &Connection,
+    cache: &mut HashMap<i64, Option<u64>>,
+    ast_id: i64,
+) -> Option<u64> {
+    *cache.entry(ast_id).or_insert_with(|| {
+        crate::ast::calculate_decision_depth(conn, ast_id)
+            .ok()
+            .flatten()
+    })
+}
+
+/// Notice attached to a search response when an AST, structural or depth
+/// filter was requested against a database without an `ast_nodes` table.
+pub const AST_FILTER_IGNORED_NOTICE: &str =
+    "AST filtering requested but this database has no ast_nodes table; filter ignored";
+
+/// Join the notices a symbol search can raise into the response `notice`.
+fn search_notice(ast_filter_ignored: bool, deduped: usize) -> Option<String> {
+    let mut notices = Vec::new();
+    if ast_filter_ignored {
+        notices.push(AST_FILTER_IGNORED_NOTICE.to_string());
+    }
+    if deduped > 0 {
+        notices.push(format!(
+            "--dedupe collapsed {} result{} with duplicate content",
+            deduped,
+            if deduped == 1 { "" } else { "s" }
+        ));
+    }
+    (!notices.is_empty()).then(|| notices.join("; "))
+}
+
+/// Count symbols matching `options` without materializing result rows.
+///
+/// Plain name searches run only the `COUNT(*)` query. Regex, depth and
+/// algorithm filters are applied after SQL, so those fall back to counting the
+/// filtered rows (capped at `candidates`) with snippets, context and scoring
+/// turned off.
+///
+/// Returns the count and whether it is partial, i.e. the fallback hit the
+/// `candidates` cap and more rows may match.
+pub(crate) fn count_symbols_impl(
+    conn: &Connection,
+    db_path: &Path,
+    options: &SearchOptions,
+) -> Result<(u64, bool), LlmError> {
+    let has_depth_filter = options.depth.min_depth.is_some() || options.depth.max_depth.is_some();
+    if options.use_regex || has_depth_filter || options.algorithm.is_active() {
+        let mut lean = options.clone();
+        lean.limit = options.candidates;
+        lean.context = Default::default();
+        lean.snippet = Default::default();
+        lean.fqn = Default::default();
+        lean.include_score = false;
+        lean.sort_by = SortMode::Position;
+        lean.ast.with_ast_context = false;
+        let (response, partial, _) = search_symbols_impl(conn, db_path, &lean, None)?;
+        return Ok((response.results.len() as u64, partial));
+    }
+
+    let has_coverage = check_coverage_tables_exist(conn);
+    let has_symbol_fts = check_symbol_fts_exists(conn).unwrap_or(false);
+    let has_metrics_table = check_metrics_table_exists(conn).unwrap_or(false);
+    let has_ast_table = check_ast_table_exists(conn).map_err(|e| LlmError::SearchFailed {
+        reason: format!("Failed to check ast_nodes table: {}", e),
+    })?;
+
+    let (count_sql, count_params, _symbol_set_strategy) = build_search_query(
+        options.query,
+        options.path_filter,
+        options.path_glob,
+        options.exclude_paths,
+        options.file_set,
+        options.kind_filter,
+        options.language_filter,
+        false,
+        true,
+        0,
+        options.metrics,
+        options.sort_by,
+        options.symbol_id,
+        options.fqn_pattern,
+        options.exact_fqn,
+        options.fqn.normalize,
+        has_ast_table,
+        has_metrics_table,
+        &options.ast.ast_kinds,
+        None,
+        None,
+        options.depth.inside,
+        options.depth.contains,
+        None,
+        has_coverage,
+        options.coverage_filter,
+        has_symbol_fts,
+    );
+    let count: i64 =
+        conn.query_row(&count_sql, params_from_iter(count_params), |row| row.get(0))?;
+    Ok((count as u64, false))
+}
+
 /// Public wrapper for search_symbols that handles connection opening and validation.
 ///
 /// This function opens the database connection, validates it, and delegates to
 /// search_symbols_impl() for the actual query logic. This maintains backward
 /// compatibility while enabling trait method implementation.
+///
+/// Each call opens a fresh connection; callers issuing several searches should
+/// open a `SqliteBackend` once and use its trait methods instead.
 pub fn search_symbols(options: SearchOptions) -> Result<(SearchResponse, bool, bool), LlmError> {
-    let conn = match Connection::open_with_flags(options.db_path, OpenFlags::SQLITE_OPEN_READ_ONLY)
-    {
-        Ok(conn) => conn,
-        Err(rusqlite::Error::SqliteFailure(err, msg)) => match err.code {
-            ErrorCode::DatabaseCorrupt | ErrorCode::NotADatabase => {
-                return Err(LlmError::DatabaseCorrupted {
-                    reason: msg
-                        .unwrap_or_else(|| "Database file is invalid or corrupted".to_string()),
-                });
-            }
-            ErrorCode::CannotOpen => {
-                return Err(LlmError::DatabaseNotFound {
-                    path: options.db_path.display().to_string(),
-                });
-            }
-            _ => return Err(LlmError::from(rusqlite::Error::SqliteFailure(err, msg))),
-        },
-        Err(e) => return Err(LlmError::from(e)),
-    };
-
-    // Force database validation by checking if schema exists
-    // This catches "not a database" errors that occur lazily
-    conn.query_row(
-        "SELECT name FROM sqlite_master WHERE type='table' LIMIT 1",
-        [],
-        |_| Ok(()),
-    )
-    .map_err(|e| match e {
-        rusqlite::Error::SqliteFailure(err, ref msg) => match err.code {
-            ErrorCode::DatabaseCorrupt | ErrorCode::NotADatabase => LlmError::DatabaseCorrupted {
-                reason: msg
-                    .as_ref()
-                    .map(|s| s.as_str())
-                    .unwrap_or("Database file is invalid or corrupted")
-                    .to_string(),
-            },
-            _ => LlmError::from(e),
-        },
-        other => LlmError::from(other),
-    })?;
+    let conn = retry_busy(|| open_readonly_validated(options.db_path))?;
 
     // Call the implementation
-    search_symbols_impl(&conn, options.db_path, &options)
+    search_symbols_impl(&conn, options.db_path, &options, None)
 }
@@ -0,0 +1,50 @@
+use super::*;
+
+#[test]
+fn test_annotate_heat_prefixes_symbol_lines_with_complexity() {
+    let (_db_file, conn) = create_test_db();
+    conn.execute(
+        "INSERT INTO symbol_metrics (symbol_id, symbol_name, kind, file_path, cyclomatic_complexity) VALUES
+            (10, 'test_func', 'Function', '/test/file.rs', 3),
+            (12, 'helper', 'Function', '/test/file.rs', 12)",
+        [],
+    )
+    .expect("failed to insert metrics");
+
+    let spans = file_heat_spans(&conn, "/test/file.rs").expect("file_heat_spans should succeed");
+    assert_eq!(
+        spans,
+        vec![
+            HeatSpan {
+                name: "test_func".to_string(),
+                start_line: 5,
+                end_line: 10,
+                cyclomatic_complexity: 3,
+            },
+            HeatSpan {
+                name: "helper".to_string(),
+                start_line: 25,
+                end_line: 30,
+                cyclomatic_complexity: 12,
+            },
+        ]
+    );
+
+    let source: String = (1..=32).map(|n| format!("line{}\n", n)).collect();
+    let annotated = annotate_heat(&source, &spans, false);
+    let lines: Vec<&str> = annotated.lines().collect();
+
+    assert_eq!(lines[0], "         1 | line1");
+    assert_eq!(lines[3], "         4 | line4");
+    assert_eq!(lines[4], "[cc:3]   5 | line5");
+    assert_eq!(lines[9], "[cc:3]  10 | line10");
+    assert_eq!(lines[10], "        11 | line11");
+    assert_eq!(lines[24], "[cc:12] 25 | line25");
+    assert_eq!(lines[29], "[cc:12] 30 | line30");
+    assert_eq!(lines[30], "        31 | line31");
+
+    let colored = annotate_heat(&source, &spans, true);
+    let colored_lines: Vec<&str> = colored.lines().collect();
+    assert!(colored_lines[4].starts_with("\x1b[32m[cc:3] \x1b[0m"));
+    assert!(colored_lines[24].starts_with("\x1b[31m[cc:12]\x1b[0m"));
+}
@@ -0,0 +1,74 @@
+use super::*;
+use std::time::{Duration, SystemTime};
+
+#[test]
+fn test_query_cache_hits_until_db_mtime_changes() {
+    let (db_file, _conn) = create_test_db();
+    let db_path = db_file.path();
+    let cache_dir = tempfile::tempdir().expect("failed to create cache dir");
+    let cache = QueryCache::new(cache_dir.path());
+
+    let options = SearchOptions {
+        db_path,
+        query: "test",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: None,
+        limit: 10,
+        use_regex: false,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions::default(),
+        snippet: SnippetOptions::default(),
+        fqn: FqnOptions::default(),
+        include_score: false,
+        sort_by: SortMode::default(),
+        metrics: MetricsOptions::default(),
+        ast: AstOptions::default(),
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
+    };
+
+    assert!(cache.get(&options).is_none());
+    let result = search_symbols(options.clone()).expect("search_symbols should succeed");
+    cache
+        .put(&options, &result)
+        .expect("cache write should succeed");
+
+    let (hit, partial, _) = cache.get(&options).expect("same options should hit");
+    assert_eq!(hit.total_count, result.0.total_count);
+    assert_eq!(
+        hit.results.iter().map(|r| &r.name).collect::<Vec<_>>(),
+        result.0.results.iter().map(|r| &r.name).collect::<Vec<_>>()
+    );
+    assert_eq!(partial, result.1);
+
+    let other = SearchOptions {
+        limit: 1,
+        ..options.clone()
+    };
+    assert!(cache.get(&other).is_none(), "different options must miss");
+
+    std::fs::File::options()
+        .write(true)
+        .open(db_path)
+        .and_then(|f| f.set_modified(SystemTime::now() + Duration::from_secs(60)))
+        .expect("failed to bump database mtime");
+    assert!(
+        cache.get(&options).is_none(),
+        "a changed database must miss"
+    );
+
+    assert_eq!(cache.clear().expect("clear should succeed"), 1);
+    assert_eq!(cache.clear().expect("clear should succeed"), 0);
+}
@@ -0,0 +1,75 @@
+use super::*;
+
+#[test]
+fn test_capabilities_reflect_present_tables() {
+    let (_db_file, conn) = create_test_db();
+
+    let report = capabilities(&conn).expect("capabilities should succeed");
+
+    assert_eq!(report.backend, "sqlite");
+    assert!(report.search && report.complete && report.lookup && report.search_by_label);
+    assert!(report.edit_script);
+    assert!(report.metrics);
+    assert!(!report.ast && !report.find_ast);
+    assert!(!report.semantic && !report.docs && !report.facts);
+    assert!(!report.coverage && !report.chunks && !report.fts);
+
+    conn.execute(crate::ast::ast_nodes_table_schema(), [])
+        .expect("failed to create ast_nodes table");
+    let report = capabilities(&conn).expect("capabilities should succeed");
+    assert!(report.ast && report.find_ast);
+}
+
+#[test]
+fn test_min_viable_projection_omits_and_declares_unavailable_metrics() {
+    let (db_file, conn) = create_test_db();
+    conn.execute("DROP TABLE symbol_metrics", [])
+        .expect("failed to drop symbol_metrics");
+
+    let options = SearchOptions {
+        db_path: db_file.path(),
+        query: "test_func",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: None,
+        limit: 10,
+        use_regex: false,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions::default(),
+        snippet: SnippetOptions::default(),
+        fqn: FqnOptions::default(),
+        include_score: false,
+        sort_by: SortMode::default(),
+        metrics: MetricsOptions::default(),
+        ast: AstOptions::default(),
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
+    };
+    let (mut response, _, _) = search_symbols(options).expect("search_symbols should succeed");
+    let report = capabilities(&conn).expect("capabilities should succeed");
+    response.project_available_fields(report.available_symbol_fields());
+
+    let value = serde_json::to_value(&response).expect("failed to serialize response");
+    assert_eq!(value["available_fields"], serde_json::json!(["fan_in"]));
+    let result = &value["results"][0];
+    assert_eq!(result["name"], "test_func");
+    for field in [
+        "fan_out",
+        "cyclomatic_complexity",
+        "ast_context",
+        "ast_node_kind",
+    ] {
+        assert!(result.get(field).is_none(), "{field} should be omitted");
+    }
+}
@@ -0,0 +1,95 @@
+use super::*;
+
+#[test]
+fn test_symbol_card_populates_all_fields() {
+    let (db_file, conn) = create_test_db();
+    conn.execute(
+        "INSERT INTO symbol_metrics (symbol_id, symbol_name, kind, file_path, loc, fan_in, fan_out, cyclomatic_complexity) VALUES
+            (10, 'test_func', 'Function', '/test/file.rs', 6, 2, 1, 4)",
+        [],
+    )
+    .expect("failed to insert metrics");
+    conn.execute(
+        "INSERT INTO graph_entities (id, kind, data) VALUES
+            (20, 'Call', '{\"caller\":\"main\",\"callee\":\"test_func\",\"caller_symbol_id\":\"sym9\",\"callee_symbol_id\":\"sym1\"}'),
+            (21, 'Call', '{\"caller\":\"main\",\"callee\":\"test_func\",\"caller_symbol_id\":\"sym9\",\"callee_symbol_id\":\"sym1\"}'),
+            (22, 'Call', '{\"caller\":\"run\",\"callee\":\"test_func\"}'),
+            (23, 'Call', '{\"caller\":\"test_func\",\"callee\":\"helper\",\"caller_symbol_id\":\"sym1\",\"callee_symbol_id\":\"sym3\"}')",
+        [],
+    )
+    .expect("failed to insert calls");
+    conn.execute(crate::ast::ast_nodes_table_schema(), [])
+        .expect("failed to create ast_nodes table");
+    conn.execute(
+        "INSERT INTO ast_nodes (id, parent_id, kind, byte_start, byte_end) VALUES (1, NULL, 'function_item', 100, 200)",
+        [],
+    )
+    .expect("failed to insert ast node");
+
+    let options = SearchOptions {
+        db_path: db_file.path(),
+        query: "test_func",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: None,
+        limit: 1,
+        use_regex: false,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions::default(),
+        snippet: SnippetOptions::default(),
+        fqn: FqnOptions {
+            fqn: true,
+            canonical_fqn: true,
+            display_fqn: true,
+            normalize: false,
+            defined_in: false,
+        },
+        include_score: false,
+        sort_by: SortMode::default(),
+        metrics: MetricsOptions::default(),
+        ast: AstOptions::default(),
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
+    };
+    let (response, _, _) = search_symbols(options).expect("search_symbols should succeed");
+    let symbol = response
+        .results
+        .into_iter()
+        .next()
+        .expect("test_func should match");
+
+    let card = symbol_card(&conn, symbol).expect("symbol_card should succeed");
+    assert_eq!(
+        card,
+        SymbolCard {
+            name: "test_func".to_string(),
+            kind: "Function".to_string(),
+            file: "/test/file.rs".to_string(),
+            start_line: 5,
+            start_col: 0,
+            end_line: 10,
+            language: Some("Rust".to_string()),
+            fqn: Some("module::test_func".to_string()),
+            canonical_fqn: Some("/test/file.rs::test_func".to_string()),
+            display_fqn: Some("test_func".to_string()),
+            cyclomatic_complexity: Some(4),
+            fan_in: Some(2),
+            fan_out: Some(1),
+            loc: Some(6),
+            callers: 2,
+            callees: 1,
+            ast_kind: Some("function_item".to_string()),
+        }
+    );
+}
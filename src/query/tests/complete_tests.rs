@@ -0,0 +1,38 @@
+use super::*;
+
+#[test]
+fn test_rank_completions_shortest_then_lexical() {
+    let mut completions = vec![
+        "crate::backend::sqlite".to_string(),
+        "crate::cli".to_string(),
+        "crate::backend".to_string(),
+        "crate::algo".to_string(),
+    ];
+    rank_completions(&mut completions);
+    assert_eq!(
+        completions,
+        vec![
+            "crate::cli",
+            "crate::algo",
+            "crate::backend",
+            "crate::backend::sqlite"
+        ]
+    );
+}
+
+#[test]
+fn test_next_segments_returns_segment_after_prefix() {
+    let completions = vec![
+        "crate::backend::sqlite::SqliteBackend".to_string(),
+        "crate::backend::sqlite::open".to_string(),
+        "crate::backend::native_v3".to_string(),
+        "crate::backend".to_string(),
+        "other::backend::x".to_string(),
+    ];
+    assert_eq!(
+        next_segments(&completions, "crate::backend::"),
+        vec!["sqlite", "native_v3"]
+    );
+    // A partial segment completes to the whole segment
+    assert_eq!(next_segments(&completions, "crate::back"), vec!["backend"]);
+}
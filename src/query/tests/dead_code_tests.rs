@@ -0,0 +1,69 @@
+use super::*;
+
+#[test]
+fn test_mutually_calling_dead_functions_list_each_other_as_callers() {
+    let (db_file, conn) = create_test_db();
+    conn.execute(
+        "INSERT INTO graph_entities (id, kind, data) VALUES
+            (20, 'Call', '{\"caller\":\"test_func\",\"callee\":\"helper\",\"caller_symbol_id\":\"sym1\",\"callee_symbol_id\":\"sym3\"}'),
+            (21, 'Call', '{\"caller\":\"helper\",\"callee\":\"test_func\",\"caller_symbol_id\":\"sym3\",\"callee_symbol_id\":\"sym1\"}'),
+            (22, 'Call', '{\"caller\":\"main\",\"callee\":\"TestStruct\",\"caller_symbol_id\":\"sym9\",\"callee_symbol_id\":\"sym2\"}')",
+        [],
+    )
+    .expect("failed to insert calls");
+
+    let options = SearchOptions {
+        db_path: db_file.path(),
+        query: "^(test_func|helper|TestStruct)$",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: None,
+        limit: 10,
+        use_regex: true,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions::default(),
+        snippet: SnippetOptions::default(),
+        fqn: FqnOptions::default(),
+        include_score: false,
+        sort_by: SortMode::Position,
+        metrics: MetricsOptions::default(),
+        ast: AstOptions::default(),
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
+    };
+    let (mut response, _, _) = search_symbols(options).expect("search_symbols should succeed");
+    assert_eq!(response.results.len(), 3);
+    attach_dead_code_callers(&conn, &mut response.results).expect("callers should attach");
+
+    let callers_of = |name: &str| {
+        response
+            .results
+            .iter()
+            .find(|r| r.name == name)
+            .and_then(|r| r.callers.clone())
+            .expect("callers attached")
+    };
+    let dead_caller = |name: &str, id: &str| crate::output::CallerRef {
+        name: name.to_string(),
+        symbol_id: Some(id.to_string()),
+        dead: true,
+    };
+    assert_eq!(callers_of("test_func"), vec![dead_caller("helper", "sym3")]);
+    assert_eq!(callers_of("helper"), vec![dead_caller("test_func", "sym1")]);
+
+    // A caller outside the dead set means removal is not safe
+    let struct_callers = callers_of("TestStruct");
+    assert_eq!(struct_callers.len(), 1);
+    assert!(!struct_callers[0].dead);
+}
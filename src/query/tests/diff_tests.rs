@@ -0,0 +1,95 @@
+use super::*;
+
+#[test]
+fn test_diff_complexity_reports_changed_symbol_delta() {
+    let (_old_file, old) = create_test_db();
+    let (_new_file, new) = create_test_db();
+
+    old.execute(
+        "INSERT INTO symbol_metrics (symbol_id, symbol_name, kind, file_path, cyclomatic_complexity) VALUES
+            (10, 'test_func', 'Function', '/test/file.rs', 3),
+            (12, 'helper', 'Function', '/test/file.rs', 2)",
+        [],
+    )
+    .expect("failed to insert baseline metrics");
+    new.execute(
+        "INSERT INTO symbol_metrics (symbol_id, symbol_name, kind, file_path, cyclomatic_complexity) VALUES
+            (10, 'test_func', 'Function', '/test/file.rs', 7),
+            (12, 'helper', 'Function', '/test/file.rs', 2)",
+        [],
+    )
+    .expect("failed to insert current metrics");
+
+    let response = run_diff(&old, &new, DiffMetric::Complexity).expect("diff should succeed");
+    assert_eq!(response.total_count, 1, "Unchanged helper must be omitted");
+    assert_eq!(
+        response.deltas[0],
+        MetricDelta {
+            fqn: "/test/file.rs::test_func".to_string(),
+            old: 3,
+            new: 7,
+            delta: 4,
+        }
+    );
+}
+
+#[test]
+fn test_symbol_diff_reports_added_removed_and_moved() {
+    let (_old_file, old) = create_test_db();
+    let (_new_file, new) = create_test_db();
+
+    // TestStruct removed, helper shifted down 10 lines, parse_args added;
+    // test_func is unchanged and must not appear.
+    new.execute("DELETE FROM graph_entities WHERE id = 11", [])
+        .expect("failed to remove TestStruct");
+    new.execute(
+        "UPDATE graph_entities SET data = json_set(data, '$.byte_start', 700, '$.byte_end', 800, '$.start_line', 35, '$.end_line', 40) WHERE id = 12",
+        [],
+    )
+    .expect("failed to move helper");
+    new.execute(
+        "INSERT INTO graph_entities (id, kind, data) VALUES
+            (13, 'Symbol', '{\"name\":\"parse_args\",\"kind\":\"Function\",\"fqn\":\"module::parse_args\",\"symbol_id\":\"sym4\",\"byte_start\":900,\"byte_end\":950,\"start_line\":45,\"start_col\":0,\"end_line\":48,\"end_col\":1}')",
+        [],
+    )
+    .expect("failed to insert parse_args");
+    new.execute(
+        "INSERT INTO graph_edges (from_id, to_id, edge_type) VALUES (1, 13, 'DEFINES')",
+        [],
+    )
+    .expect("failed to insert DEFINES edge");
+
+    let diff = run_symbol_diff(&old, &new).expect("symbol diff should succeed");
+
+    assert_eq!(diff.added.len(), 1);
+    assert_eq!(diff.added[0].name, "parse_args");
+    assert_eq!(diff.added[0].symbol_id.as_deref(), Some("sym4"));
+    assert_eq!(diff.added[0].span.file_path, "/test/file.rs");
+
+    assert_eq!(diff.removed.len(), 1);
+    assert_eq!(diff.removed[0].name, "TestStruct");
+
+    assert_eq!(diff.moved.len(), 1);
+    let moved = &diff.moved[0];
+    assert_eq!(moved.symbol.name, "helper");
+    assert_eq!(
+        (moved.old_span.start_line, moved.old_span.byte_start),
+        (25, 500)
+    );
+    assert_eq!(
+        (moved.new_span.start_line, moved.new_span.byte_start),
+        (35, 700)
+    );
+
+    let json = serde_json::to_value(&diff).unwrap();
+    let keys: Vec<&str> = json
+        .as_object()
+        .unwrap()
+        .keys()
+        .map(|k| k.as_str())
+        .collect();
+    assert_eq!(keys, vec!["added", "moved", "removed"]);
+    assert_eq!(json["moved"][0]["symbol"]["name"], "helper");
+    assert_eq!(json["moved"][0]["old_span"]["start_line"], 25);
+    assert_eq!(json["moved"][0]["new_span"]["start_line"], 35);
+}
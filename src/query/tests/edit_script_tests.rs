@@ -0,0 +1,72 @@
+use super::*;
+
+#[test]
+fn test_rename_edits_cover_definition_and_each_reference() {
+    let (db_file, conn) = create_test_db();
+    let source_dir = tempfile::tempdir().expect("failed to create source dir");
+    let source_path = source_dir.path().join("lib.rs");
+    std::fs::write(
+        &source_path,
+        "fn assist_me() {}\nfn main() { assist_me(); assist_me(); }\n",
+    )
+    .expect("failed to write source");
+    let source = source_path.to_string_lossy().to_string();
+
+    conn.execute(
+        "INSERT INTO graph_entities (id, kind, data) VALUES
+            (2, 'File', json_object('path', ?1)),
+            (13, 'Symbol', '{\"name\":\"assist_me\",\"kind\":\"Function\",\"fqn\":\"module::assist_me\",\"symbol_id\":\"sym13\",\"byte_start\":0,\"byte_end\":17,\"start_line\":1,\"start_col\":0,\"end_line\":1,\"end_col\":17}'),
+            (20, 'Reference', json_object('file', ?1, 'byte_start', 30, 'byte_end', 39, 'start_line', 2, 'start_col', 12, 'end_line', 2, 'end_col', 21)),
+            (21, 'Reference', json_object('file', ?1, 'byte_start', 43, 'byte_end', 52, 'start_line', 2, 'start_col', 25, 'end_line', 2, 'end_col', 34))",
+        [&source],
+    )
+    .expect("failed to insert entities");
+    conn.execute(
+        "INSERT INTO graph_edges (from_id, to_id, edge_type) VALUES
+            (2, 13, 'DEFINES'), (20, 13, 'REFERENCES'), (21, 13, 'REFERENCES')",
+        [],
+    )
+    .expect("failed to insert edges");
+
+    let options = SearchOptions {
+        db_path: db_file.path(),
+        query: "assist_me",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: None,
+        limit: 1,
+        use_regex: false,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions::default(),
+        snippet: SnippetOptions::default(),
+        fqn: FqnOptions::default(),
+        include_score: false,
+        sort_by: SortMode::default(),
+        metrics: MetricsOptions::default(),
+        ast: AstOptions::default(),
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
+    };
+    let (response, _, _) = search_symbols(options).expect("search_symbols should succeed");
+    let symbol = &response.results[0];
+    assert_eq!(symbol.span.file_path, source);
+
+    let edits = rename_edits(&conn, symbol, "help_out").expect("rename_edits should succeed");
+
+    let ranges: Vec<(u64, u64)> = edits.iter().map(|e| (e.byte_start, e.byte_end)).collect();
+    assert_eq!(ranges, vec![(3, 12), (30, 39), (43, 52)]);
+    assert!(edits
+        .iter()
+        .all(|e| e.file == source && e.replacement == "help_out"));
+}
@@ -0,0 +1,36 @@
+use super::*;
+
+#[test]
+fn test_folded_call_stacks_weight_paths_by_call_sites() {
+    let (_db_file, conn) = create_test_db();
+    conn.execute(
+        "INSERT INTO graph_entities (id, kind, data) VALUES
+            (20, 'Call', '{\"file\":\"/test/file.rs\",\"caller\":\"main\",\"callee\":\"parse\"}'),
+            (21, 'Call', '{\"file\":\"/test/file.rs\",\"caller\":\"main\",\"callee\":\"parse\"}'),
+            (22, 'Call', '{\"file\":\"/test/file.rs\",\"caller\":\"main\",\"callee\":\"log\"}'),
+            (23, 'Call', '{\"file\":\"/test/file.rs\",\"caller\":\"parse\",\"callee\":\"lex\"}'),
+            (24, 'Call', '{\"file\":\"/test/file.rs\",\"caller\":\"lex\",\"callee\":\"parse\"}'),
+            (25, 'Call', '{\"file\":\"/test/other.rs\",\"caller\":\"unrelated\",\"callee\":\"log\"}')",
+        [],
+    )
+    .expect("failed to insert calls");
+
+    let calls = call_site_counts(&conn, None).expect("call_site_counts should succeed");
+    let folded = folded_call_stacks(&calls, "main", MAX_FOLDED_DEPTH);
+
+    // Two call sites for main -> parse double that path; lex -> parse recursion
+    // is cut at the first repeated frame.
+    assert_eq!(
+        folded,
+        vec![
+            ("main;log".to_string(), 1),
+            ("main;parse;lex".to_string(), 2),
+        ]
+    );
+
+    let shallow = folded_call_stacks(&calls, "main", 2);
+    assert_eq!(
+        shallow,
+        vec![("main;log".to_string(), 1), ("main;parse".to_string(), 2)]
+    );
+}
@@ -0,0 +1,40 @@
+use super::*;
+use std::path::Path;
+
+#[test]
+fn test_resolved_imports_groups_external_targets_by_module() {
+    let (_db_file, conn) = create_test_db();
+    conn.execute(
+        "INSERT INTO graph_entities (id, kind, data) VALUES
+            (2, 'File', '{\"path\":\"/lib/net/http.rs\"}'),
+            (3, 'File', '{\"path\":\"/lib/io.rs\"}'),
+            (13, 'Symbol', '{\"name\":\"get\",\"fqn\":\"net::http::get\",\"kind\":\"Function\"}'),
+            (14, 'Symbol', '{\"name\":\"read\",\"fqn\":\"io::read\",\"kind\":\"Function\"}'),
+            (20, 'Reference', '{\"file\":\"/test/file.rs\"}'),
+            (21, 'Reference', '{\"file\":\"/test/file.rs\"}'),
+            (22, 'Reference', '{\"file\":\"/test/file.rs\"}'),
+            (23, 'Reference', '{\"file\":\"/test/file.rs\"}'),
+            (24, 'Reference', '{\"file\":\"/lib/io.rs\"}')",
+        [],
+    )
+    .expect("failed to insert entities");
+    conn.execute(
+        "INSERT INTO graph_edges (from_id, to_id, edge_type) VALUES
+            (2, 13, 'DEFINES'), (3, 14, 'DEFINES'),
+            (20, 13, 'REFERENCES'), (21, 14, 'REFERENCES'), (22, 13, 'REFERENCES'),
+            (23, 12, 'REFERENCES'), (24, 13, 'REFERENCES')",
+        [],
+    )
+    .expect("failed to insert edges");
+
+    let imports =
+        resolved_imports(&conn, Path::new("/test/")).expect("resolved_imports should succeed");
+
+    // The repeated reference to `get` is listed once, and `helper` (defined
+    // inside /test/) is not an external dependency.
+    let expected: Vec<(String, Vec<String>)> = vec![
+        ("io".to_string(), vec!["read".to_string()]),
+        ("net::http".to_string(), vec!["get".to_string()]),
+    ];
+    assert_eq!(imports.into_iter().collect::<Vec<_>>(), expected);
+}
@@ -80,9 +80,12 @@ fn test_metrics_filter_by_min_complexity() {
         db_path,
         query: "complexity",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -93,7 +96,14 @@ fn test_metrics_filter_by_min_complexity() {
             min_complexity: Some(10),
             max_complexity: None,
             min_fan_in: None,
+            max_fan_in: None,
             min_fan_out: None,
+            max_fan_out: None,
+            min_loc: None,
+            max_loc: None,
+            weight_by_calls: false,
+            boost_fan_in: false,
+            with_fan_in: false,
         },
         ast: AstOptions::default(),
         depth: DepthOptions::default(),
@@ -103,6 +113,10 @@ fn test_metrics_filter_by_min_complexity() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, partial, _) = search_symbols(options).expect("search_symbols should succeed");
@@ -137,9 +151,12 @@ fn test_metrics_filter_by_max_complexity() {
         db_path,
         query: "complexity",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -150,7 +167,14 @@ fn test_metrics_filter_by_max_complexity() {
             min_complexity: None,
             max_complexity: Some(10),
             min_fan_in: None,
+            max_fan_in: None,
             min_fan_out: None,
+            max_fan_out: None,
+            min_loc: None,
+            max_loc: None,
+            weight_by_calls: false,
+            boost_fan_in: false,
+            with_fan_in: false,
         },
         ast: AstOptions::default(),
         depth: DepthOptions::default(),
@@ -160,6 +184,10 @@ fn test_metrics_filter_by_max_complexity() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, partial, _) = search_symbols(options).expect("search_symbols should succeed");
@@ -181,9 +209,12 @@ fn test_metrics_filter_combined_min_max_complexity() {
         db_path,
         query: "complexity",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -194,7 +225,14 @@ fn test_metrics_filter_combined_min_max_complexity() {
             min_complexity: Some(10),
             max_complexity: Some(20),
             min_fan_in: None,
+            max_fan_in: None,
             min_fan_out: None,
+            max_fan_out: None,
+            min_loc: None,
+            max_loc: None,
+            weight_by_calls: false,
+            boost_fan_in: false,
+            with_fan_in: false,
         },
         ast: AstOptions::default(),
         depth: DepthOptions::default(),
@@ -204,6 +242,10 @@ fn test_metrics_filter_combined_min_max_complexity() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, partial, _) = search_symbols(options).expect("search_symbols should succeed");
@@ -225,9 +267,12 @@ fn test_metrics_filter_by_min_fan_in() {
         db_path,
         query: "complexity",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -238,7 +283,14 @@ fn test_metrics_filter_by_min_fan_in() {
             min_complexity: None,
             max_complexity: None,
             min_fan_in: Some(8),
+            max_fan_in: None,
             min_fan_out: None,
+            max_fan_out: None,
+            min_loc: None,
+            max_loc: None,
+            weight_by_calls: false,
+            boost_fan_in: false,
+            with_fan_in: false,
         },
         ast: AstOptions::default(),
         depth: DepthOptions::default(),
@@ -248,6 +300,10 @@ fn test_metrics_filter_by_min_fan_in() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, partial, _) = search_symbols(options).expect("search_symbols should succeed");
@@ -274,9 +330,12 @@ fn test_metrics_filter_by_min_fan_out() {
         db_path,
         query: "complexity",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -287,7 +346,14 @@ fn test_metrics_filter_by_min_fan_out() {
             min_complexity: None,
             max_complexity: None,
             min_fan_in: None,
+            max_fan_in: None,
             min_fan_out: Some(10),
+            max_fan_out: None,
+            min_loc: None,
+            max_loc: None,
+            weight_by_calls: false,
+            boost_fan_in: false,
+            with_fan_in: false,
         },
         ast: AstOptions::default(),
         depth: DepthOptions::default(),
@@ -297,6 +363,10 @@ fn test_metrics_filter_by_min_fan_out() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, partial, _) = search_symbols(options).expect("search_symbols should succeed");
@@ -323,9 +393,12 @@ fn test_metrics_sort_by_fan_in() {
         db_path,
         query: "complexity",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -341,6 +414,10 @@ fn test_metrics_sort_by_fan_in() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, partial, _) = search_symbols(options).expect("search_symbols should succeed");
@@ -373,9 +450,12 @@ fn test_metrics_sort_by_fan_out() {
         db_path,
         query: "complexity",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -391,6 +471,10 @@ fn test_metrics_sort_by_fan_out() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, partial, _) = search_symbols(options).expect("search_symbols should succeed");
@@ -423,9 +507,12 @@ fn test_metrics_sort_by_complexity() {
         db_path,
         query: "complexity",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -441,6 +528,10 @@ fn test_metrics_sort_by_complexity() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, partial, _) = search_symbols(options).expect("search_symbols should succeed");
@@ -473,9 +564,12 @@ fn test_metrics_fields_populated() {
         db_path,
         query: "low_complexity",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -491,6 +585,10 @@ fn test_metrics_fields_populated() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, partial, _) = search_symbols(options).expect("search_symbols should succeed");
@@ -584,9 +682,12 @@ fn test_metrics_null_handling() {
         db_path,
         query: "",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -602,6 +703,10 @@ fn test_metrics_null_handling() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, _partial, _) = search_symbols(options).expect("search_symbols should succeed");
@@ -642,9 +747,12 @@ fn test_metrics_null_handling() {
         db_path,
         query: "",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -653,6 +761,7 @@ fn test_metrics_null_handling() {
         sort_by: SortMode::default(),
         metrics: MetricsOptions {
             min_fan_in: Some(5),
+            max_fan_in: None,
             ..Default::default()
         },
         ast: AstOptions::default(),
@@ -663,6 +772,10 @@ fn test_metrics_null_handling() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response_filter, _, _) =
@@ -674,3 +787,159 @@ fn test_metrics_null_handling() {
     );
     assert_eq!(response_filter.results[0].name, "with_metrics");
 }
+
+#[test]
+fn test_weight_by_calls_ranks_by_total_call_sites() {
+    let (db_file, conn) = create_test_db();
+    // Distinct fan-in favours helper (3 callers) over test_func (1 caller)
+    conn.execute(
+        "INSERT INTO symbol_metrics (symbol_id, symbol_name, kind, file_path, fan_in) VALUES
+            (10, 'test_func', 'Function', '/test/file.rs', 1),
+            (12, 'helper', 'Function', '/test/file.rs', 3)",
+        [],
+    )
+    .expect("failed to insert metrics");
+    // test_func: 5 call sites from one caller, resolved by symbol id.
+    // helper: one call site from each of 3 callers, resolved by name only.
+    conn.execute(
+        "INSERT INTO graph_entities (id, kind, data) VALUES
+            (20, 'Call', '{\"caller\":\"main\",\"callee\":\"test_func\",\"callee_symbol_id\":\"sym1\"}'),
+            (21, 'Call', '{\"caller\":\"main\",\"callee\":\"test_func\",\"callee_symbol_id\":\"sym1\"}'),
+            (22, 'Call', '{\"caller\":\"main\",\"callee\":\"test_func\",\"callee_symbol_id\":\"sym1\"}'),
+            (23, 'Call', '{\"caller\":\"main\",\"callee\":\"test_func\",\"callee_symbol_id\":\"sym1\"}'),
+            (24, 'Call', '{\"caller\":\"main\",\"callee\":\"test_func\",\"callee_symbol_id\":\"sym1\"}'),
+            (25, 'Call', '{\"caller\":\"a\",\"callee\":\"helper\"}'),
+            (26, 'Call', '{\"caller\":\"b\",\"callee\":\"helper\"}'),
+            (27, 'Call', '{\"caller\":\"c\",\"callee\":\"helper\"}')",
+        [],
+    )
+    .expect("failed to insert calls");
+
+    let ranking = |weight_by_calls: bool| -> Vec<(String, Option<u64>)> {
+        let options = SearchOptions {
+            db_path: db_file.path(),
+            query: "",
+            path_filter: None,
+            exclude_paths: &[],
+            path_glob: false,
+            kind_filter: Some("Function"),
+            limit: 10,
+            use_regex: false,
+            ignore_case: false,
+            candidates: 100,
+            context: ContextOptions::default(),
+            snippet: SnippetOptions::default(),
+            fqn: FqnOptions::default(),
+            include_score: false,
+            sort_by: SortMode::FanIn,
+            metrics: MetricsOptions {
+                weight_by_calls,
+                ..Default::default()
+            },
+            ast: AstOptions::default(),
+            depth: DepthOptions::default(),
+            algorithm: AlgorithmOptions::default(),
+            symbol_id: None,
+            fqn_pattern: None,
+            exact_fqn: None,
+            language_filter: None,
+            coverage_filter: None,
+            file_set: None,
+            max_per_file: None,
+            public_only: false,
+            prefer: None,
+        };
+        let (response, _, _) = search_symbols(options).expect("search_symbols should succeed");
+        response
+            .results
+            .into_iter()
+            .map(|r| (r.name, r.call_count))
+            .collect()
+    };
+
+    assert_eq!(
+        ranking(false),
+        vec![
+            ("helper".to_string(), None),
+            ("test_func".to_string(), None)
+        ]
+    );
+    assert_eq!(
+        ranking(true),
+        vec![
+            ("test_func".to_string(), Some(5)),
+            ("helper".to_string(), Some(3))
+        ]
+    );
+}
+
+#[test]
+fn test_boost_fan_in_breaks_name_score_ties() {
+    let (db_file, conn) = create_test_db();
+    conn.execute(
+        "INSERT INTO symbol_metrics (symbol_id, symbol_name, kind, file_path, fan_in) VALUES
+            (10, 'test_func', 'Function', '/test/file.rs', 1),
+            (12, 'helper', 'Function', '/test/file.rs', 300)",
+        [],
+    )
+    .expect("failed to insert metrics");
+
+    let ranking = |boost_fan_in: bool| -> Vec<(String, Option<u64>)> {
+        let options = SearchOptions {
+            db_path: db_file.path(),
+            query: "",
+            path_filter: None,
+            exclude_paths: &[],
+            path_glob: false,
+            kind_filter: Some("Function"),
+            limit: 10,
+            use_regex: false,
+            ignore_case: false,
+            candidates: 100,
+            context: ContextOptions::default(),
+            snippet: SnippetOptions::default(),
+            fqn: FqnOptions::default(),
+            include_score: true,
+            sort_by: SortMode::Relevance,
+            metrics: MetricsOptions {
+                boost_fan_in,
+                ..Default::default()
+            },
+            ast: AstOptions::default(),
+            depth: DepthOptions::default(),
+            algorithm: AlgorithmOptions::default(),
+            symbol_id: None,
+            fqn_pattern: None,
+            exact_fqn: None,
+            language_filter: None,
+            coverage_filter: None,
+            file_set: None,
+            max_per_file: None,
+            public_only: false,
+            prefer: None,
+        };
+        let (response, _, _) = search_symbols(options).expect("search_symbols should succeed");
+        response
+            .results
+            .into_iter()
+            .map(|r| (r.name, r.score))
+            .collect()
+    };
+
+    // Equal name scores fall back to source position
+    assert_eq!(
+        ranking(false),
+        vec![
+            ("test_func".to_string(), Some(80)),
+            ("helper".to_string(), Some(80))
+        ]
+    );
+    // The bonus is capped, so fan-in 300 adds only FAN_IN_BOOST_CAP (20)
+    assert_eq!(
+        ranking(true),
+        vec![
+            ("helper".to_string(), Some(100)),
+            ("test_func".to_string(), Some(81))
+        ]
+    );
+}
@@ -1,17 +1,36 @@
 use super::builder::{build_call_query, build_reference_query, build_search_query};
-use super::util::{like_pattern, like_prefix, load_file, normalize_kind_label, score_match};
+use super::util::{
+    like_glob, like_pattern, like_prefix, load_file, normalize_fqn, normalize_kind_label,
+    number_snippet_lines, read_with_timeout, score_match, score_match_case, sort_by_file_mtime,
+    SourceCache,
+};
 use super::*;
 use crate::algorithm::AlgorithmOptions;
 use crate::SortMode;
 use rusqlite::Connection;
 
+mod annotate_tests;
+mod cache_tests;
+mod capabilities_tests;
+mod card_tests;
 mod chunk_tests;
+mod complete_tests;
+mod dead_code_tests;
+mod diff_tests;
+mod edit_script_tests;
+mod flamegraph_tests;
+mod imports_tests;
 mod metrics_tests;
+mod multi_db_tests;
+mod navigate_tests;
 mod query_builder_tests;
 mod scoring_tests;
 mod search_calls_tests;
 mod search_references_tests;
 mod search_symbols_tests;
+mod slice_tests;
+mod stats_tests;
+mod supernodes_tests;
 mod symbol_id_tests;
 mod util_tests;
 
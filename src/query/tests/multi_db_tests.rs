@@ -0,0 +1,152 @@
+use super::*;
+use crate::error::LlmError;
+use crate::output::SearchResponse;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+fn corrupt_db() -> tempfile::NamedTempFile {
+    let mut file = tempfile::NamedTempFile::new().expect("failed to create temp file");
+    file.write_all(b"this is definitely not a sqlite database file, just garbage bytes")
+        .expect("failed to write garbage bytes");
+    file
+}
+
+fn options_for(db_path: &Path) -> SearchOptions<'_> {
+    SearchOptions {
+        db_path,
+        query: "test_func",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: None,
+        limit: 10,
+        use_regex: false,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions::default(),
+        snippet: SnippetOptions::default(),
+        fqn: FqnOptions::default(),
+        include_score: true,
+        sort_by: SortMode::default(),
+        metrics: MetricsOptions::default(),
+        ast: AstOptions::default(),
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
+    }
+}
+
+fn search_db(db_path: &Path) -> Result<(SearchResponse, bool), LlmError> {
+    search_symbols(options_for(db_path)).map(|(response, partial, _)| (response, partial))
+}
+
+#[test]
+fn test_search_symbols_across_skips_corrupt_database() {
+    let (valid_db, _conn) = create_test_db();
+    let corrupt = corrupt_db();
+    let databases: Vec<(PathBuf, ())> = vec![
+        (corrupt.path().to_path_buf(), ()),
+        (valid_db.path().to_path_buf(), ()),
+    ];
+    let merge = options_for(valid_db.path());
+
+    let (response, _) = search_symbols_across(&databases, true, &merge, |path, _| search_db(path))
+        .expect("valid database results should survive");
+    assert_eq!(response.results.len(), 1);
+    assert_eq!(response.results[0].name, "test_func");
+    assert_eq!(
+        response.results[0].source_db.as_deref(),
+        Some(valid_db.path().display().to_string().as_str())
+    );
+
+    let err = search_symbols_across(&databases, false, &merge, |path, _| search_db(path))
+        .expect_err("fail-fast should surface the corrupt database");
+    assert!(err.is_unreadable_database());
+}
+
+#[test]
+fn test_open_databases_fails_when_none_readable() {
+    let paths = vec![PathBuf::from("/nonexistent/a.db")];
+    let err = open_databases(&paths, true, |_| {
+        Err::<(), _>(LlmError::DatabaseCorrupted {
+            reason: "bad header".to_string(),
+        })
+    })
+    .expect_err("no readable database should be an error");
+    assert!(matches!(err, LlmError::DatabaseCorrupted { .. }));
+}
+
+#[test]
+fn test_attach_callers_across_reads_each_results_own_database() {
+    let (db_a, conn_a) = create_test_db();
+    let (db_b, conn_b) = create_test_db();
+    for (conn, caller) in [(&conn_a, "caller_a"), (&conn_b, "caller_b")] {
+        conn.execute(
+            "INSERT INTO graph_entities (id, kind, data) VALUES
+                (20, 'Call', json_object('caller', ?1, 'callee', 'test_func', 'callee_symbol_id', 'sym1'))",
+            [caller],
+        )
+        .expect("failed to insert call");
+    }
+    let databases: Vec<(PathBuf, ())> = vec![
+        (db_a.path().to_path_buf(), ()),
+        (db_b.path().to_path_buf(), ()),
+    ];
+    let merge = options_for(db_a.path());
+
+    let (mut response, _) = search_symbols_across(&databases, false, &merge, |path, _| search_db(path))
+        .expect("both databases should be searched");
+    attach_callers_across(&databases, &mut response.results).expect("callers should attach");
+
+    assert_eq!(response.results.len(), 2);
+    for result in &response.results {
+        let expected = if result.source_db == Some(db_a.path().display().to_string()) {
+            "caller_a"
+        } else {
+            "caller_b"
+        };
+        let callers = result.callers.as_ref().expect("callers attached");
+        let names: Vec<&str> = callers.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec![expected]);
+    }
+}
+
+#[test]
+fn test_search_symbols_across_sorts_merged_results_and_applies_limit_once() {
+    let (db_a, conn_a) = create_test_db();
+    let (db_b, _conn_b) = create_test_db();
+    // The second database holds the exact match, so relevance must lift it first
+    conn_a
+        .execute(
+            "UPDATE graph_entities SET data = json_set(data, '$.name', 'test_func_helper') WHERE id = 10",
+            [],
+        )
+        .expect("failed to rename symbol");
+    let databases: Vec<(PathBuf, ())> = vec![
+        (db_a.path().to_path_buf(), ()),
+        (db_b.path().to_path_buf(), ()),
+    ];
+    let merge = SearchOptions {
+        limit: 1,
+        ..options_for(db_a.path())
+    };
+
+    let (response, _) = search_symbols_across(&databases, false, &merge, |path, _| search_db(path))
+        .expect("both databases should be searched");
+
+    assert_eq!(response.total_count, 2);
+    assert_eq!(response.results.len(), 1);
+    assert_eq!(response.results[0].name, "test_func");
+    assert_eq!(
+        response.results[0].source_db.as_deref(),
+        Some(db_b.path().display().to_string().as_str())
+    );
+}
@@ -0,0 +1,52 @@
+use crate::query::navigate::{build_caller_tree, NavSymbol};
+use std::collections::HashMap;
+
+fn nav(id: i64, name: &str) -> NavSymbol {
+    NavSymbol {
+        id,
+        name: name.to_string(),
+        kind: "Function".to_string(),
+        file: Some("src/lib.rs".to_string()),
+        line: id as usize,
+    }
+}
+
+#[test]
+fn test_build_caller_tree_two_levels_with_cycle() {
+    // main -> handle -> parse, and parse -> handle closes a cycle
+    let callers: HashMap<i64, Vec<NavSymbol>> = HashMap::from([
+        (3, vec![nav(2, "handle")]),
+        (2, vec![nav(1, "main"), nav(3, "parse")]),
+        (1, vec![]),
+    ]);
+
+    let tree = build_caller_tree(nav(3, "parse"), 3, &mut |id| {
+        Ok(callers.get(&id).cloned().unwrap_or_default())
+    })
+    .expect("tree should build");
+
+    let json = serde_json::to_value(&tree).unwrap();
+    assert_eq!(json["symbol"]["name"], "parse");
+    let handle = &json["callers"][0];
+    assert_eq!(handle["symbol"]["name"], "handle");
+    assert_eq!(handle["callers"][0]["symbol"]["name"], "main");
+    assert_eq!(handle["callers"][0]["callers"], serde_json::json!([]));
+    assert_eq!(handle["callers"][1]["symbol"]["name"], "parse");
+    assert_eq!(handle["callers"][1]["cycle"], true);
+    assert!(handle.get("cycle").is_none());
+}
+
+#[test]
+fn test_build_caller_tree_respects_depth() {
+    let tree = build_caller_tree(nav(3, "parse"), 1, &mut |id| {
+        Ok(if id == 3 {
+            vec![nav(2, "handle")]
+        } else {
+            vec![nav(1, "main")]
+        })
+    })
+    .expect("tree should build");
+
+    assert_eq!(tree.callers.len(), 1);
+    assert!(tree.callers[0].callers.is_empty());
+}
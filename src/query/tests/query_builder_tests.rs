@@ -10,6 +10,9 @@ fn test_build_search_query_basic() {
     let (sql, params, _strategy) = build_search_query(
         "test",
         None,
+        false,
+        &[],
+        None,
         None,
         None,
         false,
@@ -21,6 +24,8 @@ fn test_build_search_query_basic() {
         None,
         None,
         false,
+        false,
+        true,
         &[],
         None,
         None,
@@ -47,6 +52,9 @@ fn test_build_search_query_with_fts5() {
     let (sql, params, _strategy) = build_search_query(
         "Mutex RwLock",
         None,
+        false,
+        &[],
+        None,
         None,
         None,
         false,
@@ -58,6 +66,8 @@ fn test_build_search_query_with_fts5() {
         None,
         None,
         false,
+        false,
+        true,
         &[],
         None,
         None,
@@ -105,6 +115,9 @@ fn test_build_search_query_with_kind_filter() {
     let (sql, params, _strategy) = build_search_query(
         "test",
         None,
+        false,
+        &[],
+        None,
         Some("Function"),
         None,
         false,
@@ -116,6 +129,8 @@ fn test_build_search_query_with_kind_filter() {
         None,
         None,
         false,
+        false,
+        true,
         &[],
         None,
         None,
@@ -132,12 +147,52 @@ fn test_build_search_query_with_kind_filter() {
     assert_eq!(count_params(&sql), 6);
 }
 
+#[test]
+fn test_build_search_query_with_multiple_kind_filters() {
+    let (sql, params, _strategy) = build_search_query(
+        "test",
+        None,
+        false,
+        &[],
+        None,
+        Some("Function, Struct"),
+        None,
+        false,
+        false,
+        100,
+        MetricsOptions::default(),
+        SortMode::default(),
+        None,
+        None,
+        None,
+        false,
+        false,
+        true,
+        &[],
+        None,
+        None,
+        None,
+        None,
+        None,
+        false,
+        None,
+        false,
+    );
+
+    assert!(sql.contains("s.kind_normalized IN (?,?) OR s.kind IN (?,?)"));
+    assert_eq!(params.len(), 8);
+    assert_eq!(count_params(&sql), 8);
+}
+
 #[test]
 fn test_build_search_query_with_path_filter() {
     let path = PathBuf::from("/src/module");
     let (sql, params, _strategy) = build_search_query(
         "test",
         Some(&path),
+        false,
+        &[],
+        None,
         None,
         None,
         false,
@@ -149,6 +204,8 @@ fn test_build_search_query_with_path_filter() {
         None,
         None,
         false,
+        false,
+        true,
         &[],
         None,
         None,
@@ -165,11 +222,54 @@ fn test_build_search_query_with_path_filter() {
     assert_eq!(count_params(&sql), 5);
 }
 
+#[test]
+fn test_build_search_query_with_path_and_exclusions() {
+    let path = PathBuf::from("/src");
+    let excluded = [PathBuf::from("/src/tests"), PathBuf::from("/src/vendor_%")];
+    let (sql, params, _strategy) = build_search_query(
+        "test",
+        Some(&path),
+        false,
+        &excluded,
+        None,
+        None,
+        None,
+        false,
+        false,
+        100,
+        MetricsOptions::default(),
+        SortMode::default(),
+        None,
+        None,
+        None,
+        false,
+        false,
+        true,
+        &[],
+        None,
+        None,
+        None,
+        None,
+        None,
+        false,
+        None,
+        false,
+    );
+
+    assert!(sql.contains("f.file_path LIKE ? ESCAPE '\\'"));
+    assert_eq!(sql.matches("f.file_path NOT LIKE ? ESCAPE '\\'").count(), 2);
+    assert_eq!(params.len(), 7);
+    assert_eq!(count_params(&sql), 7);
+}
+
 #[test]
 fn test_build_search_query_regex_mode() {
     let (sql, params, _strategy) = build_search_query(
         "test.*",
         None,
+        false,
+        &[],
+        None,
         None,
         None,
         true,
@@ -181,6 +281,8 @@ fn test_build_search_query_regex_mode() {
         None,
         None,
         false,
+        false,
+        true,
         &[],
         None,
         None,
@@ -203,6 +305,9 @@ fn test_build_search_query_count_only() {
     let (sql, params, _strategy) = build_search_query(
         "test",
         None,
+        false,
+        &[],
+        None,
         None,
         None,
         false,
@@ -214,6 +319,8 @@ fn test_build_search_query_count_only() {
         None,
         None,
         false,
+        false,
+        true,
         &[],
         None,
         None,
@@ -236,6 +343,9 @@ fn test_build_search_query_regular_query() {
     let (sql, params, _strategy) = build_search_query(
         "test",
         None,
+        false,
+        &[],
+        None,
         None,
         None,
         false,
@@ -247,6 +357,8 @@ fn test_build_search_query_regular_query() {
         None,
         None,
         false,
+        false,
+        true,
         &[],
         None,
         None,
@@ -268,6 +380,9 @@ fn test_build_search_query_with_metrics_fan_in_sort() {
     let (sql, params, _strategy) = build_search_query(
         "test",
         None,
+        false,
+        &[],
+        None,
         None,
         None,
         false,
@@ -279,6 +394,8 @@ fn test_build_search_query_with_metrics_fan_in_sort() {
         None,
         None,
         false,
+        false,
+        true,
         &[],
         None,
         None,
@@ -299,6 +416,9 @@ fn test_build_search_query_with_metrics_fan_out_sort() {
     let (sql, params, _strategy) = build_search_query(
         "test",
         None,
+        false,
+        &[],
+        None,
         None,
         None,
         false,
@@ -310,6 +430,8 @@ fn test_build_search_query_with_metrics_fan_out_sort() {
         None,
         None,
         false,
+        false,
+        true,
         &[],
         None,
         None,
@@ -330,6 +452,9 @@ fn test_build_search_query_with_metrics_complexity_sort() {
     let (sql, params, _strategy) = build_search_query(
         "test",
         None,
+        false,
+        &[],
+        None,
         None,
         None,
         false,
@@ -341,6 +466,8 @@ fn test_build_search_query_with_metrics_complexity_sort() {
         None,
         None,
         false,
+        false,
+        true,
         &[],
         None,
         None,
@@ -365,6 +492,9 @@ fn test_build_search_query_with_min_complexity_filter() {
     let (sql, params, _strategy) = build_search_query(
         "test",
         None,
+        false,
+        &[],
+        None,
         None,
         None,
         false,
@@ -376,6 +506,8 @@ fn test_build_search_query_with_min_complexity_filter() {
         None,
         None,
         false,
+        false,
+        true,
         &[],
         None,
         None,
@@ -401,6 +533,9 @@ fn test_build_search_query_with_max_complexity_filter() {
     let (sql, params, _strategy) = build_search_query(
         "test",
         None,
+        false,
+        &[],
+        None,
         None,
         None,
         false,
@@ -412,6 +547,8 @@ fn test_build_search_query_with_max_complexity_filter() {
         None,
         None,
         false,
+        false,
+        true,
         &[],
         None,
         None,
@@ -437,6 +574,9 @@ fn test_build_search_query_with_min_fan_in_filter() {
     let (sql, params, _strategy) = build_search_query(
         "test",
         None,
+        false,
+        &[],
+        None,
         None,
         None,
         false,
@@ -448,6 +588,8 @@ fn test_build_search_query_with_min_fan_in_filter() {
         None,
         None,
         false,
+        false,
+        true,
         &[],
         None,
         None,
@@ -469,6 +611,9 @@ fn test_build_search_query_with_metrics_join() {
     let (sql, _, _) = build_search_query(
         "test",
         None,
+        false,
+        &[],
+        None,
         None,
         None,
         false,
@@ -480,6 +625,8 @@ fn test_build_search_query_with_metrics_join() {
         None,
         None,
         false,
+        false,
+        true,
         &[],
         None,
         None,
@@ -506,6 +653,9 @@ fn test_build_search_query_combined_filters() {
     let (sql, params, _strategy) = build_search_query(
         "test",
         None,
+        false,
+        &[],
+        None,
         None,
         None,
         false,
@@ -517,6 +667,8 @@ fn test_build_search_query_combined_filters() {
         None,
         None,
         false,
+        false,
+        true,
         &[],
         None,
         None,
@@ -535,9 +687,133 @@ fn test_build_search_query_combined_filters() {
     assert_eq!(count_params(&sql), 7);
 }
 
+#[test]
+fn test_build_search_query_with_metrics_loc_sort() {
+    let (sql, params, _strategy) = build_search_query(
+        "test",
+        None,
+        false,
+        &[],
+        None,
+        None,
+        None,
+        false,
+        false,
+        100,
+        MetricsOptions::default(),
+        SortMode::Loc,
+        None,
+        None,
+        None,
+        false,
+        false,
+        true,
+        &[],
+        None,
+        None,
+        None,
+        None,
+        None,
+        false,
+        None,
+        false,
+    );
+
+    assert!(sql.contains("COALESCE(sm.loc, 0) DESC"));
+    assert!(!params.is_empty());
+}
+
+#[test]
+fn test_build_search_query_with_loc_filters() {
+    let metrics = MetricsOptions {
+        min_loc: Some(50),
+        max_loc: Some(400),
+        ..Default::default()
+    };
+    let (sql, params, _strategy) = build_search_query(
+        "test",
+        None,
+        false,
+        &[],
+        None,
+        None,
+        None,
+        false,
+        false,
+        100,
+        metrics,
+        SortMode::default(),
+        None,
+        None,
+        None,
+        false,
+        false,
+        true,
+        &[],
+        None,
+        None,
+        None,
+        None,
+        None,
+        false,
+        None,
+        false,
+    );
+
+    assert!(sql.contains("sm.loc >= ?"));
+    assert!(sql.contains("sm.loc <= ?"));
+    assert_eq!(params.len(), 6);
+    assert_eq!(count_params(&sql), 6);
+}
+
+#[test]
+fn test_build_search_query_with_fan_in_and_fan_out_ranges() {
+    let metrics = MetricsOptions {
+        min_fan_in: Some(1),
+        max_fan_in: Some(3),
+        max_fan_out: Some(5),
+        ..Default::default()
+    };
+    let (sql, params, _strategy) = build_search_query(
+        "test",
+        None,
+        false,
+        &[],
+        None,
+        None,
+        None,
+        false,
+        false,
+        100,
+        metrics,
+        SortMode::default(),
+        None,
+        None,
+        None,
+        false,
+        false,
+        true,
+        &[],
+        None,
+        None,
+        None,
+        None,
+        None,
+        false,
+        None,
+        false,
+    );
+
+    assert!(sql.contains("sm.fan_in >= ?"));
+    assert!(sql.contains("sm.fan_in <= ?"));
+    assert!(sql.contains("sm.fan_out <= ?"));
+    assert_eq!(params.len(), 7);
+    assert_eq!(count_params(&sql), 7);
+}
+
 #[test]
 fn test_build_reference_query_basic() {
-    let (sql, params) = build_reference_query("test", None, false, false, 100);
+    let (sql, params) = build_reference_query("test", None, false, &[], None, false, false, 100);
 
     assert!(sql.contains("r.kind = 'Reference'"));
     assert!(sql.contains("LEFT JOIN graph_edges e"));
@@ -550,7 +826,8 @@ fn test_build_reference_query_basic() {
 #[test]
 fn test_build_reference_query_with_path_filter() {
     let path = PathBuf::from("/src/module");
-    let (sql, params) = build_reference_query("test", Some(&path), false, false, 100);
+    let (sql, params) =
+        build_reference_query("test", Some(&path), false, &[], None, false, false, 100);
 
     assert!(sql.contains("json_extract(r.data, '$.file') LIKE ? ESCAPE '\\'"));
     assert_eq!(params.len(), 3);
@@ -559,7 +836,7 @@ fn test_build_reference_query_with_path_filter() {
 
 #[test]
 fn test_build_reference_query_count_only() {
-    let (sql, params) = build_reference_query("test", None, false, true, 0);
+    let (sql, params) = build_reference_query("test", None, false, &[], None, false, true, 0);
 
     assert!(sql.starts_with("SELECT COUNT(*)"));
     assert!(!sql.contains("LIMIT"));
@@ -569,7 +846,7 @@ fn test_build_reference_query_count_only() {
 
 #[test]
 fn test_build_call_query_basic() {
-    let (sql, params) = build_call_query("test", None, false, false, 100);
+    let (sql, params) = build_call_query("test", None, false, &[], false, false, 100);
 
     assert!(sql.contains("c.kind = 'Call'"));
     assert!(sql.contains("json_extract(c.data, '$.caller')"));
@@ -584,16 +861,31 @@ fn test_build_call_query_basic() {
 #[test]
 fn test_build_call_query_with_path_filter() {
     let path = PathBuf::from("/src/module");
-    let (sql, params) = build_call_query("test", Some(&path), false, false, 100);
+    let (sql, params) = build_call_query("test", Some(&path), false, &[], false, false, 100);
 
     assert!(sql.contains("json_extract(c.data, '$.file') LIKE ? ESCAPE '\\'"));
     assert_eq!(params.len(), 4);
     assert_eq!(count_params(&sql), 4);
 }
 
+#[test]
+fn test_build_reference_and_call_queries_with_exclusions() {
+    let excluded = [PathBuf::from("/src/tests")];
+    let (sql, params) =
+        build_reference_query("test", None, false, &excluded, None, false, false, 100);
+    assert!(sql.contains("json_extract(r.data, '$.file') NOT LIKE ? ESCAPE '\\'"));
+    assert_eq!(params.len(), 3);
+    assert_eq!(count_params(&sql), 3);
+
+    let (sql, params) = build_call_query("test", None, false, &excluded, false, false, 100);
+    assert!(sql.contains("json_extract(c.data, '$.file') NOT LIKE ? ESCAPE '\\'"));
+    assert_eq!(params.len(), 4);
+    assert_eq!(count_params(&sql), 4);
+}
+
 #[test]
 fn test_build_call_query_count_only() {
-    let (sql, params) = build_call_query("test", None, false, true, 0);
+    let (sql, params) = build_call_query("test", None, false, &[], false, true, 0);
 
     assert!(sql.starts_with("SELECT COUNT(*)"));
     assert!(!sql.contains("LIMIT"));
@@ -659,12 +951,28 @@ fn test_like_prefix_with_backslash() {
     assert_eq!(result, "C:\\\\src\\\\path%");
 }
 
+#[test]
+fn test_like_glob_translates_wildcards() {
+    assert_eq!(like_glob("**/tests/**"), "%/tests/%");
+    assert_eq!(like_glob("*.rs"), "%.rs");
+    assert_eq!(like_glob("src/mod?.rs"), "src/mod_.rs");
+}
+
+#[test]
+fn test_like_glob_escapes_literal_wildcards() {
+    assert_eq!(like_glob("*/100%_done/*"), "%/100\\%\\_done/%");
+    assert_eq!(like_glob("C:\\src\\*"), "C:\\\\src\\\\%");
+}
+
 #[test]
 fn test_build_search_query_combined_filters_path_kind() {
     let path = PathBuf::from("/src/module");
     let (sql, params, _strategy) = build_search_query(
         "test",
         Some(&path),
+        false,
+        &[],
+        None,
         Some("Function"),
         None,
         false,
@@ -676,6 +984,8 @@ fn test_build_search_query_combined_filters_path_kind() {
         None,
         None,
         false,
+        false,
+        true,
         &[],
         None,
         None,
@@ -696,7 +1006,7 @@ fn test_build_search_query_combined_filters_path_kind() {
 
 #[test]
 fn test_build_reference_query_regex_mode() {
-    let (sql, params) = build_reference_query("test.*", None, true, false, 100);
+    let (sql, params) = build_reference_query("test.*", None, false, &[], None, true, false, 100);
 
     assert!(!sql.contains("LIKE ? ESCAPE '\\'"));
     assert!(sql.contains("LIMIT ?"));
@@ -706,10 +1016,98 @@ fn test_build_reference_query_regex_mode() {
 
 #[test]
 fn test_build_call_query_regex_mode() {
-    let (sql, params) = build_call_query("test.*", None, true, false, 100);
+    let (sql, params) = build_call_query("test.*", None, false, &[], true, false, 100);
 
     assert!(!sql.contains("LIKE ? ESCAPE '\\'"));
     assert!(sql.contains("LIMIT ?"));
     assert_eq!(params.len(), 1);
     assert_eq!(count_params(&sql), 1);
 }
+
+#[test]
+fn test_build_search_query_empty_query_has_no_name_predicate() {
+    let (sql, params, _strategy) = build_search_query(
+        "",
+        None,
+        false,
+        &[],
+        None,
+        None,
+        None,
+        false,
+        false,
+        100,
+        MetricsOptions::default(),
+        SortMode::default(),
+        None,
+        None,
+        None,
+        false,
+        false,
+        true,
+        &[],
+        None,
+        None,
+        None,
+        None,
+        None,
+        false,
+        None,
+        true,
+    );
+
+    assert!(!sql.contains("s.name LIKE"));
+    assert!(!sql.contains("symbol_fts"));
+    assert_eq!(params.len(), 1, "Only the LIMIT parameter remains");
+}
+
+#[test]
+fn test_build_search_query_counts_reference_fan_in_only_when_needed() {
+    let build = |metrics: MetricsOptions, sort_by: SortMode| {
+        build_search_query(
+            "test",
+            None,
+            false,
+            &[],
+            None,
+            None,
+            None,
+            false,
+            false,
+            100,
+            metrics,
+            sort_by,
+            None,
+            None,
+            None,
+            false,
+            false,
+            false,
+            &[],
+            None,
+            None,
+            None,
+            None,
+            None,
+            false,
+            None,
+            false,
+        )
+        .0
+    };
+
+    let plain = build(MetricsOptions::default(), SortMode::Relevance);
+    assert!(!plain.contains("REFERENCES"), "no edge scan without fan-in: {plain}");
+
+    let sorted = build(MetricsOptions::default(), SortMode::FanIn);
+    assert!(sorted.contains("COUNT(DISTINCT from_id) AS fan_in"));
+
+    let filtered = build(
+        MetricsOptions {
+            min_fan_in: Some(2),
+            ..Default::default()
+        },
+        SortMode::Relevance,
+    );
+    assert!(filtered.contains("COUNT(DISTINCT from_id) AS fan_in"));
+}
@@ -1,5 +1,5 @@
 use super::*;
-use regex::Regex;
+use regex::{Regex, RegexBuilder};
 
 #[test]
 fn test_score_match_empty_query() {
@@ -140,6 +140,19 @@ fn test_score_match_case_sensitive() {
     assert_eq!(score2, 0, "Different case should not match");
 }
 
+#[test]
+fn test_score_match_case_ignores_case_when_requested() {
+    assert_eq!(score_match_case("FOO", "foo", "", "", None, false), 0);
+    assert_eq!(score_match_case("FOO", "foo", "", "", None, true), 100);
+    assert_eq!(score_match_case("Par", "parse_args", "", "", None, true), 80);
+
+    let regex = RegexBuilder::new("^PARSE").case_insensitive(true).build().unwrap();
+    assert_eq!(
+        score_match_case("^PARSE", "parse_args", "", "", Some(&regex), true),
+        70
+    );
+}
+
 #[test]
 fn test_score_match_empty_name_field() {
     let score = score_match("foo", "", "", "", None);
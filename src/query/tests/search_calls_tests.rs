@@ -36,9 +36,12 @@ fn test_search_calls_basic() {
         db_path: _db_file.path(),
         query: "test_func",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -54,6 +57,10 @@ fn test_search_calls_basic() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, _partial) = search_calls(options).expect("search_calls should succeed");
@@ -75,9 +82,12 @@ fn test_search_calls_caller_match() {
         db_path: _db_file.path(),
         query: "main",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -93,6 +103,10 @@ fn test_search_calls_caller_match() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, _partial) = search_calls(options).expect("search_calls should succeed");
@@ -113,9 +127,12 @@ fn test_search_calls_empty_results() {
         db_path: _db_file.path(),
         query: "nonexistent",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -131,6 +148,10 @@ fn test_search_calls_empty_results() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, _partial) = search_calls(options).expect("search_calls should succeed");
@@ -147,9 +168,12 @@ fn test_search_calls_regex_mode() {
         db_path: _db_file.path(),
         query: "test.*",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: true,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -165,6 +189,10 @@ fn test_search_calls_regex_mode() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, _partial) = search_calls(options).expect("search_calls should succeed");
@@ -185,9 +213,12 @@ fn test_search_calls_regex_no_match() {
         db_path: _db_file.path(),
         query: "xyz.*",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: true,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -203,6 +234,10 @@ fn test_search_calls_regex_no_match() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, _partial) = search_calls(options).expect("search_calls should succeed");
@@ -219,9 +254,12 @@ fn test_search_calls_score_callee_match() {
         db_path: _db_file.path(),
         query: "test_func",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -237,6 +275,10 @@ fn test_search_calls_score_callee_match() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, _partial) = search_calls(options).expect("search_calls should succeed");
@@ -257,9 +299,12 @@ fn test_search_calls_score_caller_match() {
         db_path: _db_file.path(),
         query: "main",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -275,6 +320,10 @@ fn test_search_calls_score_caller_match() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, _partial) = search_calls(options).expect("search_calls should succeed");
@@ -295,9 +344,12 @@ fn test_search_calls_limit() {
         db_path: _db_file.path(),
         query: "test_func",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 1,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -313,6 +365,10 @@ fn test_search_calls_limit() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, _partial) = search_calls(options).expect("search_calls should succeed");
@@ -329,9 +385,12 @@ fn test_search_calls_total_count() {
         db_path: _db_file.path(),
         query: "test_func",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -347,6 +406,10 @@ fn test_search_calls_total_count() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, _partial) = search_calls(options).expect("search_calls should succeed");
@@ -363,9 +426,12 @@ fn test_search_calls_path_filter() {
         db_path: _db_file.path(),
         query: "test_func",
         path_filter: Some(&path),
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -381,6 +447,10 @@ fn test_search_calls_path_filter() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, _partial) = search_calls(options).expect("search_calls should succeed");
@@ -398,9 +468,12 @@ fn test_search_calls_include_score() {
         db_path: _db_file.path(),
         query: "test_func",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -416,6 +489,10 @@ fn test_search_calls_include_score() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, _partial) = search_calls(options).expect("search_calls should succeed");
@@ -434,9 +511,12 @@ fn test_search_calls_ordering() {
         db_path: _db_file.path(),
         query: "test_func",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -452,6 +532,10 @@ fn test_search_calls_ordering() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, _partial) = search_calls(options).expect("search_calls should succeed");
@@ -470,3 +554,122 @@ fn test_search_calls_ordering() {
         }
     }
 }
+
+#[test]
+fn test_call_matrix_counts_repeated_pairs() {
+    let (_db_file, conn) = create_test_db_with_calls();
+    conn.execute(
+        "INSERT INTO graph_entities (id, kind, data) VALUES
+            (13, 'Call', '{\"file\":\"/test/file.rs\",\"caller\":\"main\",\"callee\":\"test_func\",\"caller_symbol_id\":\"sym1\",\"callee_symbol_id\":\"sym2\",\"byte_start\":130,\"byte_end\":150,\"start_line\":12,\"start_col\":4,\"end_line\":12,\"end_col\":24}')",
+        [],
+    )
+    .expect("failed to insert repeated call");
+
+    let options = SearchOptions {
+        db_path: _db_file.path(),
+        query: "",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: None,
+        limit: 10,
+        use_regex: false,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions::default(),
+        snippet: SnippetOptions::default(),
+        fqn: FqnOptions::default(),
+        include_score: false,
+        sort_by: SortMode::default(),
+        metrics: MetricsOptions::default(),
+        ast: AstOptions::default(),
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
+    };
+
+    let (response, _partial) = search_calls(options).expect("search_calls should succeed");
+    let matrix = serde_json::to_value(response.call_matrix()).unwrap();
+    assert_eq!(
+        matrix,
+        serde_json::json!({
+            "main": {"helper": 1, "test_func": 2},
+            "process": {"test_func": 1},
+        })
+    );
+}
+
+#[test]
+fn test_resolve_callees_attaches_callee_definition_span() {
+    let (db_file, conn) = create_test_db();
+    conn.execute(
+        "INSERT INTO graph_entities (id, kind, data) VALUES
+            (20, 'Call', '{\"file\":\"/test/main.rs\",\"caller\":\"main\",\"callee\":\"helper\",\"caller_symbol_id\":\"sym9\",\"callee_symbol_id\":\"sym3\",\"byte_start\":40,\"byte_end\":48,\"start_line\":3,\"start_col\":4,\"end_line\":3,\"end_col\":12}'),
+            (21, 'Call', '{\"file\":\"/test/main.rs\",\"caller\":\"main\",\"callee\":\"helper_ext\",\"byte_start\":60,\"byte_end\":72,\"start_line\":4,\"start_col\":4,\"end_line\":4,\"end_col\":16}')",
+        [],
+    )
+    .expect("failed to insert calls");
+
+    let options = SearchOptions {
+        db_path: db_file.path(),
+        query: "helper",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: None,
+        limit: 10,
+        use_regex: false,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions::default(),
+        snippet: SnippetOptions::default(),
+        fqn: FqnOptions::default(),
+        include_score: false,
+        sort_by: SortMode::Position,
+        metrics: MetricsOptions::default(),
+        ast: AstOptions::default(),
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
+    };
+    let (mut response, _partial) = search_calls(options).expect("search_calls should succeed");
+    resolve_callee_definitions(&conn, &mut response.results).expect("resolution should succeed");
+
+    let resolved = response
+        .results
+        .iter()
+        .find(|call| call.callee == "helper")
+        .expect("call to helper");
+    assert_eq!(resolved.span.file_path, "/test/main.rs");
+    assert_eq!(resolved.span.start_line, 3);
+    let definition = resolved
+        .callee_definition
+        .as_ref()
+        .expect("callee definition resolved");
+    assert_eq!(definition.file_path, "/test/file.rs");
+    assert_eq!(definition.start_line, 25);
+    assert_eq!((definition.byte_start, definition.byte_end), (500, 600));
+
+    let unresolved = response
+        .results
+        .iter()
+        .find(|call| call.callee == "helper_ext")
+        .expect("call to helper_ext");
+    assert!(unresolved.callee_definition.is_none());
+}
@@ -110,9 +110,12 @@ fn test_search_references_basic() {
         db_path: db_file.path(),
         query: "test_func",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 100,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -128,6 +131,10 @@ fn test_search_references_basic() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (result, _partial) = search_references(options).expect("search_references should succeed");
@@ -148,9 +155,12 @@ fn test_search_references_empty_results() {
         db_path: db_file.path(),
         query: "nonexistent",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 100,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -166,6 +176,10 @@ fn test_search_references_empty_results() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (result, _partial) = search_references(options).expect("search_references should succeed");
@@ -184,9 +198,12 @@ fn test_search_references_prefix_match() {
         db_path: db_file.path(),
         query: "test",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 100,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -202,6 +219,10 @@ fn test_search_references_prefix_match() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (result, _partial) = search_references(options).expect("search_references should succeed");
@@ -221,9 +242,12 @@ fn test_search_references_regex_mode() {
         db_path: db_file.path(),
         query: "test.*",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 100,
         use_regex: true,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -239,6 +263,10 @@ fn test_search_references_regex_mode() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (result, _partial) = search_references(options).expect("search_references should succeed");
@@ -258,9 +286,12 @@ fn test_search_references_regex_no_match() {
         db_path: db_file.path(),
         query: "xyz.*",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 100,
         use_regex: true,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -276,6 +307,10 @@ fn test_search_references_regex_no_match() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (result, _partial) = search_references(options).expect("search_references should succeed");
@@ -294,9 +329,12 @@ fn test_search_references_score_exact_match() {
         db_path: db_file.path(),
         query: "test_func",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 100,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -312,6 +350,10 @@ fn test_search_references_score_exact_match() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (result, _partial) = search_references(options).expect("search_references should succeed");
@@ -331,9 +373,12 @@ fn test_search_references_limit() {
         db_path: db_file.path(),
         query: "test",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 1,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -349,6 +394,10 @@ fn test_search_references_limit() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (result, _partial) = search_references(options).expect("search_references should succeed");
@@ -367,9 +416,12 @@ fn test_search_references_total_count() {
         db_path: db_file.path(),
         query: "test_func",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 100,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -385,6 +437,10 @@ fn test_search_references_total_count() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (result, _partial) = search_references(options).expect("search_references should succeed");
@@ -400,9 +456,12 @@ fn test_search_references_path_filter() {
         db_path: db_file.path(),
         query: "test_func",
         path_filter: Some(&path_filter),
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 100,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -418,6 +477,10 @@ fn test_search_references_path_filter() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (result, _partial) = search_references(options).expect("search_references should succeed");
@@ -437,9 +500,12 @@ fn test_search_references_include_score() {
         db_path: db_file.path(),
         query: "test_func",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 100,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -455,6 +521,10 @@ fn test_search_references_include_score() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (result, _partial) = search_references(options).expect("search_references should succeed");
@@ -473,9 +543,12 @@ fn test_search_references_ordering() {
         db_path: db_file.path(),
         query: "test",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 100,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -491,6 +564,10 @@ fn test_search_references_ordering() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (result, _partial) = search_references(options).expect("search_references should succeed");
@@ -503,3 +580,322 @@ fn test_search_references_ordering() {
         );
     }
 }
+
+#[test]
+fn test_search_references_sort_by_name() {
+    let (db_file, _conn) = create_test_db_with_references();
+
+    let search = |sort_by| {
+        let options = SearchOptions {
+            db_path: db_file.path(),
+            query: "e",
+            path_filter: None,
+            exclude_paths: &[],
+            path_glob: false,
+            kind_filter: None,
+            limit: 100,
+            use_regex: false,
+            ignore_case: false,
+            candidates: 100,
+            context: ContextOptions::default(),
+            snippet: SnippetOptions::default(),
+            fqn: FqnOptions::default(),
+            include_score: false,
+            sort_by,
+            metrics: MetricsOptions::default(),
+            ast: AstOptions::default(),
+            depth: DepthOptions::default(),
+            algorithm: AlgorithmOptions::default(),
+            symbol_id: None,
+            fqn_pattern: None,
+            exact_fqn: None,
+            language_filter: None,
+            coverage_filter: None,
+            file_set: None,
+            max_per_file: None,
+            public_only: false,
+            prefer: None,
+        };
+        let (result, _partial) =
+            search_references(options).expect("search_references should succeed");
+        result
+            .results
+            .into_iter()
+            .map(|r| r.referenced_symbol)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        search(SortMode::Name),
+        vec!["helper", "test_func", "TestStruct"]
+    );
+    assert_eq!(
+        search(SortMode::NameDesc),
+        vec!["TestStruct", "test_func", "helper"]
+    );
+}
+
+#[test]
+fn test_search_references_ignore_case() {
+    let (db_file, _conn) = create_test_db_with_references();
+
+    let search = |query, use_regex, ignore_case| {
+        let options = SearchOptions {
+            db_path: db_file.path(),
+            query,
+            path_filter: None,
+            exclude_paths: &[],
+            path_glob: false,
+            kind_filter: None,
+            limit: 100,
+            use_regex,
+            ignore_case,
+            candidates: 100,
+            context: ContextOptions::default(),
+            snippet: SnippetOptions::default(),
+            fqn: FqnOptions::default(),
+            include_score: true,
+            sort_by: SortMode::Relevance,
+            metrics: MetricsOptions::default(),
+            ast: AstOptions::default(),
+            depth: DepthOptions::default(),
+            algorithm: AlgorithmOptions::default(),
+            symbol_id: None,
+            fqn_pattern: None,
+            exact_fqn: None,
+            language_filter: None,
+            coverage_filter: None,
+            file_set: None,
+            max_per_file: None,
+            public_only: false,
+            prefer: None,
+        };
+        let (result, _partial) =
+            search_references(options).expect("search_references should succeed");
+        result.results
+    };
+
+    assert!(search("TEST_FUNC", false, false).is_empty());
+    let results = search("TEST_FUNC", false, true);
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].referenced_symbol, "test_func");
+    assert_eq!(results[0].score, Some(100), "scoring should ignore case too");
+
+    assert!(search("^TEST", true, false).is_empty());
+    let results = search("^TEST", true, true);
+    let mut names: Vec<&str> = results
+        .iter()
+        .map(|r| r.referenced_symbol.as_str())
+        .collect();
+    names.sort_unstable();
+    assert_eq!(names, vec!["TestStruct", "test_func"]);
+}
+
+#[test]
+fn test_search_references_reference_kind_filter() {
+    let (db_file, conn) = create_test_db_with_references();
+    conn.execute(
+        "UPDATE graph_entities SET data = json_set(data, '$.kind', 'write') WHERE id = 10",
+        [],
+    )
+    .expect("failed to set write kind");
+    conn.execute(
+        "UPDATE graph_entities SET data = json_set(data, '$.kind', 'read') WHERE id IN (11, 12)",
+        [],
+    )
+    .expect("failed to set read kind");
+
+    let search = |kind_filter: Option<&str>| {
+        let options = SearchOptions {
+            db_path: db_file.path(),
+            query: "",
+            path_filter: None,
+            exclude_paths: &[],
+            path_glob: false,
+            kind_filter,
+            limit: 100,
+            use_regex: false,
+            ignore_case: false,
+            candidates: 100,
+            context: ContextOptions::default(),
+            snippet: SnippetOptions::default(),
+            fqn: FqnOptions::default(),
+            include_score: false,
+            sort_by: SortMode::Position,
+            metrics: MetricsOptions::default(),
+            ast: AstOptions::default(),
+            depth: DepthOptions::default(),
+            algorithm: AlgorithmOptions::default(),
+            symbol_id: None,
+            fqn_pattern: None,
+            exact_fqn: None,
+            language_filter: None,
+            coverage_filter: None,
+            file_set: None,
+            max_per_file: None,
+            public_only: false,
+            prefer: None,
+        };
+        let (response, _) = search_references(options).expect("search should succeed");
+        response
+            .results
+            .into_iter()
+            .map(|r| (r.referenced_symbol, r.reference_kind))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        search(Some("write")),
+        vec![("test_func".to_string(), Some("write".to_string()))]
+    );
+    assert_eq!(search(Some("read, write")).len(), 3);
+    assert_eq!(search(None).len(), 3);
+}
+
+#[test]
+fn test_resolve_defs_attaches_definition_span() {
+    let (db_file, conn) = create_test_db_with_references();
+    let symbol_data = json!({
+        "symbol_id": "sym1",
+        "name": "test_func",
+        "kind": "Function",
+        "kind_normalized": "function",
+        "byte_start": 300,
+        "byte_end": 420,
+        "start_line": 20,
+        "start_col": 0,
+        "end_line": 26,
+        "end_col": 1
+    })
+    .to_string();
+    conn.execute(
+        "UPDATE graph_entities SET data = ?1 WHERE id = 1",
+        [symbol_data],
+    )
+    .expect("failed to update symbol");
+    conn.execute(
+        "INSERT INTO graph_entities (id, kind, data) VALUES (2, 'File', '{\"path\":\"/test/defs.rs\"}')",
+        [],
+    )
+    .expect("failed to insert file");
+    conn.execute(
+        "INSERT INTO graph_edges (from_id, to_id, edge_type) VALUES (2, 1, 'DEFINES')",
+        [],
+    )
+    .expect("failed to insert DEFINES edge");
+
+    let options = SearchOptions {
+        db_path: db_file.path(),
+        query: "test_func|helper",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: None,
+        limit: 100,
+        use_regex: true,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions::default(),
+        snippet: SnippetOptions::default(),
+        fqn: FqnOptions::default(),
+        include_score: false,
+        sort_by: SortMode::Position,
+        metrics: MetricsOptions::default(),
+        ast: AstOptions::default(),
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
+    };
+    let (mut response, _partial) =
+        search_references(options).expect("search_references should succeed");
+    resolve_reference_definitions(&conn, &mut response.results).expect("resolution should succeed");
+
+    let resolved = response
+        .results
+        .iter()
+        .find(|r| r.referenced_symbol == "test_func")
+        .expect("reference to test_func");
+    assert_eq!(resolved.span.file_path, "/test/file.rs");
+    let definition = resolved.definition.as_ref().expect("definition resolved");
+    assert_eq!(definition.file_path, "/test/defs.rs");
+    assert_eq!((definition.start_line, definition.start_col), (20, 0));
+    assert_eq!((definition.byte_start, definition.byte_end), (300, 420));
+
+    // --compact flattens the resolved definition to a location string too
+    let mut json = serde_json::to_value(&response).expect("response should serialize");
+    crate::output::compact_spans(&mut json, false, false);
+    let compacted = json["results"]
+        .as_array()
+        .expect("results array")
+        .iter()
+        .find(|r| r["referenced_symbol"] == "test_func")
+        .expect("compacted reference to test_func");
+    assert_eq!(compacted["definition"], "/test/defs.rs:20:0");
+
+    let unresolved = response
+        .results
+        .iter()
+        .find(|r| r.referenced_symbol == "helper")
+        .expect("reference to helper");
+    assert!(unresolved.definition.is_none());
+}
+
+#[test]
+fn test_reference_escape_control_chars_neutralizes_snippet_and_name() {
+    let (db_file, _conn) = create_test_db_with_references();
+
+    let options = SearchOptions {
+        db_path: db_file.path(),
+        query: "test_func",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: None,
+        limit: 100,
+        use_regex: false,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions::default(),
+        snippet: SnippetOptions::default(),
+        fqn: FqnOptions::default(),
+        include_score: false,
+        sort_by: SortMode::default(),
+        metrics: MetricsOptions::default(),
+        ast: AstOptions::default(),
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
+    };
+    let (mut response, _) = search_references(options).expect("search_references should succeed");
+    let reference = &mut response.results[0];
+    reference.referenced_symbol = "test_func\x1b[2J".to_string();
+    reference.snippet = Some("call(\"\x1b]0;pwned\x07\");\nnext".to_string());
+
+    response.escape_control_chars();
+
+    let reference = &response.results[0];
+    assert!(!reference.referenced_symbol.contains('\x1b'));
+    let line = crate::output::vimgrep_line(
+        &reference.span,
+        reference.snippet.as_deref(),
+        &reference.referenced_symbol,
+    );
+    assert!(!line.contains('\x1b') && !line.contains('\x07'), "{line:?}");
+    assert!(line.contains("call("));
+}
@@ -9,9 +9,12 @@ fn test_search_symbols_basic() {
         db_path,
         query: "test_func",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -27,6 +30,10 @@ fn test_search_symbols_basic() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, partial, _) = search_symbols(options).expect("search_symbols should succeed");
@@ -47,9 +54,12 @@ fn test_search_symbols_empty_results() {
         db_path,
         query: "nonexistent",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -65,6 +75,10 @@ fn test_search_symbols_empty_results() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, partial, _) = search_symbols(options).expect("search_symbols should succeed");
@@ -81,9 +95,12 @@ fn test_search_symbols_prefix_match() {
         db_path,
         query: "test",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -99,6 +116,10 @@ fn test_search_symbols_prefix_match() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, partial, _) = search_symbols(options).expect("search_symbols should succeed");
@@ -119,9 +140,12 @@ fn test_search_symbols_contains_match() {
         db_path,
         query: "helper",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -137,6 +161,10 @@ fn test_search_symbols_contains_match() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, partial, _) = search_symbols(options).expect("search_symbols should succeed");
@@ -154,9 +182,12 @@ fn test_search_symbols_kind_filter() {
         db_path,
         query: "test",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: Some("Function"),
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -172,6 +203,10 @@ fn test_search_symbols_kind_filter() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, partial, _) = search_symbols(options).expect("search_symbols should succeed");
@@ -184,6 +219,108 @@ fn test_search_symbols_kind_filter() {
     );
 }
 
+#[test]
+fn test_search_symbols_multiple_kind_filters_match_every_kind() {
+    let (_db_file, _conn) = create_test_db();
+    let db_path = _db_file.path();
+
+    let options = SearchOptions {
+        db_path,
+        query: "test",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: Some("Function,Struct"),
+        limit: 10,
+        use_regex: false,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions::default(),
+        snippet: SnippetOptions::default(),
+        fqn: FqnOptions::default(),
+        include_score: false,
+        sort_by: SortMode::default(),
+        metrics: MetricsOptions::default(),
+        ast: AstOptions::default(),
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
+    };
+
+    let (response, _, _) = search_symbols(options).expect("search_symbols should succeed");
+    let mut names: Vec<&str> = response.results.iter().map(|r| r.name.as_str()).collect();
+    names.sort_unstable();
+    assert_eq!(names, vec!["TestStruct", "test_func"]);
+}
+
+#[test]
+fn test_count_symbols_ignores_limit_and_matches_regex_fallback() {
+    let (_db_file, conn) = create_test_db();
+    let db_path = _db_file.path();
+
+    let options = SearchOptions {
+        db_path,
+        query: "test",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: None,
+        limit: 1,
+        use_regex: false,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions::default(),
+        snippet: SnippetOptions::default(),
+        fqn: FqnOptions::default(),
+        include_score: false,
+        sort_by: SortMode::default(),
+        metrics: MetricsOptions::default(),
+        ast: AstOptions::default(),
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
+    };
+
+    let count = count_symbols_impl(&conn, db_path, &options).expect("count should succeed");
+    assert_eq!(count, (2, false));
+
+    let regex_options = SearchOptions {
+        query: "^test_",
+        use_regex: true,
+        ..options.clone()
+    };
+    let count =
+        count_symbols_impl(&conn, db_path, &regex_options).expect("regex count should succeed");
+    assert_eq!(count, (1, false));
+
+    // The fallback stops at --candidates and says so
+    let capped_options = SearchOptions {
+        query: ".",
+        use_regex: true,
+        candidates: 1,
+        ..options
+    };
+    let count =
+        count_symbols_impl(&conn, db_path, &capped_options).expect("capped count should succeed");
+    assert_eq!(count, (1, true));
+}
+
 #[test]
 fn test_search_symbols_limit() {
     let (_db_file, _conn) = create_test_db();
@@ -193,9 +330,12 @@ fn test_search_symbols_limit() {
         db_path,
         query: "test",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 1,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -211,6 +351,10 @@ fn test_search_symbols_limit() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, partial, _) = search_symbols(options).expect("search_symbols should succeed");
@@ -231,9 +375,12 @@ fn test_search_symbols_regex_mode() {
         db_path,
         query: "test.*",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: true,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -249,6 +396,10 @@ fn test_search_symbols_regex_mode() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, partial, _) = search_symbols(options).expect("search_symbols should succeed");
@@ -270,9 +421,12 @@ fn test_search_symbols_regex_no_match() {
         db_path,
         query: "xyz.*",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: true,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -288,6 +442,10 @@ fn test_search_symbols_regex_no_match() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, partial, _) = search_symbols(options).expect("search_symbols should succeed");
@@ -304,9 +462,12 @@ fn test_search_symbols_score_exact_match() {
         db_path,
         query: "test_func",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -322,6 +483,10 @@ fn test_search_symbols_score_exact_match() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, partial, _) = search_symbols(options).expect("search_symbols should succeed");
@@ -343,9 +508,12 @@ fn test_search_symbols_score_prefix_match() {
         db_path,
         query: "test",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -361,6 +529,10 @@ fn test_search_symbols_score_prefix_match() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, partial, _) = search_symbols(options).expect("search_symbols should succeed");
@@ -399,9 +571,12 @@ fn test_search_symbols_partial_result() {
         db_path,
         query: "test",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 1,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -417,6 +592,10 @@ fn test_search_symbols_partial_result() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, partial, _) = search_symbols(options).expect("search_symbols should succeed");
@@ -433,9 +612,12 @@ fn test_search_symbols_total_count() {
         db_path,
         query: "test",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -451,6 +633,10 @@ fn test_search_symbols_total_count() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, partial, _) = search_symbols(options).expect("search_symbols should succeed");
@@ -467,9 +653,12 @@ fn test_search_symbols_ordering() {
         db_path,
         query: "test",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -485,6 +674,10 @@ fn test_search_symbols_ordering() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, partial, _) = search_symbols(options).expect("search_symbols should succeed");
@@ -520,9 +713,12 @@ fn test_search_symbols_include_score() {
         db_path,
         query: "test_func",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -538,6 +734,10 @@ fn test_search_symbols_include_score() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, partial, _) = search_symbols(options).expect("search_symbols should succeed");
@@ -559,9 +759,12 @@ fn test_search_symbols_with_fqn() {
         db_path,
         query: "test_func",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -569,6 +772,8 @@ fn test_search_symbols_with_fqn() {
             fqn: true,
             canonical_fqn: false,
             display_fqn: false,
+            normalize: false,
+            defined_in: false,
         },
         include_score: false,
         sort_by: SortMode::default(),
@@ -581,6 +786,10 @@ fn test_search_symbols_with_fqn() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, partial, _) = search_symbols(options).expect("search_symbols should succeed");
@@ -596,3 +805,2711 @@ fn test_search_symbols_with_fqn() {
         "display_fqn should not be included"
     );
 }
+
+#[test]
+fn test_search_symbols_empty_query_matches_all_of_kind() {
+    let (_db_file, _conn) = create_test_db();
+    let db_path = _db_file.path();
+
+    // `--all --kind function` reaches the query layer as an empty query
+    let options = SearchOptions {
+        db_path,
+        query: "",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: Some("Function"),
+        limit: 10,
+        use_regex: false,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions::default(),
+        snippet: SnippetOptions::default(),
+        fqn: FqnOptions::default(),
+        include_score: false,
+        sort_by: SortMode::default(),
+        metrics: MetricsOptions::default(),
+        ast: AstOptions::default(),
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
+    };
+
+    let (response, _, _) = search_symbols(options).expect("search_symbols should succeed");
+    let mut names: Vec<&str> = response.results.iter().map(|r| r.name.as_str()).collect();
+    names.sort();
+    assert_eq!(names, vec!["helper", "test_func"]);
+}
+
+#[test]
+fn test_context_cards_contain_only_name_file_context() {
+    let (_db_file, conn) = create_test_db();
+    let source = tempfile::NamedTempFile::new().expect("failed to create source file");
+    let lines: Vec<String> = (1..=12).map(|i| format!("line{}", i)).collect();
+    std::fs::write(source.path(), lines.join("\n")).expect("failed to write source file");
+    conn.execute(
+        "UPDATE graph_entities SET data = ?1 WHERE id = 1",
+        [serde_json::json!({"path": source.path().to_str().unwrap()}).to_string()],
+    )
+    .expect("failed to point File entity at source");
+
+    let options = SearchOptions {
+        db_path: _db_file.path(),
+        query: "test_func",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: None,
+        limit: 10,
+        use_regex: false,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions {
+            include: true,
+            lines: 1,
+            before: None,
+            after: None,
+            max_lines: 20,
+            exclude_self: false,
+        },
+        snippet: SnippetOptions::default(),
+        fqn: FqnOptions::default(),
+        include_score: true,
+        sort_by: SortMode::default(),
+        metrics: MetricsOptions::default(),
+        ast: AstOptions::default(),
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
+    };
+
+    let (response, _, _) = search_symbols(options).expect("search_symbols should succeed");
+    let cards = serde_json::to_value(response.context_cards()).unwrap();
+    let card = cards[0].as_object().expect("card should be an object");
+    let mut keys: Vec<&str> = card.keys().map(|k| k.as_str()).collect();
+    keys.sort();
+    assert_eq!(keys, vec!["context", "file", "name"]);
+    assert_eq!(card["name"], "test_func");
+    // test_func spans lines 5-10
+    assert_eq!(card["context"]["before"], serde_json::json!(["line4"]));
+    assert_eq!(
+        card["context"]["selected"],
+        serde_json::json!(["line5", "line6", "line7", "line8", "line9", "line10"])
+    );
+    assert_eq!(card["context"]["after"], serde_json::json!(["line11"]));
+}
+
+#[test]
+fn test_distinct_names_dedupes_shared_name() {
+    let (_db_file, conn) = create_test_db();
+    conn.execute(
+        "INSERT INTO graph_entities (id, kind, data) VALUES
+            (13, 'Symbol', '{\"name\":\"helper\",\"kind\":\"Function\",\"kind_normalized\":\"function\",\"display_fqn\":\"helper\",\"fqn\":\"other::helper\",\"canonical_fqn\":\"/test/file.rs::other::helper\",\"symbol_id\":\"sym4\",\"byte_start\":700,\"byte_end\":800,\"start_line\":35,\"start_col\":0,\"end_line\":40,\"end_col\":1}')",
+        [],
+    )
+    .expect("failed to insert duplicate-name symbol");
+    conn.execute(
+        "INSERT INTO graph_edges (from_id, to_id, edge_type) VALUES (1, 13, 'DEFINES')",
+        [],
+    )
+    .expect("failed to insert DEFINES edge");
+
+    let options = SearchOptions {
+        db_path: _db_file.path(),
+        query: "",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: None,
+        limit: 10,
+        use_regex: false,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions::default(),
+        snippet: SnippetOptions::default(),
+        fqn: FqnOptions::default(),
+        include_score: false,
+        sort_by: SortMode::default(),
+        metrics: MetricsOptions::default(),
+        ast: AstOptions::default(),
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
+    };
+
+    let (response, _, _) = search_symbols(options).expect("search_symbols should succeed");
+    assert_eq!(response.results.len(), 4);
+    assert_eq!(response.distinct_names(), vec!["TestStruct", "helper", "test_func"]);
+}
+
+#[test]
+fn test_score_histogram_buckets_exact_prefix_contains() {
+    let (_db_file, conn) = create_test_db();
+    conn.execute(
+        "INSERT INTO graph_entities (id, kind, data) VALUES
+            (13, 'Symbol', '{\"name\":\"test_func_helper\",\"kind\":\"Function\",\"display_fqn\":\"test_func_helper\",\"fqn\":\"module::test_func_helper\",\"byte_start\":700,\"byte_end\":800,\"start_line\":35,\"start_col\":0,\"end_line\":40,\"end_col\":1}'),
+            (14, 'Symbol', '{\"name\":\"my_test_func\",\"kind\":\"Function\",\"display_fqn\":\"my_test_func\",\"fqn\":\"module::my_test_func\",\"byte_start\":900,\"byte_end\":1000,\"start_line\":45,\"start_col\":0,\"end_line\":50,\"end_col\":1}')",
+        [],
+    )
+    .expect("failed to insert symbols");
+    conn.execute(
+        "INSERT INTO graph_edges (from_id, to_id, edge_type) VALUES (1, 13, 'DEFINES'), (1, 14, 'DEFINES')",
+        [],
+    )
+    .expect("failed to insert DEFINES edges");
+
+    let options = SearchOptions {
+        db_path: _db_file.path(),
+        query: "test_func",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: None,
+        limit: 10,
+        use_regex: false,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions::default(),
+        snippet: SnippetOptions::default(),
+        fqn: FqnOptions::default(),
+        include_score: true,
+        sort_by: SortMode::default(),
+        metrics: MetricsOptions::default(),
+        ast: AstOptions::default(),
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
+    };
+
+    let (response, _, _) = search_symbols(options).expect("search_symbols should succeed");
+    assert_eq!(
+        response.score_histogram(),
+        vec![("90-100", 1), ("70-89", 1), ("50-69", 1), ("0-49", 0)]
+    );
+}
+
+#[test]
+fn test_partition_by_language_groups_rs_and_py() {
+    let (_db_file, conn) = create_test_db();
+    conn.execute(
+        "INSERT INTO graph_entities (id, kind, data) VALUES
+            (2, 'File', '{\"path\":\"/test/app.py\"}'),
+            (20, 'Symbol', '{\"name\":\"load_config\",\"kind\":\"Function\",\"display_fqn\":\"load_config\",\"fqn\":\"app.load_config\",\"byte_start\":0,\"byte_end\":50,\"start_line\":1,\"start_col\":0,\"end_line\":3,\"end_col\":0}')",
+        [],
+    )
+    .expect("failed to insert python symbol");
+    conn.execute(
+        "INSERT INTO graph_edges (from_id, to_id, edge_type) VALUES (2, 20, 'DEFINES')",
+        [],
+    )
+    .expect("failed to insert DEFINES edge");
+
+    let options = SearchOptions {
+        db_path: _db_file.path(),
+        query: "",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: None,
+        limit: 10,
+        use_regex: false,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions::default(),
+        snippet: SnippetOptions::default(),
+        fqn: FqnOptions::default(),
+        include_score: false,
+        sort_by: SortMode::default(),
+        metrics: MetricsOptions::default(),
+        ast: AstOptions::default(),
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
+    };
+
+    let (response, _, _) = search_symbols(options).expect("search_symbols should succeed");
+    let groups = response.partition_by_language();
+    assert_eq!(groups.keys().collect::<Vec<_>>(), vec!["python", "rust"]);
+    let python: Vec<&str> = groups["python"].iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(python, vec!["load_config"]);
+    assert_eq!(groups["rust"].len(), 3);
+}
+
+#[test]
+fn test_quickfix_items_use_setqflist_fields_and_one_based_col() {
+    let (_db_file, _conn) = create_test_db();
+
+    let options = SearchOptions {
+        db_path: _db_file.path(),
+        query: "test_func",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: None,
+        limit: 10,
+        use_regex: false,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions::default(),
+        snippet: SnippetOptions::default(),
+        fqn: FqnOptions::default(),
+        include_score: false,
+        sort_by: SortMode::default(),
+        metrics: MetricsOptions::default(),
+        ast: AstOptions::default(),
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
+    };
+
+    let (response, _, _) = search_symbols(options).expect("search_symbols should succeed");
+    let items = serde_json::to_value(response.quickfix_items()).unwrap();
+    assert_eq!(
+        items,
+        serde_json::json!([{
+            "filename": "/test/file.rs",
+            "lnum": 5,
+            "col": 1,
+            "text": "test_func Function",
+            "type": "F"
+        }])
+    );
+}
+
+#[test]
+fn test_vimgrep_line_prefers_first_snippet_line_over_label() {
+    let (_db_file, _conn) = create_test_db();
+
+    let options = SearchOptions {
+        db_path: _db_file.path(),
+        query: "test_func",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: None,
+        limit: 10,
+        use_regex: false,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions::default(),
+        snippet: SnippetOptions::default(),
+        fqn: FqnOptions::default(),
+        include_score: false,
+        sort_by: SortMode::default(),
+        metrics: MetricsOptions::default(),
+        ast: AstOptions::default(),
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
+    };
+
+    let (response, _, _) = search_symbols(options).expect("search_symbols should succeed");
+    let span = &response.results[0].span;
+    assert_eq!(
+        crate::output::vimgrep_line(span, None, "test_func Function"),
+        "/test/file.rs:5:1:test_func Function"
+    );
+    assert_eq!(
+        crate::output::vimgrep_line(
+            span,
+            Some("\n    fn test_func() {\n}"),
+            "test_func Function"
+        ),
+        "/test/file.rs:5:1:fn test_func() {"
+    );
+}
+
+#[test]
+fn test_fqn_list_dedupes_and_sorts_fqns() {
+    let (_db_file, conn) = create_test_db();
+    conn.execute(
+        "INSERT INTO graph_entities (id, kind, data) VALUES
+            (13, 'Symbol', '{\"name\":\"test_func\",\"kind\":\"Function\",\"display_fqn\":\"test_func\",\"canonical_fqn\":\"/test/file.rs::test_func\",\"symbol_id\":\"sym4\",\"byte_start\":700,\"byte_end\":800,\"start_line\":35,\"start_col\":0,\"end_line\":40,\"end_col\":1}'),
+            (14, 'Symbol', '{\"name\":\"free_fn\",\"kind\":\"Function\",\"display_fqn\":\"free_fn\",\"symbol_id\":\"sym5\",\"byte_start\":900,\"byte_end\":950,\"start_line\":45,\"start_col\":0,\"end_line\":47,\"end_col\":1}')",
+        [],
+    )
+    .expect("failed to insert symbols");
+    conn.execute(
+        "INSERT INTO graph_edges (from_id, to_id, edge_type) VALUES (1, 13, 'DEFINES'), (1, 14, 'DEFINES')",
+        [],
+    )
+    .expect("failed to insert DEFINES edges");
+
+    let options = SearchOptions {
+        db_path: _db_file.path(),
+        query: "",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: None,
+        limit: 10,
+        use_regex: false,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions::default(),
+        snippet: SnippetOptions::default(),
+        fqn: FqnOptions {
+            fqn: false,
+            canonical_fqn: true,
+            display_fqn: true,
+            normalize: false,
+            defined_in: false,
+        },
+        include_score: false,
+        sort_by: SortMode::default(),
+        metrics: MetricsOptions::default(),
+        ast: AstOptions::default(),
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
+    };
+
+    let (response, _, _) = search_symbols(options).expect("search_symbols should succeed");
+    assert_eq!(response.results.len(), 5);
+    assert_eq!(
+        response.fqn_list(),
+        vec![
+            "/test/file.rs::TestStruct",
+            "/test/file.rs::helper",
+            "/test/file.rs::test_func",
+            "free_fn",
+        ]
+    );
+}
+
+#[test]
+fn test_snippet_bundle_concatenates_snippets_under_headers() {
+    let (_db_file, conn) = create_test_db();
+    let mut source = tempfile::NamedTempFile::new().expect("failed to create source file");
+    std::io::Write::write_all(&mut source, b"fn test_func() {}\nfn helper() {}\n")
+        .expect("failed to write source file");
+    let source_path = source.path().to_string_lossy().to_string();
+
+    conn.execute(
+        "UPDATE graph_entities SET data = json_set(data, '$.path', ?1) WHERE id = 1",
+        [&source_path],
+    )
+    .expect("failed to point File entity at source");
+    conn.execute_batch(
+        "UPDATE graph_entities SET data = json_set(data, '$.byte_start', 0, '$.byte_end', 17, '$.start_line', 1, '$.end_line', 1) WHERE id = 10;
+         UPDATE graph_entities SET data = json_set(data, '$.byte_start', 18, '$.byte_end', 32, '$.start_line', 2, '$.end_line', 2) WHERE id = 12;",
+    )
+    .expect("failed to update symbol spans");
+
+    let options = SearchOptions {
+        db_path: _db_file.path(),
+        query: "^(test_func|helper)$",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: None,
+        limit: 10,
+        use_regex: true,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions::default(),
+        snippet: SnippetOptions {
+            include: true,
+            max_bytes: 1024,
+            line_numbers: false,
+            line_text: false,
+            read_timeout: None,
+            ..Default::default()
+        },
+        fqn: FqnOptions::default(),
+        include_score: false,
+        sort_by: SortMode::Position,
+        metrics: MetricsOptions::default(),
+        ast: AstOptions::default(),
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
+    };
+
+    let (response, _, _) = search_symbols(options).expect("search_symbols should succeed");
+    assert_eq!(
+        response.snippet_bundle(),
+        format!(
+            "// {0}:1-1 test_func (Function)\nfn test_func() {{}}\n\n// {0}:2-2 helper (Function)\nfn helper() {{}}\n\n",
+            source_path
+        )
+    );
+}
+
+#[test]
+fn test_sort_by_review_orders_by_file_then_line() {
+    let (_db_file, conn) = create_test_db();
+    conn.execute(
+        "INSERT INTO graph_entities (id, kind, data) VALUES
+            (2, 'File', '{\"path\":\"/test/alpha.rs\"}'),
+            (13, 'Symbol', '{\"name\":\"late_alpha\",\"kind\":\"Function\",\"symbol_id\":\"sym4\",\"byte_start\":900,\"byte_end\":950,\"start_line\":40,\"start_col\":0,\"end_line\":42,\"end_col\":1}'),
+            (14, 'Symbol', '{\"name\":\"early_alpha\",\"kind\":\"Function\",\"symbol_id\":\"sym5\",\"byte_start\":10,\"byte_end\":50,\"start_line\":2,\"start_col\":0,\"end_line\":4,\"end_col\":1}')",
+        [],
+    )
+    .expect("failed to insert alpha file");
+    conn.execute(
+        "INSERT INTO graph_edges (from_id, to_id, edge_type) VALUES (2, 13, 'DEFINES'), (2, 14, 'DEFINES')",
+        [],
+    )
+    .expect("failed to insert DEFINES edges");
+
+    let options = SearchOptions {
+        db_path: _db_file.path(),
+        query: "",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: None,
+        limit: 10,
+        use_regex: false,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions::default(),
+        snippet: SnippetOptions::default(),
+        fqn: FqnOptions::default(),
+        include_score: true,
+        sort_by: SortMode::Review,
+        metrics: MetricsOptions::default(),
+        ast: AstOptions::default(),
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
+    };
+
+    let (response, _, _) = search_symbols(options).expect("search_symbols should succeed");
+    let order: Vec<(&str, &str)> = response
+        .results
+        .iter()
+        .map(|r| (r.span.file_path.as_str(), r.name.as_str()))
+        .collect();
+    assert_eq!(
+        order,
+        vec![
+            ("/test/alpha.rs", "early_alpha"),
+            ("/test/alpha.rs", "late_alpha"),
+            ("/test/file.rs", "test_func"),
+            ("/test/file.rs", "TestStruct"),
+            ("/test/file.rs", "helper"),
+        ]
+    );
+    assert!(response.results.iter().all(|r| r.score == Some(0)));
+}
+
+#[test]
+fn test_flatten_tags_items_from_all_modes() {
+    let (_db_file, conn) = create_test_db();
+    conn.execute_batch(
+        "ALTER TABLE graph_entities ADD COLUMN name TEXT;
+         INSERT INTO graph_entities (id, kind, name, data) VALUES
+            (20, 'Reference', 'ref to test_func', '{\"file\":\"/test/file.rs\",\"byte_start\":50,\"byte_end\":59,\"start_line\":3,\"start_col\":4,\"end_line\":3,\"end_col\":13}'),
+            (21, 'Call', NULL, '{\"file\":\"/test/file.rs\",\"caller\":\"helper\",\"callee\":\"test_func\",\"caller_symbol_id\":\"sym3\",\"callee_symbol_id\":\"sym1\",\"byte_start\":520,\"byte_end\":531,\"start_line\":26,\"start_col\":4,\"end_line\":26,\"end_col\":15}');",
+    )
+    .expect("failed to insert reference and call");
+
+    let options = || SearchOptions {
+        db_path: _db_file.path(),
+        query: "test_func",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: None,
+        limit: 10,
+        use_regex: false,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions::default(),
+        snippet: SnippetOptions::default(),
+        fqn: FqnOptions::default(),
+        include_score: false,
+        sort_by: SortMode::default(),
+        metrics: MetricsOptions::default(),
+        ast: AstOptions::default(),
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
+    };
+    let (symbols, _, _) = search_symbols(options()).expect("search_symbols should succeed");
+    let (references, _) = search_references(options()).expect("search_references should succeed");
+    let (calls, _) = search_calls(options()).expect("search_calls should succeed");
+
+    let combined = crate::output::CombinedSearchResponse {
+        query: "test_func".to_string(),
+        path_filter: None,
+        total_count: symbols.total_count + references.total_count + calls.total_count,
+        symbols,
+        references,
+        calls,
+        limit_mode: "per-mode".to_string(),
+        omitted: None,
+    };
+    let flat = serde_json::to_value(combined.flatten()).unwrap();
+    let items = flat["results"]
+        .as_array()
+        .expect("results should be an array");
+
+    let tags: Vec<&str> = items
+        .iter()
+        .map(|item| item["result_type"].as_str().unwrap())
+        .collect();
+    assert_eq!(tags, vec!["symbol", "reference", "call"]);
+    assert_eq!(items[0]["name"], "test_func");
+    assert_eq!(items[1]["referenced_symbol"], "test_func");
+    assert_eq!(items[2]["caller"], "helper");
+    assert_eq!(flat["total_count"], 3);
+}
+
+#[test]
+fn test_json_lines_typed_tags_every_record() {
+    let (_db_file, conn) = create_test_db();
+    conn.execute_batch(
+        "ALTER TABLE graph_entities ADD COLUMN name TEXT;
+         INSERT INTO graph_entities (id, kind, name, data) VALUES
+            (20, 'Reference', 'ref to test_func', '{\"file\":\"/test/file.rs\",\"byte_start\":50,\"byte_end\":59,\"start_line\":3,\"start_col\":4,\"end_line\":3,\"end_col\":13}'),
+            (21, 'Call', NULL, '{\"file\":\"/test/file.rs\",\"caller\":\"helper\",\"callee\":\"test_func\",\"caller_symbol_id\":\"sym3\",\"callee_symbol_id\":\"sym1\",\"byte_start\":520,\"byte_end\":531,\"start_line\":26,\"start_col\":4,\"end_line\":26,\"end_col\":15}');",
+    )
+    .expect("failed to insert reference and call");
+
+    let options = || SearchOptions {
+        db_path: _db_file.path(),
+        query: "test_func",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: None,
+        limit: 10,
+        use_regex: false,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions::default(),
+        snippet: SnippetOptions::default(),
+        fqn: FqnOptions::default(),
+        include_score: false,
+        sort_by: SortMode::default(),
+        metrics: MetricsOptions::default(),
+        ast: AstOptions::default(),
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
+    };
+    let (symbols, _, _) = search_symbols(options()).expect("search_symbols should succeed");
+    let (references, _) = search_references(options()).expect("search_references should succeed");
+    let (calls, _) = search_calls(options()).expect("search_calls should succeed");
+
+    let combined = crate::output::CombinedSearchResponse {
+        query: "test_func".to_string(),
+        path_filter: None,
+        total_count: symbols.total_count + references.total_count + calls.total_count,
+        symbols,
+        references,
+        calls,
+        limit_mode: "per-mode".to_string(),
+        omitted: None,
+    };
+    let ndjson = crate::output::typed_json_lines(combined.typed_records()).unwrap();
+    let records: Vec<serde_json::Value> = ndjson
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("each line should be a JSON object"))
+        .collect();
+
+    let tags: Vec<&str> = records
+        .iter()
+        .map(|record| record["_type"].as_str().unwrap())
+        .collect();
+    assert_eq!(tags, vec!["symbol", "reference", "call"]);
+    assert_eq!(records[0]["name"], "test_func");
+    assert_eq!(records[1]["referenced_symbol"], "test_func");
+    assert_eq!(records[2]["caller"], "helper");
+}
+
+#[test]
+fn test_entity_ids_emit_only_valid_symbol_ids() {
+    let (_db_file, conn) = create_test_db();
+    conn.execute(
+        "INSERT INTO graph_entities (id, kind, data) VALUES
+            (13, 'Symbol', '{\"name\":\"hashed_fn\",\"kind\":\"Function\",\"symbol_id\":\"0123456789abcdef0123456789abcdef\",\"byte_start\":700,\"byte_end\":800,\"start_line\":35,\"start_col\":0,\"end_line\":40,\"end_col\":1}')",
+        [],
+    )
+    .expect("failed to insert symbol");
+    conn.execute(
+        "INSERT INTO graph_edges (from_id, to_id, edge_type) VALUES (1, 13, 'DEFINES')",
+        [],
+    )
+    .expect("failed to insert DEFINES edge");
+
+    let options = SearchOptions {
+        db_path: _db_file.path(),
+        query: "",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: None,
+        limit: 10,
+        use_regex: false,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions::default(),
+        snippet: SnippetOptions::default(),
+        fqn: FqnOptions::default(),
+        include_score: false,
+        sort_by: SortMode::Position,
+        metrics: MetricsOptions::default(),
+        ast: AstOptions::default(),
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
+    };
+
+    let (response, _, _) = search_symbols(options).expect("search_symbols should succeed");
+    assert_eq!(response.results.len(), 4);
+    let ids = response.entity_ids();
+    assert_eq!(ids, vec!["0123456789abcdef0123456789abcdef"]);
+    assert!(crate::algorithm::SymbolSet { symbol_ids: ids }
+        .validate()
+        .is_ok());
+}
+
+#[test]
+fn test_normalize_fqn_matches_dotted_python_fqn_with_colons() {
+    let (_db_file, conn) = create_test_db();
+    conn.execute(
+        "INSERT INTO graph_entities (id, kind, data) VALUES
+            (13, 'Symbol', '{\"name\":\"parse\",\"kind\":\"Function\",\"display_fqn\":\"module.Parser.parse\",\"canonical_fqn\":\"pkg.module.Parser.parse\",\"symbol_id\":\"sym4\",\"byte_start\":700,\"byte_end\":800,\"start_line\":35,\"start_col\":0,\"end_line\":40,\"end_col\":1}')",
+        [],
+    )
+    .expect("failed to insert Python symbol");
+    conn.execute(
+        "INSERT INTO graph_edges (from_id, to_id, edge_type) VALUES (1, 13, 'DEFINES')",
+        [],
+    )
+    .expect("failed to insert DEFINES edge");
+
+    let search = |normalize: bool, fqn_pattern: Option<&str>, exact_fqn: Option<&str>| {
+        let options = SearchOptions {
+            db_path: _db_file.path(),
+            query: "",
+            path_filter: None,
+            exclude_paths: &[],
+            path_glob: false,
+            kind_filter: None,
+            limit: 10,
+            use_regex: false,
+            ignore_case: false,
+            candidates: 100,
+            context: ContextOptions::default(),
+            snippet: SnippetOptions::default(),
+            fqn: FqnOptions {
+                fqn: false,
+                canonical_fqn: true,
+                display_fqn: true,
+                normalize,
+                defined_in: false,
+            },
+            include_score: false,
+            sort_by: SortMode::default(),
+            metrics: MetricsOptions::default(),
+            ast: AstOptions::default(),
+            depth: DepthOptions::default(),
+            algorithm: AlgorithmOptions::default(),
+            symbol_id: None,
+            fqn_pattern,
+            exact_fqn,
+            language_filter: None,
+            coverage_filter: None,
+            file_set: None,
+            max_per_file: None,
+            public_only: false,
+            prefer: None,
+        };
+        search_symbols(options)
+            .expect("search_symbols should succeed")
+            .0
+    };
+
+    let exact = search(true, None, Some("pkg::module::Parser::parse"));
+    assert_eq!(exact.results.len(), 1);
+    assert_eq!(
+        exact.results[0].canonical_fqn.as_deref(),
+        Some("pkg::module::Parser::parse")
+    );
+    assert_eq!(
+        exact.results[0].display_fqn.as_deref(),
+        Some("module::Parser::parse")
+    );
+
+    let pattern = search(true, Some("pkg::module::%"), None);
+    assert_eq!(pattern.results.len(), 1);
+    assert_eq!(pattern.results[0].name, "parse");
+
+    let unnormalized = search(false, None, Some("pkg::module::Parser::parse"));
+    assert!(unnormalized.results.is_empty());
+}
+
+#[test]
+fn test_context_exclude_self_blanks_selected_lines() {
+    let (_db_file, conn) = create_test_db();
+    let source = tempfile::NamedTempFile::new().expect("failed to create source file");
+    let lines: Vec<String> = (1..=12).map(|i| format!("line{}", i)).collect();
+    std::fs::write(source.path(), lines.join("\n")).expect("failed to write source file");
+    conn.execute(
+        "UPDATE graph_entities SET data = ?1 WHERE id = 1",
+        [serde_json::json!({"path": source.path().to_str().unwrap()}).to_string()],
+    )
+    .expect("failed to point File entity at source");
+
+    let options = SearchOptions {
+        db_path: _db_file.path(),
+        query: "test_func",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: None,
+        limit: 10,
+        use_regex: false,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions {
+            include: true,
+            lines: 2,
+            before: None,
+            after: None,
+            max_lines: 20,
+            exclude_self: true,
+        },
+        snippet: SnippetOptions::default(),
+        fqn: FqnOptions::default(),
+        include_score: true,
+        sort_by: SortMode::default(),
+        metrics: MetricsOptions::default(),
+        ast: AstOptions::default(),
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
+    };
+
+    let (response, _, _) = search_symbols(options).expect("search_symbols should succeed");
+    // test_func spans lines 5-10
+    let context = response.results[0]
+        .span
+        .context
+        .as_ref()
+        .expect("context should be present");
+    assert!(context.selected.is_empty());
+    assert_eq!(context.before, vec!["line3", "line4"]);
+    assert_eq!(context.after, vec!["line11", "line12"]);
+    assert!(!context.truncated);
+}
+
+#[test]
+fn test_name_frequency_ranks_repeated_names() {
+    let (_db_file, conn) = create_test_db();
+    conn.execute(
+        "INSERT INTO graph_entities (id, kind, data) VALUES
+            (2, 'File', '{\"path\":\"/test/other.rs\"}'),
+            (13, 'Symbol', '{\"name\":\"helper\",\"kind\":\"Function\",\"symbol_id\":\"sym4\",\"byte_start\":10,\"byte_end\":50,\"start_line\":2,\"start_col\":0,\"end_line\":4,\"end_col\":1}'),
+            (14, 'Symbol', '{\"name\":\"helper\",\"kind\":\"Method\",\"symbol_id\":\"sym5\",\"byte_start\":60,\"byte_end\":90,\"start_line\":6,\"start_col\":4,\"end_line\":8,\"end_col\":5}'),
+            (15, 'Symbol', '{\"name\":\"test_func\",\"kind\":\"Function\",\"symbol_id\":\"sym6\",\"byte_start\":100,\"byte_end\":150,\"start_line\":10,\"start_col\":0,\"end_line\":12,\"end_col\":1}')",
+        [],
+    )
+    .expect("failed to insert repeated symbols");
+    conn.execute(
+        "INSERT INTO graph_edges (from_id, to_id, edge_type) VALUES (2, 13, 'DEFINES'), (2, 14, 'DEFINES'), (2, 15, 'DEFINES')",
+        [],
+    )
+    .expect("failed to insert DEFINES edges");
+
+    let options = SearchOptions {
+        db_path: _db_file.path(),
+        query: "",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: None,
+        limit: 10,
+        use_regex: false,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions::default(),
+        snippet: SnippetOptions::default(),
+        fqn: FqnOptions::default(),
+        include_score: false,
+        sort_by: SortMode::Position,
+        metrics: MetricsOptions::default(),
+        ast: AstOptions::default(),
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
+    };
+
+    let (response, _, _) = search_symbols(options).expect("search_symbols should succeed");
+    assert_eq!(
+        response.name_frequency(),
+        vec![
+            ("helper".to_string(), 3),
+            ("test_func".to_string(), 2),
+            ("TestStruct".to_string(), 1),
+        ]
+    );
+}
+
+#[test]
+fn test_line_text_is_symbol_start_line() {
+    let (_db_file, conn) = create_test_db();
+    let source = tempfile::NamedTempFile::new().expect("failed to create source file");
+    let mut lines: Vec<String> = (1..=12).map(|i| format!("line{}", i)).collect();
+    lines[4] = "pub fn test_func() {".to_string();
+    std::fs::write(source.path(), lines.join("\r\n")).expect("failed to write source file");
+    conn.execute(
+        "UPDATE graph_entities SET data = ?1 WHERE id = 1",
+        [serde_json::json!({"path": source.path().to_str().unwrap()}).to_string()],
+    )
+    .expect("failed to point File entity at source");
+
+    let options = SearchOptions {
+        db_path: _db_file.path(),
+        query: "test_func",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: None,
+        limit: 10,
+        use_regex: false,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions::default(),
+        snippet: SnippetOptions {
+            line_text: true,
+            read_timeout: None,
+            ..SnippetOptions::default()
+        },
+        fqn: FqnOptions::default(),
+        include_score: true,
+        sort_by: SortMode::default(),
+        metrics: MetricsOptions::default(),
+        ast: AstOptions::default(),
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
+    };
+
+    let (response, _, _) = search_symbols(options).expect("search_symbols should succeed");
+    // test_func starts on line 5
+    assert_eq!(
+        response.results[0].line_text.as_deref(),
+        Some("pub fn test_func() {")
+    );
+    assert!(response.results[0].snippet.is_none());
+}
+
+#[test]
+fn test_fan_in_falls_back_to_reference_edges_without_metrics_table() {
+    let (_db_file, conn) = create_test_db();
+    conn.execute("DROP TABLE symbol_metrics", [])
+        .expect("failed to drop symbol_metrics");
+    conn.execute(
+        "INSERT INTO graph_entities (id, kind, data) VALUES
+            (20, 'Reference', '{\"name\":\"helper\"}'),
+            (21, 'Reference', '{\"name\":\"helper\"}'),
+            (22, 'Reference', '{\"name\":\"test_func\"}')",
+        [],
+    )
+    .expect("failed to insert references");
+    conn.execute(
+        "INSERT INTO graph_edges (from_id, to_id, edge_type) VALUES
+            (20, 12, 'REFERENCES'), (21, 12, 'REFERENCES'), (22, 10, 'REFERENCES')",
+        [],
+    )
+    .expect("failed to insert REFERENCES edges");
+
+    let options = SearchOptions {
+        db_path: _db_file.path(),
+        query: "",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: None,
+        limit: 10,
+        use_regex: false,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions::default(),
+        snippet: SnippetOptions::default(),
+        fqn: FqnOptions::default(),
+        include_score: true,
+        sort_by: SortMode::FanIn,
+        metrics: MetricsOptions::default(),
+        ast: AstOptions::default(),
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
+    };
+
+    let (response, _, _) = search_symbols(options.clone()).expect("search_symbols should succeed");
+    let ranked: Vec<(&str, Option<u64>)> = response
+        .results
+        .iter()
+        .map(|r| (r.name.as_str(), r.fan_in))
+        .collect();
+    assert_eq!(
+        ranked,
+        vec![
+            ("helper", Some(2)),
+            ("test_func", Some(1)),
+            ("TestStruct", None)
+        ]
+    );
+
+    // The derived metrics have no loc, so loc sorting degrades instead of failing
+    let (response, _, _) = search_symbols(SearchOptions {
+        sort_by: SortMode::Loc,
+        ..options.clone()
+    })
+    .expect("loc sort should succeed without symbol_metrics");
+    assert_eq!(response.results.len(), 3);
+
+    // Unreferenced symbols have no derived row but still count as fan-in 0
+    let (response, _, _) = search_symbols(SearchOptions {
+        sort_by: SortMode::Position,
+        metrics: MetricsOptions {
+            max_fan_in: Some(1),
+            ..MetricsOptions::default()
+        },
+        ..options
+    })
+    .expect("max fan-in should succeed without symbol_metrics");
+    let names: Vec<&str> = response.results.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, vec!["test_func", "TestStruct"]);
+}
+
+#[test]
+fn test_public_only_excludes_private_symbols() {
+    let (_db_file, conn) = create_test_db();
+    conn.execute(
+        "UPDATE graph_entities SET data = '{\"path\":\"/repo/src/lib.rs\"}' WHERE id = 1",
+        [],
+    )
+    .expect("failed to move File entity out of a test directory");
+    conn.execute(
+        "INSERT INTO graph_entities (id, kind, data) VALUES
+            (2, 'File', '{\"path\":\"/repo/tests/fixtures.rs\"}'),
+            (13, 'Symbol', '{\"name\":\"_private_helper\",\"kind\":\"Function\",\"symbol_id\":\"sym4\",\"byte_start\":400,\"byte_end\":450,\"start_line\":35,\"start_col\":0,\"end_line\":37,\"end_col\":1}'),
+            (14, 'Symbol', '{\"name\":\"fixture\",\"kind\":\"Function\",\"symbol_id\":\"sym5\",\"byte_start\":10,\"byte_end\":50,\"start_line\":2,\"start_col\":0,\"end_line\":4,\"end_col\":1}'),
+            (15, 'Symbol', '{\"name\":\"internal\",\"kind\":\"Function\",\"symbol_id\":\"sym6\",\"visibility\":\"private\",\"byte_start\":500,\"byte_end\":550,\"start_line\":40,\"start_col\":0,\"end_line\":42,\"end_col\":1}')",
+        [],
+    )
+    .expect("failed to insert private symbols");
+    conn.execute(
+        "INSERT INTO graph_edges (from_id, to_id, edge_type) VALUES
+            (1, 13, 'DEFINES'), (2, 14, 'DEFINES'), (1, 15, 'DEFINES')",
+        [],
+    )
+    .expect("failed to insert DEFINES edges");
+
+    let options = SearchOptions {
+        db_path: _db_file.path(),
+        query: "",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: None,
+        limit: 10,
+        use_regex: false,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions::default(),
+        snippet: SnippetOptions::default(),
+        fqn: FqnOptions::default(),
+        include_score: true,
+        sort_by: SortMode::Position,
+        metrics: MetricsOptions::default(),
+        ast: AstOptions::default(),
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: true,
+        prefer: None,
+    };
+
+    let (response, _, _) =
+        search_symbols(options.clone()).expect("search_symbols should succeed");
+    let mut names: Vec<&str> = response.results.iter().map(|r| r.name.as_str()).collect();
+    names.sort_unstable();
+    assert_eq!(names, vec!["TestStruct", "helper", "test_func"]);
+    assert_eq!(response.total_count, 3);
+
+    // The filter runs before --limit, so private symbols ahead in position
+    // order do not crowd out public ones
+    let (response, _, _) = search_symbols(SearchOptions { limit: 2, ..options })
+        .expect("search_symbols should succeed");
+    assert_eq!(response.results.len(), 2);
+    assert!(response.results.iter().all(|r| r.is_public()));
+    assert_eq!(response.total_count, 3);
+}
+
+#[test]
+fn test_wide_table_aligns_all_metadata_columns() {
+    let (_db_file, conn) = create_test_db();
+    conn.execute(
+        "INSERT INTO symbol_metrics (symbol_id, symbol_name, kind, file_path, fan_in, fan_out, cyclomatic_complexity)
+         VALUES (10, 'test_func', 'Function', '/test/file.rs', 12, 3, 7)",
+        [],
+    )
+    .expect("failed to insert metrics");
+
+    let options = SearchOptions {
+        db_path: _db_file.path(),
+        query: "test_func",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: None,
+        limit: 10,
+        use_regex: false,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions::default(),
+        snippet: SnippetOptions::default(),
+        fqn: FqnOptions::default(),
+        include_score: true,
+        sort_by: SortMode::default(),
+        metrics: MetricsOptions::default(),
+        ast: AstOptions::default(),
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
+    };
+
+    let (response, _, _) = search_symbols(options).expect("search_symbols should succeed");
+    let table = response.wide_table();
+    let lines: Vec<&str> = table.lines().collect();
+    assert_eq!(lines.len(), 2);
+    let header: Vec<&str> = lines[0].split_whitespace().collect();
+    assert_eq!(
+        header,
+        vec!["LOCATION", "NAME", "KIND", "SCORE", "CC", "FAN_IN", "FAN_OUT", "ID"]
+    );
+    let row: Vec<&str> = lines[1].split_whitespace().collect();
+    let score = response.results[0].score.unwrap().to_string();
+    assert_eq!(
+        row,
+        vec![
+            "/test/file.rs:5:0",
+            "test_func",
+            "Function",
+            score.as_str(),
+            "7",
+            "12",
+            "3",
+            "sym1"
+        ]
+    );
+    // Every column starts at the same offset in the header and the row
+    let column_starts = |line: &str| -> Vec<usize> {
+        line.char_indices()
+            .filter(|&(i, c)| c != ' ' && (i == 0 || line.as_bytes()[i - 1] == b' '))
+            .map(|(i, _)| i)
+            .collect()
+    };
+    assert_eq!(column_starts(lines[0]), column_starts(lines[1]));
+}
+
+#[test]
+fn test_file_fallback_snippet_carries_content_hash() {
+    use sha2::{Digest, Sha256};
+    let (_db_file, conn) = create_test_db();
+    let source = tempfile::NamedTempFile::new().expect("failed to create source file");
+    let contents: Vec<u8> = (0..300u32).map(|i| b'a' + (i % 26) as u8).collect();
+    std::fs::write(source.path(), &contents).expect("failed to write source file");
+    conn.execute(
+        "UPDATE graph_entities SET data = ?1 WHERE id = 1",
+        [serde_json::json!({"path": source.path().to_str().unwrap()}).to_string()],
+    )
+    .expect("failed to point File entity at source");
+
+    let options = SearchOptions {
+        db_path: _db_file.path(),
+        query: "test_func",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: None,
+        limit: 10,
+        use_regex: false,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions::default(),
+        snippet: SnippetOptions {
+            include: true,
+            max_bytes: 10,
+            ..SnippetOptions::default()
+        },
+        fqn: FqnOptions::default(),
+        include_score: true,
+        sort_by: SortMode::default(),
+        metrics: MetricsOptions::default(),
+        ast: AstOptions::default(),
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
+    };
+
+    let (response, _, _) = search_symbols(options).expect("search_symbols should succeed");
+    let result = &response.results[0];
+    assert_eq!(result.snippet_truncated, Some(true));
+    // The hash covers the whole 100..200 span, not the 10-byte snippet
+    assert_eq!(
+        result.content_hash.as_deref(),
+        Some(hex::encode(Sha256::digest(&contents[100..200])).as_str())
+    );
+}
+
+#[test]
+fn test_complexity_percentiles_rank_within_matched_set() {
+    let (_db_file, conn) = create_test_db();
+    conn.execute(
+        "INSERT INTO graph_entities (id, kind, data) VALUES
+            (13, 'Symbol', '{\"name\":\"tangled\",\"kind\":\"Function\",\"symbol_id\":\"sym4\",\"byte_start\":400,\"byte_end\":450,\"start_line\":35,\"start_col\":0,\"end_line\":37,\"end_col\":1}')",
+        [],
+    )
+    .expect("failed to insert symbol");
+    conn.execute(
+        "INSERT INTO graph_edges (from_id, to_id, edge_type) VALUES (1, 13, 'DEFINES')",
+        [],
+    )
+    .expect("failed to insert DEFINES edge");
+    conn.execute(
+        "INSERT INTO symbol_metrics (symbol_id, symbol_name, kind, file_path, cyclomatic_complexity) VALUES
+            (10, 'test_func', 'Function', '/test/file.rs', 2),
+            (11, 'TestStruct', 'Struct', '/test/file.rs', 2),
+            (12, 'helper', 'Function', '/test/file.rs', 5),
+            (13, 'tangled', 'Function', '/test/file.rs', 20)",
+        [],
+    )
+    .expect("failed to insert metrics");
+
+    let options = SearchOptions {
+        db_path: _db_file.path(),
+        query: "",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: None,
+        limit: 10,
+        use_regex: false,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions::default(),
+        snippet: SnippetOptions::default(),
+        fqn: FqnOptions::default(),
+        include_score: true,
+        sort_by: SortMode::Complexity,
+        metrics: MetricsOptions::default(),
+        ast: AstOptions::default(),
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
+    };
+
+    let (mut response, _, _) = search_symbols(options).expect("search_symbols should succeed");
+    response.assign_complexity_percentiles();
+    let percentiles: Vec<(&str, Option<u8>)> = response
+        .results
+        .iter()
+        .map(|r| (r.name.as_str(), r.complexity_percentile))
+        .collect();
+    // Ties share the higher rank: both cc=2 symbols are at or below 2 of 4
+    assert_eq!(
+        percentiles,
+        vec![
+            ("tangled", Some(100)),
+            ("helper", Some(75)),
+            ("test_func", Some(50)),
+            ("TestStruct", Some(50)),
+        ]
+    );
+}
+
+#[test]
+fn test_deadcode_csv_rows_are_sorted_and_quoted() {
+    let (_db_file, conn) = create_test_db();
+    conn.execute(
+        "INSERT INTO graph_entities (id, kind, data) VALUES
+            (2, 'File', '{\"path\":\"/test/a,b.rs\"}'),
+            (13, 'Symbol', '{\"name\":\"orphan\",\"kind\":\"Function\",\"canonical_fqn\":\"/test/a,b.rs::orphan\",\"symbol_id\":\"sym4\",\"byte_start\":10,\"byte_end\":50,\"start_line\":2,\"start_col\":0,\"end_line\":4,\"end_col\":1}')",
+        [],
+    )
+    .expect("failed to insert unreachable symbol");
+    conn.execute(
+        "INSERT INTO graph_edges (from_id, to_id, edge_type) VALUES (2, 13, 'DEFINES')",
+        [],
+    )
+    .expect("failed to insert DEFINES edge");
+
+    // Stand-in for a --dead-code-in result set: every symbol is unreachable
+    let options = SearchOptions {
+        db_path: _db_file.path(),
+        query: "",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: None,
+        limit: 10,
+        use_regex: false,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions::default(),
+        snippet: SnippetOptions::default(),
+        fqn: FqnOptions {
+            canonical_fqn: true,
+            display_fqn: true,
+            ..FqnOptions::default()
+        },
+        include_score: false,
+        sort_by: SortMode::Position,
+        metrics: MetricsOptions::default(),
+        ast: AstOptions::default(),
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
+    };
+
+    let (response, _, _) = search_symbols(options).expect("search_symbols should succeed");
+    assert_eq!(
+        response.deadcode_csv(),
+        "fqn,file,line,kind\n\
+         \"/test/a,b.rs::orphan\",\"/test/a,b.rs\",2,Function\n\
+         /test/file.rs::TestStruct,/test/file.rs,15,Struct\n\
+         /test/file.rs::helper,/test/file.rs,25,Function\n\
+         /test/file.rs::test_func,/test/file.rs,5,Function\n"
+    );
+}
+
+#[test]
+fn test_file_set_restricts_results_to_listed_files() {
+    let (_db_file, conn) = create_test_db();
+    conn.execute(
+        "INSERT INTO graph_entities (id, kind, data) VALUES
+            (2, 'File', '{\"path\":\"/test/other.rs\"}'),
+            (3, 'File', '{\"path\":\"/test/skipped.rs\"}'),
+            (20, 'Symbol', '{\"name\":\"other_func\",\"kind\":\"Function\",\"display_fqn\":\"other_func\",\"fqn\":\"other::other_func\",\"byte_start\":0,\"byte_end\":50,\"start_line\":1,\"start_col\":0,\"end_line\":3,\"end_col\":0}'),
+            (30, 'Symbol', '{\"name\":\"skipped_func\",\"kind\":\"Function\",\"display_fqn\":\"skipped_func\",\"fqn\":\"skipped::skipped_func\",\"byte_start\":0,\"byte_end\":50,\"start_line\":1,\"start_col\":0,\"end_line\":3,\"end_col\":0}')",
+        [],
+    )
+    .expect("failed to insert extra symbols");
+    conn.execute(
+        "INSERT INTO graph_edges (from_id, to_id, edge_type) VALUES (2, 20, 'DEFINES'), (3, 30, 'DEFINES')",
+        [],
+    )
+    .expect("failed to insert DEFINES edges");
+
+    let files = vec!["/test/file.rs".to_string(), "/test/other.rs".to_string()];
+    let options = SearchOptions {
+        db_path: _db_file.path(),
+        query: "",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: None,
+        limit: 10,
+        use_regex: false,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions::default(),
+        snippet: SnippetOptions::default(),
+        fqn: FqnOptions::default(),
+        include_score: false,
+        sort_by: SortMode::default(),
+        metrics: MetricsOptions::default(),
+        ast: AstOptions::default(),
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: Some(&files),
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
+    };
+
+    let (response, _, _) = search_symbols(options).expect("search_symbols should succeed");
+    let mut names: Vec<&str> = response.results.iter().map(|r| r.name.as_str()).collect();
+    names.sort_unstable();
+    assert_eq!(
+        names,
+        vec!["TestStruct", "helper", "other_func", "test_func"]
+    );
+    assert_eq!(response.total_count, 4);
+}
+
+#[test]
+fn test_prefer_shortest_path_keeps_shallowest_definition() {
+    let (_db_file, conn) = create_test_db();
+    conn.execute(
+        "INSERT INTO graph_entities (id, kind, data) VALUES
+            (2, 'File', '{\"path\":\"/test/legacy/copy/file.rs\"}'),
+            (20, 'Symbol', '{\"name\":\"test_func\",\"kind\":\"Function\",\"display_fqn\":\"test_func\",\"fqn\":\"legacy::copy::test_func\",\"byte_start\":0,\"byte_end\":50,\"start_line\":1,\"start_col\":0,\"end_line\":3,\"end_col\":0}')",
+        [],
+    )
+    .expect("failed to insert stale copy");
+    conn.execute(
+        "INSERT INTO graph_edges (from_id, to_id, edge_type) VALUES (2, 20, 'DEFINES')",
+        [],
+    )
+    .expect("failed to insert DEFINES edge");
+
+    let options = SearchOptions {
+        db_path: _db_file.path(),
+        query: "",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: None,
+        limit: 10,
+        use_regex: false,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions::default(),
+        snippet: SnippetOptions::default(),
+        fqn: FqnOptions::default(),
+        include_score: false,
+        sort_by: SortMode::Position,
+        metrics: MetricsOptions::default(),
+        ast: AstOptions::default(),
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: Some(crate::output::Prefer::ShortestPath),
+    };
+
+    let (response, _, _) =
+        search_symbols(options.clone()).expect("search_symbols should succeed");
+    assert_eq!(response.results.len(), 3);
+    assert_eq!(response.total_count, 3);
+    let test_func = response
+        .results
+        .iter()
+        .find(|r| r.name == "test_func")
+        .expect("test_func kept");
+    assert_eq!(test_func.span.file_path, "/test/file.rs");
+
+    // The winner is picked before --limit, whichever copy comes first
+    let (response, _, _) = search_symbols(SearchOptions {
+        query: "test_func",
+        limit: 1,
+        ..options
+    })
+    .expect("search_symbols should succeed");
+    assert_eq!(response.results.len(), 1);
+    assert_eq!(response.results[0].span.file_path, "/test/file.rs");
+    assert_eq!(response.total_count, 1);
+}
+
+#[test]
+fn test_defined_in_carries_defining_file_id_and_path() {
+    let (_db_file, _conn) = create_test_db();
+
+    let options = SearchOptions {
+        db_path: _db_file.path(),
+        query: "test_func",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: None,
+        limit: 10,
+        use_regex: false,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions::default(),
+        snippet: SnippetOptions::default(),
+        fqn: FqnOptions {
+            defined_in: true,
+            ..FqnOptions::default()
+        },
+        include_score: false,
+        sort_by: SortMode::default(),
+        metrics: MetricsOptions::default(),
+        ast: AstOptions::default(),
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
+    };
+
+    let (response, _, _) = search_symbols(options).expect("search_symbols should succeed");
+    let defined_in = response.results[0]
+        .defined_in
+        .as_ref()
+        .expect("defined_in requested");
+    assert_eq!(defined_in.file_id, 1);
+    assert_eq!(defined_in.path, "/test/file.rs");
+    assert_eq!(defined_in.language.as_deref(), Some("Rust"));
+
+    let json = serde_json::to_value(&response.results[0]).expect("serialize");
+    assert_eq!(json["defined_in"]["file_id"], 1);
+    assert_eq!(json["defined_in"]["path"], "/test/file.rs");
+}
+
+#[test]
+fn test_auto_mode_token_budget_truncates_modes_proportionally() {
+    let (_db_file, conn) = create_test_db();
+    conn.execute_batch(
+        "ALTER TABLE graph_entities ADD COLUMN name TEXT;
+         INSERT INTO graph_entities (id, kind, name, data) VALUES
+            (20, 'Reference', 'ref to test_func', '{\"file\":\"/test/file.rs\",\"byte_start\":50,\"byte_end\":59,\"start_line\":3,\"start_col\":4,\"end_line\":3,\"end_col\":13}'),
+            (21, 'Call', NULL, '{\"file\":\"/test/file.rs\",\"caller\":\"helper\",\"callee\":\"test_func\",\"caller_symbol_id\":\"sym3\",\"callee_symbol_id\":\"sym1\",\"byte_start\":520,\"byte_end\":531,\"start_line\":26,\"start_col\":4,\"end_line\":26,\"end_col\":15}');",
+    )
+    .expect("failed to insert reference and call");
+
+    let options = || SearchOptions {
+        db_path: _db_file.path(),
+        query: "test_func",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: None,
+        limit: 10,
+        use_regex: false,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions::default(),
+        snippet: SnippetOptions::default(),
+        fqn: FqnOptions::default(),
+        include_score: false,
+        sort_by: SortMode::default(),
+        metrics: MetricsOptions::default(),
+        ast: AstOptions::default(),
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
+    };
+    let (mut symbols, _, _) = search_symbols(options()).expect("search_symbols should succeed");
+    let (mut references, _) =
+        search_references(options()).expect("search_references should succeed");
+    let (mut calls, _) = search_calls(options()).expect("search_calls should succeed");
+    // 8 symbols, 4 references, 2 calls
+    symbols.results = symbols.results.iter().cycle().take(8).cloned().collect();
+    references.results = references.results.iter().cycle().take(4).cloned().collect();
+    calls.results = calls.results.iter().cycle().take(2).cloned().collect();
+
+    let mut combined = crate::output::CombinedSearchResponse {
+        query: "test_func".to_string(),
+        path_filter: None,
+        total_count: 14,
+        symbols,
+        references,
+        calls,
+        limit_mode: "per-mode".to_string(),
+        omitted: None,
+    };
+    let render = |c: &crate::output::CombinedSearchResponse| serde_json::to_string(c).unwrap();
+    let budget = render(&combined).len() / 4 / 3;
+
+    let tokens = combined.truncate_to_tokens(budget, render);
+
+    assert!(tokens <= budget, "{} tokens over budget {}", tokens, budget);
+    let kept = (
+        combined.symbols.results.len(),
+        combined.references.results.len(),
+        combined.calls.results.len(),
+    );
+    // Every pass halves each mode, so the 8:4:2 ratio survives
+    let passes = (8 / kept.0.max(1)).trailing_zeros();
+    assert!(passes >= 1);
+    assert_eq!(kept, (8 >> passes, 4 >> passes, 2 >> passes));
+    let omitted = combined.omitted.expect("omitted counts recorded");
+    assert_eq!(omitted.symbols, 8 - kept.0);
+    assert_eq!(omitted.references, 4 - kept.1);
+    assert_eq!(omitted.calls, 2 - kept.2);
+
+    let json = serde_json::to_value(&combined).unwrap();
+    assert_eq!(json["omitted"]["symbols"], 8 - kept.0);
+    assert_eq!(json["total_count"], 14);
+}
+
+#[test]
+fn test_regex_capture_groups_are_surfaced_per_result() {
+    let (_db_file, conn) = create_test_db();
+    conn.execute(
+        "INSERT INTO graph_entities (id, kind, data) VALUES
+            (20, 'Symbol', '{\"name\":\"get_user\",\"kind\":\"Function\",\"display_fqn\":\"get_user\",\"fqn\":\"api::get_user\",\"byte_start\":300,\"byte_end\":350,\"start_line\":30,\"start_col\":0,\"end_line\":32,\"end_col\":0}'),
+            (21, 'Symbol', '{\"name\":\"get_config\",\"kind\":\"Function\",\"display_fqn\":\"get_config\",\"fqn\":\"api::get_config\",\"byte_start\":400,\"byte_end\":450,\"start_line\":40,\"start_col\":0,\"end_line\":42,\"end_col\":0}')",
+        [],
+    )
+    .expect("failed to insert getters");
+    conn.execute(
+        "INSERT INTO graph_edges (from_id, to_id, edge_type) VALUES (1, 20, 'DEFINES'), (1, 21, 'DEFINES')",
+        [],
+    )
+    .expect("failed to insert DEFINES edges");
+
+    let options = SearchOptions {
+        db_path: _db_file.path(),
+        // ASCII `\w`: the Unicode class compiles past MAX_REGEX_SIZE
+        query: r"(?-u)get_(\w+)",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: None,
+        limit: 10,
+        use_regex: true,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions::default(),
+        snippet: SnippetOptions::default(),
+        fqn: FqnOptions::default(),
+        include_score: false,
+        sort_by: SortMode::Position,
+        metrics: MetricsOptions::default(),
+        ast: AstOptions::default(),
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
+    };
+
+    let (response, _, _) = search_symbols(options).expect("search_symbols should succeed");
+    let captured: Vec<(&str, Vec<String>)> = response
+        .results
+        .iter()
+        .map(|r| (r.name.as_str(), r.captures.clone().expect("captures set")))
+        .collect();
+    assert_eq!(
+        captured,
+        vec![
+            ("get_user", vec!["user".to_string()]),
+            ("get_config", vec!["config".to_string()]),
+        ]
+    );
+}
+
+#[test]
+fn test_progressive_records_emit_coarse_pass_before_enrichment() {
+    let (_db_file, conn) = create_test_db();
+    conn.execute(
+        "INSERT INTO symbol_metrics (symbol_id, symbol_name, kind, file_path, loc, fan_in, fan_out, cyclomatic_complexity) VALUES
+            (10, 'test_func', 'Function', '/test/file.rs', 6, 2, 1, 4)",
+        [],
+    )
+    .expect("failed to insert metrics");
+
+    let options = SearchOptions {
+        db_path: _db_file.path(),
+        query: "e",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: None,
+        limit: 10,
+        use_regex: false,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions::default(),
+        snippet: SnippetOptions::default(),
+        fqn: FqnOptions::default(),
+        include_score: false,
+        sort_by: SortMode::Position,
+        metrics: MetricsOptions::default(),
+        ast: AstOptions::default(),
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
+    };
+    let (response, _, _) = search_symbols(options).expect("search_symbols should succeed");
+    assert_eq!(response.results.len(), 3);
+
+    let ndjson = crate::output::progressive_json_lines(&response.results).unwrap();
+    let records: Vec<serde_json::Value> = ndjson
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("each line should be a JSON object"))
+        .collect();
+
+    let phases: Vec<&str> = records
+        .iter()
+        .map(|record| record["_phase"].as_str().unwrap())
+        .collect();
+    assert_eq!(phases, vec!["coarse", "coarse", "coarse", "enrichment"]);
+    for (record, result) in records.iter().zip(&response.results) {
+        assert_eq!(record["match_id"], result.match_id.as_str());
+        assert_eq!(record["name"], result.name.as_str());
+        assert!(record.get("fan_in").is_none());
+    }
+
+    let test_func = response
+        .results
+        .iter()
+        .find(|r| r.name == "test_func")
+        .expect("test_func result");
+    assert_eq!(records[3]["match_id"], test_func.match_id.as_str());
+    assert_eq!(records[3]["fan_in"], 2);
+    assert_eq!(records[3]["cyclomatic_complexity"], 4);
+    assert!(records[3].get("name").is_none());
+}
+
+#[test]
+fn test_hybrid_sort_reorders_position_window_by_relevance() {
+    let (_db_file, conn) = create_test_db();
+    conn.execute(
+        "INSERT INTO graph_entities (id, kind, data) VALUES
+            (13, 'Symbol', '{\"name\":\"my_helper_x\",\"kind\":\"Function\",\"kind_normalized\":\"function\",\"symbol_id\":\"sym13\",\"byte_start\":10,\"byte_end\":50,\"start_line\":1,\"start_col\":0,\"end_line\":3,\"end_col\":1}')",
+        [],
+    )
+    .expect("failed to insert symbol");
+    conn.execute(
+        "INSERT INTO graph_edges (from_id, to_id, edge_type) VALUES (1, 13, 'DEFINES')",
+        [],
+    )
+    .expect("failed to insert edge");
+
+    let names = |sort_by: SortMode, limit: usize| -> Vec<String> {
+        let options = SearchOptions {
+            db_path: _db_file.path(),
+            query: "helper",
+            path_filter: None,
+            exclude_paths: &[],
+            path_glob: false,
+            kind_filter: None,
+            limit,
+            use_regex: false,
+            ignore_case: false,
+            candidates: 100,
+            context: ContextOptions::default(),
+            snippet: SnippetOptions::default(),
+            fqn: FqnOptions::default(),
+            include_score: true,
+            sort_by,
+            metrics: MetricsOptions::default(),
+            ast: AstOptions::default(),
+            depth: DepthOptions::default(),
+            algorithm: AlgorithmOptions::default(),
+            symbol_id: None,
+            fqn_pattern: None,
+            exact_fqn: None,
+            language_filter: None,
+            coverage_filter: None,
+            file_set: None,
+            max_per_file: None,
+            public_only: false,
+            prefer: None,
+        };
+        let (response, _, _) = search_symbols(options).expect("search_symbols should succeed");
+        response.results.into_iter().map(|r| r.name).collect()
+    };
+
+    assert_eq!(names(SortMode::Position, 2), vec!["my_helper_x", "helper"]);
+    assert_eq!(names(SortMode::Hybrid, 2), vec!["helper", "my_helper_x"]);
+
+    // The window is position-bounded: hybrid never reaches past it for a better match
+    assert_eq!(names(SortMode::Hybrid, 1), vec!["my_helper_x"]);
+    assert_eq!(names(SortMode::Relevance, 1), vec!["helper"]);
+}
+
+#[test]
+fn test_ndjson_writes_one_match_per_line_then_tagged_summary() {
+    let (_db_file, _conn) = create_test_db();
+
+    let options = SearchOptions {
+        db_path: _db_file.path(),
+        query: "e",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: None,
+        limit: 2,
+        use_regex: false,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions::default(),
+        snippet: SnippetOptions::default(),
+        fqn: FqnOptions::default(),
+        include_score: false,
+        sort_by: SortMode::Position,
+        metrics: MetricsOptions::default(),
+        ast: AstOptions::default(),
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
+    };
+    let (response, partial, _) = search_symbols(options).expect("search_symbols should succeed");
+    assert_eq!(response.results.len(), 2);
+
+    let mut buf = Vec::new();
+    crate::output::write_ndjson(
+        &mut buf,
+        &response.results,
+        crate::output::NdjsonSummary {
+            total_count: response.total_count,
+            partial,
+        },
+    )
+    .unwrap();
+    let records: Vec<serde_json::Value> = String::from_utf8(buf)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("each line should be a JSON object"))
+        .collect();
+
+    assert_eq!(records.len(), 3);
+    for (record, result) in records.iter().zip(&response.results) {
+        assert_eq!(record["name"], result.name.as_str());
+        assert!(record.get("_type").is_none());
+    }
+    assert_eq!(records[2]["_type"], "summary");
+    assert_eq!(records[2]["total_count"], response.total_count);
+    assert_eq!(records[2]["partial"], partial);
+}
+
+#[test]
+fn test_sort_by_name_orders_case_insensitively_with_file_tiebreak() {
+    let (_db_file, conn) = create_test_db();
+    conn.execute(
+        "INSERT INTO graph_entities (id, kind, data) VALUES
+            (2, 'File', '{\"path\":\"/test/alpha.rs\"}'),
+            (13, 'Symbol', '{\"name\":\"Helper\",\"kind\":\"Struct\",\"symbol_id\":\"sym4\",\"byte_start\":900,\"byte_end\":950,\"start_line\":40,\"start_col\":0,\"end_line\":42,\"end_col\":1}')",
+        [],
+    )
+    .expect("failed to insert alpha file");
+    conn.execute(
+        "INSERT INTO graph_edges (from_id, to_id, edge_type) VALUES (2, 13, 'DEFINES')",
+        [],
+    )
+    .expect("failed to insert DEFINES edge");
+
+    let search = |sort_by| {
+        let options = SearchOptions {
+            db_path: _db_file.path(),
+            query: "",
+            path_filter: None,
+            exclude_paths: &[],
+            path_glob: false,
+            kind_filter: None,
+            limit: 10,
+            use_regex: false,
+            ignore_case: false,
+            candidates: 100,
+            context: ContextOptions::default(),
+            snippet: SnippetOptions::default(),
+            fqn: FqnOptions::default(),
+            include_score: true,
+            sort_by,
+            metrics: MetricsOptions::default(),
+            ast: AstOptions::default(),
+            depth: DepthOptions::default(),
+            algorithm: AlgorithmOptions::default(),
+            symbol_id: None,
+            fqn_pattern: None,
+            exact_fqn: None,
+            language_filter: None,
+            coverage_filter: None,
+            file_set: None,
+            max_per_file: None,
+            public_only: false,
+            prefer: None,
+        };
+        let (response, _, _) = search_symbols(options).expect("search_symbols should succeed");
+        response
+            .results
+            .into_iter()
+            .map(|r| (r.span.file_path, r.name))
+            .collect::<Vec<_>>()
+    };
+    let entry = |file: &str, name: &str| (file.to_string(), name.to_string());
+
+    assert_eq!(
+        search(SortMode::Name),
+        vec![
+            entry("/test/alpha.rs", "Helper"),
+            entry("/test/file.rs", "helper"),
+            entry("/test/file.rs", "test_func"),
+            entry("/test/file.rs", "TestStruct"),
+        ]
+    );
+    // Descending reverses names only; equal names still tie-break by file ascending
+    assert_eq!(
+        search(SortMode::NameDesc),
+        vec![
+            entry("/test/file.rs", "TestStruct"),
+            entry("/test/file.rs", "test_func"),
+            entry("/test/alpha.rs", "Helper"),
+            entry("/test/file.rs", "helper"),
+        ]
+    );
+}
+
+#[test]
+fn test_search_symbols_notices_ast_filter_without_ast_table() {
+    let (_db_file, _conn) = create_test_db();
+    let search = |inside: Option<&str>| {
+        let options = SearchOptions {
+            db_path: _db_file.path(),
+            query: "test_func",
+            path_filter: None,
+            exclude_paths: &[],
+            path_glob: false,
+            kind_filter: None,
+            limit: 10,
+            use_regex: false,
+            ignore_case: false,
+            candidates: 100,
+            context: ContextOptions::default(),
+            snippet: SnippetOptions::default(),
+            fqn: FqnOptions::default(),
+            include_score: false,
+            sort_by: SortMode::default(),
+            metrics: MetricsOptions::default(),
+            ast: AstOptions::default(),
+            depth: DepthOptions {
+                inside,
+                ..DepthOptions::default()
+            },
+            algorithm: AlgorithmOptions::default(),
+            symbol_id: None,
+            fqn_pattern: None,
+            exact_fqn: None,
+            language_filter: None,
+            coverage_filter: None,
+            file_set: None,
+            max_per_file: None,
+            public_only: false,
+            prefer: None,
+        };
+        search_symbols(options)
+            .expect("search_symbols should succeed")
+            .0
+    };
+
+    let unfiltered = search(None);
+    assert_eq!(unfiltered.notice, None);
+
+    // The filter is dropped, not failed: results come back with a notice
+    let filtered = search(Some("closure_expression"));
+    assert_eq!(filtered.results.len(), 1);
+    assert_eq!(
+        filtered.notice.as_deref(),
+        Some(crate::query::AST_FILTER_IGNORED_NOTICE)
+    );
+}
+
+#[test]
+fn test_max_per_file_keeps_leading_matches_from_each_file() {
+    let (_db_file, conn) = create_test_db();
+    conn.execute(
+        "INSERT INTO graph_entities (id, kind, data) VALUES
+            (2, 'File', '{\"path\":\"/test/alpha.rs\"}'),
+            (13, 'Symbol', '{\"name\":\"late_alpha\",\"kind\":\"Function\",\"symbol_id\":\"sym4\",\"byte_start\":900,\"byte_end\":950,\"start_line\":40,\"start_col\":0,\"end_line\":42,\"end_col\":1}'),
+            (14, 'Symbol', '{\"name\":\"early_alpha\",\"kind\":\"Function\",\"symbol_id\":\"sym5\",\"byte_start\":10,\"byte_end\":50,\"start_line\":2,\"start_col\":0,\"end_line\":4,\"end_col\":1}')",
+        [],
+    )
+    .expect("failed to insert alpha file");
+    conn.execute(
+        "INSERT INTO graph_edges (from_id, to_id, edge_type) VALUES (2, 13, 'DEFINES'), (2, 14, 'DEFINES')",
+        [],
+    )
+    .expect("failed to insert DEFINES edges");
+
+    let options = SearchOptions {
+        db_path: _db_file.path(),
+        query: "",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: None,
+        limit: 3,
+        use_regex: false,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions::default(),
+        snippet: SnippetOptions::default(),
+        fqn: FqnOptions::default(),
+        include_score: false,
+        sort_by: SortMode::Review,
+        metrics: MetricsOptions::default(),
+        ast: AstOptions::default(),
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: None,
+        max_per_file: Some(1),
+        public_only: false,
+        prefer: None,
+    };
+
+    // Without the cap, limit 3 would return both alpha.rs matches first
+    let (response, _, _) = search_symbols(options).expect("search_symbols should succeed");
+    let order: Vec<(&str, &str)> = response
+        .results
+        .iter()
+        .map(|r| (r.span.file_path.as_str(), r.name.as_str()))
+        .collect();
+    assert_eq!(
+        order,
+        vec![
+            ("/test/alpha.rs", "early_alpha"),
+            ("/test/file.rs", "test_func"),
+        ]
+    );
+}
+
+#[test]
+fn test_sort_by_nesting_depth_puts_deepest_decision_depth_first() {
+    let (_db_file, conn) = create_test_db();
+    conn.execute(crate::ast::ast_nodes_table_schema(), [])
+        .expect("failed to create ast_nodes table");
+    // test_func inside an `if`, helper inside `if` > `for`, and TestStruct as
+    // deep in the tree as helper but under no decision point
+    conn.execute(
+        "INSERT INTO ast_nodes (id, parent_id, kind, byte_start, byte_end) VALUES
+            (1, NULL, 'source_file', 0, 1000),
+            (2, 1, 'if_expression', 50, 700),
+            (3, 2, 'function_item', 100, 200),
+            (4, 2, 'for_expression', 450, 650),
+            (5, 4, 'function_item', 500, 600),
+            (6, 1, 'mod_item', 250, 450),
+            (7, 6, 'declaration_list', 260, 440),
+            (8, 7, 'struct_item', 300, 400)",
+        [],
+    )
+    .expect("failed to insert ast nodes");
+
+    let options = SearchOptions {
+        db_path: _db_file.path(),
+        query: "",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: None,
+        limit: 10,
+        use_regex: false,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions::default(),
+        snippet: SnippetOptions::default(),
+        fqn: FqnOptions::default(),
+        include_score: false,
+        sort_by: SortMode::NestingDepth,
+        metrics: MetricsOptions::default(),
+        ast: AstOptions::default(),
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
+    };
+
+    let (response, _, _) = search_symbols(options).expect("search_symbols should succeed");
+    let names: Vec<&str> = response.results.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, vec!["helper", "test_func", "TestStruct"]);
+}
+
+#[test]
+fn test_exclude_paths_drop_matching_prefixes() {
+    let (_db_file, conn) = create_test_db();
+    conn.execute(
+        "INSERT INTO graph_entities (id, kind, data) VALUES
+            (2, 'File', '{\"path\":\"/test/vendor/dep.rs\"}'),
+            (13, 'Symbol', '{\"name\":\"vendored\",\"kind\":\"Function\",\"symbol_id\":\"sym4\",\"byte_start\":10,\"byte_end\":50,\"start_line\":2,\"start_col\":0,\"end_line\":4,\"end_col\":1}')",
+        [],
+    )
+    .expect("failed to insert vendored file");
+    conn.execute(
+        "INSERT INTO graph_edges (from_id, to_id, edge_type) VALUES (2, 13, 'DEFINES')",
+        [],
+    )
+    .expect("failed to insert DEFINES edge");
+
+    let path = std::path::PathBuf::from("/test");
+    let search = |exclude_paths: &[std::path::PathBuf]| {
+        let options = SearchOptions {
+            db_path: _db_file.path(),
+            query: "",
+            path_filter: Some(&path),
+            exclude_paths,
+            path_glob: false,
+            kind_filter: None,
+            limit: 10,
+            use_regex: false,
+            ignore_case: false,
+            candidates: 100,
+            context: ContextOptions::default(),
+            snippet: SnippetOptions::default(),
+            fqn: FqnOptions::default(),
+            include_score: false,
+            sort_by: SortMode::Position,
+            metrics: MetricsOptions::default(),
+            ast: AstOptions::default(),
+            depth: DepthOptions::default(),
+            algorithm: AlgorithmOptions::default(),
+            symbol_id: None,
+            fqn_pattern: None,
+            exact_fqn: None,
+            language_filter: None,
+            coverage_filter: None,
+            file_set: None,
+            max_per_file: None,
+            public_only: false,
+            prefer: None,
+        };
+        let (response, _, _) = search_symbols(options).expect("search_symbols should succeed");
+        assert_eq!(response.total_count, response.results.len() as u64);
+        response
+            .results
+            .into_iter()
+            .map(|r| r.name)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(search(&[]).len(), 4);
+    let vendor = std::path::PathBuf::from("/test/vendor");
+    let kept = search(std::slice::from_ref(&vendor));
+    assert_eq!(kept, vec!["test_func", "TestStruct", "helper"]);
+    // Exclusions stack: dropping the last file as well leaves nothing
+    let file = std::path::PathBuf::from("/test/file.rs");
+    assert!(search(&[vendor, file]).is_empty());
+}
+
+#[test]
+fn test_path_glob_matches_shell_style_patterns() {
+    let (_db_file, conn) = create_test_db();
+    conn.execute(
+        "INSERT INTO graph_entities (id, kind, data) VALUES
+            (2, 'File', '{\"path\":\"/test/100%_done/lib.py\"}'),
+            (13, 'Symbol', '{\"name\":\"finished\",\"kind\":\"Function\",\"symbol_id\":\"sym4\",\"byte_start\":10,\"byte_end\":50,\"start_line\":2,\"start_col\":0,\"end_line\":4,\"end_col\":1}')",
+        [],
+    )
+    .expect("failed to insert second file");
+    conn.execute(
+        "INSERT INTO graph_edges (from_id, to_id, edge_type) VALUES (2, 13, 'DEFINES')",
+        [],
+    )
+    .expect("failed to insert DEFINES edge");
+
+    let search = |pattern: &str, path_glob: bool| {
+        let path = std::path::PathBuf::from(pattern);
+        let options = SearchOptions {
+            db_path: _db_file.path(),
+            query: "",
+            path_filter: Some(&path),
+            exclude_paths: &[],
+            path_glob,
+            kind_filter: None,
+            limit: 10,
+            use_regex: false,
+            ignore_case: false,
+            candidates: 100,
+            context: ContextOptions::default(),
+            snippet: SnippetOptions::default(),
+            fqn: FqnOptions::default(),
+            include_score: false,
+            sort_by: SortMode::Position,
+            metrics: MetricsOptions::default(),
+            ast: AstOptions::default(),
+            depth: DepthOptions::default(),
+            algorithm: AlgorithmOptions::default(),
+            symbol_id: None,
+            fqn_pattern: None,
+            exact_fqn: None,
+            language_filter: None,
+            coverage_filter: None,
+            file_set: None,
+            max_per_file: None,
+            public_only: false,
+            prefer: None,
+        };
+        let (response, _, _) = search_symbols(options).expect("search_symbols should succeed");
+        response
+            .results
+            .into_iter()
+            .map(|r| r.name)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(search("*.py", true), vec!["finished"]);
+    assert_eq!(search("**/file.?s", true).len(), 3);
+    // `%` and `_` in a glob are literal, not LIKE wildcards
+    assert_eq!(search("*/100%_done/*", true), vec!["finished"]);
+    assert!(search("*/1000_done/*", true).is_empty());
+    // Without the flag the same pattern is a literal prefix
+    assert!(search("*.py", false).is_empty());
+}
+
+#[test]
+fn test_snippet_lines_mode_keeps_whole_leading_lines() {
+    let (_db_file, conn) = create_test_db();
+    let mut source = tempfile::NamedTempFile::new().expect("failed to create source file");
+    std::io::Write::write_all(
+        &mut source,
+        b"fn test_func() {\n    let a = 1;\n    let b = 2;\n}\n",
+    )
+    .expect("failed to write source file");
+    let source_path = source.path().to_string_lossy().to_string();
+
+    conn.execute(
+        "UPDATE graph_entities SET data = json_set(data, '$.path', ?1) WHERE id = 1",
+        [&source_path],
+    )
+    .expect("failed to point File entity at source");
+    conn.execute(
+        "UPDATE graph_entities SET data = json_set(data, '$.byte_start', 0, '$.byte_end', 48, '$.start_line', 1, '$.end_line', 4) WHERE id = 10",
+        [],
+    )
+    .expect("failed to update symbol span");
+
+    let search = |max_lines: usize| {
+        let options = SearchOptions {
+            db_path: _db_file.path(),
+            query: "test_func",
+            path_filter: None,
+            exclude_paths: &[],
+            path_glob: false,
+            kind_filter: None,
+            limit: 10,
+            use_regex: false,
+            ignore_case: false,
+            candidates: 100,
+            context: ContextOptions::default(),
+            snippet: SnippetOptions {
+                include: true,
+                max_bytes: 8,
+                mode: SnippetMode::Lines,
+                max_lines,
+                ..Default::default()
+            },
+            fqn: FqnOptions::default(),
+            include_score: false,
+            sort_by: SortMode::Position,
+            metrics: MetricsOptions::default(),
+            ast: AstOptions::default(),
+            depth: DepthOptions::default(),
+            algorithm: AlgorithmOptions::default(),
+            symbol_id: None,
+            fqn_pattern: None,
+            exact_fqn: None,
+            language_filter: None,
+            coverage_filter: None,
+            file_set: None,
+            max_per_file: None,
+            public_only: false,
+            prefer: None,
+        };
+        let (response, _, _) = search_symbols(options).expect("search_symbols should succeed");
+        let result = response
+            .results
+            .into_iter()
+            .next()
+            .expect("test_func matches");
+        (result.snippet, result.snippet_truncated)
+    };
+
+    // The byte cap is ignored in line mode; lines are never cut in half
+    assert_eq!(
+        search(2),
+        (
+            Some("fn test_func() {\n    let a = 1;".to_string()),
+            Some(true)
+        )
+    );
+    assert_eq!(
+        search(10),
+        (
+            Some("fn test_func() {\n    let a = 1;\n    let b = 2;\n}".to_string()),
+            Some(false)
+        )
+    );
+}
+
+#[test]
+fn test_compact_spans_replaces_span_with_loc() {
+    let (_db_file, _conn) = create_test_db();
+    let options = SearchOptions {
+        db_path: _db_file.path(),
+        query: "test_func",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: None,
+        limit: 10,
+        use_regex: false,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions::default(),
+        snippet: SnippetOptions::default(),
+        fqn: FqnOptions::default(),
+        include_score: false,
+        sort_by: SortMode::default(),
+        metrics: MetricsOptions::default(),
+        ast: AstOptions::default(),
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
+    };
+    let (response, _, _) = search_symbols(options).expect("search_symbols should succeed");
+    let span = response.results[0].span.clone();
+
+    let mut json = serde_json::to_value(&response).expect("response should serialize");
+    crate::output::compact_spans(&mut json, false, false);
+    let result = &json["results"][0];
+    assert_eq!(
+        result["loc"],
+        format!("{}:{}:{}", span.file_path, span.start_line, span.start_col)
+    );
+    assert!(result.get("span").is_none());
+    assert!(result.get("span_id").is_none());
+    assert!(result.get("match_id").is_none());
+
+    let mut json = serde_json::to_value(&response).expect("response should serialize");
+    crate::output::compact_spans(&mut json, true, true);
+    let result = &json["results"][0];
+    assert_eq!(result["span_id"], span.span_id);
+    assert!(result.get("match_id").is_some());
+}
+
+#[test]
+fn test_dedupe_keeps_first_result_per_content_hash() {
+    let (_db_file, conn) = create_test_db();
+    let mut source = tempfile::NamedTempFile::new().expect("failed to create source file");
+    std::io::Write::write_all(&mut source, b"fn body() {}\n").expect("failed to write source file");
+    let source_path = source.path().to_string_lossy().to_string();
+
+    conn.execute(
+        "UPDATE graph_entities SET data = json_set(data, '$.path', ?1) WHERE id = 1",
+        [&source_path],
+    )
+    .expect("failed to point File entity at source");
+    // Both symbols cover the same bytes, so their snippets hash identically
+    conn.execute(
+        "UPDATE graph_entities SET data = json_set(data, '$.byte_start', 0, '$.byte_end', 12) WHERE id IN (10, 12)",
+        [],
+    )
+    .expect("failed to update symbol spans");
+
+    let search = |include: bool, dedupe: bool| {
+        let options = SearchOptions {
+            db_path: _db_file.path(),
+            query: "test_func|helper",
+            path_filter: None,
+            exclude_paths: &[],
+            path_glob: false,
+            kind_filter: None,
+            limit: 10,
+            use_regex: true,
+            ignore_case: false,
+            candidates: 100,
+            context: ContextOptions::default(),
+            snippet: SnippetOptions {
+                include,
+                max_bytes: 200,
+                dedupe,
+                ..Default::default()
+            },
+            fqn: FqnOptions::default(),
+            include_score: false,
+            sort_by: SortMode::Position,
+            metrics: MetricsOptions::default(),
+            ast: AstOptions::default(),
+            depth: DepthOptions::default(),
+            algorithm: AlgorithmOptions::default(),
+            symbol_id: None,
+            fqn_pattern: None,
+            exact_fqn: None,
+            language_filter: None,
+            coverage_filter: None,
+            file_set: None,
+            max_per_file: None,
+            public_only: false,
+            prefer: None,
+        };
+        let (response, _, _) = search_symbols(options).expect("search_symbols should succeed");
+        response
+    };
+
+    assert_eq!(search(true, false).results.len(), 2);
+
+    let deduped = search(true, true);
+    assert_eq!(deduped.results.len(), 1);
+    assert_eq!(
+        deduped.notice.as_deref(),
+        Some("--dedupe collapsed 1 result with duplicate content")
+    );
+
+    // Without snippets there is no content hash, so nothing is dropped
+    let unhashed = search(false, true);
+    assert_eq!(unhashed.results.len(), 2);
+    assert_eq!(unhashed.notice, None);
+}
+
+#[test]
+fn test_context_before_and_after_are_independent() {
+    let (_db_file, conn) = create_test_db();
+    let source = tempfile::NamedTempFile::new().expect("failed to create source file");
+    let lines: Vec<String> = (1..=12).map(|i| format!("line{}", i)).collect();
+    std::fs::write(source.path(), lines.join("\n")).expect("failed to write source file");
+    conn.execute(
+        "UPDATE graph_entities SET data = ?1 WHERE id = 1",
+        [serde_json::json!({"path": source.path().to_str().unwrap()}).to_string()],
+    )
+    .expect("failed to point File entity at source");
+
+    let search = |before: Option<usize>, after: Option<usize>| {
+        let options = SearchOptions {
+            db_path: _db_file.path(),
+            query: "test_func",
+            path_filter: None,
+            exclude_paths: &[],
+            path_glob: false,
+            kind_filter: None,
+            limit: 10,
+            use_regex: false,
+            ignore_case: false,
+            candidates: 100,
+            context: ContextOptions {
+                include: true,
+                lines: 1,
+                before,
+                after,
+                max_lines: 20,
+                exclude_self: true,
+            },
+            snippet: SnippetOptions::default(),
+            fqn: FqnOptions::default(),
+            include_score: false,
+            sort_by: SortMode::default(),
+            metrics: MetricsOptions::default(),
+            ast: AstOptions::default(),
+            depth: DepthOptions::default(),
+            algorithm: AlgorithmOptions::default(),
+            symbol_id: None,
+            fqn_pattern: None,
+            exact_fqn: None,
+            language_filter: None,
+            coverage_filter: None,
+            file_set: None,
+            max_per_file: None,
+            public_only: false,
+            prefer: None,
+        };
+        let (response, _, _) = search_symbols(options).expect("search_symbols should succeed");
+        response.results[0]
+            .span
+            .context
+            .clone()
+            .expect("context should be present")
+    };
+
+    // test_func spans lines 5-10
+    let context = search(Some(0), Some(2));
+    assert!(context.before.is_empty());
+    assert_eq!(context.after, vec!["line11", "line12"]);
+    assert!(!context.truncated);
+
+    // Unset sides fall back to `lines`; only two lines follow the span
+    let context = search(None, Some(5));
+    assert_eq!(context.before, vec!["line4"]);
+    assert_eq!(context.after, vec!["line11", "line12"]);
+    assert!(context.truncated);
+}
+
+#[test]
+fn test_streamed_search_hands_over_rows_in_position_order() {
+    let (db_file, conn) = create_test_db();
+
+    let options = SearchOptions {
+        db_path: db_file.path(),
+        query: "e",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: None,
+        limit: 2,
+        use_regex: false,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions::default(),
+        snippet: SnippetOptions::default(),
+        fqn: FqnOptions::default(),
+        include_score: false,
+        sort_by: SortMode::Position,
+        metrics: MetricsOptions::default(),
+        ast: AstOptions::default(),
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
+    };
+    let (buffered, buffered_partial, _) =
+        search_symbols_impl(&conn, db_file.path(), &options, None).unwrap();
+
+    let mut streamed = Vec::new();
+    let (response, partial, _) = search_symbols_impl(
+        &conn,
+        db_file.path(),
+        &options,
+        Some(&mut |row| {
+            streamed.push(row);
+            Ok(())
+        }),
+    )
+    .unwrap();
+
+    assert!(response.results.is_empty());
+    assert_eq!(response.total_count, buffered.total_count);
+    assert_eq!(partial, buffered_partial);
+    let names = |results: &[crate::output::SymbolMatch]| {
+        results.iter().map(|r| r.name.clone()).collect::<Vec<_>>()
+    };
+    assert_eq!(names(&streamed), names(&buffered.results));
+
+    // Any other sort needs every row before the first can be written
+    let relevance = SearchOptions {
+        sort_by: SortMode::Relevance,
+        ..options
+    };
+    let err = search_symbols_impl(&conn, db_file.path(), &relevance, Some(&mut |_| Ok(())))
+        .unwrap_err();
+    assert!(matches!(err, crate::error::LlmError::InvalidQuery { .. }));
+    assert!(err.to_string().contains("--sort-by position"));
+}
@@ -0,0 +1,70 @@
+use super::*;
+
+#[test]
+fn test_slice_dot_highlights_seed_and_draws_slice_edges() {
+    let (db_file, conn) = create_test_db();
+    conn.execute(
+        "INSERT INTO graph_edges (from_id, to_id, edge_type) VALUES (10, 12, 'CALLS'), (10, 11, 'USES')",
+        [],
+    )
+    .expect("failed to insert slice edges");
+
+    let options = SearchOptions {
+        db_path: db_file.path(),
+        query: "",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: None,
+        limit: 10,
+        use_regex: false,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions::default(),
+        snippet: SnippetOptions::default(),
+        fqn: FqnOptions::default(),
+        include_score: false,
+        sort_by: SortMode::default(),
+        metrics: MetricsOptions::default(),
+        ast: AstOptions::default(),
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
+    };
+    let (response, _, _) = search_symbols(options).expect("search_symbols should succeed");
+
+    // Slice of test_func: itself and helper; TestStruct is outside the slice.
+    let slice: Vec<_> = response
+        .results
+        .into_iter()
+        .filter(|r| r.name != "TestStruct")
+        .collect();
+    let ids: Vec<String> = slice.iter().filter_map(|r| r.symbol_id.clone()).collect();
+
+    let edges = slice_edges(&conn, &ids).expect("slice_edges should succeed");
+    assert_eq!(
+        edges,
+        vec![SliceEdge {
+            from: "sym1".to_string(),
+            to: "sym3".to_string(),
+            edge_type: "CALLS".to_string(),
+        }]
+    );
+
+    let dot = render_slice_dot(&slice, "test_func", &edges);
+    assert!(dot.starts_with("digraph slice {"));
+    assert!(dot.contains(
+        "\"sym1\" [label=\"test_func\\nFunction\", style=filled, fillcolor=gold, penwidth=2];"
+    ));
+    assert!(dot.contains("\"sym3\" [label=\"helper\\nFunction\"];"));
+    assert!(dot.contains("\"sym1\" -> \"sym3\" [label=\"CALLS\"];"));
+    assert!(!dot.contains("sym2"));
+}
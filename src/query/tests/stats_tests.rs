@@ -0,0 +1,38 @@
+use super::*;
+
+#[test]
+fn test_run_stats_reports_composition() {
+    let (db_file, conn) = create_test_db();
+    conn.execute(
+        "INSERT INTO graph_entities (id, kind, data) VALUES
+            (2, 'File', '{\"path\":\"/test/tool.py\"}'),
+            (13, 'Symbol', '{\"name\":\"main\",\"kind\":\"Function\"}'),
+            (20, 'Reference', '{\"referenced_symbol\":\"helper\"}'),
+            (21, 'Call', '{\"caller\":\"test_func\",\"callee\":\"helper\"}')",
+        [],
+    )
+    .expect("failed to insert extra entities");
+    conn.execute(
+        "INSERT INTO graph_edges (from_id, to_id, edge_type) VALUES (2, 13, 'DEFINES')",
+        [],
+    )
+    .expect("failed to insert DEFINES edge");
+
+    let stats = run_stats(&conn, db_file.path()).expect("run_stats should succeed");
+    let composition = stats.composition;
+    assert_eq!(composition.files, 2);
+    assert_eq!(composition.symbols, 4);
+    assert_eq!(composition.references, 1);
+    assert_eq!(composition.calls, 1);
+    assert_eq!(
+        composition.symbols_by_language,
+        vec![("Rust".to_string(), 3), ("Python".to_string(), 1)]
+    );
+    assert_eq!(
+        composition.symbols_by_kind,
+        vec![("Function".to_string(), 3), ("Struct".to_string(), 1)]
+    );
+    assert_eq!(composition.metrics_rows, 0);
+    assert!(!composition.has_ast_nodes);
+    assert!(!composition.has_code_chunks);
+}
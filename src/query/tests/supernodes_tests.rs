@@ -0,0 +1,86 @@
+use super::*;
+
+#[test]
+fn test_group_supernodes_marks_members_entered_from_outside() {
+    let (db_file, conn) = create_test_db();
+    // test_func <-> helper form a cycle; TestStruct calls into it via helper
+    conn.execute(
+        "INSERT INTO graph_edges (from_id, to_id, edge_type) VALUES
+            (10, 12, 'CALLS'), (12, 10, 'CALLS'), (11, 12, 'CALLS')",
+        [],
+    )
+    .expect("failed to insert call edges");
+    // Call inside the cycle, and one from a symbol outside every SCC
+    conn.execute(
+        "INSERT INTO graph_entities (id, kind, data) VALUES
+            (30, 'Call', '{\"caller\":\"test_func\",\"callee\":\"helper\",\"caller_symbol_id\":\"sym1\",\"callee_symbol_id\":\"sym3\"}'),
+            (31, 'Call', '{\"caller\":\"main\",\"callee\":\"TestStruct\",\"caller_symbol_id\":\"sym9\",\"callee_symbol_id\":\"sym2\"}')",
+        [],
+    )
+    .expect("failed to insert Call entities");
+
+    let options = SearchOptions {
+        db_path: db_file.path(),
+        query: "",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: None,
+        limit: 10,
+        use_regex: false,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions::default(),
+        snippet: SnippetOptions::default(),
+        fqn: FqnOptions::default(),
+        include_score: false,
+        sort_by: SortMode::Position,
+        metrics: MetricsOptions::default(),
+        ast: AstOptions::default(),
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
+    };
+    let (mut response, _, _) = search_symbols(options).expect("search_symbols should succeed");
+    for result in &mut response.results {
+        let supernode = if result.name == "TestStruct" {
+            "supernode_1"
+        } else {
+            "supernode_0"
+        };
+        result.supernode_id = Some(supernode.to_string());
+    }
+
+    let groups = group_supernodes(&conn, &response.results).expect("grouping should succeed");
+    let names = |members: &[SupernodeMember]| {
+        let mut names: Vec<String> = members.iter().map(|m| m.name.clone()).collect();
+        names.sort();
+        names
+    };
+
+    assert_eq!(groups.supernodes.len(), 2);
+    let cycle = &groups.supernodes[0];
+    assert_eq!(cycle.id, "supernode_0");
+    assert_eq!(cycle.size, 2);
+    assert_eq!(names(&cycle.members), vec!["helper", "test_func"]);
+    assert_eq!(names(&cycle.entry_points), vec!["helper"]);
+
+    let single = &groups.supernodes[1];
+    assert_eq!(single.id, "supernode_1");
+    assert_eq!(names(&single.members), vec!["TestStruct"]);
+    assert_eq!(names(&single.entry_points), vec!["TestStruct"]);
+
+    let json = serde_json::to_value(&groups).unwrap();
+    assert_eq!(
+        json["supernodes"][0]["entry_points"][0]["symbol_id"],
+        "sym3"
+    );
+}
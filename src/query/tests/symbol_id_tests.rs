@@ -11,9 +11,12 @@ fn test_symbol_id_lookup_returns_single_result() {
         db_path,
         query: "unused",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -29,6 +32,10 @@ fn test_symbol_id_lookup_returns_single_result() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, partial, _) = search_symbols(options).expect("search_symbols should succeed");
@@ -51,9 +58,12 @@ fn test_fqn_pattern_filter() {
         db_path,
         query: "test",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -69,6 +79,10 @@ fn test_fqn_pattern_filter() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, _partial, _) = search_symbols(options).expect("search_symbols should succeed");
@@ -87,9 +101,12 @@ fn test_exact_fqn_filter() {
         db_path,
         query: "",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -97,6 +114,8 @@ fn test_exact_fqn_filter() {
             fqn: false,
             canonical_fqn: true,
             display_fqn: false,
+            normalize: false,
+            defined_in: false,
         },
         include_score: false,
         sort_by: SortMode::default(),
@@ -109,6 +128,10 @@ fn test_exact_fqn_filter() {
         exact_fqn: Some("/test/file.rs::test_func"),
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, _partial, _) = search_symbols(options).expect("search_symbols should succeed");
@@ -133,9 +156,12 @@ fn test_symbol_id_included_in_json_output() {
         db_path,
         query: "test",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -143,6 +169,8 @@ fn test_symbol_id_included_in_json_output() {
             fqn: false,
             canonical_fqn: true,
             display_fqn: true,
+            normalize: false,
+            defined_in: false,
         },
         include_score: false,
         sort_by: SortMode::default(),
@@ -155,6 +183,10 @@ fn test_symbol_id_included_in_json_output() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, _partial, _) = search_symbols(options).expect("search_symbols should succeed");
@@ -237,9 +269,12 @@ fn test_ambiguity_detection_with_duplicate_names() {
         db_path,
         query: "parse",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -247,6 +282,8 @@ fn test_ambiguity_detection_with_duplicate_names() {
             fqn: false,
             canonical_fqn: true,
             display_fqn: false,
+            normalize: false,
+            defined_in: false,
         },
         include_score: false,
         sort_by: SortMode::default(),
@@ -259,6 +296,10 @@ fn test_ambiguity_detection_with_duplicate_names() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, _partial, _) = search_symbols(options).expect("search_symbols should succeed");
@@ -335,9 +376,12 @@ fn test_symbol_id_bypasses_ambiguity() {
         db_path,
         query: "ignored",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -345,6 +389,8 @@ fn test_symbol_id_bypasses_ambiguity() {
             fqn: false,
             canonical_fqn: true,
             display_fqn: false,
+            normalize: false,
+            defined_in: false,
         },
         include_score: false,
         sort_by: SortMode::default(),
@@ -357,6 +403,10 @@ fn test_symbol_id_bypasses_ambiguity() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, _partial, _) = search_symbols(options).expect("search_symbols should succeed");
@@ -408,6 +458,9 @@ fn test_build_search_query_with_language_filter() {
     let (sql, params, _strategy) = build_search_query(
         "test",
         None,
+        false,
+        &[],
+        None,
         None,
         Some("rust"),
         false,
@@ -419,6 +472,8 @@ fn test_build_search_query_with_language_filter() {
         None,
         None,
         false,
+        false,
+        true,
         &[],
         None,
         None,
@@ -439,6 +494,9 @@ fn test_build_search_query_with_unknown_language() {
     let (_sql, params, _) = build_search_query(
         "test",
         None,
+        false,
+        &[],
+        None,
         None,
         Some("unknown_language"),
         false,
@@ -450,6 +508,8 @@ fn test_build_search_query_with_unknown_language() {
         None,
         None,
         false,
+        false,
+        true,
         &[],
         None,
         None,
@@ -470,6 +530,9 @@ fn test_build_search_query_combined_language_and_kind() {
     let (sql, params, _strategy) = build_search_query(
         "test",
         Some(&path),
+        false,
+        &[],
+        None,
         Some("Function"),
         Some("python"),
         false,
@@ -481,6 +544,8 @@ fn test_build_search_query_combined_language_and_kind() {
         None,
         None,
         false,
+        false,
+        true,
         &[],
         None,
         None,
@@ -503,6 +568,9 @@ fn test_build_search_query_with_cpp_language() {
     let (sql, params, _strategy) = build_search_query(
         "test",
         None,
+        false,
+        &[],
+        None,
         None,
         Some("cpp"),
         false,
@@ -514,6 +582,8 @@ fn test_build_search_query_with_cpp_language() {
         None,
         None,
         false,
+        false,
+        true,
         &[],
         None,
         None,
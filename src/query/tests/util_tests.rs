@@ -1,14 +1,13 @@
 use super::*;
 use crate::algorithm::AlgorithmOptions;
 use crate::error::LlmError;
-use std::collections::HashMap;
 
 #[test]
 fn test_load_file_returns_none_on_missing_file() {
-    let mut cache = HashMap::new();
+    let mut cache = SourceCache::default();
     let result = load_file("/nonexistent/path/to/file.rs", &mut cache);
     assert!(result.is_none());
-    assert!(!cache.contains_key("/nonexistent/path/to/file.rs"));
+    assert!(!cache.files.contains_key("/nonexistent/path/to/file.rs"));
 }
 
 #[test]
@@ -20,7 +19,7 @@ fn test_load_file_caches_successful_reads() {
     file.write_all(b"line1\nline2\nline3")
         .expect("failed to execute SQL");
 
-    let mut cache = HashMap::new();
+    let mut cache = SourceCache::default();
     let path_str = temp_file
         .to_str()
         .expect("failed to convert path to string");
@@ -31,11 +30,56 @@ fn test_load_file_caches_successful_reads() {
 
     let result2 = load_file(path_str, &mut cache);
     assert!(result2.is_some());
-    assert_eq!(cache.len(), 1);
+    assert_eq!(cache.files.len(), 1);
 
     std::fs::remove_file(&temp_file).ok();
 }
 
+#[test]
+fn test_read_with_timeout_gives_up_on_slow_read() {
+    use std::time::{Duration, Instant};
+    let started = Instant::now();
+    let result = read_with_timeout("/slow/file.rs", Duration::from_millis(50), |_| {
+        std::thread::sleep(Duration::from_secs(5));
+        Ok(b"too late".to_vec())
+    });
+    assert_eq!(
+        result.expect_err("slow read should time out").kind(),
+        std::io::ErrorKind::TimedOut
+    );
+    assert!(started.elapsed() < Duration::from_secs(2));
+
+    let fast = read_with_timeout("/fast/file.rs", Duration::from_secs(5), |_| {
+        Ok(b"fn main() {}".to_vec())
+    });
+    assert_eq!(fast.expect("fast read should succeed"), b"fn main() {}");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_load_file_treats_timed_out_read_as_unreadable() {
+    use std::time::Duration;
+    // Opening a FIFO with no writer blocks, like a stalled network mount.
+    let dir = tempfile::tempdir().expect("failed to create temp dir");
+    let fifo = dir.path().join("stalled.rs");
+    let created = std::process::Command::new("mkfifo")
+        .arg(&fifo)
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(false);
+    if !created {
+        eprintln!("SKIP: mkfifo unavailable");
+        return;
+    }
+    let path = fifo.to_str().unwrap();
+
+    let mut cache = SourceCache::new(Some(Duration::from_millis(50)));
+    assert!(load_file(path, &mut cache).is_none());
+    // The stalled file is remembered and not waited on again.
+    assert!(load_file(path, &mut cache).is_none());
+    assert!(cache.files.is_empty());
+}
+
 #[test]
 fn test_search_symbols_corrupted_database() {
     use std::io::Write;
@@ -52,9 +96,12 @@ fn test_search_symbols_corrupted_database() {
         db_path: &fake_db,
         query: "test",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 50,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -70,6 +117,10 @@ fn test_search_symbols_corrupted_database() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     });
 
     match result {
@@ -80,3 +131,80 @@ fn test_search_symbols_corrupted_database() {
 
     std::fs::remove_file(&fake_db).ok();
 }
+
+#[test]
+fn test_number_snippet_lines_prefixes_source_lines() {
+    let snippet = "/// Docs\nfn parse(input: &str) {\n    todo!()\n}";
+    let numbered = number_snippet_lines(snippet, 9, "parse");
+    assert_eq!(
+        numbered,
+        "   9 | /// Docs\n> 10 | fn parse(input: &str) {\n  11 |     todo!()\n  12 | }"
+    );
+}
+
+#[test]
+fn test_sort_by_file_mtime_newest_first_missing_last() {
+    use std::time::{Duration, SystemTime};
+    let older = tempfile::NamedTempFile::new().expect("failed to create temp file");
+    let newer = tempfile::NamedTempFile::new().expect("failed to create temp file");
+    let now = SystemTime::now();
+    older
+        .as_file()
+        .set_modified(now - Duration::from_secs(3600))
+        .expect("failed to set mtime");
+    newer.as_file().set_modified(now).expect("failed to set mtime");
+
+    let older_path = older.path().to_str().unwrap();
+    let newer_path = newer.path().to_str().unwrap();
+    let mut items = vec!["/nonexistent/llmgrep_mtime.rs", older_path, newer_path];
+    sort_by_file_mtime(&mut items, |p| p);
+
+    assert_eq!(items, vec![newer_path, older_path, "/nonexistent/llmgrep_mtime.rs"]);
+}
+
+#[test]
+fn test_normalize_fqn_unifies_separators() {
+    assert_eq!(
+        normalize_fqn("pkg.module.Class.method"),
+        "pkg::module::Class::method"
+    );
+    assert_eq!(normalize_fqn("src/lib::Foo"), "src::lib::Foo");
+    assert_eq!(normalize_fqn("crate::Foo::bar"), "crate::Foo::bar");
+}
+
+#[test]
+fn test_cap_snippet_lines_keeps_whole_lines() {
+    use crate::query::util::cap_snippet_lines;
+    assert_eq!(
+        cap_snippet_lines("a\r\nb\nc\n", 2),
+        ("a\r\nb".to_string(), true)
+    );
+    assert_eq!(
+        cap_snippet_lines("a\nb\n", 2),
+        ("a\nb\n".to_string(), false)
+    );
+    assert_eq!(cap_snippet_lines("a\nb", 5), ("a\nb".to_string(), false));
+}
+
+#[test]
+fn test_next_row_ends_an_interrupted_scan_as_timed_out() {
+    use crate::query::util::next_row;
+    let conn = Connection::open_in_memory().unwrap();
+    let mut stmt = conn
+        .prepare("WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n) SELECT i FROM n")
+        .unwrap();
+    let mut rows = stmt.query([]).unwrap();
+
+    let mut timed_out = false;
+    let first: i64 = next_row(&mut rows, &mut timed_out)
+        .unwrap()
+        .expect("first row")
+        .get(0)
+        .unwrap();
+    assert_eq!(first, 1);
+    assert!(!timed_out);
+
+    crate::backend::install_query_deadline(&conn, std::time::Instant::now());
+    while next_row(&mut rows, &mut timed_out).unwrap().is_some() {}
+    assert!(timed_out);
+}
@@ -2,11 +2,17 @@
 //!
 //! This module provides helper functions for file loading, snippet extraction,
 /// scoring, and ID generation.
-use crate::output::SpanContext;
+use crate::backend::open_connection_with_flags;
+use crate::error::LlmError;
+use crate::output::{DefinedIn, Span, SpanContext};
 use regex::Regex;
+use rusqlite::{Connection, ErrorCode, OpenFlags};
 use sha2::{Digest, Sha256};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::io;
 use std::path::Path;
+use std::sync::mpsc;
+use std::time::Duration;
 
 pub(crate) const MAX_REGEX_SIZE: usize = 10_000; // 10KB limit to prevent memory exhaustion
 
@@ -41,6 +47,29 @@ pub fn infer_language(file_path: &str) -> Option<&'static str> {
     }
 }
 
+/// Build a symbol's `defined_in` provenance from its defining File entity.
+///
+/// `language` and `size` recorded in the File entity's data win; otherwise
+/// the language is inferred from the extension and the size read from disk.
+pub(crate) fn defined_in(file_id: i64, file_path: &str, file_data: Option<&str>) -> DefinedIn {
+    let data: serde_json::Value = file_data
+        .and_then(|d| serde_json::from_str(d).ok())
+        .unwrap_or_default();
+    DefinedIn {
+        file_id,
+        path: file_path.to_string(),
+        language: data
+            .get("language")
+            .and_then(|l| l.as_str())
+            .or_else(|| infer_language(file_path))
+            .map(str::to_string),
+        size_bytes: data
+            .get("size")
+            .and_then(|s| s.as_u64())
+            .or_else(|| std::fs::metadata(file_path).ok().map(|m| m.len())),
+    }
+}
+
 /// Normalize symbol kind to standard label name
 ///
 /// Converts various kind representations to lowercase normalized form.
@@ -49,6 +78,18 @@ pub(crate) fn normalize_kind_label(kind: &str) -> String {
     kind.to_lowercase()
 }
 
+/// Normalize FQN separators to `::`
+///
+/// Python/Java `.` and path `/` separators become `::`, so one FQN syntax
+/// matches across languages. Must stay in sync with [`NORMALIZED_CANONICAL_FQN_SQL`].
+pub(crate) fn normalize_fqn(fqn: &str) -> String {
+    fqn.replace(['.', '/'], "::")
+}
+
+/// SQL expression applying [`normalize_fqn`] to a symbol's `canonical_fqn`
+pub(crate) const NORMALIZED_CANONICAL_FQN_SQL: &str =
+    "replace(replace(json_extract(s.data, '$.canonical_fqn'), '.', '::'), '/', '::')";
+
 /// Create a LIKE pattern for SQL queries
 pub(crate) fn like_pattern(query: &str) -> String {
     let escaped = query
@@ -68,6 +109,41 @@ pub(crate) fn like_prefix(path: &std::path::Path) -> String {
     format!("{}%", escaped)
 }
 
+/// Translate a shell-style glob into a LIKE pattern for SQL queries
+///
+/// `*` (and `**`) becomes `%` and `?` becomes `_`; literal `%`, `_` and `\\`
+/// are escaped. Like LIKE itself, `*` also matches across `/`.
+pub(crate) fn like_glob(pattern: &str) -> String {
+    let mut out = String::with_capacity(pattern.len());
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' => {
+                while chars.peek() == Some(&'*') {
+                    chars.next();
+                }
+                out.push('%');
+            }
+            '?' => out.push('_'),
+            '\\' | '%' | '_' => {
+                out.push('\\');
+                out.push(c);
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// LIKE pattern for a `--path` value: a glob when `glob` is set, else a prefix
+pub(crate) fn like_path(path: &std::path::Path, glob: bool) -> String {
+    if glob {
+        like_glob(&path.to_string_lossy())
+    } else {
+        like_prefix(path)
+    }
+}
+
 /// Extract the referenced symbol name from a reference name
 pub(crate) fn referenced_symbol_from_name(name: &str) -> String {
     name.strip_prefix("ref to ").unwrap_or(name).to_string()
@@ -79,24 +155,102 @@ pub(crate) struct FileCache {
     pub(crate) lines: Vec<String>,
 }
 
+/// Source files read during one search, keyed by path
+#[derive(Default)]
+pub(crate) struct SourceCache {
+    pub(crate) files: HashMap<String, FileCache>,
+    /// Upper bound for reading one file; `None` waits indefinitely
+    read_timeout: Option<Duration>,
+    /// Files whose read timed out; they are not retried
+    timed_out: HashSet<String>,
+}
+
+impl SourceCache {
+    pub(crate) fn new(read_timeout: Option<Duration>) -> Self {
+        Self {
+            read_timeout,
+            ..Self::default()
+        }
+    }
+}
+
+/// Run `read` on a helper thread and give up after `timeout`.
+///
+/// A read that misses the deadline keeps running on its detached thread; its
+/// result is discarded.
+pub(crate) fn read_with_timeout<F>(path: &str, timeout: Duration, read: F) -> io::Result<Vec<u8>>
+where
+    F: FnOnce(&str) -> io::Result<Vec<u8>> + Send + 'static,
+{
+    let (tx, rx) = mpsc::channel();
+    let owned = path.to_string();
+    std::thread::spawn(move || {
+        let _ = tx.send(read(&owned));
+    });
+    match rx.recv_timeout(timeout) {
+        Ok(result) => result,
+        Err(_) => Err(io::Error::new(
+            io::ErrorKind::TimedOut,
+            format!("read did not finish within {}ms", timeout.as_millis()),
+        )),
+    }
+}
+
 /// Load a file into the cache
-pub(crate) fn load_file<'a>(
-    path: &str,
-    cache: &'a mut HashMap<String, FileCache>,
-) -> Option<&'a FileCache> {
-    if !cache.contains_key(path) {
-        let bytes = match std::fs::read(path) {
+pub(crate) fn load_file<'a>(path: &str, cache: &'a mut SourceCache) -> Option<&'a FileCache> {
+    if cache.timed_out.contains(path) {
+        return None;
+    }
+    if !cache.files.contains_key(path) {
+        let read = match cache.read_timeout {
+            Some(timeout) => read_with_timeout(path, timeout, |p| std::fs::read(p)),
+            None => std::fs::read(path),
+        };
+        let bytes = match read {
             Ok(bytes) => bytes,
             Err(e) => {
                 eprintln!("Warning: Failed to read file '{}': {}", path, e);
+                if e.kind() == io::ErrorKind::TimedOut {
+                    cache.timed_out.insert(path.to_string());
+                }
                 return None;
             }
         };
         let text = String::from_utf8_lossy(&bytes);
         let lines = text.split('\n').map(|line| line.to_string()).collect();
-        cache.insert(path.to_string(), FileCache { bytes, lines });
+        cache
+            .files
+            .insert(path.to_string(), FileCache { bytes, lines });
     }
-    cache.get(path)
+    cache.files.get(path)
+}
+
+/// Text of one 1-based line of a file, without its line terminator
+pub(crate) fn line_text_from_file(
+    file_path: &str,
+    line: u64,
+    cache: &mut SourceCache,
+) -> Option<String> {
+    let file = load_file(file_path, cache)?;
+    let index = usize::try_from(line.checked_sub(1)?).ok()?;
+    file.lines
+        .get(index)
+        .map(|text| text.trim_end_matches('\r').to_string())
+}
+
+/// SHA-256 (hex) of the bytes a span covers in a file.
+///
+/// Hashes the whole span, not a `max_bytes`-capped snippet, so file-fallback
+/// results hash the same content as a code chunk for that span would.
+pub(crate) fn content_hash_from_file(
+    file_path: &str,
+    byte_start: u64,
+    byte_end: u64,
+    cache: &mut SourceCache,
+) -> Option<String> {
+    let file = load_file(file_path, cache)?;
+    let bytes = file.bytes.get(byte_start as usize..byte_end as usize)?;
+    Some(hex::encode(Sha256::digest(bytes)))
 }
 
 /// Extract a snippet from a file
@@ -105,7 +259,7 @@ pub(crate) fn snippet_from_file(
     byte_start: u64,
     byte_end: u64,
     max_bytes: usize,
-    cache: &mut HashMap<String, FileCache>,
+    cache: &mut SourceCache,
 ) -> (Option<String>, Option<bool>) {
     if max_bytes == 0 {
         return (None, None);
@@ -137,14 +291,371 @@ pub(crate) fn snippet_from_file(
     (Some(snippet), Some(truncated))
 }
 
-/// Extract context lines from a file
+/// Keep the first `max_lines` lines of a snippet.
+///
+/// Returns the kept text and whether any lines were dropped.
+pub(crate) fn cap_snippet_lines(content: &str, max_lines: usize) -> (String, bool) {
+    let mut lines = content.split_inclusive('\n');
+    let kept: String = lines.by_ref().take(max_lines).collect();
+    if lines.next().is_some() {
+        (kept.trim_end_matches(['\n', '\r']).to_string(), true)
+    } else {
+        (kept, false)
+    }
+}
+
+/// Extract a span from a file as a snippet of at most `max_lines` lines
+pub(crate) fn snippet_lines_from_file(
+    file_path: &str,
+    byte_start: u64,
+    byte_end: u64,
+    max_lines: usize,
+    cache: &mut SourceCache,
+) -> (Option<String>, Option<bool>) {
+    let span_len = byte_end.saturating_sub(byte_start) as usize;
+    match snippet_from_file(file_path, byte_start, byte_end, span_len, cache) {
+        (Some(span), _) => {
+            let (snippet, truncated) = cap_snippet_lines(&span, max_lines);
+            (Some(snippet), Some(truncated))
+        }
+        (None, truncated) => (None, truncated),
+    }
+}
+
+/// Stable-sort items by the modification time of their file, newest first.
+///
+/// Each distinct path is stat-ed once. Items whose file cannot be stat-ed
+/// (missing, permission denied) sort after all others.
+pub(crate) fn sort_by_file_mtime<T>(items: &mut [T], path: impl Fn(&T) -> &str) {
+    let mut mtimes: HashMap<String, Option<std::time::SystemTime>> = HashMap::new();
+    for item in items.iter() {
+        let p = path(item);
+        if !mtimes.contains_key(p) {
+            let mtime = std::fs::metadata(p).and_then(|m| m.modified()).ok();
+            mtimes.insert(p.to_string(), mtime);
+        }
+    }
+    // Option orders None before Some, so compare b to a for newest-first, None-last
+    items.sort_by(|a, b| mtimes[path(b)].cmp(&mtimes[path(a)]));
+}
+
+/// Keep at most `max` items per file, dropping the later ones.
+///
+/// Run after sorting so each file keeps its best-ranked items; the relative
+/// order of the survivors is unchanged.
+pub(crate) fn cap_per_file<T>(items: &mut Vec<T>, max: usize, path: impl Fn(&T) -> &str) {
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    items.retain(|item| {
+        let count = seen.entry(path(item).to_string()).or_default();
+        *count += 1;
+        *count <= max
+    });
+}
+
+/// Next row of a search scan, or `None` once `--timeout` interrupts it.
+///
+/// Sets `timed_out` on interruption so the caller can keep the rows read so
+/// far as a partial result instead of failing the whole search.
+pub(crate) fn next_row<'a, 'stmt>(
+    rows: &'a mut rusqlite::Rows<'stmt>,
+    timed_out: &mut bool,
+) -> Result<Option<&'a rusqlite::Row<'stmt>>, rusqlite::Error> {
+    match rows.next() {
+        Err(rusqlite::Error::SqliteFailure(err, _))
+            if err.code == rusqlite::ErrorCode::OperationInterrupted =>
+        {
+            *timed_out = true;
+            Ok(None)
+        }
+        next => next,
+    }
+}
+
+/// Warn that `--timeout` cut a search scan short after `kept` matches.
+pub(crate) fn warn_timed_out(kept: u64) {
+    eprintln!(
+        "Warning: --timeout reached after {} matches; results are partial",
+        kept
+    );
+}
+
+/// Callback that receives each match as a search's row loop builds it.
+pub type RowSink<'a, T> = &'a mut dyn FnMut(T) -> Result<(), LlmError>;
+
+/// Reject options a streamed search cannot honour one row at a time.
+///
+/// Rows are handed over in SQL order, so only `--sort-by position` (which
+/// needs no in-memory sort) can stream, and `--prefer` has to see every row
+/// with a name before it can pick one.
+pub(crate) fn check_streamable(options: &crate::query::SearchOptions) -> Result<(), LlmError> {
+    if options.sort_by != crate::SortMode::Position {
+        return Err(LlmError::InvalidQuery {
+            query: "--output ndjson writes matches as they are read and requires --sort-by position"
+                .to_string(),
+        });
+    }
+    if options.prefer.is_some() {
+        return Err(LlmError::InvalidQuery {
+            query: "--output ndjson cannot be combined with --prefer".to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Hands rows to a [`RowSink`] as they are read, applying `--dedupe`,
+/// `--max-per-file` and `--limit` one row at a time in place of the passes
+/// over collected results.
+pub(crate) struct RowStream<'a, T> {
+    sink: RowSink<'a, T>,
+    limit: usize,
+    max_per_file: Option<usize>,
+    content_hash: Option<fn(&T) -> Option<&str>>,
+    path: fn(&T) -> &str,
+    per_file: HashMap<String, usize>,
+    hashes: HashSet<String>,
+    written: usize,
+    /// Rows dropped by `--dedupe`
+    pub(crate) deduped: usize,
+}
+
+impl<'a, T> RowStream<'a, T> {
+    pub(crate) fn new(
+        sink: RowSink<'a, T>,
+        limit: usize,
+        max_per_file: Option<usize>,
+        content_hash: Option<fn(&T) -> Option<&str>>,
+        path: fn(&T) -> &str,
+    ) -> Self {
+        Self {
+            sink,
+            limit,
+            max_per_file,
+            content_hash,
+            path,
+            per_file: HashMap::new(),
+            hashes: HashSet::new(),
+            written: 0,
+            deduped: 0,
+        }
+    }
+
+    /// Whether `limit` rows have been written; later rows are only counted.
+    pub(crate) fn is_full(&self) -> bool {
+        self.written >= self.limit
+    }
+
+    /// Write `row` unless the limit is reached or `--dedupe`/`--max-per-file`
+    /// drops it.
+    pub(crate) fn push(&mut self, row: T) -> Result<(), LlmError> {
+        if self.is_full() {
+            return Ok(());
+        }
+        if let Some(hash) = self.content_hash.and_then(|hash| hash(&row)) {
+            if !self.hashes.insert(hash.to_string()) {
+                self.deduped += 1;
+                return Ok(());
+            }
+        }
+        if let Some(max) = self.max_per_file {
+            let count = self.per_file.entry((self.path)(&row).to_string()).or_default();
+            *count += 1;
+            if *count > max {
+                return Ok(());
+            }
+        }
+        self.written += 1;
+        (self.sink)(row)
+    }
+}
+
+/// Open `db_path` read-only and force SQLite to validate it.
+///
+/// Corrupt or non-SQLite files surface as [`LlmError::DatabaseCorrupted`] and
+/// a missing file as [`LlmError::DatabaseNotFound`]; busy errors pass through
+/// unchanged so callers can wrap this in [`crate::backend::retry_busy`].
+pub(crate) fn open_readonly_validated(db_path: &Path) -> Result<Connection, LlmError> {
+    let conn = match open_connection_with_flags(db_path, OpenFlags::SQLITE_OPEN_READ_ONLY) {
+        Ok(conn) => conn,
+        Err(rusqlite::Error::SqliteFailure(err, msg)) => match err.code {
+            ErrorCode::DatabaseCorrupt | ErrorCode::NotADatabase => {
+                return Err(LlmError::DatabaseCorrupted {
+                    reason: msg
+                        .unwrap_or_else(|| "Database file is invalid or corrupted".to_string()),
+                });
+            }
+            ErrorCode::CannotOpen => {
+                return Err(LlmError::DatabaseNotFound {
+                    path: db_path.display().to_string(),
+                });
+            }
+            _ => return Err(LlmError::from(rusqlite::Error::SqliteFailure(err, msg))),
+        },
+        Err(e) => return Err(LlmError::from(e)),
+    };
+
+    // Force database validation by checking if schema exists
+    // This catches "not a database" errors that occur lazily
+    conn.query_row(
+        "SELECT name FROM sqlite_master WHERE type='table' LIMIT 1",
+        [],
+        |_| Ok(()),
+    )
+    .map_err(|e| match e {
+        rusqlite::Error::SqliteFailure(err, ref msg) => match err.code {
+            ErrorCode::DatabaseCorrupt | ErrorCode::NotADatabase => LlmError::DatabaseCorrupted {
+                reason: msg
+                    .as_ref()
+                    .map(|s| s.as_str())
+                    .unwrap_or("Database file is invalid or corrupted")
+                    .to_string(),
+            },
+            _ => LlmError::from(e),
+        },
+        other => LlmError::from(other),
+    })
+    .map(|()| conn)
+}
+
+/// Look up the definition span of each symbol ID, keyed by symbol ID.
+///
+/// The definition file comes from the `DEFINES` edge of the symbol's `File`
+/// entity. IDs that are not indexed are absent from the map; when an ID is
+/// defined more than once the lowest entity id wins.
+pub(crate) fn definition_spans<'a>(
+    conn: &rusqlite::Connection,
+    symbol_ids: impl Iterator<Item = &'a str>,
+) -> Result<HashMap<String, Span>, rusqlite::Error> {
+    let mut symbol_ids: Vec<&str> = symbol_ids.collect();
+    symbol_ids.sort_unstable();
+    symbol_ids.dedup();
+    if symbol_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let placeholders = symbol_ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+    let sql = format!(
+        "SELECT json_extract(s.data, '$.symbol_id'), json_extract(f.data, '$.path'),
+                json_extract(s.data, '$.byte_start'), json_extract(s.data, '$.byte_end'),
+                json_extract(s.data, '$.start_line'), json_extract(s.data, '$.start_col'),
+                json_extract(s.data, '$.end_line'), json_extract(s.data, '$.end_col')
+         FROM graph_entities s
+         JOIN graph_edges e ON e.to_id = s.id AND e.edge_type = 'DEFINES'
+         JOIN graph_entities f ON f.id = e.from_id AND f.kind = 'File'
+         WHERE s.kind = 'Symbol' AND json_extract(s.data, '$.symbol_id') IN ({})
+         ORDER BY s.id",
+        placeholders
+    );
+
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(rusqlite::params_from_iter(symbol_ids.iter()), |row| {
+        let file_path: String = row.get(1)?;
+        let byte_start: u64 = row.get(2)?;
+        let byte_end: u64 = row.get(3)?;
+        Ok((
+            row.get::<_, String>(0)?,
+            Span {
+                span_id: span_id(&file_path, byte_start, byte_end),
+                file_path,
+                byte_start,
+                byte_end,
+                start_line: row.get(4)?,
+                start_col: row.get(5)?,
+                end_line: row.get(6)?,
+                end_col: row.get(7)?,
+                context: None,
+            },
+        ))
+    })?;
+
+    let mut definitions: HashMap<String, Span> = HashMap::new();
+    for row in rows {
+        let (symbol_id, span) = row?;
+        definitions.entry(symbol_id).or_insert(span);
+    }
+    Ok(definitions)
+}
+
+/// Keep only the first item per content hash, returning how many were dropped.
+///
+/// Run after sorting so the best-ranked item of each group survives. Items
+/// without a hash are never dropped.
+pub(crate) fn dedupe_by_content_hash<T>(
+    items: &mut Vec<T>,
+    hash: impl Fn(&T) -> Option<&str>,
+) -> usize {
+    let before = items.len();
+    let mut seen: HashSet<String> = HashSet::new();
+    items.retain(|item| match hash(item) {
+        Some(hash) => seen.insert(hash.to_string()),
+        None => true,
+    });
+    before - items.len()
+}
+
+/// Stable-sort items by name, ignoring ASCII case like SQLite `COLLATE NOCASE`.
+///
+/// `descending` reverses only the name order; ties always fall back to file
+/// path, then start line, ascending.
+pub(crate) fn sort_by_name<T>(
+    items: &mut [T],
+    descending: bool,
+    key: impl Fn(&T) -> (&str, &Span),
+) {
+    items.sort_by(|a, b| {
+        let (a_name, a_span) = key(a);
+        let (b_name, b_span) = key(b);
+        let by_name = a_name
+            .to_ascii_lowercase()
+            .cmp(&b_name.to_ascii_lowercase());
+        let by_name = if descending {
+            by_name.reverse()
+        } else {
+            by_name
+        };
+        by_name
+            .then_with(|| a_span.file_path.cmp(&b_span.file_path))
+            .then_with(|| a_span.start_line.cmp(&b_span.start_line))
+    });
+}
+
+/// Prefix each snippet line with its source line number.
+///
+/// Numbering starts at `start_line`. The first line containing `name` is
+/// marked with `>` so the snippet points at the matched symbol.
+pub(crate) fn number_snippet_lines(snippet: &str, start_line: u64, name: &str) -> String {
+    let line_count = snippet.lines().count() as u64;
+    let width = (start_line + line_count.saturating_sub(1)).to_string().len();
+    let marked = snippet.lines().position(|line| line.contains(name));
+
+    snippet
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let marker = if Some(i) == marked { '>' } else { ' ' };
+            format!(
+                "{} {:>width$} | {}",
+                marker,
+                start_line + i as u64,
+                line,
+                width = width
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Extract context lines from a file: `before_lines` above the span and
+/// `after_lines` below it
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn span_context_from_file(
     file_path: &str,
     start_line: u64,
     end_line: u64,
-    context_lines: usize,
+    before_lines: usize,
+    after_lines: usize,
     capped: bool,
-    cache: &mut HashMap<String, FileCache>,
+    exclude_self: bool,
+    cache: &mut SourceCache,
 ) -> Option<SpanContext> {
     let file = load_file(file_path, cache)?;
     let line_count = file.lines.len() as u64;
@@ -153,14 +664,17 @@ pub(crate) fn span_context_from_file(
     }
     let start_line = start_line.max(1).min(line_count);
     let end_line = end_line.max(start_line).min(line_count);
-    let before_start = start_line.saturating_sub(context_lines as u64).max(1);
-    let after_end = (end_line + context_lines as u64).min(line_count);
+    let before_start = start_line.saturating_sub(before_lines as u64).max(1);
+    let after_end = (end_line + after_lines as u64).min(line_count);
 
     let before = file.lines[(before_start - 1) as usize..(start_line - 1) as usize].to_vec();
-    let selected = file.lines[(start_line - 1) as usize..end_line as usize].to_vec();
+    let selected = if exclude_self {
+        Vec::new()
+    } else {
+        file.lines[(start_line - 1) as usize..end_line as usize].to_vec()
+    };
     let after = file.lines[end_line as usize..after_end as usize].to_vec();
-    let truncated = capped
-        || (context_lines > 0 && (before.len() < context_lines || after.len() < context_lines));
+    let truncated = capped || before.len() < before_lines || after.len() < after_lines;
 
     Some(SpanContext {
         before,
@@ -170,6 +684,12 @@ pub(crate) fn span_context_from_file(
     })
 }
 
+/// Largest bonus `boost_fan_in` adds to a relevance score.
+///
+/// Boosted scores can exceed `score_match`'s ceiling of 100, and a weaker name
+/// match with high fan-in can outrank a stronger one by up to this margin.
+pub const FAN_IN_BOOST_CAP: u64 = 20;
+
 /// Score a match based on query string
 pub(crate) fn score_match(
     query: &str,
@@ -219,6 +739,39 @@ pub(crate) fn score_match(
     score
 }
 
+/// [`score_match`], comparing case-insensitively when `ignore_case` is set.
+///
+/// `regex` is expected to have been built with the same case sensitivity.
+pub(crate) fn score_match_case(
+    query: &str,
+    name: &str,
+    display_fqn: &str,
+    fqn: &str,
+    regex: Option<&Regex>,
+    ignore_case: bool,
+) -> u64 {
+    if ignore_case {
+        score_match(
+            &query.to_lowercase(),
+            &name.to_lowercase(),
+            &display_fqn.to_lowercase(),
+            &fqn.to_lowercase(),
+            regex,
+        )
+    } else {
+        score_match(query, name, display_fqn, fqn, regex)
+    }
+}
+
+/// Whether `name` contains `query`, ignoring case when `ignore_case` is set.
+pub(crate) fn name_contains(name: &str, query: &str, ignore_case: bool) -> bool {
+    if ignore_case {
+        name.to_lowercase().contains(&query.to_lowercase())
+    } else {
+        name.contains(query)
+    }
+}
+
 /// Generate a span ID from file path and byte range
 pub(crate) fn span_id(file_path: &str, byte_start: u64, byte_end: u64) -> String {
     let mut hasher = Sha256::new();
@@ -270,6 +823,8 @@ pub(crate) struct SymbolNodeData {
     pub(crate) canonical_fqn: Option<String>,
     #[serde(default)]
     pub(crate) display_fqn: Option<String>,
+    #[serde(default)]
+    pub(crate) visibility: Option<String>,
     pub(crate) byte_start: u64,
     pub(crate) byte_end: u64,
     pub(crate) start_line: u64,
@@ -282,6 +837,9 @@ pub(crate) struct SymbolNodeData {
 #[derive(Debug, serde::Deserialize)]
 pub(crate) struct ReferenceNodeData {
     pub(crate) file: String,
+    /// Reference kind recorded by the indexer (e.g. read, write, import)
+    #[serde(default)]
+    pub(crate) kind: Option<String>,
     pub(crate) byte_start: u64,
     pub(crate) byte_end: u64,
     pub(crate) start_line: u64,
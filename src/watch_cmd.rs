@@ -4,17 +4,90 @@
 //! Enable with the `unstable-watch` feature flag.
 
 use anyhow::Result;
+use clap::ValueEnum;
+use serde::Serialize;
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 
-use crate::backend::{Backend, BackendTrait};
+use crate::backend::{restart_query_clock, Backend, BackendTrait};
 use crate::error::LlmError;
 use crate::output::{OutputFormat, SearchResponse, SymbolMatch};
 use crate::query::SearchOptions;
 
+/// What watch mode prints after each re-evaluation.
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+pub enum WatchEmit {
+    /// Initial result set, then formatted add/remove deltas in the selected output format
+    #[default]
+    Snapshot,
+    /// NDJSON `added`/`removed` events keyed by `match_id` (initial results are `added`)
+    Events,
+    /// Only the current match count and its delta from the previous evaluation
+    Count,
+}
+
+/// Match count emitted by `--emit count` after each evaluation.
+///
+/// Serializes as one NDJSON line: `{"count":12,"delta":-3}`.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+pub struct WatchCount {
+    /// Number of results in the current evaluation
+    pub count: usize,
+    /// Change from the previous evaluation (the initial evaluation counts from 0)
+    pub delta: i64,
+}
+
+impl WatchCount {
+    /// Compute the count summary for a transition between two result sets.
+    pub fn between(previous: &[SymbolMatch], current: &[SymbolMatch]) -> Self {
+        WatchCount {
+            count: current.len(),
+            delta: current.len() as i64 - previous.len() as i64,
+        }
+    }
+}
+
+/// A single incremental change emitted by `--emit events`.
+///
+/// Serializes as one NDJSON line: `{"event":"added","result":{...}}`.
+#[derive(Serialize, Clone, Debug)]
+pub struct WatchEvent<'a> {
+    /// Either `"added"` or `"removed"`
+    pub event: &'static str,
+    /// The symbol that entered or left the result set
+    pub result: &'a SymbolMatch,
+}
+
+/// Diff two result sets by `match_id`, returning added events followed by removed events.
+pub fn diff_events<'a>(
+    previous: &'a [SymbolMatch],
+    current: &'a [SymbolMatch],
+) -> Vec<WatchEvent<'a>> {
+    // Build HashSets for O(1) lookup instead of O(n×m) nested loops
+    let previous_ids: HashSet<&str> = previous.iter().map(|p| p.match_id.as_str()).collect();
+    let current_ids: HashSet<&str> = current.iter().map(|c| c.match_id.as_str()).collect();
+
+    let added = current
+        .iter()
+        .filter(|c| !previous_ids.contains(c.match_id.as_str()))
+        .map(|result| WatchEvent {
+            event: "added",
+            result,
+        });
+    let removed = previous
+        .iter()
+        .filter(|p| !current_ids.contains(p.match_id.as_str()))
+        .map(|result| WatchEvent {
+            event: "removed",
+            result,
+        });
+
+    added.chain(removed).collect()
+}
+
 /// Run the watch command with automatic backend detection.
 ///
 /// This function detects the database format and uses file watching
@@ -24,6 +97,7 @@ use crate::query::SearchOptions;
 /// * `db_path` - Path to the database file
 /// * `options` - Search options for the query
 /// * `output_format` - Output format for results
+/// * `emit` - Snapshot deltas or NDJSON change events
 /// * `shutdown` - Atomic flag for graceful shutdown
 ///
 /// # Returns
@@ -33,6 +107,7 @@ pub fn run_watch<'a>(
     db_path: PathBuf,
     options: SearchOptions<'a>,
     output_format: OutputFormat,
+    emit: WatchEmit,
     shutdown: Arc<AtomicBool>,
 ) -> Result<()> {
     // Detect backend format and open database
@@ -40,7 +115,7 @@ pub fn run_watch<'a>(
 
     match backend {
         Backend::Sqlite(inner) => {
-            run_watch_with_filesystem(&inner, db_path, options, output_format, shutdown)
+            run_watch_with_filesystem(&inner, db_path, options, output_format, emit, shutdown)
         }
     }
 }
@@ -56,6 +131,7 @@ pub fn run_watch<'a>(
 /// * `db_path` - Path to the database file
 /// * `options` - Search options for the query
 /// * `output_format` - Output format for results
+/// * `emit` - Snapshot deltas or NDJSON change events
 /// * `shutdown` - Atomic flag for graceful shutdown
 ///
 /// # Returns
@@ -66,6 +142,7 @@ fn run_watch_with_filesystem<'a>(
     db_path: PathBuf,
     options: SearchOptions<'a>,
     output_format: OutputFormat,
+    emit: WatchEmit,
     shutdown: Arc<AtomicBool>,
 ) -> Result<()> {
     // Run initial query and display results
@@ -76,7 +153,13 @@ fn run_watch_with_filesystem<'a>(
                 reason: e.to_string(),
             })?;
 
-    display_results(&response, &output_format)?;
+    match emit {
+        WatchEmit::Snapshot => display_results(&response, &output_format)?,
+        WatchEmit::Events => emit_events(&diff_events(&[], &response.results))?,
+        WatchEmit::Count => {
+            emit_count(WatchCount::between(&[], &response.results), &output_format)?
+        }
+    }
     let mut previous_results = response.results;
     let mut last_modified = get_file_modification_time(&db_path)?;
 
@@ -92,10 +175,25 @@ fn run_watch_with_filesystem<'a>(
                 last_modified = current_modified;
 
                 // Re-run query
+                restart_query_clock();
                 match backend.search_symbols(options.clone()) {
                     Ok((current_response, _, _)) => {
                         // Display delta (only new/removed results)
-                        format_delta(&previous_results, &current_response.results, &output_format)?;
+                        match emit {
+                            WatchEmit::Snapshot => format_delta(
+                                &previous_results,
+                                &current_response.results,
+                                &output_format,
+                            )?,
+                            WatchEmit::Events => emit_events(&diff_events(
+                                &previous_results,
+                                &current_response.results,
+                            ))?,
+                            WatchEmit::Count => emit_count(
+                                WatchCount::between(&previous_results, &current_response.results),
+                                &output_format,
+                            )?,
+                        }
                         previous_results = current_response.results;
                     }
                     Err(e) => {
@@ -118,6 +216,23 @@ fn get_file_modification_time(path: &Path) -> Result<SystemTime, LlmError> {
         .map_err(LlmError::IoError)
 }
 
+/// Print change events as NDJSON, one event per line.
+fn emit_events(events: &[WatchEvent]) -> Result<()> {
+    for event in events {
+        println!("{}", serde_json::to_string(event)?);
+    }
+    Ok(())
+}
+
+/// Print a count summary: `count: N (+d)` for humans, one JSON line otherwise.
+fn emit_count(summary: WatchCount, output_format: &OutputFormat) -> Result<()> {
+    match output_format {
+        OutputFormat::Human => println!("count: {} ({:+})", summary.count, summary.delta),
+        _ => println!("{}", serde_json::to_string(&summary)?),
+    }
+    Ok(())
+}
+
 /// Display search results in the specified format.
 fn display_results(response: &SearchResponse, output_format: &OutputFormat) -> Result<()> {
     match output_format {
@@ -128,7 +243,10 @@ fn display_results(response: &SearchResponse, output_format: &OutputFormat) -> R
                 println!("  {}", format_symbol_match(result));
             }
         }
-        OutputFormat::Json | OutputFormat::Pretty => {
+        OutputFormat::Quickfix => {
+            println!("{}", serde_json::to_string(&response.quickfix_items())?);
+        }
+        _ => {
             // JSON output for initial results
             let json_output = serde_json::to_string_pretty(response)?;
             println!("{}", json_output);
@@ -150,21 +268,11 @@ fn format_delta(
     current: &[SymbolMatch],
     output_format: &OutputFormat,
 ) -> Result<()> {
-    // Build HashSets for O(1) lookup instead of O(n×m) nested loops
-    let previous_ids: HashSet<&str> = previous.iter().map(|p| p.match_id.as_str()).collect();
-    let current_ids: HashSet<&str> = current.iter().map(|c| c.match_id.as_str()).collect();
-
-    // Compute added: items in current but not previous
-    let added: Vec<&SymbolMatch> = current
-        .iter()
-        .filter(|c| !previous_ids.contains(c.match_id.as_str()))
-        .collect();
-
-    // Compute removed: items in previous but not current
-    let removed: Vec<&SymbolMatch> = previous
-        .iter()
-        .filter(|p| !current_ids.contains(p.match_id.as_str()))
-        .collect();
+    let events = diff_events(previous, current);
+    let (added, removed): (Vec<&WatchEvent>, Vec<&WatchEvent>) =
+        events.iter().partition(|e| e.event == "added");
+    let added: Vec<&SymbolMatch> = added.iter().map(|e| e.result).collect();
+    let removed: Vec<&SymbolMatch> = removed.iter().map(|e| e.result).collect();
 
     if added.is_empty() && removed.is_empty() {
         return Ok(()); // No changes, skip output
@@ -186,7 +294,7 @@ fn format_delta(
                 println!("- {}", format_symbol_match(result));
             }
         }
-        OutputFormat::Json | OutputFormat::Pretty => {
+        _ => {
             // For JSON output, just emit the notice with counts
             // Full result sets are emitted via direct JSON serialization
             let notice = format!("Added: {}, Removed: {}", added.len(), removed.len());
@@ -216,3 +324,104 @@ fn format_symbol_match(result: &SymbolMatch) -> String {
 
     format!("[{}:{}] {} ({})", path, result.span.start_line, name, kind)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::Span;
+
+    fn symbol(match_id: &str, name: &str) -> SymbolMatch {
+        SymbolMatch {
+            match_id: match_id.to_string(),
+            span: Span {
+                span_id: format!("span-{}", match_id),
+                file_path: "src/lib.rs".to_string(),
+                byte_start: 0,
+                byte_end: 10,
+                start_line: 1,
+                start_col: 0,
+                end_line: 1,
+                end_col: 10,
+                context: None,
+            },
+            name: name.to_string(),
+            kind: "Function".to_string(),
+            parent: None,
+            symbol_id: None,
+            score: None,
+            fqn: None,
+            canonical_fqn: None,
+            display_fqn: None,
+            content_hash: None,
+            symbol_kind_from_chunk: None,
+            snippet: None,
+            snippet_truncated: None,
+            language: None,
+            kind_normalized: None,
+            complexity_score: None,
+            fan_in: None,
+            fan_out: None,
+            cyclomatic_complexity: None,
+            ast_context: None,
+            ast_node_kind: None,
+            supernode_id: None,
+            coverage: None,
+            line_text: None,
+            visibility: None,
+            complexity_percentile: None,
+            call_count: None,
+            source_db: None,
+            captures: None,
+            defined_in: None,
+            callers: None,
+        }
+    }
+
+    #[test]
+    fn test_diff_events_across_two_iterations() {
+        // Iteration 1: initial result set is reported entirely as added
+        let first = vec![symbol("m1", "alpha"), symbol("m2", "beta")];
+        let initial = diff_events(&[], &first);
+        assert_eq!(initial.len(), 2);
+        assert!(initial.iter().all(|e| e.event == "added"));
+
+        // Iteration 2: beta stays, alpha disappears, gamma appears
+        let second = vec![symbol("m2", "beta"), symbol("m3", "gamma")];
+        let events = diff_events(&first, &second);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].event, "added");
+        assert_eq!(events[0].result.name, "gamma");
+        assert_eq!(events[1].event, "removed");
+        assert_eq!(events[1].result.name, "alpha");
+
+        let line = serde_json::to_string(&events[0]).unwrap();
+        assert!(line.starts_with(r#"{"event":"added","result":{"match_id":"m3""#));
+    }
+
+    #[test]
+    fn test_watch_count_across_two_iterations() {
+        let first = vec![symbol("m1", "alpha"), symbol("m2", "beta")];
+        let initial = WatchCount::between(&[], &first);
+        assert_eq!(initial, WatchCount { count: 2, delta: 2 });
+
+        let second = vec![symbol("m3", "gamma")];
+        let update = WatchCount::between(&first, &second);
+        assert_eq!(
+            update,
+            WatchCount {
+                count: 1,
+                delta: -1
+            }
+        );
+        assert_eq!(
+            serde_json::to_string(&update).unwrap(),
+            r#"{"count":1,"delta":-1}"#
+        );
+    }
+
+    #[test]
+    fn test_diff_events_unchanged_is_empty() {
+        let results = vec![symbol("m1", "alpha")];
+        assert!(diff_events(&results, &results).is_empty());
+    }
+}
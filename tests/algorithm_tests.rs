@@ -276,3 +276,12 @@ fn test_error_magellan_execution_failed() {
     assert_eq!(error.error_code(), "LLM-E108");
     assert_eq!(error.severity(), "error");
 }
+
+#[test]
+fn test_algorithm_options_symbol_ids_file_active() {
+    let options = AlgorithmOptions {
+        symbol_ids_file: Some("ids.txt"),
+        ..Default::default()
+    };
+    assert!(options.is_active());
+}
@@ -194,9 +194,12 @@ fn test_ast_kind_filter() {
         db_path: &db_path,
         query: "my_",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -207,6 +210,7 @@ fn test_ast_kind_filter() {
         ast: AstOptions {
             ast_kinds: vec!["function_item".to_string()],
             with_ast_context: true, // Enable to use overlap matching
+            with_ast_kind: false,
             _phantom: std::marker::PhantomData,
         },
         depth: DepthOptions::default(),
@@ -216,6 +220,10 @@ fn test_ast_kind_filter() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, _partial, _) = search_symbols(options).expect("search should succeed");
@@ -292,9 +300,12 @@ fn test_backward_compat_no_ast_table() {
         db_path: &db_path,
         query: "my_function",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -305,6 +316,7 @@ fn test_backward_compat_no_ast_table() {
         ast: AstOptions {
             ast_kinds: vec!["function_item".to_string()],
             with_ast_context: false,
+            with_ast_kind: false,
             _phantom: std::marker::PhantomData,
         },
         depth: DepthOptions::default(),
@@ -314,6 +326,10 @@ fn test_backward_compat_no_ast_table() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, _partial, _) = search_symbols(options).expect("search should succeed");
@@ -345,9 +361,12 @@ fn test_ast_context_population() {
         db_path: &db_path,
         query: "parent_function",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -363,6 +382,10 @@ fn test_ast_context_population() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, _partial, _) = search_symbols(options).expect("search should succeed");
@@ -403,9 +426,12 @@ fn test_multiple_ast_kinds() {
         db_path: &db_path,
         query: "func",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -416,6 +442,7 @@ fn test_multiple_ast_kinds() {
         ast: AstOptions {
             ast_kinds: vec!["call_expression".to_string()],
             with_ast_context: false,
+            with_ast_kind: false,
             _phantom: std::marker::PhantomData,
         },
         depth: DepthOptions::default(),
@@ -425,6 +452,10 @@ fn test_multiple_ast_kinds() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, _partial, _) = search_symbols(options).expect("search should succeed");
@@ -439,9 +470,12 @@ fn test_multiple_ast_kinds() {
         db_path: &db_path,
         query: "func",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -452,6 +486,7 @@ fn test_multiple_ast_kinds() {
         ast: AstOptions {
             ast_kinds: vec!["function_item".to_string()],
             with_ast_context: false,
+            with_ast_kind: false,
             _phantom: std::marker::PhantomData,
         },
         depth: DepthOptions::default(),
@@ -461,6 +496,10 @@ fn test_multiple_ast_kinds() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, _partial, _) = search_symbols(options).expect("search should succeed");
@@ -532,6 +571,43 @@ fn test_calculate_ast_depth() {
     );
 }
 
+// Test: Build root-to-node kind path for a nested node
+#[test]
+fn test_ast_kind_path_walks_to_root() {
+    use llmgrep::ast::ast_kind_path;
+
+    let temp_dir = TempDir::new().expect("tempdir");
+    let db_path = temp_dir.path().join("test.db");
+    let conn = Connection::open(&db_path).expect("open db");
+
+    conn.execute(ast_nodes_table_schema(), [])
+        .expect("create ast_nodes");
+
+    // id=4 (if_expression) sits in a function body; id=5 is an unrelated sibling
+    conn.execute(
+        "INSERT INTO ast_nodes (id, parent_id, kind, byte_start, byte_end) VALUES
+        (1, NULL, 'source_file', 0, 1000),
+        (2, 1, 'function_item', 100, 500),
+        (3, 2, 'block', 150, 450),
+        (4, 3, 'if_expression', 200, 300),
+        (5, 1, 'struct_item', 600, 700)",
+        [],
+    )
+    .expect("insert nodes");
+
+    assert_eq!(
+        ast_kind_path(&conn, 4).expect("failed to build kind path"),
+        vec!["source_file", "function_item", "block", "if_expression"]
+    );
+    assert_eq!(
+        ast_kind_path(&conn, 1).expect("failed to build kind path"),
+        vec!["source_file"]
+    );
+    assert!(ast_kind_path(&conn, 99)
+        .expect("failed to build kind path")
+        .is_empty());
+}
+
 // Test: Get parent kind for AST nodes
 #[test]
 fn test_get_parent_kind() {
@@ -702,9 +778,12 @@ fn test_with_ast_context_flag() {
         db_path: &db_path,
         query: "my_function",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -715,6 +794,7 @@ fn test_with_ast_context_flag() {
         ast: AstOptions {
             ast_kinds: vec![],
             with_ast_context: true, // Enable enriched context
+            with_ast_kind: false,
             _phantom: std::marker::PhantomData,
         },
         depth: DepthOptions::default(),
@@ -724,6 +804,10 @@ fn test_with_ast_context_flag() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, _partial, _) = search_symbols(options).expect("search should succeed");
@@ -805,9 +889,12 @@ fn test_ast_context_without_flag() {
         db_path: &db_path,
         query: "my_function",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -818,6 +905,7 @@ fn test_ast_context_without_flag() {
         ast: AstOptions {
             ast_kinds: vec![],
             with_ast_context: false, // NOT enabled
+            with_ast_kind: false,
             _phantom: std::marker::PhantomData,
         },
         depth: DepthOptions::default(),
@@ -827,6 +915,10 @@ fn test_ast_context_without_flag() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, _partial, _) = search_symbols(options).expect("search should succeed");
@@ -856,6 +948,81 @@ fn test_ast_context_without_flag() {
     );
 }
 
+// Test: --with-ast-kind surfaces the exact-span kind without enrichment
+#[test]
+fn test_with_ast_kind_populates_kind_without_enrichment() {
+    let temp_dir = TempDir::new().expect("tempdir");
+    let db_path = temp_dir.path().join("test.db");
+    let conn = setup_db_with_ast(&db_path);
+
+    let file_id = 1i64;
+    insert_file(&conn, file_id, "src/test.rs");
+
+    // function_item with a nested if_expression so enrichment would have work to do
+    conn.execute(
+        "INSERT INTO ast_nodes (id, parent_id, kind, byte_start, byte_end) VALUES
+        (100, NULL, 'function_item', 0, 100),
+        (101, 100, 'if_expression', 10, 50)",
+        [],
+    )
+    .expect("insert ast nodes");
+
+    insert_symbol(&conn, 100, "my_function", "Function", file_id, 0, 100);
+    insert_define_edge(&conn, file_id, 100);
+
+    let options = SearchOptions {
+        db_path: &db_path,
+        query: "my_function",
+        path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
+        kind_filter: None,
+        limit: 10,
+        use_regex: false,
+        ignore_case: false,
+        candidates: 100,
+        context: ContextOptions::default(),
+        snippet: SnippetOptions::default(),
+        fqn: FqnOptions::default(),
+        include_score: true,
+        sort_by: llmgrep::SortMode::default(),
+        metrics: MetricsOptions::default(),
+        ast: AstOptions {
+            ast_kinds: vec![],
+            with_ast_context: false,
+            with_ast_kind: true,
+            _phantom: std::marker::PhantomData,
+        },
+        depth: DepthOptions::default(),
+        algorithm: AlgorithmOptions::default(),
+        symbol_id: None,
+        fqn_pattern: None,
+        exact_fqn: None,
+        language_filter: None,
+        coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
+    };
+
+    let (response, _partial, _) = search_symbols(options).expect("search should succeed");
+    assert_eq!(response.results.len(), 1, "Should find the function");
+
+    let result = &response.results[0];
+    assert_eq!(result.ast_node_kind.as_deref(), Some("function_item"));
+
+    // Enrichment queries must not have run
+    let ast_ctx = result.ast_context.as_ref().expect("basic ast_context");
+    assert_eq!(ast_ctx.depth, None, "Depth should not be computed");
+    assert_eq!(ast_ctx.parent_kind, None, "Parent kind should not be computed");
+    assert_eq!(ast_ctx.children_count_by_kind, None, "Children should not be counted");
+    assert_eq!(ast_ctx.decision_points, None, "Decision points should not be counted");
+
+    let json = serde_json::to_string(result).expect("serialize");
+    assert!(json.contains(r#""ast_node_kind":"function_item""#));
+}
+
 // Test: Sorting by AstComplexity (same as Complexity)
 #[test]
 fn test_sort_by_ast_complexity() {
@@ -912,9 +1079,12 @@ fn test_sort_by_ast_complexity() {
         db_path: &db_path,
         query: "func",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -930,6 +1100,10 @@ fn test_sort_by_ast_complexity() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, _partial, _) = search_symbols(options).expect("search should succeed");
@@ -1012,9 +1186,12 @@ fn test_min_depth_filter() {
         db_path: &db_path,
         query: "symbol_",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 100,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -1035,6 +1212,10 @@ fn test_min_depth_filter() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, _partial, _) = search_symbols(options).expect("search should succeed");
@@ -1086,9 +1267,12 @@ fn test_max_depth_filter() {
         db_path: &db_path,
         query: "test",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -1109,6 +1293,10 @@ fn test_max_depth_filter() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, _partial, _) = search_symbols(options).expect("search should succeed");
@@ -1180,9 +1368,12 @@ fn test_min_max_depth_range() {
         db_path: &db_path,
         query: "depth",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -1203,6 +1394,10 @@ fn test_min_max_depth_range() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, _partial, _) = search_symbols(options).expect("search should succeed");
@@ -1261,9 +1456,12 @@ fn test_inside_function_item() {
         db_path: &db_path,
         query: "closure",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -1274,6 +1472,7 @@ fn test_inside_function_item() {
         ast: AstOptions {
             ast_kinds: vec!["closure_expression".to_string()],
             with_ast_context: false,
+            with_ast_kind: false,
             _phantom: std::marker::PhantomData,
         },
         depth: DepthOptions {
@@ -1288,6 +1487,10 @@ fn test_inside_function_item() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, _partial, _) = search_symbols(options).expect("search should succeed");
@@ -1345,9 +1548,12 @@ fn test_inside_block() {
         db_path: &db_path,
         query: "let",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -1358,6 +1564,7 @@ fn test_inside_block() {
         ast: AstOptions {
             ast_kinds: vec!["let_declaration".to_string()],
             with_ast_context: false,
+            with_ast_kind: false,
             _phantom: std::marker::PhantomData,
         },
         depth: DepthOptions {
@@ -1372,6 +1579,10 @@ fn test_inside_block() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, _partial, _) = search_symbols(options).expect("search should succeed");
@@ -1437,9 +1648,12 @@ fn test_contains_if_expression() {
         db_path: &db_path,
         query: "func",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -1450,6 +1664,7 @@ fn test_contains_if_expression() {
         ast: AstOptions {
             ast_kinds: vec!["function_item".to_string()],
             with_ast_context: false,
+            with_ast_kind: false,
             _phantom: std::marker::PhantomData,
         },
         depth: DepthOptions {
@@ -1464,6 +1679,10 @@ fn test_contains_if_expression() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, _partial, _) = search_symbols(options).expect("search should succeed");
@@ -1527,9 +1746,12 @@ fn test_contains_multiple_children() {
         db_path: &db_path,
         query: "func",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -1540,6 +1762,7 @@ fn test_contains_multiple_children() {
         ast: AstOptions {
             ast_kinds: vec!["function_item".to_string()],
             with_ast_context: false,
+            with_ast_kind: false,
             _phantom: std::marker::PhantomData,
         },
         depth: DepthOptions {
@@ -1554,6 +1777,10 @@ fn test_contains_multiple_children() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, _partial, _) = search_symbols(options).expect("search should succeed");
@@ -1637,9 +1864,12 @@ fn test_combined_depth_and_inside() {
         db_path: &db_path,
         query: "closure",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -1650,6 +1880,7 @@ fn test_combined_depth_and_inside() {
         ast: AstOptions {
             ast_kinds: vec!["closure_expression".to_string()],
             with_ast_context: false,
+            with_ast_kind: false,
             _phantom: std::marker::PhantomData,
         },
         depth: DepthOptions {
@@ -1664,6 +1895,10 @@ fn test_combined_depth_and_inside() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, _partial, _) = search_symbols(options).expect("search should succeed");
@@ -1713,9 +1948,12 @@ fn test_backward_compat_no_depth_filter() {
         db_path: &db_path,
         query: "my_function",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -1731,6 +1969,10 @@ fn test_backward_compat_no_depth_filter() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let (response, _partial, _) = search_symbols(options).expect("search should succeed");
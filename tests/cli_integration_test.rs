@@ -5,8 +5,8 @@
 //! llmgrep binary via std::process::Command.
 
 use std::io::Read;
-use std::path::PathBuf;
-use std::process::Command;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
 
 /// Helper to get a SQLite database for testing.
 ///
@@ -482,3 +482,665 @@ fn test_json_output_format_via_cli() {
         "JSON output should contain braces or 'results' field"
     );
 }
+
+#[test]
+fn test_errors_json_with_human_output_via_cli() {
+    let binary = match llmgrep_binary() {
+        Some(b) => b,
+        None => {
+            eprintln!("SKIP: llmgrep binary not found. Run: cargo build --release");
+            return;
+        }
+    };
+
+    let output = Command::new(&binary)
+        .args([
+            "--db",
+            "/nonexistent/llmgrep_errors_json.db",
+            "--output",
+            "human",
+            "--errors-json",
+            "search",
+            "--query",
+            "main",
+        ])
+        .output()
+        .expect("Failed to execute llmgrep");
+
+    assert!(!output.status.success(), "Missing database should fail");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let payload: serde_json::Value =
+        serde_json::from_str(stdout.trim()).expect("error should be emitted as JSON");
+    assert_eq!(payload["data"]["code"], "LLM-E103");
+}
+
+#[test]
+fn test_text_output_formats_report_errors_on_stderr_via_cli() {
+    let binary = match llmgrep_binary() {
+        Some(b) => b,
+        None => {
+            eprintln!("SKIP: llmgrep binary not found. Run: cargo build --release");
+            return;
+        }
+    };
+
+    for format in ["vimgrep", "fqn-list", "wide"] {
+        let output = Command::new(&binary)
+            .args([
+                "--db",
+                "/nonexistent/llmgrep_text_errors.db",
+                "--output",
+                format,
+                "search",
+                "--query",
+                "main",
+            ])
+            .output()
+            .expect("Failed to execute llmgrep");
+
+        assert!(!output.status.success(), "Missing database should fail");
+        assert!(
+            output.stdout.is_empty(),
+            "{format} stdout should stay free of the error: {}",
+            String::from_utf8_lossy(&output.stdout)
+        );
+        assert!(String::from_utf8_lossy(&output.stderr).contains("LLM-E103"));
+    }
+}
+
+#[test]
+fn test_errors_minimal_prints_bare_code_via_cli() {
+    let binary = match llmgrep_binary() {
+        Some(b) => b,
+        None => {
+            eprintln!("SKIP: llmgrep binary not found. Run: cargo build --release");
+            return;
+        }
+    };
+
+    let output = Command::new(&binary)
+        .args([
+            "--db",
+            "/nonexistent/llmgrep_errors_minimal.db",
+            "--errors-minimal",
+            "search",
+            "--query",
+            "main",
+        ])
+        .output()
+        .expect("Failed to execute llmgrep");
+
+    assert!(!output.status.success(), "Missing database should fail");
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "LLM-E103\n");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("ERROR:"),
+        "detail should go to stderr: {}",
+        stderr
+    );
+}
+
+#[test]
+fn test_strict_complete_exits_distinctly_on_partial_results_via_cli() {
+    let binary = match llmgrep_binary() {
+        Some(b) => b,
+        None => {
+            eprintln!("SKIP: llmgrep binary not found. Run: cargo build --release");
+            return;
+        }
+    };
+
+    let db_path = get_test_sqlite_db();
+    if db_path.starts_with(std::env::temp_dir()) {
+        // The minimal test database holds one symbol without an end position;
+        // complete it and add a second so a single candidate cannot cover
+        // every match.
+        let conn = rusqlite::Connection::open(&db_path).expect("open test database");
+        conn.execute(
+            "UPDATE graph_entities SET data = json_set(data, '$.end_line', 2, '$.end_col', 1) WHERE id = 2",
+            [],
+        )
+        .expect("complete first symbol");
+        conn.execute(
+            "INSERT INTO graph_entities (id, kind, name, file_path, data) VALUES (3, 'Symbol', 'test_two', 'test.rs', '{\"name\":\"test_two\",\"byte_start\":20,\"byte_end\":30,\"start_line\":3,\"start_col\":0,\"end_line\":4,\"end_col\":1,\"symbol_id\":\"3\"}')",
+            [],
+        )
+        .expect("insert second symbol");
+        conn.execute(
+            "INSERT INTO graph_edges (from_id, to_id, edge_type) VALUES (1, 3, 'DEFINES')",
+            [],
+        )
+        .expect("insert DEFINES edge");
+    }
+
+    let run = |strict: bool| {
+        let mut args = vec![
+            "--db",
+            db_path.to_str().expect("failed to convert path to string"),
+            "--output",
+            "json",
+            "search",
+            "--all",
+            "--limit",
+            "1",
+            "--candidates",
+            "1",
+        ];
+        if strict {
+            args.push("--strict-complete");
+        }
+        Command::new(&binary)
+            .args(args)
+            .output()
+            .expect("Failed to execute llmgrep")
+    };
+
+    let lenient = run(false);
+    if !lenient.status.success() {
+        eprintln!(
+            "SKIP: test database cannot be searched: {}",
+            String::from_utf8_lossy(&lenient.stderr)
+        );
+        return;
+    }
+
+    let strict = run(true);
+    assert_eq!(
+        strict.status.code(),
+        Some(3),
+        "partial results should exit with 3: {}",
+        String::from_utf8_lossy(&strict.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&strict.stdout);
+    let payload: serde_json::Value =
+        serde_json::from_str(stdout.trim()).expect("results should still be one JSON document");
+    assert_eq!(payload["partial"], true);
+    assert!(String::from_utf8_lossy(&strict.stderr).contains("LLM-E022"));
+}
+
+#[test]
+fn test_timings_csv_with_queries_file_via_cli() {
+    let binary = match llmgrep_binary() {
+        Some(b) => b,
+        None => {
+            eprintln!("SKIP: llmgrep binary not found. Run: cargo build --release");
+            return;
+        }
+    };
+
+    let db_path = get_test_sqlite_db();
+    let queries_file =
+        std::env::temp_dir().join(format!("llmgrep_queries_{}.txt", std::process::id()));
+    std::fs::write(&queries_file, "# warm-up set\nmain\n\nparse\n").expect("write queries file");
+
+    let output = Command::new(&binary)
+        .args([
+            "--db",
+            db_path.to_str().expect("failed to convert path to string"),
+            "--output",
+            "timings-csv",
+            "search",
+            "--queries-file",
+            queries_file.to_str().expect("failed to convert path to string"),
+        ])
+        .output()
+        .expect("Failed to execute llmgrep");
+    let _ = std::fs::remove_file(&queries_file);
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        !stderr.contains("invalid value") && !stderr.contains("unexpected argument"),
+        "timings-csv and --queries-file should be accepted: {}",
+        stderr
+    );
+    if !output.status.success() {
+        eprintln!("SKIP: test database cannot be searched: {}", stderr);
+        return;
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(
+        lines.first(),
+        Some(&"query,backend_ms,query_ms,format_ms,total_ms,result_count")
+    );
+    assert_eq!(lines.len(), 3, "one row per query: {}", stdout);
+    assert!(lines[1].starts_with("main,"));
+    assert!(lines[2].starts_with("parse,"));
+}
+
+#[test]
+fn test_batch_results_json_keys_duplicate_queries_by_index() {
+    let binary = match llmgrep_binary() {
+        Some(b) => b,
+        None => {
+            eprintln!("SKIP: llmgrep binary not found. Run: cargo build --release");
+            return;
+        }
+    };
+
+    let db_path = get_test_sqlite_db();
+    let queries_file =
+        std::env::temp_dir().join(format!("llmgrep_batch_queries_{}.txt", std::process::id()));
+    std::fs::write(&queries_file, "main\nparse\nmain\n").expect("write queries file");
+
+    let output = Command::new(&binary)
+        .args([
+            "--db",
+            db_path.to_str().expect("failed to convert path to string"),
+            "--output",
+            "batch-results-json",
+            "search",
+            "--queries-file",
+            queries_file.to_str().expect("failed to convert path to string"),
+        ])
+        .output()
+        .expect("Failed to execute llmgrep");
+    let _ = std::fs::remove_file(&queries_file);
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        !stderr.contains("invalid value") && !stderr.contains("unexpected argument"),
+        "batch-results-json and --queries-file should be accepted: {}",
+        stderr
+    );
+    if !output.status.success() {
+        eprintln!("SKIP: test database cannot be searched: {}", stderr);
+        return;
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let batch: serde_json::Value =
+        serde_json::from_str(stdout.trim()).expect("batch output should be one JSON array");
+    let entries = batch.as_array().expect("top level should be an array");
+    assert_eq!(entries.len(), 3, "one entry per input line: {}", stdout);
+    for (index, query) in ["main", "parse", "main"].iter().enumerate() {
+        assert_eq!(entries[index]["index"], index);
+        assert_eq!(entries[index]["query"], *query);
+        assert!(entries[index]["total_count"].is_u64());
+        assert!(entries[index]["partial"].is_boolean());
+        assert!(entries[index]["results"].is_array());
+    }
+    assert_eq!(entries[0]["results"], entries[2]["results"]);
+}
+
+#[test]
+fn test_probe_reports_ok_status_and_fails_on_missing_db() {
+    let binary = match llmgrep_binary() {
+        Some(b) => b,
+        None => {
+            eprintln!("SKIP: llmgrep binary not found. Run: cargo build --release");
+            return;
+        }
+    };
+
+    let db_path = get_test_sqlite_db();
+    let output = Command::new(&binary)
+        .args([
+            "--db",
+            db_path.to_str().expect("failed to convert path to string"),
+            "probe",
+        ])
+        .output()
+        .expect("Failed to execute llmgrep");
+    assert!(
+        output.status.success(),
+        "probe should succeed on a valid database: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let status: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("probe should print JSON");
+    assert_eq!(status["status"], "ok");
+    assert_eq!(status["backend"], "sqlite");
+    assert!(status["symbols"].as_u64().is_some_and(|n| n >= 1));
+
+    let missing = Command::new(&binary)
+        .args(["--db", "/nonexistent/llmgrep_probe.db", "probe"])
+        .output()
+        .expect("Failed to execute llmgrep");
+    assert!(!missing.status.success(), "probe should fail on a missing database");
+    assert!(!String::from_utf8_lossy(&missing.stdout).contains(r#""status":"ok""#));
+}
+
+#[test]
+fn test_files_with_matches_caps_distinct_files_via_cli() {
+    let binary = match llmgrep_binary() {
+        Some(b) => b,
+        None => {
+            eprintln!("SKIP: llmgrep binary not found. Run: cargo build --release");
+            return;
+        }
+    };
+
+    let db_path = get_test_sqlite_db();
+    if !db_path.starts_with(std::env::temp_dir()) {
+        eprintln!("SKIP: fixture rows are only added to a temporary test database");
+        return;
+    }
+    // test.rs gets a second symbol ahead of the only one in other.rs, so a
+    // two-match limit would never reach other.rs
+    let conn = rusqlite::Connection::open(&db_path).expect("open test database");
+    conn.execute_batch(
+        "UPDATE graph_entities SET data = json_set(data, '$.end_line', 2, '$.end_col', 1) WHERE id = 2;
+         INSERT INTO graph_entities (id, kind, name, file_path, data) VALUES
+            (3, 'Symbol', 'test_two', 'test.rs', '{\"name\":\"test_two\",\"byte_start\":20,\"byte_end\":30,\"start_line\":3,\"start_col\":0,\"end_line\":4,\"end_col\":1,\"symbol_id\":\"3\"}'),
+            (4, 'File', 'other.rs', 'other.rs', '{\"path\":\"other.rs\"}'),
+            (5, 'Symbol', 'test_other', 'other.rs', '{\"name\":\"test_other\",\"byte_start\":40,\"byte_end\":50,\"start_line\":5,\"start_col\":0,\"end_line\":6,\"end_col\":1,\"symbol_id\":\"5\"}');
+         INSERT INTO graph_edges (from_id, to_id, edge_type) VALUES (1, 3, 'DEFINES'), (4, 5, 'DEFINES');",
+    )
+    .expect("insert fixture rows");
+
+    let run = |output: &str| {
+        Command::new(&binary)
+            .args([
+                "--db",
+                db_path.to_str().expect("failed to convert path to string"),
+                "--output",
+                output,
+                "search",
+                "--all",
+                "--sort-by",
+                "position",
+                "--limit",
+                "2",
+                "--files-with-matches",
+            ])
+            .output()
+            .expect("Failed to execute llmgrep")
+    };
+
+    let json = run("json");
+    assert!(
+        json.status.success(),
+        "search failed: {}",
+        String::from_utf8_lossy(&json.stderr)
+    );
+    let payload: serde_json::Value =
+        serde_json::from_slice(&json.stdout).expect("output should be one JSON document");
+    assert_eq!(payload, serde_json::json!({"files": ["other.rs", "test.rs"]}));
+
+    let human = run("human");
+    assert_eq!(String::from_utf8_lossy(&human.stdout), "other.rs\ntest.rs\n");
+}
+
+#[test]
+fn test_count_prints_total_matches_beyond_limit_via_cli() {
+    let binary = match llmgrep_binary() {
+        Some(b) => b,
+        None => {
+            eprintln!("SKIP: llmgrep binary not found. Run: cargo build --release");
+            return;
+        }
+    };
+
+    let db_path = get_test_sqlite_db();
+    if !db_path.starts_with(std::env::temp_dir()) {
+        eprintln!("SKIP: fixture rows are only added to a temporary test database");
+        return;
+    }
+    let conn = rusqlite::Connection::open(&db_path).expect("open test database");
+    conn.execute_batch(
+        "INSERT INTO graph_entities (id, kind, name, file_path, data) VALUES
+            (3, 'Symbol', 'test_two', 'test.rs', '{\"name\":\"test_two\",\"byte_start\":20,\"byte_end\":30,\"start_line\":3,\"start_col\":0,\"end_line\":4,\"end_col\":1,\"symbol_id\":\"3\"}'),
+            (4, 'Symbol', 'test_three', 'test.rs', '{\"name\":\"test_three\",\"byte_start\":40,\"byte_end\":50,\"start_line\":5,\"start_col\":0,\"end_line\":6,\"end_col\":1,\"symbol_id\":\"4\"}');
+         INSERT INTO graph_edges (from_id, to_id, edge_type) VALUES (1, 3, 'DEFINES'), (1, 4, 'DEFINES');",
+    )
+    .expect("insert fixture rows");
+
+    let run = |output: &str| {
+        Command::new(&binary)
+            .args([
+                "--db",
+                db_path.to_str().expect("failed to convert path to string"),
+                "--output",
+                output,
+                "search",
+                "--query",
+                "test",
+                "--limit",
+                "1",
+                "--count",
+            ])
+            .output()
+            .expect("Failed to execute llmgrep")
+    };
+
+    let json = run("json");
+    assert!(
+        json.status.success(),
+        "search failed: {}",
+        String::from_utf8_lossy(&json.stderr)
+    );
+    let payload: serde_json::Value =
+        serde_json::from_slice(&json.stdout).expect("output should be one JSON document");
+    assert_eq!(payload, serde_json::json!({"count": 3, "partial": false}));
+
+    let human = run("human");
+    assert_eq!(String::from_utf8_lossy(&human.stdout), "3\n");
+
+    // Post-SQL filters would make the count disagree with a normal search
+    let capped = Command::new(&binary)
+        .args([
+            "--db",
+            db_path.to_str().expect("failed to convert path to string"),
+            "search",
+            "--query",
+            "test",
+            "--count",
+            "--max-per-file",
+            "1",
+        ])
+        .output()
+        .expect("Failed to execute llmgrep");
+    assert!(!capped.status.success());
+    assert!(String::from_utf8_lossy(&capped.stderr).contains("--count cannot be combined"));
+}
+
+#[test]
+fn test_cache_hit_does_not_open_the_database() {
+    let binary = match llmgrep_binary() {
+        Some(b) => b,
+        None => {
+            eprintln!("SKIP: llmgrep binary not found. Run: cargo build --release");
+            return;
+        }
+    };
+
+    let db_path = get_test_sqlite_db();
+    if !db_path.starts_with(std::env::temp_dir()) {
+        eprintln!("SKIP: test overwrites its database and needs the temporary fixture");
+        return;
+    }
+    // The minimal fixture symbol lacks the end position a match needs
+    rusqlite::Connection::open(&db_path)
+        .and_then(|conn| {
+            conn.execute_batch(
+                "UPDATE graph_entities SET data = json_set(data, '$.end_line', 2, '$.end_col', 1) WHERE id = 2;",
+            )
+        })
+        .expect("complete fixture symbol");
+    let home = tempfile::tempdir().expect("create fake home");
+    let run = |home: Option<&Path>| {
+        let mut command = Command::new(&binary);
+        command.env_remove("HOME").args([
+            "--db",
+            db_path.to_str().expect("failed to convert path to string"),
+            "--output",
+            "json",
+            "--cache",
+            "search",
+            "--query",
+            "test",
+        ]);
+        if let Some(home) = home {
+            command.env("HOME", home);
+        }
+        command.output().expect("Failed to execute llmgrep")
+    };
+
+    let first = run(Some(home.path()));
+    assert!(
+        first.status.success(),
+        "search failed: {}",
+        String::from_utf8_lossy(&first.stderr)
+    );
+
+    // Garble the database but keep its modification time, so only a search
+    // answered from the cache without opening it can still succeed
+    let mtime = std::fs::metadata(&db_path)
+        .and_then(|m| m.modified())
+        .expect("database mtime");
+    let len = std::fs::metadata(&db_path).expect("database size").len();
+    std::fs::write(&db_path, vec![0u8; len as usize]).expect("garble database");
+    std::fs::File::options()
+        .write(true)
+        .open(&db_path)
+        .and_then(|file| file.set_modified(mtime))
+        .expect("restore database mtime");
+
+    let cached = run(Some(home.path()));
+    assert!(
+        cached.status.success(),
+        "cached search failed: {}",
+        String::from_utf8_lossy(&cached.stderr)
+    );
+    let data = |stdout: &[u8]| {
+        serde_json::from_slice::<serde_json::Value>(stdout).expect("valid JSON")["data"].clone()
+    };
+    assert_eq!(data(&cached.stdout), data(&first.stdout));
+
+    // Without HOME there is no cache to answer from
+    assert!(!run(None).status.success());
+}
+
+#[test]
+fn test_queries_from_stdin_prints_one_json_object_per_query() {
+    let binary = match llmgrep_binary() {
+        Some(b) => b,
+        None => {
+            eprintln!("SKIP: llmgrep binary not found. Run: cargo build --release");
+            return;
+        }
+    };
+
+    let db_path = get_test_sqlite_db();
+    if db_path.starts_with(std::env::temp_dir()) {
+        // The minimal fixture symbol lacks the end position a match needs
+        let conn = rusqlite::Connection::open(&db_path).expect("open test database");
+        conn.execute_batch(
+            "UPDATE graph_entities SET data = json_set(data, '$.end_line', 2, '$.end_col', 1) WHERE id = 2;",
+        )
+        .expect("update fixture row");
+    }
+    let mut child = Command::new(&binary)
+        .args([
+            "--db",
+            db_path.to_str().expect("failed to convert path to string"),
+            "--output",
+            "json",
+            "search",
+            "--queries-from",
+            "-",
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to execute llmgrep");
+    {
+        use std::io::Write;
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        stdin
+            .write_all(b"test\n\n# comment\nmain\n")
+            .expect("write queries to stdin");
+    }
+    let output = child.wait_with_output().expect("wait for llmgrep");
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        !stderr.contains("unexpected argument"),
+        "--queries-from should be accepted: {}",
+        stderr
+    );
+    if !output.status.success() {
+        eprintln!("SKIP: test database cannot be searched: {}", stderr);
+        return;
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let responses: Vec<serde_json::Value> = stdout
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("each line should be one JSON object"))
+        .collect();
+    assert_eq!(responses.len(), 2, "one object per query: {}", stdout);
+    for (response, query) in responses.iter().zip(["test", "main"]) {
+        assert_eq!(response["data"]["query"], query);
+        assert!(response["partial"].is_boolean());
+    }
+}
+
+#[test]
+fn test_ndjson_streams_matches_then_summary_and_requires_position_sort() {
+    let binary = match llmgrep_binary() {
+        Some(b) => b,
+        None => {
+            eprintln!("SKIP: llmgrep binary not found. Run: cargo build --release");
+            return;
+        }
+    };
+
+    let db_path = get_test_sqlite_db();
+    if !db_path.starts_with(std::env::temp_dir()) {
+        eprintln!("SKIP: test overwrites its database and needs the temporary fixture");
+        return;
+    }
+    // The minimal fixture symbol lacks the end position a match needs
+    rusqlite::Connection::open(&db_path)
+        .and_then(|conn| {
+            conn.execute_batch(
+                "UPDATE graph_entities SET data = json_set(data, '$.end_line', 2, '$.end_col', 1) WHERE id = 2;",
+            )
+        })
+        .expect("complete fixture symbol");
+    let run = |sort: &str| {
+        Command::new(&binary)
+            .args([
+                "--db",
+                db_path.to_str().expect("failed to convert path to string"),
+                "--output",
+                "ndjson",
+                "search",
+                "--query",
+                "test",
+                "--sort-by",
+                sort,
+            ])
+            .output()
+            .expect("Failed to execute llmgrep")
+    };
+
+    let streamed = run("position");
+    assert!(
+        streamed.status.success(),
+        "search failed: {}",
+        String::from_utf8_lossy(&streamed.stderr)
+    );
+    let records: Vec<serde_json::Value> = String::from_utf8_lossy(&streamed.stdout)
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("each line should be a JSON object"))
+        .collect();
+    let (summary, matches) = records.split_last().expect("summary line");
+    assert!(!matches.is_empty());
+    assert!(matches.iter().all(|m| m["_type"].is_null()));
+    assert_eq!(summary["_type"], "summary");
+    assert_eq!(summary["total_count"], matches.len());
+
+    let rejected = run("relevance");
+    assert!(!rejected.status.success(), "{}", String::from_utf8_lossy(&rejected.stdout));
+    let message = format!(
+        "{}{}",
+        String::from_utf8_lossy(&rejected.stdout),
+        String::from_utf8_lossy(&rejected.stderr)
+    );
+    assert!(message.contains("--sort-by position"), "{message}");
+}
@@ -372,6 +372,7 @@ fn test_performance_metrics_in_json_response() {
         kind_filter: None,
         total_count: 0,
         notice: None,
+        available_fields: None,
     };
 
     // Create a JSON structure with metrics
@@ -387,6 +388,20 @@ fn test_performance_metrics_in_json_response() {
     assert!(json_str.contains("\"total_ms\":18"));
 }
 
+// Test 17b: JSON envelope carries the current schema version
+#[test]
+fn test_json_envelope_carries_schema_version() {
+    use llmgrep::output::{json_response_with_partial_and_performance, SCHEMA_VERSION};
+    use serde_json::json;
+
+    let response = json_response_with_partial_and_performance(json!({"results": []}), false, None);
+    let value = serde_json::to_value(&response).expect("failed to serialize envelope");
+
+    assert_eq!(value["schema_version"], SCHEMA_VERSION);
+    assert!(value["schema_version"].is_u64(), "schema_version should be an integer");
+    assert_eq!(value["tool"], "llmgrep");
+}
+
 // Test 18: Verify lookup returns correct symbol metadata
 #[test]
 fn test_lookup_returns_symbol_metadata() {
@@ -538,10 +553,13 @@ fn test_backend_standard_search_operations() {
         db_path: &db_path,
         query: "test",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         language_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 50,
         context: Default::default(),
         snippet: Default::default(),
@@ -556,6 +574,10 @@ fn test_backend_standard_search_operations() {
         fqn_pattern: None,
         exact_fqn: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let result = backend.search_symbols(options);
@@ -612,10 +634,13 @@ fn test_multiple_backend_operations() {
         db_path: &db_path,
         query: "test",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         language_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 50,
         context: Default::default(),
         snippet: Default::default(),
@@ -630,6 +655,10 @@ fn test_multiple_backend_operations() {
         fqn_pattern: None,
         exact_fqn: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let result = backend.search_symbols(options);
@@ -653,10 +682,13 @@ fn test_search_operations_dont_return_feature_not_available() {
         db_path: &db_path,
         query: "test",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         language_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 50,
         context: Default::default(),
         snippet: Default::default(),
@@ -671,6 +703,10 @@ fn test_search_operations_dont_return_feature_not_available() {
         fqn_pattern: None,
         exact_fqn: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     // All standard search modes should NOT return FeatureNotAvailable
@@ -690,3 +726,257 @@ fn test_search_operations_dont_return_feature_not_available() {
         panic!("search_calls should not return FeatureNotAvailable");
     }
 }
+
+// Test 29: Common error variants carry an actionable remediation hint
+#[test]
+fn test_common_errors_have_actionable_remediation() {
+    let cases = [
+        (
+            LlmError::DatabaseNotFound {
+                path: "/tmp/missing.db".to_string(),
+            },
+            "--db",
+        ),
+        (LlmError::EmptyQuery, "--query"),
+        (
+            LlmError::FeatureNotAvailable {
+                feature: "ast".to_string(),
+                backend: "sqlite".to_string(),
+                message: "not indexed".to_string(),
+            },
+            "--detect-backend",
+        ),
+        (
+            LlmError::InvalidQuery {
+                query: "--flatten requires --mode auto".to_string(),
+            },
+            "--help",
+        ),
+        (
+            LlmError::DatabaseCorrupted {
+                reason: "bad header".to_string(),
+            },
+            "reindex",
+        ),
+    ];
+
+    for (error, expected) in cases {
+        let hint = error
+            .remediation()
+            .unwrap_or_else(|| panic!("{} should have a remediation hint", error.error_code()));
+        assert!(!hint.trim().is_empty());
+        assert!(
+            hint.contains(expected),
+            "{} hint should mention {}: {}",
+            error.error_code(),
+            expected,
+            hint
+        );
+    }
+}
+
+/// Minimal JSON Schema check covering the keywords `output_schema` uses.
+fn schema_violation(
+    root: &serde_json::Value,
+    schema: &serde_json::Value,
+    value: &serde_json::Value,
+    path: &str,
+) -> Option<String> {
+    use serde_json::Value;
+
+    if let Some(Value::String(target)) = schema.get("$ref") {
+        let name = target.trim_start_matches("#/$defs/");
+        return schema_violation(root, &root["$defs"][name], value, path);
+    }
+    if let Some(expected) = schema.get("const") {
+        return (expected != value).then(|| format!("{}: expected {}", path, expected));
+    }
+    if let Some(Value::Array(options)) = schema.get("anyOf") {
+        let all_fail = options
+            .iter()
+            .all(|option| schema_violation(root, option, value, path).is_some());
+        return all_fail.then(|| format!("{}: matches no anyOf branch", path));
+    }
+    if let Some(ty) = schema.get("type") {
+        let allowed: Vec<&str> = match ty {
+            Value::Array(types) => types.iter().filter_map(Value::as_str).collect(),
+            other => vec![other.as_str().unwrap_or_default()],
+        };
+        let actual = match value {
+            Value::Null => "null",
+            Value::Bool(_) => "boolean",
+            Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+            Value::Number(_) => "number",
+            Value::String(_) => "string",
+            Value::Array(_) => "array",
+            Value::Object(_) => "object",
+        };
+        let ok = allowed.contains(&actual) || (actual == "integer" && allowed.contains(&"number"));
+        if !ok {
+            return Some(format!("{}: {} is not {:?}", path, actual, allowed));
+        }
+    }
+    if let (Some(min), Some(n)) = (schema.get("minimum"), value.as_f64()) {
+        if n < min.as_f64().unwrap_or_default() {
+            return Some(format!("{}: below minimum", path));
+        }
+    }
+    match value {
+        Value::Array(items) => schema.get("items").and_then(|item_schema| {
+            items.iter().enumerate().find_map(|(i, item)| {
+                schema_violation(root, item_schema, item, &format!("{}[{}]", path, i))
+            })
+        }),
+        Value::Object(fields) => {
+            let properties = schema.get("properties").and_then(Value::as_object);
+            if let Some(Value::Array(required)) = schema.get("required") {
+                for name in required.iter().filter_map(Value::as_str) {
+                    if !fields.contains_key(name) {
+                        return Some(format!("{}: missing required `{}`", path, name));
+                    }
+                }
+            }
+            fields.iter().find_map(|(name, field)| {
+                let field_path = format!("{}.{}", path, name);
+                match properties.and_then(|p| p.get(name)) {
+                    Some(field_schema) => schema_violation(root, field_schema, field, &field_path),
+                    None => match schema.get("additionalProperties") {
+                        Some(Value::Bool(false)) => {
+                            Some(format!("{}: not declared in schema", field_path))
+                        }
+                        Some(extra) => schema_violation(root, extra, field, &field_path),
+                        None => None,
+                    },
+                }
+            })
+        }
+        _ => None,
+    }
+}
+
+// Test 17c: Real responses validate against the published JSON Schema
+#[test]
+fn test_search_responses_conform_to_json_schema() {
+    use llmgrep::ast::AstContext;
+    use llmgrep::output::{
+        json_response_with_partial_and_performance, CallSearchResponse, CallerRef,
+        CombinedSearchResponse, CoverageInfo, DefinedIn, ReferenceSearchResponse, SearchResponse,
+        SpanContext,
+    };
+    use llmgrep::output_schema::search_response_schema;
+
+    let _dir = create_sqlite_test_db();
+    let db_path = _dir.path().join("test.db");
+    let backend = llmgrep::backend::Backend::detect_and_open(&db_path)
+        .expect("failed to detect and open backend");
+    let mut symbol = backend
+        .lookup("test::module::test_function", &db_path.to_string_lossy())
+        .expect("lookup should succeed");
+
+    // Populate the nested and optional fields so they are checked too
+    symbol.score = Some(42);
+    symbol.captures = Some(vec!["test".to_string()]);
+    symbol.snippet = Some("fn test_function() {}".to_string());
+    symbol.snippet_truncated = Some(false);
+    symbol.fan_in = Some(3);
+    symbol.complexity_percentile = Some(50);
+    symbol.span.context = Some(SpanContext {
+        before: vec![],
+        selected: vec!["fn test_function() {}".to_string()],
+        after: vec![],
+        truncated: false,
+    });
+    symbol.ast_context = Some(AstContext {
+        ast_id: 1,
+        kind: "function_item".to_string(),
+        parent_id: None,
+        byte_start: 0,
+        byte_end: 21,
+        depth: Some(0),
+        parent_kind: None,
+        children_count_by_kind: Some([("block".to_string(), 1)].into_iter().collect()),
+        decision_points: Some(0),
+    });
+    symbol.coverage = Some(CoverageInfo {
+        total_blocks: 2,
+        covered_blocks: 1,
+        block_percentage: 50.0,
+        total_edges: 1,
+        covered_edges: 1,
+        edge_percentage: 100.0,
+        recorded_at: None,
+    });
+    symbol.defined_in = Some(DefinedIn {
+        file_id: 1,
+        path: "src/test.rs".to_string(),
+        language: Some("rust".to_string()),
+        size_bytes: None,
+    });
+    symbol.callers = Some(vec![CallerRef {
+        name: "main".to_string(),
+        symbol_id: None,
+        dead: false,
+    }]);
+
+    let symbols = SearchResponse {
+        results: vec![symbol],
+        query: "test_function".to_string(),
+        path_filter: None,
+        kind_filter: Some("fn".to_string()),
+        total_count: 1,
+        notice: None,
+        available_fields: Some(vec!["fan_in"]),
+    };
+    let references = ReferenceSearchResponse {
+        results: vec![],
+        query: "test_function".to_string(),
+        path_filter: None,
+        total_count: 0,
+    };
+    let calls = CallSearchResponse {
+        results: vec![],
+        query: "test_function".to_string(),
+        path_filter: None,
+        total_count: 0,
+    };
+    let combined = CombinedSearchResponse {
+        query: "test_function".to_string(),
+        path_filter: None,
+        symbols: symbols.clone(),
+        references: references.clone(),
+        calls: calls.clone(),
+        total_count: 1,
+        limit_mode: "per-mode".to_string(),
+        omitted: None,
+    };
+
+    let schema = search_response_schema();
+    let performance = Some(PerformanceMetrics::default());
+    let responses = [
+        serde_json::to_value(json_response_with_partial_and_performance(
+            symbols,
+            false,
+            performance,
+        ))
+        .unwrap(),
+        serde_json::to_value(json_response_with_partial_and_performance(
+            combined, true, None,
+        ))
+        .unwrap(),
+        serde_json::to_value(json_response_with_partial_and_performance(
+            references, false, None,
+        ))
+        .unwrap(),
+        serde_json::to_value(json_response_with_partial_and_performance(
+            calls, false, None,
+        ))
+        .unwrap(),
+    ];
+    for response in &responses {
+        assert_eq!(schema_violation(&schema, &schema, response, "$"), None);
+    }
+
+    let mut unknown = responses[0].clone();
+    unknown["data"]["results"][0]["unexpected"] = serde_json::json!(1);
+    assert!(schema_violation(&schema, &schema, &unknown, "$").is_some());
+}
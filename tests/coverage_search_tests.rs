@@ -178,24 +178,36 @@ fn make_options(db_path: &'_ PathBuf) -> SearchOptions<'_> {
         db_path,
         query: "test",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         language_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions {
             include: false,
             lines: 0,
+            before: None,
+            after: None,
             max_lines: 20,
+            exclude_self: false,
         },
         snippet: SnippetOptions {
             include: false,
             max_bytes: 0,
+            line_numbers: false,
+            line_text: false,
+            read_timeout: None,
+            ..Default::default()
         },
         fqn: FqnOptions {
             fqn: false,
             canonical_fqn: false,
             display_fqn: false,
+            normalize: false,
+            defined_in: false,
         },
         include_score: true,
         sort_by: llmgrep::SortMode::default(),
@@ -207,6 +219,10 @@ fn make_options(db_path: &'_ PathBuf) -> SearchOptions<'_> {
         fqn_pattern: None,
         exact_fqn: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     }
 }
 
@@ -208,15 +208,22 @@ fn process_emoji(input: &str) -> String {
         db_path: &db_path,
         query: symbol_name,
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         language_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions {
             include: true,
             max_bytes: 200,
+            line_numbers: false,
+            line_text: false,
+            read_timeout: None,
+            ..Default::default()
         },
         fqn: FqnOptions::default(),
         include_score: true,
@@ -229,6 +236,10 @@ fn process_emoji(input: &str) -> String {
         fqn_pattern: None,
         exact_fqn: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let response = search_symbols(options).expect("search should succeed");
@@ -315,15 +326,22 @@ fn test_chunk_retrieval_with_hash() {
         db_path: &db_path,
         query: symbol_name,
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         language_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions {
             include: true,
             max_bytes: 200,
+            line_numbers: false,
+            line_text: false,
+            read_timeout: None,
+            ..Default::default()
         },
         fqn: FqnOptions::default(),
         include_score: true,
@@ -336,6 +354,10 @@ fn test_chunk_retrieval_with_hash() {
         fqn_pattern: None,
         exact_fqn: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let response = search_symbols(options).expect("search should succeed");
@@ -421,10 +443,13 @@ fn test_metrics_filtering() {
         db_path: &db_path,
         query: "fn", // matches all
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         language_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -435,7 +460,14 @@ fn test_metrics_filtering() {
             min_complexity: Some(10),
             max_complexity: None,
             min_fan_in: None,
+            max_fan_in: None,
             min_fan_out: None,
+            max_fan_out: None,
+            min_loc: None,
+            max_loc: None,
+            weight_by_calls: false,
+            boost_fan_in: false,
+            with_fan_in: false,
         },
         ast: AstOptions::default(),
         depth: DepthOptions::default(),
@@ -444,6 +476,10 @@ fn test_metrics_filtering() {
         fqn_pattern: None,
         exact_fqn: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let response = search_symbols(options).expect("search should succeed");
@@ -509,10 +545,13 @@ fn test_symbol_id_lookup() {
         db_path: &db_path,
         query: "", // empty query, using symbol_id
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         language_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -527,6 +566,10 @@ fn test_symbol_id_lookup() {
         fqn_pattern: None,
         exact_fqn: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let response = search_symbols(options).expect("search should succeed");
@@ -574,10 +617,13 @@ fn test_language_filtering() {
         db_path: &db_path,
         query: "function", // matches all
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         language_filter: Some("rust"),
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -592,6 +638,10 @@ fn test_language_filtering() {
         fqn_pattern: None,
         exact_fqn: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let response = search_symbols(options).expect("search should succeed");
@@ -638,10 +688,13 @@ fn test_multi_kind_filtering() {
         db_path: &db_path,
         query: "process", // matches all three
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: Some("fn"), // single kind
         language_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -656,6 +709,10 @@ fn test_multi_kind_filtering() {
         fqn_pattern: None,
         exact_fqn: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let response_fn = search_symbols(options_fn).expect("search should succeed");
@@ -671,10 +728,13 @@ fn test_multi_kind_filtering() {
         db_path: &db_path,
         query: "process",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: Some("struct"),
         language_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -689,6 +749,10 @@ fn test_multi_kind_filtering() {
         fqn_pattern: None,
         exact_fqn: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let response_struct = search_symbols(options_struct).expect("search should succeed");
@@ -731,10 +795,13 @@ fn test_sort_by_fan_in() {
         db_path: &db_path,
         query: "fan_in",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         language_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -749,6 +816,10 @@ fn test_sort_by_fan_in() {
         fqn_pattern: None,
         exact_fqn: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let response = search_symbols(options).expect("search should succeed");
@@ -826,10 +897,13 @@ fn test_fqn_pattern_filtering() {
         db_path: &db_path,
         query: "helper", // matches both helper_a and helper_b
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         language_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -837,6 +911,8 @@ fn test_fqn_pattern_filtering() {
             fqn: false,
             canonical_fqn: true, // Need canonical_fqn for filtering
             display_fqn: false,
+            normalize: false,
+            defined_in: false,
         },
         include_score: true,
         sort_by: llmgrep::SortMode::Relevance,
@@ -848,6 +924,10 @@ fn test_fqn_pattern_filtering() {
         fqn_pattern: Some("%module_a%"), // Use LIKE wildcard pattern
         exact_fqn: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let response = search_symbols(options).expect("search should succeed");
@@ -931,10 +1011,13 @@ fn test_combined_metrics_and_language_filter() {
         db_path: &db_path,
         query: "complex",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         language_filter: Some("rust"),
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -945,7 +1028,14 @@ fn test_combined_metrics_and_language_filter() {
             min_complexity: Some(10),
             max_complexity: None,
             min_fan_in: None,
+            max_fan_in: None,
             min_fan_out: None,
+            max_fan_out: None,
+            min_loc: None,
+            max_loc: None,
+            weight_by_calls: false,
+            boost_fan_in: false,
+            with_fan_in: false,
         },
         ast: AstOptions::default(),
         depth: DepthOptions::default(),
@@ -954,6 +1044,10 @@ fn test_combined_metrics_and_language_filter() {
         fqn_pattern: None,
         exact_fqn: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let response = search_symbols(options).expect("search should succeed");
@@ -997,10 +1091,13 @@ fn test_metrics_present_in_search_results() {
         db_path: &db_path,
         query: test_name,
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         language_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -1015,6 +1112,10 @@ fn test_metrics_present_in_search_results() {
         fqn_pattern: None,
         exact_fqn: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let response = search_symbols(options).expect("search should succeed");
@@ -169,23 +169,35 @@ fn test_search_symbols_with_path_filter() {
         db_path: &db_path,
         query: "main",
         path_filter: Some(&PathBuf::from("src/")),
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions {
             include: false,
             lines: 0,
+            before: None,
+            after: None,
             max_lines: 20,
+            exclude_self: false,
         },
         snippet: SnippetOptions {
             include: false,
             max_bytes: 0,
+            line_numbers: false,
+            line_text: false,
+            read_timeout: None,
+            ..Default::default()
         },
         fqn: FqnOptions {
             fqn: false,
             canonical_fqn: false,
             display_fqn: false,
+            normalize: false,
+            defined_in: false,
         },
         include_score: true,
         sort_by: llmgrep::SortMode::default(),
@@ -198,6 +210,10 @@ fn test_search_symbols_with_path_filter() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
     let response = search_symbols(options).expect("search");
 
@@ -223,23 +239,35 @@ fn test_search_symbols_with_kind_filter() {
         db_path: &db_path,
         query: "thing",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: Some("fn"),
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions {
             include: false,
             lines: 0,
+            before: None,
+            after: None,
             max_lines: 20,
+            exclude_self: false,
         },
         snippet: SnippetOptions {
             include: false,
             max_bytes: 0,
+            line_numbers: false,
+            line_text: false,
+            read_timeout: None,
+            ..Default::default()
         },
         fqn: FqnOptions {
             fqn: false,
             canonical_fqn: false,
             display_fqn: false,
+            normalize: false,
+            defined_in: false,
         },
         include_score: true,
         sort_by: llmgrep::SortMode::default(),
@@ -252,6 +280,10 @@ fn test_search_symbols_with_kind_filter() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
     let response = search_symbols(options).expect("search");
 
@@ -276,23 +308,35 @@ fn test_search_symbols_rank_exact_match_first() {
         db_path: &db_path,
         query: "alpha",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions {
             include: false,
             lines: 0,
+            before: None,
+            after: None,
             max_lines: 20,
+            exclude_self: false,
         },
         snippet: SnippetOptions {
             include: false,
             max_bytes: 0,
+            line_numbers: false,
+            line_text: false,
+            read_timeout: None,
+            ..Default::default()
         },
         fqn: FqnOptions {
             fqn: false,
             canonical_fqn: false,
             display_fqn: false,
+            normalize: false,
+            defined_in: false,
         },
         include_score: true,
         sort_by: llmgrep::SortMode::default(),
@@ -305,6 +349,10 @@ fn test_search_symbols_rank_exact_match_first() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
     let response = search_symbols(options).expect("search");
 
@@ -328,23 +376,35 @@ fn test_search_symbols_regex_filters() {
         db_path: &db_path,
         query: "^main$",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: true,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions {
             include: false,
             lines: 0,
+            before: None,
+            after: None,
             max_lines: 20,
+            exclude_self: false,
         },
         snippet: SnippetOptions {
             include: false,
             max_bytes: 0,
+            line_numbers: false,
+            line_text: false,
+            read_timeout: None,
+            ..Default::default()
         },
         fqn: FqnOptions {
             fqn: false,
             canonical_fqn: false,
             display_fqn: false,
+            normalize: false,
+            defined_in: false,
         },
         include_score: true,
         sort_by: llmgrep::SortMode::default(),
@@ -357,6 +417,10 @@ fn test_search_symbols_regex_filters() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
     let response = search_symbols(options).expect("search");
 
@@ -382,23 +446,35 @@ fn test_search_symbols_with_context_and_snippet() {
         db_path: &db_path,
         query: "hello",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions {
             include: true,
             lines: 0,
+            before: None,
+            after: None,
             max_lines: 20,
+            exclude_self: false,
         },
         snippet: SnippetOptions {
             include: true,
             max_bytes: 200,
+            line_numbers: false,
+            line_text: false,
+            read_timeout: None,
+            ..Default::default()
         },
         fqn: FqnOptions {
             fqn: false,
             canonical_fqn: false,
             display_fqn: false,
+            normalize: false,
+            defined_in: false,
         },
         include_score: true,
         sort_by: llmgrep::SortMode::default(),
@@ -411,6 +487,10 @@ fn test_search_symbols_with_context_and_snippet() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
     let response = search_symbols(options).expect("search");
 
@@ -438,23 +518,35 @@ fn test_search_symbols_context_truncated_at_file_edges() {
         db_path: &db_path,
         query: "hello",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions {
             include: true,
             lines: 3,
+            before: None,
+            after: None,
             max_lines: 20,
+            exclude_self: false,
         },
         snippet: SnippetOptions {
             include: true,
             max_bytes: 200,
+            line_numbers: false,
+            line_text: false,
+            read_timeout: None,
+            ..Default::default()
         },
         fqn: FqnOptions {
             fqn: false,
             canonical_fqn: false,
             display_fqn: false,
+            normalize: false,
+            defined_in: false,
         },
         include_score: true,
         sort_by: llmgrep::SortMode::default(),
@@ -467,6 +559,10 @@ fn test_search_symbols_context_truncated_at_file_edges() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
     let response = search_symbols(options).expect("search");
 
@@ -496,23 +592,35 @@ fn test_search_symbols_context_truncated_by_cap() {
         db_path: &db_path,
         query: "hello",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions {
             include: true,
             lines: 5,
+            before: None,
+            after: None,
             max_lines: 1,
+            exclude_self: false,
         },
         snippet: SnippetOptions {
             include: true,
             max_bytes: 200,
+            line_numbers: false,
+            line_text: false,
+            read_timeout: None,
+            ..Default::default()
         },
         fqn: FqnOptions {
             fqn: false,
             canonical_fqn: false,
             display_fqn: false,
+            normalize: false,
+            defined_in: false,
         },
         include_score: true,
         sort_by: llmgrep::SortMode::default(),
@@ -525,6 +633,10 @@ fn test_search_symbols_context_truncated_by_cap() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
     let response = search_symbols(options).expect("search");
 
@@ -550,23 +662,35 @@ fn test_search_symbols_with_fqn_toggle() {
         db_path: &db_path,
         query: "hello",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions {
             include: false,
             lines: 0,
+            before: None,
+            after: None,
             max_lines: 20,
+            exclude_self: false,
         },
         snippet: SnippetOptions {
             include: false,
             max_bytes: 0,
+            line_numbers: false,
+            line_text: false,
+            read_timeout: None,
+            ..Default::default()
         },
         fqn: FqnOptions {
             fqn: false,
             canonical_fqn: false,
             display_fqn: false,
+            normalize: false,
+            defined_in: false,
         },
         include_score: true,
         sort_by: llmgrep::SortMode::default(),
@@ -579,6 +703,10 @@ fn test_search_symbols_with_fqn_toggle() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
     let response = search_symbols(options).expect("search");
     let result = &response.0.results[0];
@@ -590,23 +718,35 @@ fn test_search_symbols_with_fqn_toggle() {
         db_path: &db_path,
         query: "hello",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions {
             include: false,
             lines: 0,
+            before: None,
+            after: None,
             max_lines: 20,
+            exclude_self: false,
         },
         snippet: SnippetOptions {
             include: false,
             max_bytes: 0,
+            line_numbers: false,
+            line_text: false,
+            read_timeout: None,
+            ..Default::default()
         },
         fqn: FqnOptions {
             fqn: true,
             canonical_fqn: true,
             display_fqn: true,
+            normalize: false,
+            defined_in: false,
         },
         include_score: true,
         sort_by: llmgrep::SortMode::default(),
@@ -619,6 +759,10 @@ fn test_search_symbols_with_fqn_toggle() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
     let response = search_symbols(options).expect("search");
     let result = &response.0.results[0];
@@ -642,18 +786,28 @@ fn test_search_references_basic() {
         db_path: &db_path,
         query: "target",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions {
             include: false,
             lines: 0,
+            before: None,
+            after: None,
             max_lines: 20,
+            exclude_self: false,
         },
         snippet: SnippetOptions {
             include: false,
             max_bytes: 0,
+            line_numbers: false,
+            line_text: false,
+            read_timeout: None,
+            ..Default::default()
         },
         fqn: FqnOptions::default(),
         include_score: true,
@@ -667,6 +821,10 @@ fn test_search_references_basic() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
     let response = search_references(options).expect("search");
 
@@ -687,18 +845,28 @@ fn test_search_calls_basic() {
         db_path: &db_path,
         query: "caller_fn",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions {
             include: false,
             lines: 0,
+            before: None,
+            after: None,
             max_lines: 20,
+            exclude_self: false,
         },
         snippet: SnippetOptions {
             include: false,
             max_bytes: 0,
+            line_numbers: false,
+            line_text: false,
+            read_timeout: None,
+            ..Default::default()
         },
         fqn: FqnOptions::default(),
         include_score: true,
@@ -712,6 +880,10 @@ fn test_search_calls_basic() {
         exact_fqn: None,
         language_filter: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
     let response = search_calls(options).expect("search");
 
@@ -740,23 +912,35 @@ fn test_combined_response_counts_match() {
             db_path: &db_path,
             query: "target",
             path_filter: None,
+            exclude_paths: &[],
+            path_glob: false,
             kind_filter: None,
             limit: 10,
             use_regex: false,
+            ignore_case: false,
             candidates: 100,
             context: ContextOptions {
                 include: false,
                 lines: 0,
+                before: None,
+                after: None,
                 max_lines: 20,
+                exclude_self: false,
             },
             snippet: SnippetOptions {
                 include: false,
                 max_bytes: 0,
+                line_numbers: false,
+                line_text: false,
+                read_timeout: None,
+                ..Default::default()
             },
             fqn: FqnOptions {
                 fqn: false,
                 canonical_fqn: false,
                 display_fqn: false,
+                normalize: false,
+                defined_in: false,
             },
             include_score: true,
             sort_by: llmgrep::SortMode::default(),
@@ -769,6 +953,10 @@ fn test_combined_response_counts_match() {
             exact_fqn: None,
             language_filter: None,
             coverage_filter: None,
+            file_set: None,
+            max_per_file: None,
+            public_only: false,
+            prefer: None,
         };
         search_symbols(options).expect("symbols")
     };
@@ -778,18 +966,28 @@ fn test_combined_response_counts_match() {
             db_path: &db_path,
             query: "target",
             path_filter: None,
+            exclude_paths: &[],
+            path_glob: false,
             kind_filter: None,
             limit: 10,
             use_regex: false,
+            ignore_case: false,
             candidates: 100,
             context: ContextOptions {
                 include: false,
                 lines: 0,
+                before: None,
+                after: None,
                 max_lines: 20,
+                exclude_self: false,
             },
             snippet: SnippetOptions {
                 include: false,
                 max_bytes: 0,
+                line_numbers: false,
+                line_text: false,
+                read_timeout: None,
+                ..Default::default()
             },
             fqn: FqnOptions::default(),
             include_score: true,
@@ -803,6 +1001,10 @@ fn test_combined_response_counts_match() {
             exact_fqn: None,
             language_filter: None,
             coverage_filter: None,
+            file_set: None,
+            max_per_file: None,
+            public_only: false,
+            prefer: None,
         };
         search_references(options).expect("refs")
     };
@@ -812,18 +1014,28 @@ fn test_combined_response_counts_match() {
             db_path: &db_path,
             query: "caller_fn",
             path_filter: None,
+            exclude_paths: &[],
+            path_glob: false,
             kind_filter: None,
             limit: 10,
             use_regex: false,
+            ignore_case: false,
             candidates: 100,
             context: ContextOptions {
                 include: false,
                 lines: 0,
+                before: None,
+                after: None,
                 max_lines: 20,
+                exclude_self: false,
             },
             snippet: SnippetOptions {
                 include: false,
                 max_bytes: 0,
+                line_numbers: false,
+                line_text: false,
+                read_timeout: None,
+                ..Default::default()
             },
             fqn: FqnOptions::default(),
             include_score: true,
@@ -837,6 +1049,10 @@ fn test_combined_response_counts_match() {
             exact_fqn: None,
             language_filter: None,
             coverage_filter: None,
+            file_set: None,
+            max_per_file: None,
+            public_only: false,
+            prefer: None,
         };
         search_calls(options).expect("calls")
     };
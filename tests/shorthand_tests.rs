@@ -147,11 +147,14 @@ fn test_expand_shorthands_whitespace_handling() {
 #[test]
 fn test_get_supported_languages() {
     let languages = get_supported_languages();
-    assert_eq!(languages.len(), 4);
+    assert_eq!(languages.len(), 9);
     assert!(languages.contains(&"rust"));
     assert!(languages.contains(&"python"));
     assert!(languages.contains(&"javascript"));
     assert!(languages.contains(&"typescript"));
+    for language in ["go", "ruby", "php", "swift", "kotlin"] {
+        assert!(languages.contains(&language), "missing {}", language);
+    }
 }
 
 #[test]
@@ -261,6 +264,49 @@ fn test_expand_shorthand_with_language_typescript() {
     assert!(result.contains(&"while_statement".to_string()));
 }
 
+#[test]
+fn test_expand_shorthand_with_language_ruby() {
+    let result = expand_shorthand_with_language("functions", Some("ruby"));
+    assert!(result.contains(&"method".to_string()));
+    assert!(result.contains(&"singleton_method".to_string()));
+    assert!(result.contains(&"block".to_string()));
+    assert!(
+        !result.contains(&"function_item".to_string()),
+        "Ruby must not fall back to Rust kinds"
+    );
+}
+
+#[test]
+fn test_expand_shorthand_with_language_php_swift_go_kotlin() {
+    let php = expand_shorthand_with_language("functions", Some("php"));
+    assert!(php.contains(&"function_definition".to_string()));
+    assert!(php.contains(&"method_declaration".to_string()));
+
+    let swift = expand_shorthand_with_language("functions", Some("swift"));
+    assert!(swift.contains(&"function_declaration".to_string()));
+    assert!(swift.contains(&"closure_expression".to_string()));
+
+    let go = expand_shorthand_with_language("functions", Some("go"));
+    assert_eq!(
+        go,
+        vec!["function_declaration", "method_declaration", "func_literal"]
+    );
+
+    let kotlin = expand_shorthand_with_language("conditionals", Some("kotlin"));
+    assert!(kotlin.contains(&"when_expression".to_string()));
+}
+
+#[test]
+fn test_get_node_kinds_for_new_languages_cover_every_category() {
+    for language in ["go", "ruby", "php", "swift", "kotlin"] {
+        for category in ["loops", "conditionals", "functions", "declarations"] {
+            let kinds = get_node_kinds_for_language(language, category)
+                .unwrap_or_else(|| panic!("{} has no {} mapping", language, category));
+            assert!(!kinds.is_empty(), "{} {} is empty", language, category);
+        }
+    }
+}
+
 #[test]
 fn test_expand_shorthand_with_language_no_language() {
     // Without language, should fall back to Rust shorthands
@@ -231,10 +231,13 @@ fn test_api_metrics_filtering() {
         db_path: &db_path,
         query: "complexity", // matches both
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         language_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -245,7 +248,14 @@ fn test_api_metrics_filtering() {
             min_complexity: Some(10),
             max_complexity: None,
             min_fan_in: None,
+            max_fan_in: None,
             min_fan_out: None,
+            max_fan_out: None,
+            min_loc: None,
+            max_loc: None,
+            weight_by_calls: false,
+            boost_fan_in: false,
+            with_fan_in: false,
         },
         ast: AstOptions::default(),
         depth: DepthOptions::default(),
@@ -254,6 +264,10 @@ fn test_api_metrics_filtering() {
         fqn_pattern: None,
         exact_fqn: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let response = search_symbols(options).expect("search should succeed");
@@ -287,10 +301,13 @@ fn test_api_metrics_sorting() {
         db_path: &db_path,
         query: "fan", // matches both
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         language_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -305,6 +322,10 @@ fn test_api_metrics_sorting() {
         fqn_pattern: None,
         exact_fqn: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let response = search_symbols(options).expect("search should succeed");
@@ -333,10 +354,13 @@ fn test_api_fqn_field_population() {
         db_path: &db_path,
         query: symbol_name,
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         language_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -344,6 +368,8 @@ fn test_api_fqn_field_population() {
             fqn: true,
             canonical_fqn: true,
             display_fqn: true,
+            normalize: false,
+            defined_in: false,
         },
         include_score: true,
         sort_by: SortMode::Relevance,
@@ -355,6 +381,10 @@ fn test_api_fqn_field_population() {
         fqn_pattern: None,
         exact_fqn: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let response = search_symbols(options).expect("search should succeed");
@@ -398,10 +428,13 @@ fn test_api_language_filtering() {
         db_path: &db_path,
         query: "test_func",
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         language_filter: Some("rust"),
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -416,6 +449,10 @@ fn test_api_language_filtering() {
         fqn_pattern: None,
         exact_fqn: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let response = search_symbols(options).expect("search should succeed");
@@ -457,10 +494,13 @@ fn test_api_position_mode_sorting() {
         db_path: &db_path,
         query: "func", // matches all
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         language_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -475,6 +515,10 @@ fn test_api_position_mode_sorting() {
         fqn_pattern: None,
         exact_fqn: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let response = search_symbols(options).expect("search should succeed");
@@ -538,10 +582,13 @@ fn test_api_symbol_id_lookup() {
         db_path: &db_path,
         query: "", // empty query
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         language_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -556,6 +603,10 @@ fn test_api_symbol_id_lookup() {
         fqn_pattern: None,
         exact_fqn: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let response = search_symbols(options).expect("search should succeed");
@@ -636,10 +687,13 @@ fn test_api_fqn_pattern_filtering() {
         db_path: &db_path,
         query: "helper", // matches both
         path_filter: None,
+        exclude_paths: &[],
+        path_glob: false,
         kind_filter: None,
         language_filter: None,
         limit: 10,
         use_regex: false,
+        ignore_case: false,
         candidates: 100,
         context: ContextOptions::default(),
         snippet: SnippetOptions::default(),
@@ -647,6 +701,8 @@ fn test_api_fqn_pattern_filtering() {
             fqn: false,
             canonical_fqn: true,
             display_fqn: false,
+            normalize: false,
+            defined_in: false,
         },
         include_score: true,
         sort_by: SortMode::Relevance,
@@ -658,6 +714,10 @@ fn test_api_fqn_pattern_filtering() {
         fqn_pattern: Some("%module_a%"), // LIKE pattern
         exact_fqn: None,
         coverage_filter: None,
+        file_set: None,
+        max_per_file: None,
+        public_only: false,
+        prefer: None,
     };
 
     let response = search_symbols(options).expect("search should succeed");